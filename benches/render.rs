@@ -0,0 +1,72 @@
+use ascii_renderer::noise::Rng;
+use ascii_renderer::prelude::*;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+fn cube_field(size: usize) -> Renderer {
+    let meshs = (0..size * size)
+        .map(|i| {
+            let mut cube = ascii_renderer::create_cube();
+            cube.position = vec3!(
+                (i % size) as Scalar * 3.0 - size as Scalar * 1.5,
+                0.0,
+                (i / size) as Scalar * 3.0
+            );
+            cube
+        })
+        .collect();
+    Renderer::new(
+        meshs,
+        Camera {
+            position: vec3!(0.0, -5.0, -10.0),
+            rotation: vec3!(-0.3, 0.0, 0.0),
+            fov: vec2!(1.2, 0.6),
+        },
+    )
+}
+
+fn line_rasterization(c: &mut Criterion) {
+    let mut rng = Rng::new(0);
+    let lines: Vec<Line> = (0..1000)
+        .map(|_| Line {
+            char: '#',
+            points: (
+                vec2!(rng.range(-20.0, 220.0), rng.range(-20.0, 120.0)),
+                vec2!(rng.range(-20.0, 220.0), rng.range(-20.0, 120.0)),
+            ),
+        })
+        .collect();
+    let mut buf = CharBuffer::new(200, 100);
+    c.bench_function("draw 1000 lines", |b| {
+        b.iter(|| buf.draw_lines(black_box(lines.clone())))
+    });
+}
+
+fn full_frame(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cube field frame");
+    for size in [10, 30] {
+        let mut renderer = cube_field(size);
+        let mut buf = CharBuffer::new(200, 100);
+        group.bench_with_input(BenchmarkId::from_parameter(size * size), &size, |b, _| {
+            b.iter(|| {
+                //Moving the meshes every frame keeps the vertex cache from skipping the work
+                renderer.meshs.iter_mut().for_each(|x| x.rotation.y += 0.01);
+                buf.fill(' ');
+                renderer.draw(&mut buf);
+            })
+        });
+    }
+    group.finish();
+}
+
+fn obj_load(c: &mut Criterion) {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/face.obj");
+    c.bench_function("load face.obj", |b| {
+        b.iter(|| {
+            let meshs: Vec<Mesh> = AsciiObj::load(black_box(path)).unwrap().into();
+            meshs
+        })
+    });
+}
+
+criterion_group!(benches, line_rasterization, full_frame, obj_load);
+criterion_main!(benches);
@@ -0,0 +1,71 @@
+//! Renders a scene for a number of frames without printing anything and reports how long it took, for comparing performance between changes.
+//! Usage: `cargo run --release --example bench_frames -- [cubes|obj] [frames] [width] [height]`
+use ascii_renderer::prelude::*;
+use std::time::Instant;
+
+fn scene(name: &str) -> Vec<Mesh> {
+    match name {
+        "obj" => {
+            let mut meshs: Vec<Mesh> =
+                AsciiObj::load(concat!(env!("CARGO_MANIFEST_DIR"), "/face.obj"))
+                    .unwrap()
+                    .into();
+            meshs.iter_mut().for_each(|mesh| {
+                mesh.scale = vec3!(0.01, 0.01, 0.01);
+                mesh.rotation = vec3!(std::f32::consts::PI as Scalar, 0.0, 0.0);
+                mesh.recenter();
+            });
+            meshs
+        }
+        "cubes" => (0..900)
+            .map(|i| {
+                let mut cube = ascii_renderer::create_cube();
+                cube.position = vec3!(
+                    (i % 30) as Scalar * 3.0 - 45.0,
+                    0.0,
+                    (i / 30) as Scalar * 3.0
+                );
+                cube
+            })
+            .collect(),
+        _ => panic!("unknown scene {name}, expected cubes or obj"),
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let name = args.first().map(|x| x.as_str()).unwrap_or("cubes");
+    let frames: usize = args.get(1).map(|x| x.parse().unwrap()).unwrap_or(500);
+    let width: usize = args.get(2).map(|x| x.parse().unwrap()).unwrap_or(200);
+    let height: usize = args.get(3).map(|x| x.parse().unwrap()).unwrap_or(100);
+
+    let mut renderer = Renderer::new(
+        scene(name),
+        Camera {
+            position: vec3!(0.0, -5.0, -10.0),
+            rotation: vec3!(-0.3, 0.0, 0.0),
+            fov: vec2!(1.2, 0.6),
+        },
+    );
+    let edges: usize = renderer.meshs.iter().map(|x| x.get_edges().len()).sum();
+    let mut buf = CharBuffer::new(width, height);
+
+    let start = Instant::now();
+    for _ in 0..frames {
+        renderer.meshs.iter_mut().for_each(|x| x.rotation.y += 0.01);
+        buf.fill(' ');
+        renderer.draw(&mut buf);
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "{name}: {} meshes, {edges} edges, {width}x{height}, {frames} frames",
+        renderer.meshs.len()
+    );
+    println!(
+        "total {:.3}s, {:.3}ms per frame, {:.1} fps",
+        elapsed.as_secs_f64(),
+        elapsed.as_secs_f64() * 1000.0 / frames as f64,
+        frames as f64 / elapsed.as_secs_f64()
+    );
+}
@@ -0,0 +1,55 @@
+//! Cycles through the demoscene effects in the demos module, a few seconds each.
+use ascii_renderer::demos::{metaballs, plasma, rotozoom, tunnel, Starfield, RAMP};
+use ascii_renderer::prelude::*;
+
+struct Demo {
+    time: f32,
+    stars: Starfield,
+    texture: CharBuffer,
+}
+
+impl Logic for Demo {
+    fn process(&mut self, screen_buf: &mut CharBuffer, delta: f32) -> ProcessReturn {
+        self.time += delta;
+        let region = screen_buf.region();
+        match (self.time / 5.0) as usize % 5 {
+            0 => plasma(screen_buf, region, self.time, RAMP),
+            1 => tunnel(screen_buf, region, self.time, RAMP),
+            2 => rotozoom(screen_buf, region, &self.texture, self.time),
+            3 => {
+                let t = self.time as Scalar;
+                let balls = [
+                    (
+                        vec2!(30.0 + (t * 0.9).sin() * 15.0, 15.0 + (t * 1.3).cos() * 8.0),
+                        5.0,
+                    ),
+                    (
+                        vec2!(30.0 + (t * 1.1).cos() * 18.0, 15.0 + (t * 0.7).sin() * 10.0),
+                        4.0,
+                    ),
+                    (
+                        vec2!(30.0 + (t * 0.5).sin() * 10.0, 15.0 + (t * 1.7).sin() * 6.0),
+                        3.0,
+                    ),
+                ];
+                metaballs(screen_buf, region, &balls, RAMP);
+            }
+            _ => {
+                screen_buf.fill(' ');
+                self.stars.update(delta);
+                self.stars.draw(screen_buf, region);
+            }
+        }
+        ProcessReturn::Continue
+    }
+}
+
+fn main() {
+    let demo = Demo {
+        time: 0.0,
+        stars: Starfield::new(150, 1),
+        texture: CharBuffer::from_text("#### \n#  # \n#### \n     "),
+    };
+    let mut runner = Runner::new(60, 30, 30, demo);
+    runner.run(true);
+}
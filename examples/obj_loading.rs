@@ -11,7 +11,7 @@ impl Logic for MyLogic {
 
         self.renderer.draw(screen_buf);
 
-        self.renderer.meshs.first_mut().unwrap().rotation.y += delta;
+        self.renderer.meshs.first_mut().unwrap().rotation.y += Scalar::from(delta);
 
         ProcessReturn::Continue
     }
@@ -22,7 +22,7 @@ fn main() {
     my_meshes.iter_mut().for_each(|mesh| {
         // * Scales the obj down. rotates it so that it is rightside up, and recenters it.
         mesh.scale = vec3!(0.01, 0.01, 0.01);
-        mesh.rotation = vec3!(std::f32::consts::PI, 0.0, 0.0);
+        mesh.rotation = vec3!(std::f32::consts::PI as Scalar, 0.0, 0.0);
         mesh.recenter();   // * This OBJ is really far from the origin for some reason, so if it is not recentered it 
     });
     let mut runner = Runner::new(
@@ -30,14 +30,14 @@ fn main() {
         50,
         25,
         MyLogic {
-            renderer: Renderer {
-                meshs: my_meshes,
-                camera: Camera {
+            renderer: Renderer::new(
+                my_meshes,
+                Camera {
                     position: vec3!(0.0, 0.0, -3.0),
                     rotation: vec3!(0.0, 0.0, 0.0),
                     fov: vec2!(0.8, 0.8),
                 },
-            },
+            ),
         },
     );
     runner.run(true);
@@ -2,12 +2,13 @@ use ascii_renderer::prelude::*;
 
 struct MyLogic {
     pub renderer: Renderer,
-    pub time_offset: f32,
+    pub time_offset: Scalar,
 }
 
 impl Logic for MyLogic {
     fn process(&mut self, screen_buf: &mut CharBuffer, delta: f32) -> ProcessReturn {
         screen_buf.fill(' ');
+        let delta = Scalar::from(delta);
 
         self.time_offset += delta; //Keeps track of time
 
@@ -30,14 +31,14 @@ fn main() {
         50,
         25,
         MyLogic {
-            renderer: Renderer {
-                meshs: vec![ascii_renderer::create_cube()],
-                camera: Camera {
+            renderer: Renderer::new(
+                vec![ascii_renderer::create_cube()],
+                Camera {
                     position: vec3!(0.0, 0.0, -7.0),
                     rotation: vec3!(0.0, 0.0, 0.0),
                     fov: vec2!(0.8, 0.8),
                 },
-            },
+            ),
             time_offset: 0.0,
         },
     );
@@ -0,0 +1,43 @@
+//! A pool of water with waves rolling across it and a drop falling in every couple of seconds.
+use ascii_renderer::prelude::*;
+use ascii_renderer::wave::{Wave, WaveSurface};
+
+struct Pool {
+    renderer: Renderer,
+    water: WaveSurface,
+    time: f32,
+}
+
+impl Logic for Pool {
+    fn process(&mut self, screen_buf: &mut CharBuffer, delta: f32) -> ProcessReturn {
+        self.time += delta;
+        if self.time >= 2.0 {
+            self.time -= 2.0;
+            let (columns, rows) = self.water.dimensions();
+            self.water.disturb(columns / 3, rows / 2, 1.5);
+        }
+        self.water.update(delta);
+        screen_buf.fill(' ');
+        self.renderer.draw_renderable(&self.water, screen_buf);
+        ProcessReturn::Continue
+    }
+}
+
+fn main() {
+    let mut water = WaveSurface::new(30, 30, 0.5);
+    water.waves.push(Wave::new(vec2!(1.0, 0.2), 0.15, 5.0, 1.5));
+    water.waves.push(Wave::new(vec2!(-0.3, 1.0), 0.1, 3.0, 1.0));
+    let pool = Pool {
+        renderer: Renderer::new(
+            vec![],
+            Camera {
+                position: vec3!(0.0, -6.0, -12.0),
+                rotation: vec3!(-0.45, 0.0, 0.0),
+                fov: vec2!(1.2, 0.8),
+            },
+        ),
+        water,
+        time: 0.0,
+    };
+    Runner::new(80, 40, 30, pool).run(true);
+}
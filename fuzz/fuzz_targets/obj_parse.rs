@@ -0,0 +1,21 @@
+#![no_main]
+
+use ascii_renderer::prelude::*;
+use ascii_renderer::{vec2, vec3};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+    if let Ok(obj) = AsciiObj::parse(text) {
+        //Whatever parses has to be drawable too
+        let meshes: Vec<Mesh> = obj.into();
+        let camera = Camera {
+            position: vec3!(0.0, 0.0, -5.0),
+            rotation: vec3!(0.0, 0.0, 0.0),
+            fov: vec2!(1.0, 1.0),
+        };
+        render_frame(&Renderer::new(meshes, camera), 16, 8);
+    }
+});
@@ -0,0 +1,16 @@
+#![no_main]
+
+use ascii_renderer::prelude::*;
+use ascii_renderer::{vec2, vec3};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(mesh) = Mesh::parse_stl(data) {
+        let camera = Camera {
+            position: vec3!(0.0, 0.0, -5.0),
+            rotation: vec3!(0.0, 0.0, 0.0),
+            fov: vec2!(1.0, 1.0),
+        };
+        render_frame(&Renderer::new(vec![mesh], camera), 16, 8);
+    }
+});
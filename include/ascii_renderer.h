@@ -0,0 +1,37 @@
+/* The C ABI of ascii_renderer, built with the `ffi` feature. See src/ffi.rs for details. */
+#ifndef ASCII_RENDERER_H
+#define ASCII_RENDERER_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct ArRenderer ArRenderer;
+
+/* A renderer with no meshes and a camera at the origin looking down +z with a fov of 1 radian. Free it with ar_renderer_free. */
+ArRenderer *ar_renderer_new(void);
+
+/* Frees a renderer made by ar_renderer_new. Freeing NULL does nothing. */
+void ar_renderer_free(ArRenderer *renderer);
+
+/* Moves the camera. position and rotation point to 3 floats (x, y, z), with rotation and the fov in radians. */
+bool ar_renderer_set_camera(ArRenderer *renderer, const float *position, const float *rotation, float fov_x, float fov_y);
+
+/* Adds a mesh from vertex_count (x, y, z) triples and edge_count pairs of vertex indices, drawn with the code point ch. Returns its index, or -1 if the input doesn't make sense. */
+ptrdiff_t ar_renderer_add_mesh(ArRenderer *renderer, const float *vertices, size_t vertex_count, const uint32_t *edges, size_t edge_count, uint32_t ch);
+
+/* Sets the position, rotation (in radians), and scale of a mesh, each pointing to 3 floats. Returns false if there's no mesh with that index. */
+bool ar_renderer_set_transform(ArRenderer *renderer, size_t mesh, const float *position, const float *rotation, const float *scale);
+
+/* Draws into cells, width * height code points row by row from the top left, with ' ' where nothing is drawn. If depth isn't NULL, it gets width * height distances in front of the camera, or infinity where nothing is drawn. */
+bool ar_renderer_render(ArRenderer *renderer, size_t width, size_t height, uint32_t *cells, float *depth);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
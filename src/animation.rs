@@ -0,0 +1,306 @@
+use super::rendering::{Camera, Mesh};
+use super::{Scalar, Vector3};
+
+/// How a track blends from one keyframe to the next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Holds the value of the keyframe until the next one is reached.
+    Step,
+    Linear,
+    /// Smoothstep, starts and ends slowly.
+    EaseInOut,
+}
+
+/// What a clip does once its time passes the last keyframe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopMode {
+    /// Stops on the last keyframe.
+    Once,
+    /// Jumps back to the start.
+    Loop,
+    /// Plays backwards to the start, then forwards again.
+    PingPong,
+}
+
+impl LoopMode {
+    pub fn local_time(self, time: f32, duration: f32) -> f32 {
+        //! Maps a time since something started playing to a time within its duration.
+        if duration <= 0.0 {
+            return 0.0;
+        }
+        match self {
+            LoopMode::Once => time.clamp(0.0, duration),
+            LoopMode::Loop => time.rem_euclid(duration),
+            LoopMode::PingPong => {
+                let t = time.rem_euclid(duration * 2.0);
+                if t > duration {
+                    duration * 2.0 - t
+                } else {
+                    t
+                }
+            }
+        }
+    }
+}
+
+/// A value at a point in time (in seconds). The interpolation is used between this keyframe and the next one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keyframe {
+    pub time: f32,
+    pub value: Vector3,
+    pub interpolation: Interpolation,
+}
+
+/// A list of keyframes for a single property, kept sorted by time.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Track {
+    keyframes: Vec<Keyframe>,
+}
+
+impl Track {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn insert(&mut self, time: f32, value: Vector3, interpolation: Interpolation) {
+        //! Adds a keyframe, replacing any keyframe that is already at that exact time. Keyframes at NaN or infinite times are ignored, as they can't be placed on the timeline.
+        if !time.is_finite() {
+            return;
+        }
+        let keyframe = Keyframe {
+            time,
+            value,
+            interpolation,
+        };
+        match self
+            .keyframes
+            .binary_search_by(|x| x.time.total_cmp(&time))
+        {
+            Ok(i) => self.keyframes[i] = keyframe,
+            Err(i) => self.keyframes.insert(i, keyframe),
+        }
+    }
+    pub fn get_keyframes(&self) -> &Vec<Keyframe> {
+        &self.keyframes
+    }
+    pub fn is_empty(&self) -> bool {
+        self.keyframes.is_empty()
+    }
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |x| x.time)
+    }
+    pub fn sample(&self, time: f32) -> Option<Vector3> {
+        //! Gets the value of the track at the given time. Times before the first keyframe or after the last keyframe are clamped. Returns None if the track has no keyframes.
+        let first = self.keyframes.first()?;
+        if time <= first.time {
+            return Some(first.value);
+        }
+        let next_index = self.keyframes.iter().position(|x| x.time > time);
+        let next_index = match next_index {
+            Some(i) => i,
+            None => return self.keyframes.last().map(|x| x.value),
+        };
+        let prev = self.keyframes[next_index - 1];
+        let next = self.keyframes[next_index];
+
+        let t = (time - prev.time) / (next.time - prev.time);
+        let t = match prev.interpolation {
+            Interpolation::Step => 0.0,
+            Interpolation::Linear => t,
+            Interpolation::EaseInOut => t * t * (3.0 - 2.0 * t),
+        };
+        Some(prev.value.lerp(next.value, t as Scalar))
+    }
+}
+
+/// A set of keyframe tracks for the position, rotation, and scale of a mesh or camera. Any empty track leaves that property untouched.
+/// # Example
+/// ```
+/// # use ascii_renderer::prelude::*;
+/// # use ascii_renderer::animation::*;
+/// let mut clip = AnimationClip::new(LoopMode::PingPong);
+/// clip.rotation.insert(0.0, vec3!(0.0, 0.0, 0.0), Interpolation::EaseInOut);
+/// clip.rotation.insert(2.0, vec3!(0.0, 3.14, 0.0), Interpolation::EaseInOut);
+///
+/// let mut animator = Animator::new(clip);
+/// let mut cube = ascii_renderer::create_cube();
+/// animator.update(1.0);   //Usually the delta passed to process()
+/// animator.apply_to_mesh(&mut cube);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationClip {
+    pub position: Track,
+    pub rotation: Track,
+    pub scale: Track,
+    pub loop_mode: LoopMode,
+}
+
+impl AnimationClip {
+    pub fn new(loop_mode: LoopMode) -> Self {
+        Self {
+            position: Track::new(),
+            rotation: Track::new(),
+            scale: Track::new(),
+            loop_mode,
+        }
+    }
+    pub fn duration(&self) -> f32 {
+        //! The time of the last keyframe across all tracks.
+        self.position
+            .duration()
+            .max(self.rotation.duration())
+            .max(self.scale.duration())
+    }
+    pub fn local_time(&self, time: f32) -> f32 {
+        //! Maps a time since the clip started playing to a time within the clip, according to the loop mode.
+        self.loop_mode.local_time(time, self.duration())
+    }
+}
+
+/// Plays an AnimationClip. Call update() with the delta every frame, then apply it to whatever it is animating.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animator {
+    pub clip: AnimationClip,
+    /// Time in seconds since the clip started playing. Goes negative when a looping clip is played backwards from the start.
+    pub time: f32,
+    /// Multiplier applied to the delta passed to update(). Negative values play the clip backwards.
+    pub speed: f32,
+    pub playing: bool,
+}
+
+impl Animator {
+    pub fn new(clip: AnimationClip) -> Self {
+        Self {
+            clip,
+            time: 0.0,
+            speed: 1.0,
+            playing: true,
+        }
+    }
+    pub fn update(&mut self, delta: f32) {
+        if self.playing {
+            self.time += delta * self.speed;
+            if self.clip.loop_mode == LoopMode::Once {
+                self.time = self.time.max(0.0);
+            }
+        }
+    }
+    pub fn play(&mut self) {
+        self.playing = true;
+    }
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+    pub fn restart(&mut self) {
+        self.time = 0.0;
+        self.playing = true;
+    }
+    pub fn is_finished(&self) -> bool {
+        //! Only ever true for clips with LoopMode::Once.
+        self.clip.loop_mode == LoopMode::Once && self.time >= self.clip.duration()
+    }
+    pub fn apply_to_mesh(&self, mesh: &mut Mesh) {
+        let time = self.clip.local_time(self.time);
+        if let Some(position) = self.clip.position.sample(time) {
+            mesh.position = position;
+        }
+        if let Some(rotation) = self.clip.rotation.sample(time) {
+            mesh.rotation = rotation;
+        }
+        if let Some(scale) = self.clip.scale.sample(time) {
+            mesh.scale = scale;
+        }
+    }
+    pub fn apply_to_camera(&self, camera: &mut Camera) {
+        //! Cameras have no scale, so the scale track is ignored.
+        let time = self.clip.local_time(self.time);
+        if let Some(position) = self.clip.position.sample(time) {
+            camera.position = position;
+        }
+        if let Some(rotation) = self.clip.rotation.sample(time) {
+            camera.rotation = rotation;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec3;
+
+    #[test]
+    fn track_sampling() {
+        let mut track = Track::new();
+        track.insert(2.0, vec3!(2.0, 0.0, 0.0), Interpolation::Linear);
+        track.insert(0.0, vec3!(0.0, 0.0, 0.0), Interpolation::Linear);
+        assert_eq!(track.sample(-1.0), Some(vec3!(0.0, 0.0, 0.0)));
+        assert_eq!(track.sample(1.0), Some(vec3!(1.0, 0.0, 0.0)));
+        assert_eq!(track.sample(5.0), Some(vec3!(2.0, 0.0, 0.0)));
+        assert_eq!(Track::new().sample(1.0), None);
+
+        track.insert(0.0, vec3!(0.0, 0.0, 0.0), Interpolation::Step);
+        assert_eq!(track.get_keyframes().len(), 2);
+        assert_eq!(track.sample(1.9), Some(vec3!(0.0, 0.0, 0.0)));
+
+        track.insert(f32::NAN, vec3!(9.0, 9.0, 9.0), Interpolation::Linear);
+        track.insert(f32::INFINITY, vec3!(9.0, 9.0, 9.0), Interpolation::Linear);
+        assert_eq!(track.get_keyframes().len(), 2);
+    }
+
+    #[test]
+    fn loop_modes() {
+        let mut clip = AnimationClip::new(LoopMode::Once);
+        clip.position
+            .insert(0.0, vec3!(0.0, 0.0, 0.0), Interpolation::Linear);
+        clip.position
+            .insert(2.0, vec3!(2.0, 0.0, 0.0), Interpolation::Linear);
+        assert_eq!(clip.local_time(3.0), 2.0);
+        clip.loop_mode = LoopMode::Loop;
+        assert_eq!(clip.local_time(3.0), 1.0);
+        clip.loop_mode = LoopMode::PingPong;
+        assert_eq!(clip.local_time(3.5), 0.5);
+        assert_eq!(clip.local_time(-0.5), 0.5);
+    }
+
+    #[test]
+    fn animator_drives_mesh() {
+        let mut clip = AnimationClip::new(LoopMode::Once);
+        clip.scale
+            .insert(0.0, vec3!(1.0, 1.0, 1.0), Interpolation::Linear);
+        clip.scale
+            .insert(1.0, vec3!(3.0, 3.0, 3.0), Interpolation::Linear);
+        let mut animator = Animator::new(clip);
+        let mut mesh = Mesh::default();
+        mesh.position = vec3!(5.0, 0.0, 0.0);
+
+        animator.update(0.5);
+        animator.apply_to_mesh(&mut mesh);
+        assert_eq!(mesh.scale, vec3!(2.0, 2.0, 2.0));
+        assert_eq!(mesh.position, vec3!(5.0, 0.0, 0.0));
+        assert!(!animator.is_finished());
+
+        animator.update(1.0);
+        assert!(animator.is_finished());
+    }
+
+    #[test]
+    fn animator_plays_backwards() {
+        let mut clip = AnimationClip::new(LoopMode::Loop);
+        clip.position
+            .insert(0.0, vec3!(0.0, 0.0, 0.0), Interpolation::Linear);
+        clip.position
+            .insert(2.0, vec3!(2.0, 0.0, 0.0), Interpolation::Linear);
+        let mut animator = Animator::new(clip);
+        animator.speed = -1.0;
+        let mut mesh = Mesh::default();
+
+        animator.update(0.25);
+        animator.apply_to_mesh(&mut mesh);
+        assert_eq!(mesh.position, vec3!(1.75, 0.0, 0.0));
+
+        //Once clips stop at the start instead
+        animator.clip.loop_mode = LoopMode::Once;
+        animator.restart();
+        animator.update(0.25);
+        assert_eq!(animator.time, 0.0);
+    }
+}
@@ -0,0 +1,355 @@
+//! A registry of things loaded from files, so Logic code can pass small Handles around instead of cloning big Meshes or reading the same file twice. Loading a path that's already loaded gives back a handle to what was loaded before, and reloading re-reads the file in place, so every handle sees the new version. Handles are reference counted, and unload_unused drops everything no handle points to anymore.
+//! # Example
+//! ```no_run
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::assets::Assets;
+//! let mut meshes: Assets<Mesh> = Assets::new();
+//! let ship = meshes.load("ship.stl").unwrap();
+//! //Loading it again doesn't read the file again
+//! assert_eq!(meshes.load("ship.stl").unwrap(), ship);
+//! //Clones of a mesh share its geometry, so this is cheap
+//! let renderer = Renderer::new(
+//!     vec![meshes[&ship].clone()],
+//!     Camera {
+//!         position: vec3!(0.0, 0.0, -10.0),
+//!         rotation: vec3!(0.0, 0.0, 0.0),
+//!         fov: vec2!(1.0, 1.0),
+//!     },
+//! );
+//! //After the file is edited:
+//! meshes.reload(&ship).unwrap();
+//! ```
+
+use super::char_buffer::CharBuffer;
+use super::obj::{AsciiObj, ObjError};
+use super::rendering::Mesh;
+use super::stl::StlError;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Something that can be loaded from a file into Assets.
+pub trait Asset: Sized {
+    type Error;
+    fn load(path: &Path) -> Result<Self, Self::Error>;
+}
+
+/// The error returned when a mesh or sprite can't be loaded.
+#[derive(Debug)]
+pub enum AssetError {
+    Io(std::io::Error),
+    Obj(ObjError),
+    Stl(StlError),
+    /// The file's extension isn't one that type of asset can be loaded from.
+    UnknownFormat(PathBuf),
+    /// The file was read, but doesn't hold what was asked for.
+    Mismatch(String),
+}
+
+impl std::fmt::Display for AssetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssetError::Io(err) => write!(f, "could not read asset: {err}"),
+            AssetError::Obj(err) => write!(f, "could not load obj: {err}"),
+            AssetError::Stl(err) => write!(f, "could not load stl: {err}"),
+            AssetError::UnknownFormat(path) => {
+                write!(f, "don't know how to load {}", path.display())
+            }
+            AssetError::Mismatch(reason) => write!(f, "wrong kind of asset: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AssetError::Io(err) => Some(err),
+            AssetError::Obj(err) => Some(err),
+            AssetError::Stl(err) => Some(err),
+            AssetError::UnknownFormat(_) | AssetError::Mismatch(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for AssetError {
+    fn from(err: std::io::Error) -> Self {
+        AssetError::Io(err)
+    }
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|x| x.to_str())
+        .map(|x| x.to_ascii_lowercase())
+}
+
+impl Asset for Vec<Mesh> {
+    type Error = AssetError;
+    fn load(path: &Path) -> Result<Self, AssetError> {
+        //Every object in an .obj, or the one mesh in an .stl
+        match extension(path).as_deref() {
+            Some("obj") => Ok(AsciiObj::load(path).map_err(AssetError::Obj)?.into()),
+            Some("stl") => Ok(vec![Mesh::load_stl(path).map_err(AssetError::Stl)?]),
+            _ => Err(AssetError::UnknownFormat(path.to_path_buf())),
+        }
+    }
+}
+
+impl Asset for Mesh {
+    type Error = AssetError;
+    fn load(path: &Path) -> Result<Self, AssetError> {
+        //.objs can hold several objects, which have to be loaded as a Vec<Mesh>
+        match extension(path).as_deref() {
+            Some("stl") => Mesh::load_stl(path).map_err(AssetError::Stl),
+            _ => {
+                let mut meshes = Vec::<Mesh>::load(path)?;
+                if meshes.len() != 1 {
+                    return Err(AssetError::Mismatch(format!(
+                        "{} holds {} meshes, not 1",
+                        path.display(),
+                        meshes.len()
+                    )));
+                }
+                Ok(meshes.remove(0))
+            }
+        }
+    }
+}
+
+impl Asset for CharBuffer {
+    type Error = AssetError;
+    fn load(path: &Path) -> Result<Self, AssetError> {
+        //A sprite drawn in a text file, see CharBuffer::from_text
+        Ok(CharBuffer::from_text(&std::fs::read_to_string(path)?))
+    }
+}
+
+/// Points to an asset in an Assets. Handles are cheap to clone, and the asset counts how many there are.
+pub struct Handle<T> {
+    id: usize,
+    count: Arc<()>,
+    asset: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    pub fn id(&self) -> usize {
+        //! The same for every handle to the same asset.
+        self.id
+    }
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            count: self.count.clone(),
+            asset: PhantomData,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Handle").field(&self.id).finish()
+    }
+}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> std::hash::Hash for Handle<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+#[derive(Debug)]
+struct Entry<T> {
+    asset: T,
+    path: Option<PathBuf>,
+    count: Arc<()>,
+}
+
+/// Assets of one type, each found by a Handle.
+#[derive(Debug)]
+pub struct Assets<T> {
+    entries: HashMap<usize, Entry<T>>,
+    paths: HashMap<PathBuf, usize>,
+    next_id: usize,
+}
+
+impl<T> Default for Assets<T> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+            paths: HashMap::new(),
+            next_id: 0,
+        }
+    }
+}
+
+impl<T> Assets<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    fn handle(&self, id: usize) -> Handle<T> {
+        Handle {
+            id,
+            count: self.entries[&id].count.clone(),
+            asset: PhantomData,
+        }
+    }
+    fn add(&mut self, asset: T, path: Option<PathBuf>) -> Handle<T> {
+        let id = self.next_id;
+        self.next_id += 1;
+        if let Some(path) = &path {
+            self.paths.insert(path.clone(), id);
+        }
+        let count = Arc::new(());
+        self.entries.insert(id, Entry { asset, path, count });
+        self.handle(id)
+    }
+    pub fn insert(&mut self, asset: T) -> Handle<T> {
+        //! Adds an asset that wasn't loaded from a file (ex: one made by demos), which can't be reloaded.
+        self.add(asset, None)
+    }
+    pub fn get(&self, handle: &Handle<T>) -> Option<&T> {
+        //! Handles only work with the Assets that made them, others give None or the wrong asset.
+        self.entries.get(&handle.id).map(|x| &x.asset)
+    }
+    pub fn get_mut(&mut self, handle: &Handle<T>) -> Option<&mut T> {
+        //! Changes are seen through every handle, until the asset is reloaded.
+        self.entries.get_mut(&handle.id).map(|x| &mut x.asset)
+    }
+    pub fn get_path(&self, handle: &Handle<T>) -> Option<&Path> {
+        //! The file the asset was loaded from, if it was.
+        self.entries.get(&handle.id)?.path.as_deref()
+    }
+    pub fn find(&self, path: impl AsRef<Path>) -> Option<Handle<T>> {
+        //! A handle to the asset loaded from path, if it's loaded.
+        self.paths.get(path.as_ref()).map(|&id| self.handle(id))
+    }
+    pub fn ref_count(&self, handle: &Handle<T>) -> usize {
+        //! How many handles point to the asset, including handle.
+        Arc::strong_count(&handle.count) - 1
+    }
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    pub fn unload_unused(&mut self) -> usize {
+        //! Drops every asset without any handles left, returning how many were dropped. Their paths are loaded from the file again next time.
+        let before = self.entries.len();
+        self.entries.retain(|_, x| Arc::strong_count(&x.count) > 1);
+        let entries = &self.entries;
+        self.paths.retain(|_, id| entries.contains_key(id));
+        before - self.entries.len()
+    }
+}
+
+impl<T: Asset> Assets<T> {
+    pub fn load(&mut self, path: impl AsRef<Path>) -> Result<Handle<T>, T::Error> {
+        //! Loads the asset at path, or gives a handle to it if it's already loaded.
+        let path = path.as_ref();
+        if let Some(handle) = self.find(path) {
+            return Ok(handle);
+        }
+        let asset = T::load(path)?;
+        Ok(self.add(asset, Some(path.to_path_buf())))
+    }
+    pub fn reload(&mut self, handle: &Handle<T>) -> Result<bool, T::Error> {
+        //! Reads the asset's file again, replacing it for every handle. False if it wasn't loaded from a file. If the file can't be loaded, the old version is kept.
+        let Some(entry) = self.entries.get_mut(&handle.id) else {
+            return Ok(false);
+        };
+        let Some(path) = &entry.path else {
+            return Ok(false);
+        };
+        entry.asset = T::load(path)?;
+        Ok(true)
+    }
+    pub fn reload_all(&mut self) -> Vec<(PathBuf, T::Error)> {
+        //! Reloads everything loaded from a file (ex: on a hotkey while editing models), returning the ones that failed.
+        let mut failed = vec![];
+        for entry in self.entries.values_mut() {
+            let Some(path) = &entry.path else {
+                continue;
+            };
+            match T::load(path) {
+                Ok(asset) => entry.asset = asset,
+                Err(err) => failed.push((path.clone(), err)),
+            }
+        }
+        failed
+    }
+}
+
+impl<T> std::ops::Index<&Handle<T>> for Assets<T> {
+    type Output = T;
+    fn index(&self, handle: &Handle<T>) -> &T {
+        self.get(handle).expect("handle from another Assets")
+    }
+}
+
+impl<T> std::ops::IndexMut<&Handle<T>> for Assets<T> {
+    fn index_mut(&mut self, handle: &Handle<T>) -> &mut T {
+        self.get_mut(handle).expect("handle from another Assets")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loading_and_counting() {
+        let directory = std::env::temp_dir().join(format!("assets_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("ship.txt");
+        std::fs::write(&path, "<=>").unwrap();
+
+        let mut sprites: Assets<CharBuffer> = Assets::new();
+        let ship = sprites.load(&path).unwrap();
+        let again = sprites.load(&path).unwrap();
+        assert_eq!((ship.clone(), sprites.len()), (again.clone(), 1));
+        assert_eq!(sprites.ref_count(&ship), 2);
+        assert_eq!(sprites[&ship].get_char(1, 0), Some('='));
+        assert_eq!(sprites.get_path(&ship), Some(path.as_path()));
+
+        //Reloading changes it for every handle, and failing keeps the old version
+        std::fs::write(&path, "<#>").unwrap();
+        assert!(sprites.reload(&ship).unwrap());
+        assert_eq!(sprites[&again].get_char(1, 0), Some('#'));
+        std::fs::remove_file(&path).unwrap();
+        assert!(sprites.reload(&ship).is_err());
+        assert_eq!(sprites.reload_all().len(), 1);
+        assert_eq!(sprites[&ship].get_char(1, 0), Some('#'));
+
+        let dot = sprites.insert(CharBuffer::from_text("."));
+        assert!(!sprites.reload(&dot).unwrap());
+        drop(again);
+        assert_eq!(sprites.unload_unused(), 0);
+        drop((ship, dot));
+        assert_eq!(sprites.unload_unused(), 2);
+        assert!(sprites.is_empty() && sprites.find(&path).is_none());
+
+        let mut meshes: Assets<Mesh> = Assets::new();
+        assert!(matches!(
+            meshes.load(directory.join("ship.fbx")),
+            Err(AssetError::UnknownFormat(_))
+        ));
+        assert!(matches!(
+            meshes.load(directory.join("ship.stl")),
+            Err(AssetError::Stl(StlError::Io(_)))
+        ));
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
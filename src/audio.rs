@@ -0,0 +1,408 @@
+//! Loudness and frequency bands from audio, for visualizers that move to music, behind the `audio` feature. A WavSource plays through a WAV file as frames go by, and with the `audio-capture` feature an AudioCapture listens to the default input device (ex: a microphone, or a loopback/monitor device to hear the system's audio). Both give an AudioFrame each frame.
+//! # Example
+//! ```no_run
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::audio::{AudioSource, WavSource};
+//! struct Visualizer {
+//!     audio: WavSource,
+//! }
+//! impl Logic for Visualizer {
+//!     fn process(&mut self, screen_buf: &mut CharBuffer, delta: f32) -> ProcessReturn {
+//!         let frame = self.audio.next_frame(delta);
+//!         screen_buf.fill(' ');
+//!         let height = screen_buf.dimensions.1;
+//!         for (x, band) in frame.bands.iter().enumerate() {
+//!             let bar = ((band * 4.0).min(1.0) * height as f32) as usize;
+//!             for y in height - bar..height {
+//!                 let _ = screen_buf.set_char(x, y, '#');
+//!             }
+//!         }
+//!         ProcessReturn::Continue
+//!     }
+//! }
+//! let audio = WavSource::open("song.wav").unwrap();
+//! let mut runner = Runner::new(16, 10, 30, Visualizer { audio });
+//! runner.run(true);
+//! ```
+
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+use std::sync::Arc;
+
+/// How loud the audio was over the last window of samples.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AudioFrame {
+    /// The root mean square of the samples, from 0.0 for silence to about 0.7 for a full volume sine wave.
+    pub amplitude: f32,
+    /// The loudest sample, from 0.0 to 1.0.
+    pub peak: f32,
+    /// The strength of each frequency band, from low to high. A full volume sine wave gives about 0.5 in its band.
+    pub bands: Vec<f32>,
+}
+
+/// Something that gives an AudioFrame each frame.
+pub trait AudioSource {
+    /// Moves forward by delta seconds and analyzes the latest samples.
+    fn next_frame(&mut self, delta: f32) -> AudioFrame;
+}
+
+/// Turns windows of mono samples into AudioFrames. Bands are spaced logarithmically, the way pitch is heard, from min_frequency up to half the sample rate.
+#[derive(Clone)]
+pub struct Analyzer {
+    sample_rate: u32,
+    bands: usize,
+    /// The lowest frequency included in the first band, in Hz.
+    pub min_frequency: f32,
+    fft: Arc<dyn Fft<f32>>,
+    scratch: Vec<Complex<f32>>,
+}
+
+impl std::fmt::Debug for Analyzer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Analyzer")
+            .field("sample_rate", &self.sample_rate)
+            .field("window", &self.window())
+            .field("bands", &self.bands)
+            .field("min_frequency", &self.min_frequency)
+            .finish()
+    }
+}
+
+impl Analyzer {
+    pub fn new(sample_rate: u32, window: usize, bands: usize) -> Self {
+        //! An analyzer that looks at the last window samples. Bigger windows tell low frequencies apart better, but react to changes slower.
+        Self {
+            sample_rate,
+            bands,
+            min_frequency: 20.0,
+            fft: FftPlanner::new().plan_fft_forward(window),
+            scratch: vec![Complex::new(0.0, 0.0); window],
+        }
+    }
+    pub fn window(&self) -> usize {
+        self.scratch.len()
+    }
+    pub fn band_range(&self, band: usize) -> (f32, f32) {
+        //! The lowest and highest frequency in a band, in Hz.
+        let nyquist = self.sample_rate as f32 / 2.0;
+        let min = self.min_frequency.clamp(1.0, nyquist);
+        let ratio = nyquist / min;
+        let edge = |i: usize| min * ratio.powf(i as f32 / self.bands.max(1) as f32);
+        (edge(band), edge(band + 1))
+    }
+    pub fn analyze(&mut self, samples: &[f32]) -> AudioFrame {
+        //! Analyzes the last window samples, treating missing ones as silence.
+        let window = self.window();
+        let samples = &samples[samples.len().saturating_sub(window)..];
+        if samples.is_empty() {
+            return AudioFrame {
+                bands: vec![0.0; self.bands],
+                ..AudioFrame::default()
+            };
+        }
+        let amplitude = (samples.iter().map(|x| x * x).sum::<f32>() / samples.len() as f32).sqrt();
+        let peak = samples.iter().fold(0.0f32, |peak, x| peak.max(x.abs()));
+
+        //A Hann window keeps the cut off ends of the samples from smearing into every band
+        for (i, cell) in self.scratch.iter_mut().enumerate() {
+            let sample = samples.get(i).copied().unwrap_or(0.0);
+            let hann = 0.5 - 0.5 * (std::f32::consts::TAU * i as f32 / window as f32).cos();
+            *cell = Complex::new(sample * hann, 0.0);
+        }
+        self.fft.process(&mut self.scratch);
+
+        let bin_width = self.sample_rate as f32 / window as f32;
+        let bins = &self.scratch[..window / 2 + 1];
+        let bands = (0..self.bands)
+            .map(|band| {
+                let (low, high) = self.band_range(band);
+                let first = ((low / bin_width).ceil() as usize).min(bins.len() - 1);
+                //Every band gets at least one bin, even when it's narrower than a bin at low frequencies
+                let last = ((high / bin_width).floor() as usize).clamp(first, bins.len() - 1);
+                let strongest = bins[first..=last]
+                    .iter()
+                    .map(|x| x.norm())
+                    .fold(0.0, f32::max);
+                //The Hann window halves the amplitude, and a real signal's energy is split between two bins
+                strongest * 4.0 / window as f32 / 2.0
+            })
+            .collect();
+        AudioFrame {
+            amplitude,
+            peak,
+            bands,
+        }
+    }
+}
+
+/// Plays through audio loaded from a WAV file (or given as samples), without making any sound.
+#[derive(Debug, Clone)]
+pub struct WavSource {
+    samples: Vec<f32>,
+    sample_rate: u32,
+    time: f32,
+    /// Whether to start over after the last sample.
+    pub looping: bool,
+    pub analyzer: Analyzer,
+}
+
+impl WavSource {
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self, hound::Error> {
+        //! Loads a WAV file, mixing every channel into one.
+        Self::from_reader(std::io::BufReader::new(std::fs::File::open(path)?))
+    }
+    pub fn from_reader(reader: impl std::io::Read) -> Result<Self, hound::Error> {
+        let mut reader = hound::WavReader::new(reader)?;
+        let spec = reader.spec();
+        let samples: Vec<f32> = match spec.sample_format {
+            hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>()?,
+            hound::SampleFormat::Int => {
+                let scale = 1.0 / (1u64 << (spec.bits_per_sample - 1)) as f32;
+                reader
+                    .samples::<i32>()
+                    .map(|x| x.map(|x| x as f32 * scale))
+                    .collect::<Result<_, _>>()?
+            }
+        };
+        let channels = spec.channels.max(1) as usize;
+        let mono = samples
+            .chunks(channels)
+            .map(|x| x.iter().sum::<f32>() / channels as f32)
+            .collect();
+        Ok(Self::from_samples(mono, spec.sample_rate))
+    }
+    pub fn from_samples(samples: Vec<f32>, sample_rate: u32) -> Self {
+        //! Mono samples from -1.0 to 1.0, analyzed 1024 at a time into 16 bands.
+        Self {
+            samples,
+            sample_rate,
+            time: 0.0,
+            looping: false,
+            analyzer: Analyzer::new(sample_rate, 1024, 16),
+        }
+    }
+    pub fn get_sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+    pub fn duration(&self) -> f32 {
+        self.samples.len() as f32 / self.sample_rate as f32
+    }
+    pub fn get_time(&self) -> f32 {
+        self.time
+    }
+    pub fn set_time(&mut self, time: f32) {
+        //! Jumps to time seconds from the start.
+        self.time = time.max(0.0);
+    }
+    pub fn is_finished(&self) -> bool {
+        //! True once every sample has been played, unless it's looping.
+        !self.looping && self.time >= self.duration()
+    }
+}
+
+impl AudioSource for WavSource {
+    fn next_frame(&mut self, delta: f32) -> AudioFrame {
+        self.time += delta.max(0.0);
+        if self.looping && self.duration() > 0.0 {
+            self.time %= self.duration();
+        }
+        let end = ((self.time * self.sample_rate as f32) as usize).min(self.samples.len());
+        if self.is_finished() {
+            return self.analyzer.analyze(&[]);
+        }
+        self.analyzer.analyze(&self.samples[..end])
+    }
+}
+
+/// The error returned when audio can't be captured.
+#[cfg(feature = "audio-capture")]
+#[derive(Debug)]
+pub enum CaptureError {
+    NoDevice,
+    Config(cpal::DefaultStreamConfigError),
+    Build(cpal::BuildStreamError),
+    Play(cpal::PlayStreamError),
+}
+
+#[cfg(feature = "audio-capture")]
+impl std::fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CaptureError::NoDevice => write!(f, "no audio input device"),
+            CaptureError::Config(err) => write!(f, "could not configure audio input: {err}"),
+            CaptureError::Build(err) => write!(f, "could not open audio input: {err}"),
+            CaptureError::Play(err) => write!(f, "could not start audio input: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "audio-capture")]
+impl std::error::Error for CaptureError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CaptureError::NoDevice => None,
+            CaptureError::Config(err) => Some(err),
+            CaptureError::Build(err) => Some(err),
+            CaptureError::Play(err) => Some(err),
+        }
+    }
+}
+
+/// Listens to the default audio input device through [cpal](https://docs.rs/cpal), behind the `audio-capture` feature. Capturing stops when it's dropped.
+#[cfg(feature = "audio-capture")]
+pub struct AudioCapture {
+    _stream: cpal::Stream,
+    samples: Arc<std::sync::Mutex<std::collections::VecDeque<f32>>>,
+    sample_rate: u32,
+    pub analyzer: Analyzer,
+}
+
+#[cfg(feature = "audio-capture")]
+impl std::fmt::Debug for AudioCapture {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AudioCapture")
+            .field("sample_rate", &self.sample_rate)
+            .field("analyzer", &self.analyzer)
+            .finish()
+    }
+}
+
+#[cfg(feature = "audio-capture")]
+impl AudioCapture {
+    pub fn new() -> Result<Self, CaptureError> {
+        //! Starts capturing from the default input device, mixing every channel into one and analyzing 1024 samples at a time into 16 bands.
+        use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+        let device = cpal::default_host()
+            .default_input_device()
+            .ok_or(CaptureError::NoDevice)?;
+        let config = device
+            .default_input_config()
+            .map_err(CaptureError::Config)?;
+        let sample_rate = config.sample_rate().0;
+        let analyzer = Analyzer::new(sample_rate, 1024, 16);
+        let samples = Arc::new(std::sync::Mutex::new(std::collections::VecDeque::new()));
+        let stream = match config.sample_format() {
+            cpal::SampleFormat::I16 => {
+                Self::build_stream::<i16>(&device, &config.config(), samples.clone(), 1024)
+            }
+            cpal::SampleFormat::U16 => {
+                Self::build_stream::<u16>(&device, &config.config(), samples.clone(), 1024)
+            }
+            _ => Self::build_stream::<f32>(&device, &config.config(), samples.clone(), 1024),
+        }
+        .map_err(CaptureError::Build)?;
+        stream.play().map_err(CaptureError::Play)?;
+        Ok(Self {
+            _stream: stream,
+            samples,
+            sample_rate,
+            analyzer,
+        })
+    }
+    fn build_stream<T>(
+        device: &cpal::Device,
+        config: &cpal::StreamConfig,
+        samples: Arc<std::sync::Mutex<std::collections::VecDeque<f32>>>,
+        keep: usize,
+    ) -> Result<cpal::Stream, cpal::BuildStreamError>
+    where
+        T: cpal::SizedSample,
+        f32: cpal::FromSample<T>,
+    {
+        use cpal::traits::DeviceTrait;
+        let channels = (config.channels as usize).max(1);
+        device.build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                let mut samples = super::cache::lock(&samples);
+                for frame in data.chunks(channels) {
+                    let mono =
+                        frame.iter().map(|x| x.to_sample::<f32>()).sum::<f32>() / channels as f32;
+                    samples.push_back(mono);
+                }
+                //Keep enough for the biggest window an analyzer is likely to use
+                while samples.len() > keep * 8 {
+                    samples.pop_front();
+                }
+            },
+            |_| {},
+            None,
+        )
+    }
+    pub fn get_sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+}
+
+#[cfg(feature = "audio-capture")]
+impl AudioSource for AudioCapture {
+    fn next_frame(&mut self, _delta: f32) -> AudioFrame {
+        //The device keeps its own time, so the latest samples are always the ones analyzed
+        let samples: Vec<f32> = super::cache::lock(&self.samples).iter().copied().collect();
+        self.analyzer.analyze(&samples)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(frequency: f32, sample_rate: u32, seconds: f32) -> Vec<f32> {
+        (0..(seconds * sample_rate as f32) as usize)
+            .map(|i| (std::f32::consts::TAU * frequency * i as f32 / sample_rate as f32).sin())
+            .collect()
+    }
+
+    #[test]
+    fn bands() {
+        let mut analyzer = Analyzer::new(8000, 512, 8);
+        let (low, high) = analyzer.band_range(7);
+        assert!(low < high && (high - 4000.0).abs() < 1.0);
+
+        let frame = analyzer.analyze(&sine(1000.0, 8000, 1.0));
+        assert!((frame.amplitude - 0.707).abs() < 0.01);
+        assert!(frame.peak > 0.99);
+        let loudest = (0..8)
+            .max_by(|&a, &b| frame.bands[a].total_cmp(&frame.bands[b]))
+            .unwrap();
+        let (low, high) = analyzer.band_range(loudest);
+        assert!(low <= 1000.0 && 1000.0 <= high);
+        assert!((frame.bands[loudest] - 0.5).abs() < 0.1);
+
+        let silence = analyzer.analyze(&[]);
+        assert_eq!(silence.bands, vec![0.0; 8]);
+    }
+
+    #[test]
+    fn wav_playback() {
+        //A 16 bit stereo WAV with one second of a quiet sine
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 8000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut bytes = std::io::Cursor::new(vec![]);
+        let mut writer = hound::WavWriter::new(&mut bytes, spec).unwrap();
+        for sample in sine(440.0, 8000, 1.0) {
+            writer.write_sample((sample * 16384.0) as i16).unwrap();
+            writer.write_sample((sample * 16384.0) as i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let mut source = WavSource::from_reader(&bytes.get_ref()[..]).unwrap();
+        assert_eq!(source.get_sample_rate(), 8000);
+        assert!((source.duration() - 1.0).abs() < 1e-6);
+        let frame = source.next_frame(0.5);
+        assert!((frame.peak - 0.5).abs() < 0.01);
+        source.next_frame(0.6);
+        assert!(source.is_finished());
+        assert_eq!(source.next_frame(0.1).amplitude, 0.0);
+
+        source.looping = true;
+        source.set_time(0.9);
+        source.next_frame(0.2);
+        assert!((source.get_time() - 0.1).abs() < 1e-4);
+        assert!(!source.is_finished());
+    }
+}
@@ -0,0 +1,314 @@
+//! An interactive orbit viewer for OBJ and STL files. Built with the `cli` feature:
+//! `cargo run --features cli --bin ascii_view -- model.obj`
+//!
+//! Controls: drag with the mouse or use the arrow keys / WASD to orbit, scroll or +/- to zoom, m to switch between wireframe, filled, and points, space to toggle spinning, r to refit the camera, and q or Esc to quit. Filled faces are shaded by how directly they face the camera, so the model is lit from wherever it's viewed.
+
+use ascii_renderer::demos::{shade, RAMP};
+use ascii_renderer::geometry::Aabb;
+use ascii_renderer::prelude::*;
+use ascii_renderer::renderable::{RenderContext, Renderable};
+use ascii_renderer::runner::Backend;
+use ascii_renderer::terminal::CrosstermBackend;
+use crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, MouseButton,
+    MouseEventKind,
+};
+use crossterm::style::Print;
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{cursor, execute, QueueableCommand};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+const FRAME_TIME: Duration = Duration::from_millis(33);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Wireframe,
+    Filled,
+    Points,
+}
+
+/// The faces of each mesh, as triangles of vertex indices.
+type Faces = Vec<Vec<[usize; 3]>>;
+
+/// Orbits the camera around a target, always looking at it.
+struct Orbit {
+    target: Vector3,
+    yaw: Scalar,
+    pitch: Scalar,
+    distance: Scalar,
+    fit_distance: Scalar,
+}
+
+impl Orbit {
+    fn fit(bounds: &Aabb, fov: Vector2) -> Self {
+        //Far enough back that a sphere around the whole model fits in the narrower fov
+        let radius = (bounds.size().len() / 2.0).max(0.001);
+        let distance = radius / (fov.x.min(fov.y) / 2.0).sin() * 1.1;
+        Self {
+            target: bounds.center(),
+            yaw: 0.0,
+            pitch: 0.3,
+            distance,
+            fit_distance: distance,
+        }
+    }
+    fn apply(&self, camera: &mut Camera) {
+        //+y is down, so a positive pitch looks down on the model from above
+        let offset = vec3!(
+            self.yaw.sin() * self.pitch.cos(),
+            -self.pitch.sin(),
+            -self.yaw.cos() * self.pitch.cos()
+        );
+        camera.position = self.target + offset * self.distance;
+        camera.look_at(self.target);
+    }
+    fn rotate(&mut self, yaw: Scalar, pitch: Scalar) {
+        let limit = std::f64::consts::FRAC_PI_2 as Scalar - 0.01;
+        self.yaw += yaw;
+        self.pitch = (self.pitch + pitch).clamp(-limit, limit);
+    }
+    fn zoom(&mut self, factor: Scalar) {
+        self.distance =
+            (self.distance * factor).clamp(self.fit_distance * 0.05, self.fit_distance * 20.0);
+    }
+}
+
+fn load(path: &str) -> Result<(Vec<Mesh>, Faces), String> {
+    let (meshes, faces): (Vec<Mesh>, Faces) = if path.to_lowercase().ends_with(".stl") {
+        let mesh = Mesh::load_stl(path).map_err(|err| err.to_string())?;
+        let faces = vec![triangles(&mesh)];
+        (vec![mesh], faces)
+    } else {
+        let obj = AsciiObj::load(path).map_err(|err| format!("could not load obj file: {err}"))?;
+        let faces = obj.faces();
+        (obj.into(), faces)
+    };
+    if meshes.iter().all(|mesh| mesh.get_verticies().is_empty()) {
+        return Err(format!("{path} has no vertices"));
+    }
+    let meshes = meshes
+        .into_iter()
+        .map(|mut mesh| {
+            //Models are usually +y up, but +y is down on screen
+            mesh.rotation = vec3!(std::f64::consts::PI as Scalar, 0.0, 0.0);
+            mesh
+        })
+        .collect();
+    Ok((meshes, faces))
+}
+
+fn triangles(mesh: &Mesh) -> Vec<[usize; 3]> {
+    //STL files are nothing but triangles, so any three vertices joined to each other are a face
+    let mut neighbors: HashMap<usize, HashSet<usize>> = HashMap::new();
+    for &(a, b) in mesh.get_edges() {
+        neighbors.entry(a).or_default().insert(b);
+        neighbors.entry(b).or_default().insert(a);
+    }
+    let mut triangles = vec![];
+    for &(a, b) in mesh.get_edges() {
+        let (a, b) = (a.min(b), a.max(b));
+        //Each triangle is found once, from the edge between its two lowest vertices
+        for &c in neighbors[&a].intersection(&neighbors[&b]) {
+            if c > b {
+                triangles.push([a, b, c]);
+            }
+        }
+    }
+    triangles
+}
+
+/// Fills in the faces of the meshes, farthest first so nearer faces cover them.
+struct Filled<'a> {
+    meshes: &'a [Mesh],
+    faces: &'a Faces,
+}
+
+impl Renderable for Filled<'_> {
+    fn draw(&self, ctx: &mut RenderContext) {
+        let mut triangles: Vec<(Scalar, [Vector3; 3])> = vec![];
+        for (mesh, faces) in self.meshes.iter().zip(self.faces) {
+            let vertices = mesh.get_global_verticies();
+            for face in faces {
+                let [Some(&a), Some(&b), Some(&c)] = face.map(|i| vertices.get(&i)) else {
+                    continue;
+                };
+                let depth = (ctx.to_view(a).z + ctx.to_view(b).z + ctx.to_view(c).z) / 3.0;
+                triangles.push((depth, [a, b, c]));
+            }
+        }
+        triangles.sort_by(|a, b| b.0.total_cmp(&a.0));
+        for (_, corners) in triangles {
+            let [a, b, c] = corners.map(|x| ctx.to_view(x));
+            //Either side of a face can be seen, so only the angle to the camera matters
+            let facing = (b - a)
+                .cross(c - a)
+                .normalize()
+                .dot((a + b + c).normalize());
+            if facing.is_finite() {
+                ctx.polygon(&corners, shade(RAMP, 0.25 + 0.75 * facing.abs()));
+            }
+        }
+    }
+}
+
+fn draw_points(renderer: &Renderer, buf: &mut CharBuffer) {
+    let (width, height) = buf.dimensions;
+    for mesh in renderer.meshs.iter() {
+        for point in mesh.get_global_verticies().into_values() {
+            if renderer.camera.view_matrix().transform_point(point).z <= 0.0 {
+                continue;
+            }
+            let uv = renderer.camera.map_point_uv(point);
+            let (x, y) = (uv.x * width as Scalar, uv.y * height as Scalar);
+            if x >= 0.0 && y >= 0.0 {
+                let _ = buf.set_char(x as usize, y as usize, mesh.char);
+            }
+        }
+    }
+}
+
+fn buffer_size() -> (usize, usize) {
+    //Each char takes two columns, and the bottom row is kept for the status line
+    let (columns, rows) = terminal::size().unwrap_or((80, 24));
+    (
+        (columns as usize / 2).max(1),
+        (rows as usize).saturating_sub(1).max(1),
+    )
+}
+
+fn view(path: &str, meshes: Vec<Mesh>, faces: Faces) -> std::io::Result<()> {
+    let fov = vec2!(1.0, 1.0);
+    let mut renderer = Renderer::new(
+        meshes,
+        Camera {
+            position: vec3!(0.0, 0.0, 0.0),
+            rotation: vec3!(0.0, 0.0, 0.0),
+            fov,
+        },
+    );
+    let bounds = renderer
+        .meshs
+        .iter()
+        .filter_map(|mesh| mesh.bounding_box())
+        .reduce(|a, b| a.union(&b))
+        .unwrap();
+    let mut orbit = Orbit::fit(&bounds, fov);
+    let mut mode = Mode::Wireframe;
+    let mut spinning = false;
+    let mut drag_from: Option<(u16, u16)> = None;
+    let (width, height) = buffer_size();
+    let mut buf = CharBuffer::new(width, height);
+    let mut backend = CrosstermBackend::stdout();
+    let mut last_frame = Instant::now();
+    let mut redraw = true;
+
+    loop {
+        if event::poll(FRAME_TIME)? {
+            match event::read()? {
+                Event::Key(key) if key.kind != KeyEventKind::Release => match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Left | KeyCode::Char('a') => orbit.rotate(-0.1, 0.0),
+                    KeyCode::Right | KeyCode::Char('d') => orbit.rotate(0.1, 0.0),
+                    KeyCode::Up | KeyCode::Char('w') => orbit.rotate(0.0, 0.1),
+                    KeyCode::Down | KeyCode::Char('s') => orbit.rotate(0.0, -0.1),
+                    KeyCode::Char('+') | KeyCode::Char('=') => orbit.zoom(0.9),
+                    KeyCode::Char('-') => orbit.zoom(1.1),
+                    KeyCode::Char('m') => {
+                        mode = match mode {
+                            Mode::Wireframe => Mode::Filled,
+                            Mode::Filled => Mode::Points,
+                            Mode::Points => Mode::Wireframe,
+                        }
+                    }
+                    KeyCode::Char(' ') => spinning = !spinning,
+                    KeyCode::Char('r') => orbit = Orbit::fit(&bounds, fov),
+                    _ => {}
+                },
+                Event::Mouse(mouse) => match mouse.kind {
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        drag_from = Some((mouse.column, mouse.row))
+                    }
+                    MouseEventKind::Drag(MouseButton::Left) => {
+                        if let Some((column, row)) = drag_from {
+                            orbit.rotate(
+                                (mouse.column as Scalar - column as Scalar) * 0.03,
+                                (mouse.row as Scalar - row as Scalar) * 0.06,
+                            );
+                        }
+                        drag_from = Some((mouse.column, mouse.row));
+                    }
+                    MouseEventKind::Up(_) => drag_from = None,
+                    MouseEventKind::ScrollUp => orbit.zoom(0.9),
+                    MouseEventKind::ScrollDown => orbit.zoom(1.1),
+                    _ => {}
+                },
+                Event::Resize(..) => {
+                    let (width, height) = buffer_size();
+                    buf = CharBuffer::new(width, height);
+                    backend.present(&buf, true)?;
+                }
+                _ => {}
+            }
+            redraw = true;
+        }
+        if spinning {
+            orbit.rotate(last_frame.elapsed().as_secs_f32() as Scalar * 0.6, 0.0);
+            redraw = true;
+        }
+        last_frame = Instant::now();
+        if !redraw {
+            continue;
+        }
+        redraw = false;
+
+        orbit.apply(&mut renderer.camera);
+        buf.fill(' ');
+        match mode {
+            Mode::Wireframe => renderer.draw(&mut buf),
+            Mode::Filled => {
+                let filled = Filled {
+                    meshes: &renderer.meshs,
+                    faces: &faces,
+                };
+                renderer.draw_renderable(&filled, &mut buf);
+            }
+            Mode::Points => draw_points(&renderer, &mut buf),
+        }
+        backend.present(&buf, true)?;
+        let mut stdout = std::io::stdout();
+        stdout
+            .queue(cursor::MoveTo(0, buf.dimensions.1 as u16))?
+            .queue(terminal::Clear(terminal::ClearType::CurrentLine))?
+            .queue(Print(format!(
+                "{path} | {:?} | drag/arrows: orbit  scroll/+-: zoom  m: mode  space: spin  r: refit  q: quit",
+                mode
+            )))?;
+        stdout.flush()?;
+    }
+}
+
+fn main() {
+    let Some(path) = std::env::args().nth(1) else {
+        eprintln!("usage: ascii_view <file.obj | file.stl>");
+        std::process::exit(2);
+    };
+    let (meshes, faces) = match load(&path) {
+        Ok(model) => model,
+        Err(err) => {
+            eprintln!("ascii_view: {err}");
+            std::process::exit(1);
+        }
+    };
+
+    let setup = terminal::enable_raw_mode()
+        .and_then(|_| execute!(std::io::stdout(), EnterAlternateScreen, EnableMouseCapture));
+    let result = setup.and_then(|_| view(&path, meshes, faces));
+    let _ = execute!(std::io::stdout(), DisableMouseCapture, LeaveAlternateScreen);
+    let _ = terminal::disable_raw_mode();
+    if let Err(err) = result {
+        eprintln!("ascii_view: {err}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,120 @@
+use super::cache::lock;
+use super::char_buffer::CharBuffer;
+use super::rendering::Renderer;
+use std::time::{Duration, Instant};
+
+/// What happened during a Renderer::draw_with_budget call.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FrameReport {
+    /// The indices of the meshs that were drawn, in the order they were drawn.
+    pub drawn: Vec<usize>,
+    /// The indices of the meshs that were skipped because time ran out. They are drawn first next frame.
+    pub skipped: Vec<usize>,
+    pub elapsed: Duration,
+}
+
+impl FrameReport {
+    pub fn over_budget(&self) -> bool {
+        !self.skipped.is_empty()
+    }
+}
+
+impl Renderer {
+    pub fn draw_with_budget(&self, buffer: &mut CharBuffer, budget: Duration) -> FrameReport {
+        //! Like draw, but stops drawing once budget has passed, so a scene that is too heavy slows down how often each mesh is updated instead of the whole frame rate. Critical meshes (see Mesh::critical) are always drawn first. The rest are drawn in order, starting with the ones skipped last frame, and at least one of them is drawn every frame so everything is eventually updated. Since skipped meshes aren't erased either, clear the buffer with clear_dirty() or not at all when using this, rather than fill(' '). Frames drawn with record_depth, supersampling, or fixed_point set are drawn whole with draw, ignoring the budget.
+        //! # Example
+        //! ```
+        //! # use ascii_renderer::prelude::*;
+        //! # let renderer = Renderer::new(vec![ascii_renderer::create_cube()], Camera {
+        //! #     position: vec3!(0.0, 0.0, -10.0),
+        //! #     rotation: vec3!(0.0, 0.0, 0.0),
+        //! #     fov: vec2!(0.7, 0.7),
+        //! # });
+        //! # let mut buf = CharBuffer::new(30, 30);
+        //! let report = renderer.draw_with_budget(&mut buf, std::time::Duration::from_millis(10));
+        //! if report.over_budget() {
+        //!     println!("skipped {} meshes", report.skipped.len());
+        //! }
+        //! ```
+        let start = Instant::now();
+        if self.record_depth || self.supersampling.is_some() || self.fixed_point {
+            self.draw(buffer);
+            return FrameReport {
+                drawn: (0..self.meshs.len()).collect(),
+                skipped: Vec::new(),
+                elapsed: start.elapsed(),
+            };
+        }
+        let mut scratch = lock(&self.scratch.0);
+        let mut report = FrameReport::default();
+
+        for (i, mesh) in self.meshs.iter().enumerate() {
+            if mesh.critical {
+                self.draw_mesh_scratch(mesh, mesh.model_matrix(), buffer, &mut scratch);
+                report.drawn.push(i);
+            }
+        }
+
+        let count = self.meshs.len();
+        let resume_from = scratch.resume_from.min(count);
+        let mut drew_any = false;
+        for i in (resume_from..count).chain(0..resume_from) {
+            let mesh = &self.meshs[i];
+            if mesh.critical {
+                continue;
+            }
+            if drew_any && start.elapsed() >= budget {
+                report.skipped.push(i);
+                continue;
+            }
+            self.draw_mesh_scratch(mesh, mesh.model_matrix(), buffer, &mut scratch);
+            report.drawn.push(i);
+            drew_any = true;
+        }
+
+        scratch.resume_from = report.skipped.first().copied().unwrap_or(0);
+        report.elapsed = start.elapsed();
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn skipped_meshes_are_deferred() {
+        let mut meshs: Vec<Mesh> = (0..4).map(|_| crate::create_cube()).collect();
+        meshs[2].critical = true;
+        let renderer = Renderer::new(
+            meshs,
+            Camera {
+                position: vec3!(0.0, 0.0, -10.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(0.7, 0.7),
+            },
+        );
+        let mut buf = CharBuffer::new(30, 30);
+
+        let report = renderer.draw_with_budget(&mut buf, Duration::ZERO);
+        assert_eq!(report.drawn, vec![2, 0]);
+        assert_eq!(report.skipped, vec![1, 3]);
+        assert!(report.over_budget());
+
+        let report = renderer.draw_with_budget(&mut buf, Duration::ZERO);
+        assert_eq!(report.drawn, vec![2, 1]);
+        assert_eq!(report.skipped, vec![3, 0]);
+
+        let report = renderer.draw_with_budget(&mut buf, Duration::from_secs(60));
+        assert_eq!(report.drawn, vec![2, 3, 0, 1]);
+        assert!(!report.over_budget());
+
+        let mut renderer = renderer;
+        renderer.record_depth = true;
+        let report = renderer.draw_with_budget(&mut buf, Duration::ZERO);
+        assert_eq!(report.drawn, vec![0, 1, 2, 3]);
+        assert!(!report.over_budget());
+        assert!((0..30).any(|y| (0..30).any(|x| renderer.depth_at(x, y).is_some())));
+    }
+}
@@ -0,0 +1,334 @@
+//! A bounding volume hierarchy for quickly finding which of many boxes are in view, hit by a ray, or overlap another box. Build one over a scene's meshes with Renderer::build_bvh (or over a big mesh's edges with Mesh::build_edge_bvh), and rebuild it when things move.
+
+use super::char_buffer::CharBuffer;
+use super::geometry::{Aabb, Plane, Ray};
+use super::rendering::{Camera, Mesh, Renderer};
+use super::{Mat4, Scalar, Vector3};
+
+/// The most items kept in a single leaf of the tree.
+const MAX_LEAF_ITEMS: usize = 4;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Leaf { start: usize, count: usize },
+    Branch { left: usize, right: usize },
+}
+
+/// A tree of bounding boxes. Each item is an index (ex: into Renderer::meshs) with a box around it, and queries return the indices of the items whose boxes match.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Bvh {
+    nodes: Vec<(Aabb, Node)>,
+    items: Vec<(usize, Aabb)>,
+}
+
+/// The volume a camera can see, as planes facing inward. Anything on the back side of any plane is out of view.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frustum {
+    pub planes: Vec<Plane>,
+}
+
+impl Bvh {
+    pub fn build(items: impl IntoIterator<Item = (usize, Aabb)>) -> Self {
+        //! Builds the tree, splitting the items in half along the longest axis of their boxes at each level.
+        let mut ret = Self {
+            nodes: vec![],
+            items: items.into_iter().collect(),
+        };
+        if !ret.items.is_empty() {
+            ret.build_node(0, ret.items.len());
+        }
+        ret
+    }
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+    pub fn bounds(&self) -> Option<Aabb> {
+        //! The box around every item, or None if the tree is empty.
+        self.nodes.first().map(|x| x.0)
+    }
+    pub fn query_aabb(&self, aabb: &Aabb) -> Vec<usize> {
+        //! Every item whose box overlaps the given box.
+        self.visit(|node| node.intersects(aabb))
+            .map(|x| x.0)
+            .collect()
+    }
+    pub fn query_frustum(&self, frustum: &Frustum) -> Vec<usize> {
+        //! Every item whose box might be in view. Boxes near the corners of the frustum can be let through even though they are just out of view.
+        self.visit(|node| frustum.intersects_aabb(node))
+            .map(|x| x.0)
+            .collect()
+    }
+    pub fn query_ray(&self, ray: &Ray) -> Vec<(usize, Scalar)> {
+        //! Every item whose box the ray hits, along with how far along the ray (see Ray::at) it enters the box. Sorted from nearest to farthest.
+        let mut ret: Vec<(usize, Scalar)> = self
+            .visit(|node| ray.intersect_aabb(node).is_some())
+            .filter_map(|(index, aabb)| Some((index, ray.intersect_aabb(&aabb)?)))
+            .collect();
+        ret.sort_by(|a, b| a.1.total_cmp(&b.1));
+        ret
+    }
+
+    fn build_node(&mut self, start: usize, count: usize) -> usize {
+        let items = &mut self.items[start..start + count];
+        let bounds = items
+            .iter()
+            .skip(1)
+            .fold(items[0].1, |accum, x| accum.union(&x.1));
+        let index = self.nodes.len();
+        if count <= MAX_LEAF_ITEMS {
+            self.nodes.push((bounds, Node::Leaf { start, count }));
+            return index;
+        }
+        let size = bounds.size();
+        let axis = |x: Vector3| {
+            if size.x >= size.y && size.x >= size.z {
+                x.x
+            } else if size.y >= size.z {
+                x.y
+            } else {
+                x.z
+            }
+        };
+        items.sort_by(|a, b| axis(a.1.center()).total_cmp(&axis(b.1.center())));
+        //The children are filled in once they've been built
+        self.nodes
+            .push((bounds, Node::Branch { left: 0, right: 0 }));
+        let half = count / 2;
+        let left = self.build_node(start, half);
+        let right = self.build_node(start + half, count - half);
+        self.nodes[index].1 = Node::Branch { left, right };
+        index
+    }
+    fn visit(&self, test: impl Fn(&Aabb) -> bool) -> impl Iterator<Item = (usize, Aabb)> {
+        //! The items whose boxes pass the test, skipping every branch whose box fails it.
+        let mut found = vec![];
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let Some((aabb, node)) = self.nodes.get(index) else {
+                continue;
+            };
+            if !test(aabb) {
+                continue;
+            }
+            match *node {
+                Node::Leaf { start, count } => found.extend(
+                    self.items[start..start + count]
+                        .iter()
+                        .filter(|x| test(&x.1))
+                        .copied(),
+                ),
+                Node::Branch { left, right } => stack.extend([right, left]),
+            }
+        }
+        found.into_iter()
+    }
+}
+
+impl Frustum {
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        //! False if the box is definitely out of view.
+        self.planes.iter().all(|plane| {
+            //The corner furthest along the normal is the last to leave the plane
+            let corner = Vector3::new(
+                if plane.normal.x >= 0.0 {
+                    aabb.max.x
+                } else {
+                    aabb.min.x
+                },
+                if plane.normal.y >= 0.0 {
+                    aabb.max.y
+                } else {
+                    aabb.min.y
+                },
+                if plane.normal.z >= 0.0 {
+                    aabb.max.z
+                } else {
+                    aabb.min.z
+                },
+            );
+            plane.signed_distance(corner) >= 0.0
+        })
+    }
+    pub fn contains_point(&self, point: Vector3) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(point) >= 0.0)
+    }
+}
+
+impl Camera {
+    pub fn frustum(&self) -> Frustum {
+        //! The global space volume the camera can see. A FOV of PI or more on an axis doesn't limit the view on that axis, so only the plane through the camera is used for it.
+        let to_world = Mat4::rotation(self.rotation).transpose();
+        let mut normals = vec![Vector3::new(0.0, 0.0, 1.0)];
+        let half = self.fov / 2.0;
+        if half.x < std::f64::consts::FRAC_PI_2 as Scalar {
+            let (sin, cos) = half.x.sin_cos();
+            normals.push(Vector3::new(cos, 0.0, sin));
+            normals.push(Vector3::new(-cos, 0.0, sin));
+        }
+        if half.y < std::f64::consts::FRAC_PI_2 as Scalar {
+            let (sin, cos) = half.y.sin_cos();
+            normals.push(Vector3::new(0.0, cos, sin));
+            normals.push(Vector3::new(0.0, -cos, sin));
+        }
+        Frustum {
+            planes: normals
+                .into_iter()
+                .map(|normal| {
+                    Plane::from_point_normal(self.position, to_world.transform_vector(normal))
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Renderer {
+    pub fn build_bvh(&self) -> Bvh {
+        //! A Bvh over the global bounding boxes of the meshs, where each item is the mesh's index in meshs. Meshes without vertices are left out.
+        Bvh::build(
+            self.meshs
+                .iter()
+                .enumerate()
+                .filter_map(|(i, mesh)| Some((i, mesh.bounding_box()?))),
+        )
+    }
+    pub fn draw_culled(&self, bvh: &Bvh, buffer: &mut CharBuffer) {
+        //! Same as draw, but skips meshes that the bvh (from build_bvh) says are out of view. The bvh needs to be rebuilt when meshes move, but not when the camera does.
+        //! # Example
+        //! ```
+        //! # use ascii_renderer::prelude::*;
+        //! let mut renderer = Renderer::new(
+        //!     (0..100)
+        //!         .map(|i| {
+        //!             let mut cube = ascii_renderer::create_cube();
+        //!             cube.position = vec3!((i % 10) as Scalar * 4.0, 0.0, (i / 10) as Scalar * 4.0);
+        //!             cube
+        //!         })
+        //!         .collect(),
+        //!     Camera {
+        //!         position: vec3!(0.0, 0.0, -10.0),
+        //!         rotation: vec3!(0.0, 0.0, 0.0),
+        //!         fov: vec2!(0.7, 0.7),
+        //!     },
+        //! );
+        //! let bvh = renderer.build_bvh();
+        //! let mut buf = CharBuffer::new(30, 30);
+        //! renderer.draw_culled(&bvh, &mut buf);
+        //! ```
+        let mut visible = bvh.query_frustum(&self.camera.frustum());
+        visible.sort_unstable();
+        for index in visible {
+            if let Some(mesh) = self.meshs.get(index) {
+                self.draw_mesh(mesh, buffer);
+            }
+        }
+    }
+    pub fn pick(&self, bvh: &Bvh, ray: &Ray) -> Option<usize> {
+        //! The index of the nearest mesh whose bounding box is hit by the (global space) ray.
+        bvh.query_ray(ray).first().map(|x| x.0)
+    }
+}
+
+impl Mesh {
+    pub fn build_edge_bvh(&self) -> Bvh {
+        //! A Bvh over the mesh's edges in local space, where each item is the edge's index in get_edges(). Useful for picking parts of big meshes; transform rays into local space with `mesh.inverse_transform_point` first.
+        Bvh::build(self.get_edges().iter().enumerate().filter_map(|(i, edge)| {
+            let vertices = self.get_verticies();
+            Some((
+                i,
+                Aabb::from_points([*vertices.get(&edge.0)?, *vertices.get(&edge.1)?])?,
+            ))
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::noise::Rng;
+    use crate::{vec2, vec3, Vector2};
+
+    fn random_boxes() -> Vec<(usize, Aabb)> {
+        let mut rng = Rng::new(5);
+        (0..200)
+            .map(|i| {
+                let min = rng.vector3(50.0);
+                (
+                    i,
+                    Aabb::new(min, min + rng.vector3(3.0) + vec3!(3.0, 3.0, 3.0)),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn queries_match_brute_force() {
+        let boxes = random_boxes();
+        let bvh = Bvh::build(boxes.clone());
+        assert_eq!(bvh.len(), 200);
+
+        let query = Aabb::new(vec3!(-10.0, -10.0, -10.0), vec3!(15.0, 5.0, 20.0));
+        let mut found = bvh.query_aabb(&query);
+        found.sort_unstable();
+        let expected: Vec<usize> = boxes
+            .iter()
+            .filter(|x| x.1.intersects(&query))
+            .map(|x| x.0)
+            .collect();
+        assert_eq!(found, expected);
+
+        let ray = Ray::new(vec3!(-60.0, 1.0, 2.0), vec3!(1.0, 0.05, 0.0));
+        let hits = bvh.query_ray(&ray);
+        let mut found: Vec<usize> = hits.iter().map(|x| x.0).collect();
+        found.sort_unstable();
+        let expected: Vec<usize> = boxes
+            .iter()
+            .filter(|x| ray.intersect_aabb(&x.1).is_some())
+            .map(|x| x.0)
+            .collect();
+        assert_eq!(found, expected);
+        assert!(hits.windows(2).all(|x| x[0].1 <= x[1].1));
+    }
+
+    #[test]
+    fn frustum_culling() {
+        let camera = Camera {
+            position: vec3!(0.0, 0.0, 0.0),
+            rotation: vec3!(0.0, 0.0, 0.0),
+            fov: vec2!(1.0, 1.0),
+        };
+        let frustum = camera.frustum();
+        let unit = |center: Vector3| {
+            Aabb::new(center - vec3!(0.5, 0.5, 0.5), center + vec3!(0.5, 0.5, 0.5))
+        };
+        assert!(frustum.intersects_aabb(&unit(vec3!(0.0, 0.0, 10.0))));
+        assert!(!frustum.intersects_aabb(&unit(vec3!(0.0, 0.0, -10.0))));
+        assert!(!frustum.intersects_aabb(&unit(vec3!(-20.0, 0.0, 10.0))));
+        assert!(!frustum.intersects_aabb(&unit(vec3!(0.0, 20.0, 10.0))));
+
+        //Everything the frustum lets through projects onto the screen
+        let mut rng = Rng::new(1);
+        for _ in 0..500 {
+            let point = rng.vector3(20.0);
+            if frustum.contains_point(point) {
+                let uv = camera.map_point_uv(point);
+                assert!((-0.001..=1.001).contains(&uv.x) && (-0.001..=1.001).contains(&uv.y));
+            }
+        }
+
+        let turned = Camera {
+            rotation: Camera::look_rotation(vec3!(-1.0, 0.0, 0.0)),
+            ..camera
+        };
+        assert!(turned
+            .frustum()
+            .intersects_aabb(&unit(vec3!(-10.0, 0.0, 0.0))));
+        assert!(!turned
+            .frustum()
+            .intersects_aabb(&unit(vec3!(0.0, 0.0, 10.0))));
+    }
+}
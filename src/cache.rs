@@ -0,0 +1,187 @@
+use super::char_buffer::CharBuffer;
+use super::depth::DepthBuffer;
+use super::line::Line;
+use super::matrix::Mat4;
+use super::HashMap;
+use super::{Vector2, Vector3};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::sync::{Mutex, MutexGuard};
+//Without std there's no lock to park threads on, so it spins
+#[cfg(not(feature = "std"))]
+use spin::{Mutex, MutexGuard};
+
+/// Everything that affects where a mesh's vertices end up in a buffer, besides the vertices themselves.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ProjectionKey {
+    pub model_view: Mat4,
+    pub fov: Vector2,
+    pub dimensions: (usize, usize),
+}
+
+/// A mesh's transformed vertices from the last time they were needed, so meshes that don't move (and a camera that doesn't either) skip the math the next frame. Each cache stores what it was computed from and is thrown away when that changes. Changes to the vertices themselves clear it, which Mesh does in all of its methods that can change them.
+#[derive(Default)]
+pub(crate) struct VertexCache {
+    world: Mutex<Option<(Mat4, HashMap<usize, Vector3>)>>,
+    projected: Mutex<Option<(ProjectionKey, HashMap<usize, Vector2>)>>,
+}
+
+impl VertexCache {
+    pub fn clear(&mut self) {
+        *lock_mut(&mut self.world) = None;
+        *lock_mut(&mut self.projected) = None;
+    }
+    pub fn world(
+        &self,
+        model: Mat4,
+        compute: impl FnOnce() -> HashMap<usize, Vector3>,
+    ) -> HashMap<usize, Vector3> {
+        //! The global space vertices for the model matrix, only calling compute if they aren't cached.
+        let mut world = lock(&self.world);
+        match world.as_ref() {
+            Some((cached_model, vertices)) if *cached_model == model => vertices.clone(),
+            _ => {
+                let vertices = compute();
+                *world = Some((model, vertices.clone()));
+                vertices
+            }
+        }
+    }
+    pub fn with_projected<R>(
+        &self,
+        key: ProjectionKey,
+        spare: &mut HashMap<usize, Vector2>,
+        compute: impl FnOnce(&mut HashMap<usize, Vector2>),
+        f: impl FnOnce(&HashMap<usize, Vector2>) -> R,
+    ) -> R {
+        //! Calls f with the buffer space vertices for the key. If they aren't cached, compute fills the (emptied) spare map with them, which then becomes the cache, and the old cache's allocation is handed back as the new spare.
+        let mut projected = lock(&self.projected);
+        match projected.as_mut() {
+            Some((cached_key, points)) if *cached_key == key => f(points),
+            _ => {
+                spare.clear();
+                compute(spare);
+                let ret = f(spare);
+                let points = core::mem::take(spare);
+                if let Some((_, old)) = projected.replace((key, points)) {
+                    *spare = old;
+                }
+                ret
+            }
+        }
+    }
+    #[cfg(test)]
+    pub fn is_projected(&self) -> bool {
+        lock(&self.projected).is_some()
+    }
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    //A panic while the lock was held can't leave a half written cache, so poisoning is ignored
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock()
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn lock_mut<T>(mutex: &mut Mutex<T>) -> &mut T {
+    //! Same as lock, for when nothing else can be holding it.
+    mutex.get_mut().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn lock_mut<T>(mutex: &mut Mutex<T>) -> &mut T {
+    mutex.get_mut()
+}
+
+impl Clone for VertexCache {
+    fn clone(&self) -> Self {
+        //Clones are usually made to be changed, so they start out empty
+        Self::default()
+    }
+}
+
+impl core::fmt::Debug for VertexCache {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("VertexCache").finish_non_exhaustive()
+    }
+}
+
+/// Buffers the renderer fills every frame, kept around so their allocations are reused instead of being made again each frame.
+#[derive(Default)]
+pub(crate) struct Scratch {
+    pub lines: Vec<Line>,
+    pub points: HashMap<usize, Vector2>,
+    /// The first mesh a budgeted draw skipped, where the next one picks up from.
+    #[cfg(feature = "std")]
+    pub resume_from: usize,
+    /// The depth of the last frame drawn with Renderer::record_depth set.
+    pub depth: Option<DepthBuffer>,
+    /// The buffer the last supersampled frame was drawn into.
+    pub supersampled: Option<CharBuffer>,
+    /// The depth of the last supersampled frame drawn with Renderer::record_depth set, at the higher resolution.
+    pub supersampled_depth: Option<DepthBuffer>,
+}
+
+/// Lets a Renderer reuse its scratch buffers from `&self` methods.
+#[derive(Default)]
+pub(crate) struct ScratchBuffers(pub Mutex<Scratch>);
+
+impl Clone for ScratchBuffers {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl core::fmt::Debug for ScratchBuffers {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ScratchBuffers").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use alloc::vec;
+
+    #[test]
+    fn cache_follows_changes() {
+        let mut renderer = Renderer::new(
+            vec![crate::create_cube()],
+            Camera {
+                position: vec3!(0.0, 0.0, -10.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(0.7, 0.7),
+            },
+        );
+        let draw = |renderer: &Renderer| {
+            let mut buf = CharBuffer::new(30, 30);
+            renderer.draw(&mut buf);
+            buf
+        };
+        let first = draw(&renderer);
+        assert!(renderer.meshs[0].cache.is_projected());
+        assert_eq!(draw(&renderer), first);
+
+        renderer.meshs[0].rotation.y = 0.5;
+        let rotated = draw(&renderer);
+        assert_ne!(rotated, first);
+
+        renderer.meshs[0].get_verticies_mut().clear();
+        renderer.meshs[0].get_edges_mut().clear();
+        assert!(!renderer.meshs[0].cache.is_projected());
+        assert_eq!(draw(&renderer), CharBuffer::new(30, 30));
+
+        let mut cube = crate::create_cube();
+        let before = cube.get_global_verticies();
+        cube.position = vec3!(1.0, 0.0, 0.0);
+        assert_eq!(
+            cube.get_global_verticies()[&0],
+            before[&0] + vec3!(1.0, 0.0, 0.0)
+        );
+    }
+}
@@ -0,0 +1,233 @@
+//! Working out what the terminal can do, so one program looks its best everywhere from a modern terminal emulator down to a dumb serial console. Capabilities::detect guesses the color depth, Unicode support, size, and graphics protocol from the environment, and picks the best OutputMode from them, which says which backend and Charset to use.
+//!
+//! Users can pick the mode themselves by setting the ASCII_RENDERER_MODE environment variable to "kitty", "sixel", "unicode", or "ascii", ex: when detection guesses wrong.
+//! # Example
+//! ```no_run
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::capabilities::Capabilities;
+//! # struct MyLogic;
+//! # impl Logic for MyLogic {
+//! #     fn process(&mut self, _: &mut CharBuffer, _: f32) -> ProcessReturn { ProcessReturn::End }
+//! # }
+//! let capabilities = Capabilities::detect();
+//! let mode = capabilities.output_mode();
+//! let (width, height) = capabilities.buffer_size((40, 20));
+//! let mut runner = Runner::with_backend(width, height, 30, MyLogic, mode.backend());
+//! //Keeping every frame to what the terminal can show
+//! runner.effects.push(Box::new(mode.charset()));
+//! runner.run(true);
+//! ```
+
+use super::charset::Charset;
+use super::graphics::{GraphicsBackend, GraphicsProtocol};
+use super::palette::Palette;
+
+/// The environment variable users can set to pick the OutputMode, see OutputMode::parse.
+pub const MODE_VARIABLE: &str = "ASCII_RENDERER_MODE";
+
+/// How many colors a terminal can show, from fewest to most.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorDepth {
+    /// No colors, ex: dumb terminals or when NO_COLOR is set.
+    Monochrome,
+    /// The 16 standard ANSI colors.
+    Ansi16,
+    /// The 256 color xterm palette.
+    Ansi256,
+    /// Any RGB color.
+    TrueColor,
+}
+
+/// How frames are shown, from the most capable terminals to the least.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OutputMode {
+    /// As images, see graphics::GraphicsBackend.
+    Graphics(GraphicsProtocol),
+    /// As chars, including Unicode ones.
+    Unicode,
+    /// As 7-bit ASCII chars only.
+    Ascii,
+}
+
+impl OutputMode {
+    pub fn parse(name: &str) -> Option<Self> {
+        //! A mode from its name ("kitty", "sixel", "unicode", or "ascii"), ignoring case and surrounding whitespace.
+        match name.trim().to_lowercase().as_str() {
+            "kitty" => Some(Self::Graphics(GraphicsProtocol::Kitty)),
+            "sixel" => Some(Self::Graphics(GraphicsProtocol::Sixel)),
+            "unicode" => Some(Self::Unicode),
+            "ascii" => Some(Self::Ascii),
+            _ => None,
+        }
+    }
+    pub fn get_protocol(self) -> Option<GraphicsProtocol> {
+        match self {
+            Self::Graphics(protocol) => Some(protocol),
+            _ => None,
+        }
+    }
+    pub fn charset(self) -> Charset {
+        //! The chars frames can use in this mode. Images can show any char, since only whether a cell is empty matters.
+        match self {
+            Self::Graphics(_) | Self::Unicode => Charset::Unicode,
+            Self::Ascii => Charset::Ascii,
+        }
+    }
+    pub fn backend(self) -> GraphicsBackend {
+        //! A backend showing frames in this mode, which prints chars unless the mode is Graphics.
+        GraphicsBackend::new(self.get_protocol())
+    }
+}
+
+/// What a terminal can do, see the module docs. Every field is public, so detection can be corrected before picking a mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Capabilities {
+    pub color: ColorDepth,
+    /// Whether chars outside of ASCII are shown properly.
+    pub unicode: bool,
+    /// The terminal's size in columns and rows, if it's known.
+    pub size: Option<(usize, usize)>,
+    /// The way of showing images the terminal supports, if any.
+    pub graphics: Option<GraphicsProtocol>,
+    /// The mode the user picked, which output_mode uses over everything else.
+    pub mode_override: Option<OutputMode>,
+}
+
+impl Capabilities {
+    pub fn detect() -> Self {
+        //! Guesses the terminal's capabilities from its environment variables. With the `crossterm` feature, the size is asked from the terminal if the environment doesn't say.
+        #[allow(unused_mut)]
+        let mut capabilities = Self::detect_from(|name| std::env::var(name).ok());
+        #[cfg(feature = "crossterm")]
+        if capabilities.size.is_none() {
+            capabilities.size = crossterm::terminal::size()
+                .ok()
+                .map(|(columns, rows)| (columns as usize, rows as usize));
+        }
+        capabilities
+    }
+    fn detect_from(var: impl Fn(&str) -> Option<String>) -> Self {
+        let term = var("TERM").unwrap_or_default();
+        let dumb = term.is_empty() || term == "dumb";
+        let colorterm = var("COLORTERM").unwrap_or_default();
+        let color = if dumb || var("NO_COLOR").is_some_and(|x| !x.is_empty()) {
+            ColorDepth::Monochrome
+        } else if colorterm == "truecolor" || colorterm == "24bit" {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        };
+        //The first locale variable that's set decides, like it does for programs reading it
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .find_map(|x| var(x).filter(|x| !x.is_empty()))
+            .unwrap_or_default()
+            .to_lowercase();
+        let unicode = !dumb && (locale.contains("utf-8") || locale.contains("utf8"));
+        let number = |name: &str| var(name).and_then(|x| x.trim().parse::<usize>().ok());
+        Self {
+            color,
+            unicode,
+            size: number("COLUMNS").zip(number("LINES")),
+            graphics: if dumb {
+                None
+            } else {
+                GraphicsProtocol::detect_from(&var)
+            },
+            mode_override: var(MODE_VARIABLE).and_then(|x| OutputMode::parse(&x)),
+        }
+    }
+    pub fn dumb() -> Self {
+        //! The least a terminal can do: no colors, ASCII only, and an unknown size.
+        Self {
+            color: ColorDepth::Monochrome,
+            unicode: false,
+            size: None,
+            graphics: None,
+            mode_override: None,
+        }
+    }
+    pub fn output_mode(&self) -> OutputMode {
+        //! The mode the user picked, or else the best one the terminal supports.
+        if let Some(mode) = self.mode_override {
+            return mode;
+        }
+        match self.graphics {
+            Some(protocol) => OutputMode::Graphics(protocol),
+            None if self.unicode => OutputMode::Unicode,
+            None => OutputMode::Ascii,
+        }
+    }
+    pub fn palette(&self) -> Palette {
+        //! Palette::monochrome if the terminal has no colors, otherwise Palette::dark.
+        if self.color == ColorDepth::Monochrome {
+            Palette::monochrome()
+        } else {
+            Palette::dark()
+        }
+    }
+    pub fn buffer_size(&self, default: (usize, usize)) -> (usize, usize) {
+        //! The size of a CharBuffer filling the terminal, with every char taking two columns, or default if the size isn't known.
+        match self.size {
+            Some((columns, rows)) => (columns / 2, rows),
+            None => default,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detecting_capabilities() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|x| x.0 == name).map(|x| x.1.to_string())
+        };
+        let modern = Capabilities::detect_from(env(&[
+            ("TERM", "xterm-256color"),
+            ("COLORTERM", "truecolor"),
+            ("LANG", "en_US.UTF-8"),
+            ("COLUMNS", "120"),
+            ("LINES", "40"),
+        ]));
+        assert_eq!(modern.color, ColorDepth::TrueColor);
+        assert_eq!(modern.output_mode(), OutputMode::Unicode);
+        assert_eq!(modern.buffer_size((10, 10)), (60, 40));
+        assert_eq!(modern.palette(), Palette::dark());
+
+        let kitty = Capabilities::detect_from(env(&[("TERM", "xterm-kitty")]));
+        assert_eq!(kitty.color, ColorDepth::Ansi16);
+        assert_eq!(
+            kitty.output_mode(),
+            OutputMode::Graphics(GraphicsProtocol::Kitty)
+        );
+
+        //A serial console gets nothing, even with a UTF-8 locale
+        let console = Capabilities::detect_from(env(&[("TERM", "dumb"), ("LC_ALL", "C.UTF-8")]));
+        assert_eq!(console, Capabilities::dumb());
+        assert_eq!(console.output_mode(), OutputMode::Ascii);
+        assert_eq!(console.output_mode().charset(), Charset::Ascii);
+        assert_eq!(console.palette(), Palette::monochrome());
+        //The locale variables are checked in order, and NO_COLOR turns colors off
+        let c = Capabilities::detect_from(env(&[
+            ("TERM", "xterm"),
+            ("LC_ALL", "C"),
+            ("LANG", "en_US.UTF-8"),
+            ("NO_COLOR", "1"),
+        ]));
+        assert!(!c.unicode && c.color == ColorDepth::Monochrome);
+
+        //The user's choice wins
+        let overridden =
+            Capabilities::detect_from(env(&[("TERM", "xterm-kitty"), (MODE_VARIABLE, " ASCII ")]));
+        assert_eq!(overridden.output_mode(), OutputMode::Ascii);
+        assert_eq!(
+            OutputMode::parse("sixel").unwrap().get_protocol(),
+            Some(GraphicsProtocol::Sixel)
+        );
+        assert_eq!(OutputMode::parse("teletype"), None);
+    }
+}
@@ -1,16 +1,58 @@
+use super::stencil::Stencil;
+use alloc::{string::String, vec, vec::Vec};
+
 ///The buffer used by the runner and mutated by the logic struct. Just a wrapper around a 2D char vector.
 /// # Example
 /// ```
+/// # use ascii_renderer::prelude::*;
 /// let mut buf = CharBuffer::new(3, 3);
 /// buf.set_char(0, 0, 'n').unwrap();
 /// buf.set_char(2, 1, 'x').unwrap();
 /// buf.set_char(2, 2, 'z').unwrap();
 /// assert_eq!(&buf.to_string(), "n     \n    x \n    z \n");
 ///```
-#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[derive(Debug, Clone)]
 pub struct CharBuffer {
     pub data: Vec<Vec<char>>,
     pub dimensions: (usize, usize),
+    /// The range of x values written to in each row since the last clear, see clear_dirty.
+    dirty: Vec<Option<(usize, usize)>>,
+    /// Where writes are allowed, see stencil::Stencil.
+    pub(crate) stencil: Option<Stencil>,
+}
+
+/// A rectangle of cells in a buffer, from its top left corner. Effects and widgets that draw into part of a buffer take one of these.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Region {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Region {
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x >= self.x && y >= self.y && x - self.x < self.width && y - self.y < self.height
+    }
+    pub fn clipped(&self, dimensions: (usize, usize)) -> Region {
+        //! The part of the region inside a buffer with the given dimensions.
+        let x = self.x.min(dimensions.0);
+        let y = self.y.min(dimensions.1);
+        Region::new(
+            x,
+            y,
+            self.width.min(dimensions.0 - x),
+            self.height.min(dimensions.1 - y),
+        )
+    }
 }
 
 #[derive(Debug)]
@@ -24,12 +66,19 @@ impl CharBuffer {
         CharBuffer {
             data: vec![vec![' '; width]; height],
             dimensions: (width, height),
+            dirty: vec![None; height],
+            stencil: None,
         }
     }
+    pub fn region(&self) -> Region {
+        //! The region covering the whole buffer.
+        Region::new(0, 0, self.dimensions.0, self.dimensions.1)
+    }
     pub fn get_char(&self, x: usize, y: usize) -> Option<char> {
-        self.data.get(y)?.get(x).map(|x| *x)
+        self.data.get(y)?.get(x).copied()
     }
     pub fn set_char(&mut self, x: usize, y: usize, value: char) -> Result<(), CharBufferError> {
+        //! Writes to cells the stencil masks out (see set_stencil) are skipped without an error, so everything drawn through set_char stays inside it.
         let row = self.data.get_mut(y);
         if row.is_none() {
             return Err(CharBufferError::RowOutOfRange { row: y });
@@ -39,41 +88,181 @@ impl CharBuffer {
         if item.is_none() {
             return Err(CharBufferError::ItemOutOfRange { index: x });
         }
+        if self.stencil.as_ref().is_some_and(|stencil| !stencil.get(x, y)) {
+            return Ok(());
+        }
 
         *item.unwrap() = value;
+        self.mark_dirty(x, y);
 
         Ok(())
     }
+    pub fn mark_dirty(&mut self, x: usize, y: usize) {
+        //! Makes the next clear_dirty() clear the cell. set_char does this automatically, so this is only needed after writing to data directly.
+        if let Some(span) = self.dirty.get_mut(y) {
+            *span = Some(match *span {
+                Some((start, end)) => (start.min(x), end.max(x)),
+                None => (x, x),
+            });
+        }
+    }
+    pub fn clear_dirty(&mut self) {
+        //! Clears (sets to ' ') only the cells written to since the last clear, instead of the whole buffer like fill(' ') does. Much faster for sparse wireframes on big buffers. Cells are tracked as one span per row, so a few cells between written ones may be cleared too. The stencil doesn't apply, so everything written is cleared.
+        //! # Example
+        //! ```
+        //! # use ascii_renderer::prelude::*;
+        //! let mut buf = CharBuffer::new(100, 100);
+        //! buf.set_char(3, 4, '#').unwrap();
+        //! buf.clear_dirty();
+        //! assert_eq!(buf, CharBuffer::new(100, 100));
+        //! ```
+        for (row, span) in self.data.iter_mut().zip(self.dirty.iter_mut()) {
+            if let Some((start, end)) = span.take() {
+                let end = end.min(row.len().saturating_sub(1));
+                if let Some(cells) = row.get_mut(start..=end) {
+                    cells.fill(' ');
+                }
+            }
+        }
+    }
+    pub fn write_str(&mut self, x: usize, y: usize, text: &str) -> usize {
+        //! Writes text into a row one char per cell, starting at (x, y). Anything past the edge of the buffer is cut off, and the number of chars actually written is returned.
+        //! # Example
+        //! ```
+        //! # use ascii_renderer::prelude::*;
+        //! let mut buf = CharBuffer::new(4, 1);
+        //! assert_eq!(buf.write_str(1, 0, "hello"), 3);
+        //! assert_eq!(&buf.to_string(), "  h e l \n");
+        //! ```
+        let mut written = 0;
+        for (i, char) in text.chars().enumerate() {
+            if self.set_char(x + i, y, char).is_err() {
+                break;
+            }
+            written += 1;
+        }
+        written
+    }
+    pub fn from_text(text: &str) -> Self {
+        //! A buffer holding text, one line per row. It's as wide as the longest line, and shorter lines are padded with ' '.
+        //! # Example
+        //! ```
+        //! # use ascii_renderer::prelude::*;
+        //! let buf = CharBuffer::from_text("/\\\n\\/");
+        //! assert_eq!(buf.dimensions, (2, 2));
+        //! assert_eq!(buf.get_char(1, 1), Some('/'));
+        //! ```
+        let width = text.lines().map(|x| x.chars().count()).max().unwrap_or(0);
+        let mut buf = CharBuffer::new(width, text.lines().count());
+        for (y, line) in text.lines().enumerate() {
+            buf.write_str(0, y, line);
+        }
+        buf
+    }
+    pub fn blit(&mut self, source: &CharBuffer, x: isize, y: isize, transparent: Option<char>) {
+        //! Copies source into this buffer with its top left corner at (x, y), which can be off the edge. Cells of source that are the transparent char are skipped, so what's under them shows through.
+        for (source_y, row) in source.data.iter().enumerate() {
+            let Ok(target_y) = usize::try_from(y + source_y as isize) else {
+                continue;
+            };
+            for (source_x, &char) in row.iter().enumerate() {
+                if Some(char) == transparent {
+                    continue;
+                }
+                if let Ok(target_x) = usize::try_from(x + source_x as isize) {
+                    let _ = self.set_char(target_x, target_y, char);
+                }
+            }
+        }
+    }
     pub fn fill(&mut self, char: char) {
-        //! Filles the char buffer with the given char
+        //! Filles the char buffer with the given char, or only the cells the stencil allows if there is one.
+        if let Some(stencil) = self.stencil.take() {
+            for (x, y) in stencil.cells() {
+                let _ = self.set_char(x, y, char);
+            }
+            self.stencil = Some(stencil);
+            return;
+        }
         for row in self.data.iter_mut() {
             for item in row.iter_mut() {
                 *item = char;
             }
         }
+        let span = (char != ' ').then_some((0, self.dimensions.0.saturating_sub(1)));
+        self.dirty = vec![span; self.data.len()];
+    }
+    pub fn hash(&self) -> u64 {
+        //! A 64-bit FNV-1a hash of the dimensions and every char, ex: to check a frame hasn't changed without keeping a copy of it. Unlike hashing with std's DefaultHasher, it's the same on every platform and Rust version, so it can be saved and compared later.
+        //! # Example
+        //! ```
+        //! # use ascii_renderer::prelude::*;
+        //! let buf = CharBuffer::from_text("ab");
+        //! assert_eq!(buf.hash(), CharBuffer::from_text("ab").hash());
+        //! assert_ne!(buf.hash(), CharBuffer::from_text("ba").hash());
+        //! ```
+        let mut hash: u64 = 0xcbf29ce484222325;
+        let mut write = |bytes: &[u8]| {
+            for byte in bytes {
+                hash = (hash ^ *byte as u64).wrapping_mul(0x100000001b3);
+            }
+        };
+        write(&(self.dimensions.0 as u64).to_le_bytes());
+        write(&(self.dimensions.1 as u64).to_le_bytes());
+        for &char in self.data.iter().flatten() {
+            write(&(char as u32).to_le_bytes());
+        }
+        hash
+    }
+    pub fn write_to_string(&self, out: &mut String) {
+        //! Writes the buffer into out the same way Display does, replacing whatever was in it. Reusing the same String every frame avoids the allocations to_string() makes.
+        out.clear();
+        out.reserve(self.data.iter().map(|x| x.len() * 2 + 1).sum());
+        for row in self.data.iter() {
+            for &item in row.iter() {
+                out.push(item);
+                out.push(' ');
+            }
+            out.push('\n');
+        }
+    }
+    #[cfg(feature = "std")]
+    pub fn write_all_to(
+        &self,
+        writer: &mut impl std::io::Write,
+        scratch: &mut String,
+    ) -> std::io::Result<()> {
+        //! Writes the buffer to writer (ex: stdout) in a single write_all, using scratch as the reusable string it is built in.
+        self.write_to_string(scratch);
+        writer.write_all(scratch.as_bytes())
     }
 }
 
-impl std::fmt::Display for CharBuffer {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            self.data
-                .iter()
-                .map(|x| [
-                    x.iter().map(|x| [x, &' ']).flatten().collect::<String>(),
-                    "\n".to_owned()
-                ])
-                .flatten()
-                .collect::<String>()
-        )
+/// Buffers are compared by their chars, ignoring which cells are dirty and the stencil.
+impl PartialEq for CharBuffer {
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data && self.dimensions == other.dimensions
+    }
+}
+
+impl PartialOrd for CharBuffer {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        (&self.data, self.dimensions).partial_cmp(&(&other.data, other.dimensions))
+    }
+}
+
+impl core::fmt::Display for CharBuffer {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let mut out = String::new();
+        self.write_to_string(&mut out);
+        f.write_str(&out)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::string::ToString;
 
     #[test]
     fn char_retreival() {
@@ -104,4 +293,89 @@ mod tests {
         buf.set_char(2, 2, 'z').unwrap();
         assert_eq!(&buf.to_string(), "n     \n    x \n    z \n");
     }
+
+    #[test]
+    fn stable_hash() {
+        //Saved hashes have to keep matching
+        assert_eq!(CharBuffer::from_text("ab").hash(), 0x7dd08d7dbcbc22c5);
+        //Same chars, different shape
+        assert_ne!(
+            CharBuffer::from_text("ab\ncd").hash(),
+            CharBuffer::from_text("abcd").hash()
+        );
+    }
+
+    #[test]
+    fn reused_string_output() {
+        let mut buf = CharBuffer::new(4, 2);
+        buf.set_char(1, 1, '#').unwrap();
+        let mut out = String::from("leftover");
+        buf.write_to_string(&mut out);
+        assert_eq!(out, buf.to_string());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn reused_byte_output() {
+        let mut buf = CharBuffer::new(4, 2);
+        let mut out = String::from("leftover");
+        buf.write_to_string(&mut out);
+        let capacity = out.capacity();
+        buf.fill('.');
+        let mut bytes = vec![];
+        buf.write_all_to(&mut bytes, &mut out).unwrap();
+        assert_eq!(out.capacity(), capacity);
+        assert_eq!(bytes, b". . . . \n. . . . \n");
+    }
+
+    #[test]
+    fn dirty_clearing() {
+        let mut buf = CharBuffer::new(10, 4);
+        buf.data[0][0] = '@'; //Written directly, so it isn't tracked
+        buf.set_char(2, 1, 'a').unwrap();
+        buf.set_char(7, 1, 'b').unwrap();
+        buf.set_char(5, 3, 'c').unwrap();
+        buf.clear_dirty();
+        let mut expected = CharBuffer::new(10, 4);
+        expected.data[0][0] = '@';
+        assert_eq!(buf, expected);
+
+        buf.fill('.');
+        buf.clear_dirty();
+        assert_eq!(buf, CharBuffer::new(10, 4));
+    }
+
+    #[test]
+    fn blitting() {
+        let sprite = CharBuffer::from_text("ab\n c");
+        let mut buf = CharBuffer::new(3, 3);
+        buf.fill('.');
+        buf.blit(&sprite, -1, 2, Some(' '));
+        //Without transparency the space is copied too
+        buf.blit(&sprite, 2, 0, None);
+        assert_eq!(&buf.to_string(), ". . a \n. .   \nb . . \n");
+        buf.blit(&sprite, 0, 0, Some(' '));
+        assert_eq!(&buf.to_string(), "a b a \n. c   \nb . . \n");
+    }
+
+    #[test]
+    fn regions() {
+        let region = Region::new(2, 1, 4, 3);
+        assert!(region.contains(2, 1) && region.contains(5, 3));
+        assert!(!region.contains(6, 1) && !region.contains(1, 2));
+        assert_eq!(region.clipped((4, 10)), Region::new(2, 1, 2, 3));
+        assert_eq!(region.clipped((1, 1)).width, 0);
+        assert_eq!(CharBuffer::new(3, 2).region(), Region::new(0, 0, 3, 2));
+    }
+
+    #[test]
+    fn text_is_clipped() {
+        let mut buf = CharBuffer::new(5, 2);
+        assert_eq!(buf.write_str(0, 1, "ab"), 2);
+        assert_eq!(buf.write_str(3, 0, "xyz"), 2);
+        assert_eq!(buf.write_str(0, 2, "below"), 0);
+        assert_eq!(&buf.to_string(), "      x y \na b       \n");
+        buf.clear_dirty();
+        assert_eq!(buf, CharBuffer::new(5, 2));
+    }
 }
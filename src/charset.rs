@@ -0,0 +1,238 @@
+//! Which chars output is allowed to use. Charset::Ascii keeps everything to 7-bit ASCII for dumb terminals and log files, while Charset::Unicode and Charset::Braille upgrade shading ramps and borders to block, braille, and box drawing chars. Ramps and border styles can be remapped to fit a charset, and restricting a buffer replaces every char that doesn't fit with the closest one that does. Charsets are also post::PostEffects, so adding one to Runner::effects restricts every frame shown.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::char_buffer::Region;
+//! # use ascii_renderer::charset::{BorderStyle, Charset};
+//! # use ascii_renderer::demos::RAMP;
+//! let charset = Charset::Unicode;
+//! let ramp = charset.remap_ramp(RAMP);
+//! let mut buf = CharBuffer::new(6, 3);
+//! charset.remap_border(BorderStyle::ASCII).draw(&mut buf, Region::new(0, 0, 6, 3));
+//! buf.set_char(2, 1, ramp[9]).unwrap();
+//! assert_eq!(&buf.to_string(), "┌ ─ ─ ─ ─ ┐ \n│   █     │ \n└ ─ ─ ─ ─ ┘ \n");
+//! //Writing to a log file, everything goes back to ASCII
+//! Charset::Ascii.restrict(&mut buf);
+//! assert_eq!(&buf.to_string(), "+ - - - - + \n|   @     | \n+ - - - - + \n");
+//! ```
+
+use super::char_buffer::{CharBuffer, Region};
+use super::demos::RAMP;
+use super::post::PostEffect;
+use super::transition::density;
+use super::Scalar;
+
+/// Unicode shade blocks from dark to bright.
+pub const SHADE_RAMP: &[char] = &[' ', '░', '▒', '▓', '█'];
+/// Braille patterns from dark to bright, filling in one more dot each.
+pub const BRAILLE_RAMP: &[char] = &[' ', '⠁', '⠃', '⠇', '⡇', '⡏', '⡟', '⡿', '⣿'];
+
+const HORIZONTAL: &str = "─━═╌╍┄┅┈┉╴╶╸╺╼╾";
+const VERTICAL: &str = "│┃║╎╏┆┇┊┋╵╷╹╻╽╿";
+
+/// A set of chars output can be restricted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Charset {
+    /// Only 7-bit ASCII, shading with demos::RAMP.
+    #[default]
+    Ascii,
+    /// Any char, shading with SHADE_RAMP and drawing borders with box drawing chars.
+    Unicode,
+    /// Any char, shading with BRAILLE_RAMP for finer steps than SHADE_RAMP, and drawing borders with box drawing chars.
+    Braille,
+}
+
+impl Charset {
+    pub fn ramp(self) -> &'static [char] {
+        //! The chars this charset shades with, from dark to bright.
+        match self {
+            Charset::Ascii => RAMP,
+            Charset::Unicode => SHADE_RAMP,
+            Charset::Braille => BRAILLE_RAMP,
+        }
+    }
+    pub fn border(self) -> BorderStyle {
+        //! The border style this charset draws boxes with.
+        match self {
+            Charset::Ascii => BorderStyle::ASCII,
+            Charset::Unicode | Charset::Braille => BorderStyle::LIGHT,
+        }
+    }
+    pub fn contains(self, char: char) -> bool {
+        //! True if char can be output with this charset as it is.
+        match self {
+            Charset::Ascii => char.is_ascii(),
+            Charset::Unicode | Charset::Braille => true,
+        }
+    }
+    pub fn remap(self, char: char) -> char {
+        //! char if it's in this charset, otherwise the closest char that is: box drawing chars become lines and corners made of '-', '|', and '+', shade blocks and braille become chars from demos::RAMP covering about as much of their cell, and anything else becomes '?'.
+        if self.contains(char) {
+            return char;
+        }
+        match char {
+            '╱' => '/',
+            '╲' => '\\',
+            '╳' => 'X',
+            _ if HORIZONTAL.contains(char) => '-',
+            _ if VERTICAL.contains(char) => '|',
+            '\u{2500}'..='\u{257f}' => '+',
+            '\u{2580}'..='\u{259f}' | '\u{2800}'..='\u{28ff}' => nearest(RAMP, density(char)),
+            _ => '?',
+        }
+    }
+    pub fn remap_ramp(self, ramp: &[char]) -> Vec<char> {
+        //! A ramp of the same length as ramp, so indices into it still work, with each char swapped for the one in this charset's ramp covering about as much of its cell. Chars that cover any of their cell stay visible.
+        ramp.iter()
+            .map(|&x| nearest(self.ramp(), density(x)))
+            .collect()
+    }
+    pub fn remap_border(self, style: BorderStyle) -> BorderStyle {
+        //! style with every char remapped to fit this charset. Upgrading BorderStyle::ASCII to a Unicode charset gives its border instead, since '+' and '-' don't say which box drawing chars they should be.
+        if style == BorderStyle::ASCII {
+            return self.border();
+        }
+        BorderStyle {
+            horizontal: self.remap(style.horizontal),
+            vertical: self.remap(style.vertical),
+            top_left: self.remap(style.top_left),
+            top_right: self.remap(style.top_right),
+            bottom_left: self.remap(style.bottom_left),
+            bottom_right: self.remap(style.bottom_right),
+        }
+    }
+    pub fn restrict(self, buf: &mut CharBuffer) {
+        //! Remaps every char in buf that isn't in this charset.
+        let (width, height) = buf.dimensions;
+        for y in 0..height {
+            for x in 0..width {
+                if let Some(char) = buf.get_char(x, y).filter(|&x| !self.contains(x)) {
+                    let _ = buf.set_char(x, y, self.remap(char));
+                }
+            }
+        }
+    }
+}
+
+impl PostEffect for Charset {
+    fn apply(&mut self, frame: &mut CharBuffer, _delta: f32) {
+        self.restrict(frame);
+    }
+}
+
+fn nearest(ramp: &[char], density: Scalar) -> char {
+    //Rounding up, so faint chars don't disappear into the first (empty) char of the ramp
+    if ramp.is_empty() {
+        return ' ';
+    }
+    let index = (density.clamp(0.0, 1.0) * (ramp.len() - 1) as Scalar - 1e-4).ceil();
+    ramp[(index.max(0.0) as usize).min(ramp.len() - 1)]
+}
+
+/// The chars a box's outline is drawn with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BorderStyle {
+    pub horizontal: char,
+    pub vertical: char,
+    pub top_left: char,
+    pub top_right: char,
+    pub bottom_left: char,
+    pub bottom_right: char,
+}
+
+impl BorderStyle {
+    pub const ASCII: Self = Self::new('-', '|', ['+', '+', '+', '+']);
+    pub const LIGHT: Self = Self::new('─', '│', ['┌', '┐', '└', '┘']);
+    pub const ROUNDED: Self = Self::new('─', '│', ['╭', '╮', '╰', '╯']);
+    pub const HEAVY: Self = Self::new('━', '┃', ['┏', '┓', '┗', '┛']);
+    pub const DOUBLE: Self = Self::new('═', '║', ['╔', '╗', '╚', '╝']);
+
+    pub const fn new(horizontal: char, vertical: char, corners: [char; 4]) -> Self {
+        //! The corners go top left, top right, bottom left, then bottom right.
+        Self {
+            horizontal,
+            vertical,
+            top_left: corners[0],
+            top_right: corners[1],
+            bottom_left: corners[2],
+            bottom_right: corners[3],
+        }
+    }
+    pub fn draw(&self, buf: &mut CharBuffer, region: Region) {
+        //! Draws the outline of region, leaving the inside alone. Regions less than 2 cells wide or tall get nothing.
+        if region.width < 2 || region.height < 2 {
+            return;
+        }
+        let (right, bottom) = (region.x + region.width - 1, region.y + region.height - 1);
+        for x in region.x + 1..right {
+            let _ = buf.set_char(x, region.y, self.horizontal);
+            let _ = buf.set_char(x, bottom, self.horizontal);
+        }
+        for y in region.y + 1..bottom {
+            let _ = buf.set_char(region.x, y, self.vertical);
+            let _ = buf.set_char(right, y, self.vertical);
+        }
+        let _ = buf.set_char(region.x, region.y, self.top_left);
+        let _ = buf.set_char(right, region.y, self.top_right);
+        let _ = buf.set_char(region.x, bottom, self.bottom_left);
+        let _ = buf.set_char(right, bottom, self.bottom_right);
+    }
+}
+
+impl Default for BorderStyle {
+    fn default() -> Self {
+        Self::ASCII
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remapping() {
+        assert_eq!(Charset::Ascii.remap('a'), 'a');
+        assert_eq!(Charset::Unicode.remap('╔'), '╔');
+        let ascii: String = "═║╔┼╱░▒▓█⣿⠁é"
+            .chars()
+            .map(|x| Charset::Ascii.remap(x))
+            .collect();
+        assert_eq!(ascii, "-|++/-+#@@:?");
+        //Ramps keep their length, and faint chars stay visible
+        let shades = Charset::Unicode.remap_ramp(RAMP);
+        assert_eq!(shades.len(), RAMP.len());
+        assert_eq!((shades[0], shades[1], shades[9]), (' ', '░', '█'));
+        let braille = Charset::Braille.remap_ramp(&[' ', '.', '@']);
+        assert_eq!(braille, [' ', '⠁', '⣿']);
+        assert_eq!(
+            Charset::Ascii.remap_ramp(SHADE_RAMP),
+            [' ', '-', '+', '#', '@']
+        );
+        //Every ramp maps back onto itself
+        for charset in [Charset::Ascii, Charset::Unicode, Charset::Braille] {
+            assert_eq!(charset.remap_ramp(charset.ramp()), charset.ramp());
+        }
+    }
+
+    #[test]
+    fn borders() {
+        assert_eq!(
+            Charset::Ascii.remap_border(BorderStyle::DOUBLE),
+            BorderStyle::ASCII
+        );
+        assert_eq!(
+            Charset::Braille.remap_border(BorderStyle::ASCII),
+            BorderStyle::LIGHT
+        );
+        assert_eq!(
+            Charset::Unicode.remap_border(BorderStyle::ROUNDED),
+            BorderStyle::ROUNDED
+        );
+        let mut buf = CharBuffer::new(3, 3);
+        BorderStyle::HEAVY.draw(&mut buf, Region::new(1, 1, 5, 5));
+        assert_eq!(&buf.to_string(), "      \n  ┏ ━ \n  ┃   \n");
+        let mut tiny = CharBuffer::new(2, 2);
+        BorderStyle::ASCII.draw(&mut tiny, Region::new(0, 0, 1, 2));
+        assert_eq!(&tiny.to_string(), "    \n    \n");
+    }
+}
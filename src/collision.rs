@@ -0,0 +1,185 @@
+//! 2D collision checks for games played in buffer space, like pong or breakout. Shapes are measured in cells, with x to the right and y down, the same as CharBuffer.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::collision::{Circle, Rect};
+//! let paddle = Rect::new(2.0, 10.0, 6.0, 1.0);
+//! let ball = Circle::new(vec2!(4.0, 9.5), 0.75);
+//! if let Some(push) = ball.penetration_rect(&paddle) {
+//!     //Move the ball back out and bounce it off the paddle
+//!     assert!(push.y < 0.0);
+//! }
+//! ```
+
+use super::char_buffer::CharBuffer;
+use super::{Scalar, Vector2};
+
+/// An axis aligned rectangle, from its top left corner.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: Scalar,
+    pub y: Scalar,
+    pub width: Scalar,
+    pub height: Scalar,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Circle {
+    pub center: Vector2,
+    pub radius: Scalar,
+}
+
+impl Rect {
+    pub fn new(x: Scalar, y: Scalar, width: Scalar, height: Scalar) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+    pub fn right(&self) -> Scalar {
+        self.x + self.width
+    }
+    pub fn bottom(&self) -> Scalar {
+        self.y + self.height
+    }
+    pub fn center(&self) -> Vector2 {
+        Vector2::new(self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+    pub fn contains(&self, point: Vector2) -> bool {
+        //! Whether the point is inside the rectangle, counting its top and left edges but not its bottom and right ones, so a 1 x 1 rectangle contains exactly one cell's points.
+        point.x >= self.x && point.x < self.right() && point.y >= self.y && point.y < self.bottom()
+    }
+    pub fn intersects(&self, other: &Rect) -> bool {
+        //! Whether the rectangles overlap. Rectangles that only touch edges don't.
+        self.x < other.right()
+            && other.x < self.right()
+            && self.y < other.bottom()
+            && other.y < self.bottom()
+    }
+    pub fn penetration(&self, other: &Rect) -> Option<Vector2> {
+        //! If the rectangles overlap, the shortest move that would push this one out of other, along whichever axis they overlap least on. Its sign says which way to bounce.
+        if !self.intersects(other) {
+            return None;
+        }
+        let left = other.x - self.right();
+        let right = other.right() - self.x;
+        let up = other.y - self.bottom();
+        let down = other.bottom() - self.y;
+        let x = if -left < right { left } else { right };
+        let y = if -up < down { up } else { down };
+        Some(if x.abs() < y.abs() {
+            Vector2::new(x, 0.0)
+        } else {
+            Vector2::new(0.0, y)
+        })
+    }
+    pub fn closest_point(&self, point: Vector2) -> Vector2 {
+        //! The point on or in the rectangle closest to point.
+        Vector2::new(
+            point.x.clamp(self.x, self.right()),
+            point.y.clamp(self.y, self.bottom()),
+        )
+    }
+}
+
+impl Circle {
+    pub fn new(center: Vector2, radius: Scalar) -> Self {
+        Self { center, radius }
+    }
+    pub fn contains(&self, point: Vector2) -> bool {
+        (point - self.center).length_squared() <= self.radius * self.radius
+    }
+    pub fn intersects(&self, other: &Circle) -> bool {
+        let reach = self.radius + other.radius;
+        (other.center - self.center).length_squared() < reach * reach
+    }
+    pub fn intersects_rect(&self, rect: &Rect) -> bool {
+        let closest = rect.closest_point(self.center);
+        (closest - self.center).length_squared() < self.radius * self.radius
+    }
+    pub fn penetration_rect(&self, rect: &Rect) -> Option<Vector2> {
+        //! If the circle overlaps the rectangle, the shortest move that would push it back out.
+        if !self.intersects_rect(rect) {
+            return None;
+        }
+        if rect.contains(self.center) {
+            //The center is inside, so push it out of the nearest edge and then a radius further
+            let left = rect.x - self.center.x - self.radius;
+            let right = rect.right() - self.center.x + self.radius;
+            let up = rect.y - self.center.y - self.radius;
+            let down = rect.bottom() - self.center.y + self.radius;
+            let x = if -left < right { left } else { right };
+            let y = if -up < down { up } else { down };
+            return Some(if x.abs() < y.abs() {
+                Vector2::new(x, 0.0)
+            } else {
+                Vector2::new(0.0, y)
+            });
+        }
+        let away = self.center - rect.closest_point(self.center);
+        let distance = away.length();
+        Some(away / distance * (self.radius - distance))
+    }
+}
+
+impl CharBuffer {
+    pub fn solid_at(&self, x: usize, y: usize, solids: &[char]) -> bool {
+        //! Whether the char at (x, y) is one of the solid chars, ex: to check if a ball hit a wall or brick drawn in the buffer. Cells outside the buffer aren't solid.
+        self.get_char(x, y).is_some_and(|x| solids.contains(&x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec2;
+
+    #[test]
+    fn rects() {
+        let a = Rect::new(0.0, 0.0, 4.0, 2.0);
+        assert!(a.contains(vec2!(0.0, 1.5)) && !a.contains(vec2!(4.0, 1.0)));
+        assert_eq!(a.center(), vec2!(2.0, 1.0));
+        assert!(!a.intersects(&Rect::new(4.0, 0.0, 1.0, 1.0)));
+        let b = Rect::new(3.0, 1.5, 4.0, 4.0);
+        assert!(a.intersects(&b));
+        //Less overlap on y than on x, so pushed up
+        assert_eq!(a.penetration(&b), Some(vec2!(0.0, -0.5)));
+        assert_eq!(b.penetration(&a), Some(vec2!(0.0, 0.5)));
+        assert_eq!(a.penetration(&Rect::new(9.0, 9.0, 1.0, 1.0)), None);
+    }
+
+    #[test]
+    fn circles() {
+        let circle = Circle::new(vec2!(0.0, 0.0), 1.0);
+        assert!(circle.contains(vec2!(0.6, 0.6)) && !circle.contains(vec2!(0.8, 0.8)));
+        assert!(circle.intersects(&Circle::new(vec2!(1.5, 0.0), 1.0)));
+        assert!(!circle.intersects(&Circle::new(vec2!(2.0, 0.0), 1.0)));
+
+        let wall = Rect::new(0.5, -5.0, 2.0, 10.0);
+        assert!(circle.intersects_rect(&wall));
+        assert_eq!(circle.penetration_rect(&wall), Some(vec2!(-0.5, 0.0)));
+        assert_eq!(
+            Circle::new(vec2!(0.75, 4.0), 0.5).penetration_rect(&wall),
+            Some(vec2!(-0.75, 0.0))
+        );
+        assert_eq!(
+            Circle::new(vec2!(-2.0, 0.0), 1.0).penetration_rect(&wall),
+            None
+        );
+        //A center inside the wall is pushed out the nearest side
+        assert_eq!(
+            Circle::new(vec2!(1.0, 0.0), 1.0).penetration_rect(&wall),
+            Some(vec2!(-1.5, 0.0))
+        );
+    }
+
+    #[test]
+    fn solid_chars() {
+        let buf = CharBuffer::from_text("#=\n .");
+        assert!(buf.solid_at(0, 0, &['#', '=']) && buf.solid_at(1, 0, &['#', '=']));
+        assert!(!buf.solid_at(1, 1, &['#', '=']));
+        assert!(!buf.solid_at(5, 5, &['#', '=']));
+    }
+}
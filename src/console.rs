@@ -0,0 +1,366 @@
+//! A drop-down console for debugging running scenes. The Logic registers commands on it, ex: `toggle wireframe` or `load model.obj`, which are run against whatever state they're given when typed in.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::console::Console;
+//! struct Scene {
+//!     wireframe: bool,
+//!     speed: f32,
+//! }
+//! struct Game {
+//!     console: Console<Scene>,
+//!     scene: Scene,
+//! }
+//! impl Logic for Game {
+//!     fn process(&mut self, screen_buf: &mut CharBuffer, _delta: f32) -> ProcessReturn {
+//!         screen_buf.fill(' ');
+//!         self.console.draw(screen_buf);
+//!         ProcessReturn::Continue
+//!     }
+//!     fn input(&mut self, event: &InputEvent) {
+//!         if self.console.handle(event, &mut self.scene) {
+//!             return; //The console used the event
+//!         }
+//!         //Anything else the game does with input
+//!     }
+//! }
+//! let mut console = Console::new();
+//! console.register("toggle", "toggle wireframe", |scene: &mut Scene, args| {
+//!     match args.get_str(0)? {
+//!         "wireframe" => scene.wireframe = !scene.wireframe,
+//!         other => return Err(format!("can't toggle {other}")),
+//!     }
+//!     Ok(String::new())
+//! });
+//! console.register("speed", "speed <value>", |scene: &mut Scene, args| {
+//!     scene.speed = args.get(0)?;
+//!     Ok(format!("speed is now {}", scene.speed))
+//! });
+//! let mut scene = Scene { wireframe: false, speed: 1.0 };
+//! assert_eq!(console.execute("speed 2.5", &mut scene), Ok("speed is now 2.5".to_string()));
+//! let game = Game { console, scene };
+//! ```
+
+use super::char_buffer::CharBuffer;
+use super::runner::InputEvent;
+use super::ui::TextBox;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// How many lines of output the console remembers.
+const MAX_OUTPUT: usize = 200;
+
+type Handler<T> = Box<dyn FnMut(&mut T, &Args) -> Result<String, String>>;
+
+struct Command<T> {
+    usage: String,
+    handler: Handler<T>,
+}
+
+/// The words typed after a command's name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Args {
+    pub words: Vec<String>,
+}
+
+impl Args {
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+    pub fn get_str(&self, index: usize) -> Result<&str, String> {
+        //! The word at index, or an error message saying it's missing.
+        self.words
+            .get(index)
+            .map(|x| x.as_str())
+            .ok_or_else(|| format!("missing argument {}", index + 1))
+    }
+    pub fn get<F: FromStr>(&self, index: usize) -> Result<F, String> {
+        //! The word at index parsed as F, ex: a number, or an error message saying why it couldn't be.
+        let word = self.get_str(index)?;
+        word.parse()
+            .map_err(|_| format!("argument {} ({word}) is not a valid value", index + 1))
+    }
+}
+
+/// Splits a line into words by whitespace, except where it's inside double quotes.
+fn split_words(line: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut word: Option<String> = None;
+    let mut quoted = false;
+    for char in line.chars() {
+        match char {
+            '"' => {
+                quoted = !quoted;
+                word.get_or_insert_with(String::new);
+            }
+            _ if char.is_whitespace() && !quoted => words.extend(word.take()),
+            _ => word.get_or_insert_with(String::new).push(char),
+        }
+    }
+    words.extend(word);
+    words
+}
+
+/// A console that runs commands on a T, which is usually the part of the Logic the commands change. It's opened and closed with the toggle key, and while it's open it takes every key event given to handle.
+pub struct Console<T> {
+    commands: HashMap<String, Command<T>>,
+    open: bool,
+    line: TextBox,
+    output: Vec<String>,
+    history: Vec<String>,
+    /// Where in history ArrowUp and ArrowDown have moved to, if anywhere.
+    history_index: Option<usize>,
+    /// The key that opens and closes the console, named the same way as InputEvent. This is "`" by default.
+    pub toggle_key: String,
+    /// How many rows tall the console is when open, including the line being typed.
+    pub height: usize,
+}
+
+impl<T> std::fmt::Debug for Console<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut commands: Vec<&String> = self.commands.keys().collect();
+        commands.sort_unstable();
+        f.debug_struct("Console")
+            .field("commands", &commands)
+            .field("open", &self.open)
+            .field("line", &self.line)
+            .field("output", &self.output)
+            .field("toggle_key", &self.toggle_key)
+            .field("height", &self.height)
+            .finish()
+    }
+}
+
+impl<T> Default for Console<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Console<T> {
+    pub fn new() -> Self {
+        //! A closed console with no commands other than help, which lists them.
+        Self {
+            commands: HashMap::new(),
+            open: false,
+            line: TextBox::default(),
+            output: vec![],
+            history: vec![],
+            history_index: None,
+            toggle_key: "`".to_string(),
+            height: 10,
+        }
+    }
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        usage: impl Into<String>,
+        handler: impl FnMut(&mut T, &Args) -> Result<String, String> + 'static,
+    ) {
+        //! Adds a command, replacing any with the same name. Usage is shown by help, ex: "load <path>". The handler returns what to print, or an error message.
+        self.commands.insert(
+            name.into(),
+            Command {
+                usage: usage.into(),
+                handler: Box::new(handler),
+            },
+        );
+    }
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+    pub fn get_output(&self) -> &[String] {
+        //! The lines printed so far, oldest first.
+        &self.output
+    }
+    pub fn print(&mut self, text: &str) {
+        //! Adds lines to the console's output, ex: to report something from the Logic.
+        self.output.extend(text.lines().map(String::from));
+        let extra = self.output.len().saturating_sub(MAX_OUTPUT);
+        self.output.drain(..extra);
+    }
+    pub fn execute(&mut self, line: &str, state: &mut T) -> Result<String, String> {
+        //! Runs a line as if it was typed in, without printing anything. Words can be grouped with double quotes, ex: `load "my model.obj"`.
+        let mut words = split_words(line);
+        if words.is_empty() {
+            return Ok(String::new());
+        }
+        let name = words.remove(0);
+        if name == "help" && !self.commands.contains_key("help") {
+            let mut usages: Vec<&str> = self.commands.values().map(|x| x.usage.as_str()).collect();
+            usages.sort_unstable();
+            return Ok(usages.join("\n"));
+        }
+        let Some(command) = self.commands.get_mut(&name) else {
+            return Err(format!("unknown command: {name}"));
+        };
+        (command.handler)(state, &Args { words })
+    }
+    pub fn handle(&mut self, event: &InputEvent, state: &mut T) -> bool {
+        //! Gives the console an input event, returning true if it was used, in which case nothing else should use it. Pressing Enter runs the line typed in and prints its result, and ArrowUp and ArrowDown go through lines typed before.
+        let InputEvent::KeyDown(key) = event else {
+            //Keys released while open are used too, so they don't come up somewhere they never went down
+            return self.open && matches!(event, InputEvent::KeyUp(_));
+        };
+        if *key == self.toggle_key {
+            self.open = !self.open;
+            return true;
+        }
+        if !self.open {
+            return false;
+        }
+        match key.as_str() {
+            "Escape" => self.open = false,
+            "ArrowUp" | "ArrowDown" => self.browse_history(key == "ArrowUp"),
+            _ => {
+                if self.line.type_key(key) {
+                    self.submit(state);
+                }
+            }
+        }
+        true
+    }
+    fn submit(&mut self, state: &mut T) {
+        let line = std::mem::take(&mut self.line).text;
+        self.history_index = None;
+        self.print(&format!("> {line}"));
+        if line.trim().is_empty() {
+            return;
+        }
+        if self.history.last() != Some(&line) {
+            self.history.push(line.clone());
+        }
+        match self.execute(&line, state) {
+            Ok(output) => self.print(&output),
+            Err(error) => self.print(&format!("error: {error}")),
+        }
+    }
+    fn browse_history(&mut self, back: bool) {
+        let index = match (self.history_index, back) {
+            (None, true) => self.history.len().checked_sub(1),
+            (Some(index), true) => Some(index.saturating_sub(1)),
+            (Some(index), false) if index + 1 < self.history.len() => Some(index + 1),
+            (_, false) => None,
+        };
+        self.history_index = index;
+        let text = index.map_or(String::new(), |x| self.history[x].clone());
+        self.line = TextBox::new(text);
+    }
+    pub fn draw(&self, buf: &mut CharBuffer) {
+        //! Draws the console over the top of the buffer if it's open, with the latest output above the line being typed.
+        if !self.open {
+            return;
+        }
+        let (width, height) = (buf.dimensions.0, self.height.min(buf.dimensions.1));
+        if height == 0 {
+            return;
+        }
+        let blank = " ".repeat(width);
+        for y in 0..height {
+            buf.write_str(0, y, &blank);
+        }
+        let lines = height - 1;
+        let shown = &self.output[self.output.len().saturating_sub(lines)..];
+        for (y, line) in shown.iter().enumerate() {
+            buf.write_str(0, y, line);
+        }
+        //The end of the line is shown if it's too long to fit
+        let mut typed: Vec<char> = self.line.text.chars().collect();
+        typed.insert(self.line.get_cursor(), '|');
+        let room = width.saturating_sub(2);
+        let start = (self.line.get_cursor() + 1).saturating_sub(room);
+        let typed: String = typed.iter().skip(start).collect();
+        buf.write_str(0, lines, &format!("> {typed}"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn console() -> Console<Vec<String>> {
+        let mut console = Console::new();
+        console.register("load", "load <path>", |loaded: &mut Vec<String>, args| {
+            loaded.push(args.get_str(0)?.to_string());
+            Ok(format!("loaded {}", loaded.len()))
+        });
+        console.register("add", "add <a> <b>", |_: &mut Vec<String>, args| {
+            Ok((args.get::<i32>(0)? + args.get::<i32>(1)?).to_string())
+        });
+        console
+    }
+
+    #[test]
+    fn parsing() {
+        assert_eq!(
+            split_words("load  \"my model.obj\" x\"\""),
+            vec!["load", "my model.obj", "x"]
+        );
+        assert_eq!(split_words(" \"\" "), vec![""]);
+        assert!(split_words("   ").is_empty());
+    }
+
+    #[test]
+    fn commands() {
+        let mut console = console();
+        let mut loaded = vec![];
+        assert_eq!(
+            console.execute("load \"a b.obj\"", &mut loaded),
+            Ok("loaded 1".to_string())
+        );
+        assert_eq!(loaded, vec!["a b.obj"]);
+        assert_eq!(console.execute("add 2 3", &mut loaded), Ok("5".to_string()));
+        assert_eq!(
+            console.execute("add 2", &mut loaded),
+            Err("missing argument 2".to_string())
+        );
+        assert_eq!(
+            console.execute("add 2 x", &mut loaded),
+            Err("argument 2 (x) is not a valid value".to_string())
+        );
+        assert_eq!(
+            console.execute("fly", &mut loaded),
+            Err("unknown command: fly".to_string())
+        );
+        assert_eq!(
+            console.execute("help", &mut loaded),
+            Ok("add <a> <b>\nload <path>".to_string())
+        );
+    }
+
+    #[test]
+    fn typing() {
+        let mut console = console();
+        let mut loaded = vec![];
+        let mut type_keys = |console: &mut Console<Vec<String>>, keys: &[&str]| {
+            keys.iter()
+                .map(|x| console.handle(&InputEvent::KeyDown(x.to_string()), &mut loaded))
+                .collect::<Vec<bool>>()
+        };
+        assert_eq!(
+            type_keys(&mut console, &["a", "`", "a"]),
+            [false, true, true]
+        );
+        assert!(console.is_open());
+        type_keys(&mut console, &["d", "d", " ", "1", " ", "2", "Enter"]);
+        assert_eq!(console.get_output(), &["> add 1 2", "3"]);
+        type_keys(&mut console, &["x", "Enter", "ArrowUp", "ArrowUp"]);
+        assert_eq!(console.line.text, "add 1 2");
+        type_keys(&mut console, &["ArrowDown"]);
+        assert_eq!(console.line.text, "x");
+
+        let mut buf = CharBuffer::new(8, 4);
+        console.height = 3;
+        console.draw(&mut buf);
+        let rows: Vec<String> = buf.data.iter().map(|x| x.iter().collect()).collect();
+        assert_eq!(rows, ["> x     ", "error: u", "> x|    ", "        "]);
+        type_keys(&mut console, &["Escape"]);
+        assert!(!console.is_open());
+    }
+}
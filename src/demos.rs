@@ -0,0 +1,239 @@
+//! Classic demoscene effects drawn straight into a region of a buffer: plasma, a tunnel, rotozoom, metaballs, and a starfield. Most are functions of time, so they only need the seconds since the start to animate, and shade with a ramp of chars from dark to bright like RAMP.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::char_buffer::Region;
+//! # use ascii_renderer::demos::{plasma, Starfield, RAMP};
+//! let mut buf = CharBuffer::new(40, 20);
+//! let (left, right) = (Region::new(0, 0, 20, 20), Region::new(20, 0, 20, 20));
+//! plasma(&mut buf, left, 1.5, RAMP);
+//! let mut stars = Starfield::new(50, 7);
+//! stars.update(0.1);
+//! stars.draw(&mut buf, right);
+//! ```
+
+use super::char_buffer::{CharBuffer, Region};
+#[cfg(not(feature = "std"))]
+use super::float::Float;
+use super::noise::Rng;
+use super::{Scalar, Vector2, Vector3};
+use alloc::{vec, vec::Vec};
+
+/// Chars from dark to bright, for shading effects.
+pub const RAMP: &[char] = &[' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+
+pub fn shade(ramp: &[char], value: Scalar) -> char {
+    //! The char in ramp for a brightness from 0.0 to 1.0, clamping values outside that. Returns ' ' for an empty ramp.
+    if ramp.is_empty() {
+        return ' ';
+    }
+    let index = (value.clamp(0.0, 1.0) * ramp.len() as Scalar) as usize;
+    ramp[index.min(ramp.len() - 1)]
+}
+
+fn fill_region(
+    buf: &mut CharBuffer,
+    region: Region,
+    mut char_at: impl FnMut(Scalar, Scalar) -> char,
+) {
+    //Effects are written in terms of the cell within the region, so they look the same wherever they're drawn
+    let region = region.clipped(buf.dimensions);
+    for y in 0..region.height {
+        for x in 0..region.width {
+            let char = char_at(x as Scalar, y as Scalar);
+            let _ = buf.set_char(region.x + x, region.y + y, char);
+        }
+    }
+}
+
+pub fn plasma(buf: &mut CharBuffer, region: Region, time: f32, ramp: &[char]) {
+    //! Overlapping sine waves that drift over time.
+    let time = time as Scalar;
+    fill_region(buf, region, |x, y| {
+        let value = (x * 0.3 + time).sin()
+            + (y * 0.25 - time * 1.3).sin()
+            + ((x + y) * 0.2 + time * 0.7).sin()
+            + ((x * x + y * y).sqrt() * 0.3 - time * 2.0).sin();
+        shade(ramp, value / 8.0 + 0.5)
+    });
+}
+
+pub fn tunnel(buf: &mut CharBuffer, region: Region, time: f32, ramp: &[char]) {
+    //! Flying down a checkered tunnel, which gets darker in the distance.
+    let time = time as Scalar;
+    let center = Vector2::new(region.width as Scalar / 2.0, region.height as Scalar / 2.0);
+    fill_region(buf, region, |x, y| {
+        let offset = Vector2::new(x + 0.5, y + 0.5) - center;
+        let distance = offset.length().max(0.5);
+        let depth = 8.0 / distance + time * 2.0;
+        let angle = offset.y.atan2(offset.x) / core::f64::consts::PI as Scalar * 4.0 + time * 0.5;
+        let checker = (depth.floor() + angle.floor()).rem_euclid(2.0);
+        let brightness = (distance / center.length().max(1.0)).min(1.0);
+        shade(ramp, (0.35 + 0.65 * checker) * brightness)
+    });
+}
+
+pub fn rotozoom(buf: &mut CharBuffer, region: Region, texture: &CharBuffer, time: f32) {
+    //! Texture tiled endlessly, spinning and zooming in and out around the middle of the region.
+    let (width, height) = texture.dimensions;
+    if width == 0 || height == 0 {
+        return;
+    }
+    let time = time as Scalar;
+    let (sin, cos) = (time * 0.7).sin_cos();
+    let zoom = 1.0 + 0.6 * (time * 0.5).sin();
+    let center = Vector2::new(region.width as Scalar / 2.0, region.height as Scalar / 2.0);
+    fill_region(buf, region, |x, y| {
+        let offset = (Vector2::new(x, y) - center) * zoom;
+        let u = (offset.x * cos - offset.y * sin).floor() as isize;
+        let v = (offset.x * sin + offset.y * cos).floor() as isize;
+        let (u, v) = (
+            u.rem_euclid(width as isize) as usize,
+            v.rem_euclid(height as isize) as usize,
+        );
+        texture.get_char(u, v).unwrap_or(' ')
+    });
+}
+
+pub fn metaballs(buf: &mut CharBuffer, region: Region, balls: &[(Vector2, Scalar)], ramp: &[char]) {
+    //! Blobs that melt together where they get close. Each ball is its center in cells from the region's top left corner, and its radius.
+    fill_region(buf, region, |x, y| {
+        let point = Vector2::new(x + 0.5, y + 0.5);
+        let field: Scalar = balls
+            .iter()
+            .map(|(center, radius)| radius * radius / (point - *center).length_squared().max(1e-4))
+            .sum();
+        //A single ball's field is 1.0 at its radius, which is where it starts being drawn
+        if field < 1.0 {
+            ramp.first().copied().unwrap_or(' ')
+        } else {
+            shade(ramp, 0.5 + (field - 1.0) / 4.0)
+        }
+    });
+}
+
+/// Stars flying out of the middle of the screen towards the viewer.
+#[derive(Debug, Clone)]
+pub struct Starfield {
+    /// Each star's x and y from -1.0 to 1.0 and its distance, from 1.0 (far) to 0.0 (passing the viewer).
+    stars: Vec<Vector3>,
+    /// How much closer stars get each second.
+    pub speed: Scalar,
+    /// The chars stars are drawn with from far to near.
+    pub ramp: Vec<char>,
+    rng: Rng,
+}
+
+impl Starfield {
+    pub fn new(count: usize, seed: u32) -> Self {
+        //! count stars spread through the field, moving at 0.5.
+        let mut rng = Rng::new(seed);
+        let stars = (0..count)
+            .map(|_| {
+                Vector3::new(
+                    rng.range(-1.0, 1.0),
+                    rng.range(-1.0, 1.0),
+                    rng.range(0.05, 1.0),
+                )
+            })
+            .collect();
+        Self {
+            stars,
+            speed: 0.5,
+            ramp: vec!['.', '+', '*'],
+            rng,
+        }
+    }
+    pub fn get_stars(&self) -> &[Vector3] {
+        &self.stars
+    }
+    pub fn update(&mut self, delta: f32) {
+        //! Moves stars closer, replacing the ones that pass the viewer with new ones in the distance.
+        for star in self.stars.iter_mut() {
+            star.z -= self.speed * delta as Scalar;
+            if star.z <= 0.05 {
+                *star = Vector3::new(self.rng.range(-1.0, 1.0), self.rng.range(-1.0, 1.0), 1.0);
+            }
+        }
+    }
+    pub fn draw(&self, buf: &mut CharBuffer, region: Region) {
+        //! Draws the stars over whatever is in the region, so clear it first for a black sky.
+        let region = region.clipped(buf.dimensions);
+        let half = Vector2::new(region.width as Scalar / 2.0, region.height as Scalar / 2.0);
+        for star in self.stars.iter() {
+            let x = half.x + star.x / star.z * half.x * 0.5;
+            let y = half.y + star.y / star.z * half.y * 0.5;
+            if x < 0.0 || y < 0.0 || x >= region.width as Scalar || y >= region.height as Scalar {
+                continue;
+            }
+            let char = shade(&self.ramp, 1.0 - star.z);
+            let _ = buf.set_char(region.x + x as usize, region.y + y as usize, char);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec2;
+    use alloc::string::ToString;
+
+    #[test]
+    fn shading() {
+        assert_eq!(shade(RAMP, 0.0), ' ');
+        assert_eq!(shade(RAMP, 1.0), '@');
+        assert_eq!(shade(RAMP, 2.0), '@');
+        assert_eq!(shade(&['a', 'b'], 0.49), 'a');
+        assert_eq!(shade(&[], 0.5), ' ');
+    }
+
+    #[test]
+    fn effects_stay_in_region() {
+        let region = Region::new(2, 1, 4, 3);
+        let texture = CharBuffer::from_text("ab\ncd");
+        let effects: [&dyn Fn(&mut CharBuffer); 4] = [
+            &|buf| plasma(buf, region, 0.5, &['x']),
+            &|buf| tunnel(buf, region, 0.5, &['x']),
+            &|buf| rotozoom(buf, region, &texture, 0.5),
+            &|buf| metaballs(buf, region, &[(vec2!(2.0, 1.5), 10.0)], &['x']),
+        ];
+        for effect in effects {
+            let mut buf = CharBuffer::new(8, 5);
+            buf.fill('.');
+            effect(&mut buf);
+            for y in 0..5 {
+                for x in 0..8 {
+                    assert_eq!(buf.get_char(x, y) == Some('.'), !region.contains(x, y));
+                }
+            }
+        }
+        //Regions past the edge of the buffer are cut off
+        let mut buf = CharBuffer::new(3, 3);
+        plasma(&mut buf, Region::new(1, 1, 10, 10), 0.0, &['x']);
+        assert_eq!(&buf.to_string(), "      \n  x x \n  x x \n");
+    }
+
+    #[test]
+    fn metaballs_merge() {
+        let mut buf = CharBuffer::new(9, 1);
+        let region = buf.region();
+        let balls = [(vec2!(2.5, 0.5), 1.5), (vec2!(6.5, 0.5), 1.5)];
+        metaballs(&mut buf, region, &balls, &[' ', '#']);
+        //The gap between them is filled in, which neither would reach alone
+        assert_eq!(&buf.to_string(), "  # # # # # # #   \n");
+        metaballs(&mut buf, region, &balls[..1], &[' ', '#']);
+        assert_eq!(&buf.to_string(), "  # # #           \n");
+    }
+
+    #[test]
+    fn starfield() {
+        let mut stars = Starfield::new(20, 3);
+        stars.speed = 10.0;
+        stars.update(1.0);
+        //Every star passed the viewer and was replaced in the distance
+        assert!(stars.get_stars().iter().all(|x| x.z == 1.0));
+        let mut buf = CharBuffer::new(10, 10);
+        stars.draw(&mut buf, Region::new(0, 0, 10, 10));
+        assert!(buf.to_string().contains('.'));
+    }
+}
@@ -0,0 +1,374 @@
+//! Depth testing, for drawing several renderers into one buffer without the last one drawn always covering the others. A DepthBuffer keeps how far away whatever was drawn in each cell is, and drawing with it only replaces a cell when the new line is closer. Depths are distances in front of each renderer's own camera, so a foreground renderer (ex: a cockpit with its own camera right behind it) is merged with the scene by how close things are to each camera.
+//!
+//! Once a frame has depth, the DepthEdges post effect can outline everything in it by drawing over the cells where the depth jumps, which are the silhouettes of filled shapes and the edges where one thing passes in front of another.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::depth::{composite, DepthEdges};
+//! # use ascii_renderer::post::PostEffect;
+//! let camera = Camera {
+//!     position: vec3!(0.0, 0.0, -10.0),
+//!     rotation: vec3!(0.0, 0.0, 0.0),
+//!     fov: vec2!(1.0, 1.0),
+//! };
+//! let mut near = ascii_renderer::create_cube();
+//! near.position.z = -4.0;
+//! near.char = '#';
+//! let scene = Renderer::new(vec![ascii_renderer::create_cube()], camera.clone());
+//! let foreground = Renderer::new(vec![near], camera);
+//! let mut buf = CharBuffer::new(40, 40);
+//! //The foreground is drawn over the scene, even though it's drawn first
+//! let depth = composite(&[&foreground, &scene], &mut buf);
+//! assert_eq!(buf.get_char(20, 20), Some(' '));
+//! assert!(depth.get(20, 20).is_none());
+//!
+//! //Outlining only where the depth jumps, for a blueprint of the scene
+//! let mut blueprint = DepthEdges::new(depth).with_background(' ');
+//! blueprint.apply(&mut buf, 0.0);
+//! ```
+
+use super::cache::lock;
+use super::char_buffer::CharBuffer;
+use super::line::{for_each_cell, Line};
+use super::post::PostEffect;
+use super::renderable::NEAR;
+use super::rendering::{Mesh, Renderer};
+use super::{Scalar, Vector2, Vector3};
+use alloc::{vec, vec::Vec};
+
+/// How far away (in front of the camera) whatever was drawn in each cell is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthBuffer {
+    pub dimensions: (usize, usize),
+    depths: Vec<Scalar>,
+}
+
+impl DepthBuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        //! A buffer with nothing drawn in it, so anything can be drawn anywhere.
+        Self {
+            dimensions: (width, height),
+            depths: vec![Scalar::INFINITY; width * height],
+        }
+    }
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.dimensions.0 && y < self.dimensions.1).then_some(y * self.dimensions.0 + x)
+    }
+    pub fn get(&self, x: usize, y: usize) -> Option<Scalar> {
+        //! The depth of whatever is drawn in the cell, or None if nothing is (or it's outside the buffer).
+        self.depths
+            .get(self.index(x, y)?)
+            .copied()
+            .filter(|x| x.is_finite())
+    }
+    pub fn test(&mut self, x: usize, y: usize, depth: Scalar) -> bool {
+        //! True if something depth away would be in front of what's in the cell, in which case the cell takes that depth. Ties go to the newer, so things drawn at the same depth overwrite each other in draw order like they do without depth testing.
+        match self.index(x, y) {
+            Some(index) if depth <= self.depths[index] => {
+                self.depths[index] = depth;
+                true
+            }
+            _ => false,
+        }
+    }
+    pub fn clear(&mut self) {
+        //! Forgets everything drawn, ready for the next frame.
+        self.depths.fill(Scalar::INFINITY);
+    }
+    /// old cleared if it has the given dimensions, otherwise a new buffer, so frames can reuse the last one's.
+    pub(crate) fn reuse(old: Option<Self>, dimensions: (usize, usize)) -> Self {
+        match old {
+            Some(mut depth) if depth.dimensions == dimensions => {
+                depth.clear();
+                depth
+            }
+            _ => Self::new(dimensions.0, dimensions.1),
+        }
+    }
+    /// Gives each cell of into the nearest depth of its factor by factor cells here, for shrinking a supersampled frame's depth.
+    pub(crate) fn shrink_into(&self, factor: usize, into: &mut DepthBuffer) {
+        for y in 0..into.dimensions.1 {
+            for x in 0..into.dimensions.0 {
+                for sub_y in y * factor..(y + 1) * factor {
+                    for sub_x in x * factor..(x + 1) * factor {
+                        if let Some(depth) = self.get(sub_x, sub_y) {
+                            into.test(x, y, depth);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Renderer {
+    pub fn draw_with_depth(&self, buffer: &mut CharBuffer, depth: &mut DepthBuffer) {
+        //! Draws all the meshes like draw, but only into cells where they're in front of what depth says is already there. Parts of lines behind the camera are cut off, and lines aren't antialiased.
+        for mesh in self.meshs.iter() {
+            self.draw_mesh_with_depth(mesh, buffer, depth);
+        }
+    }
+    pub(crate) fn draw_mesh_with_depth(
+        &self,
+        mesh: &Mesh,
+        buffer: &mut CharBuffer,
+        depth: &mut DepthBuffer,
+    ) {
+        let mesh = &*self.shade(mesh);
+        let model = mesh.model_matrix();
+        let model_view = self.camera.view_matrix() * model;
+        let vertices = mesh.get_verticies();
+        for (a, b) in self.lod_edges(mesh, &model) {
+            let (Some(a), Some(b)) = (vertices.get(a), vertices.get(b)) else {
+                continue;
+            };
+            let (a, b) = (
+                model_view.transform_point(*a),
+                model_view.transform_point(*b),
+            );
+            self.draw_depth_line(buffer, depth, a, b, mesh.char);
+        }
+    }
+    pub(crate) fn draw_recording_depth(&self, buffer: &mut CharBuffer) {
+        let mut depth = DepthBuffer::reuse(lock(&self.scratch.0).depth.take(), buffer.dimensions);
+        self.draw_with_depth(buffer, &mut depth);
+        lock(&self.scratch.0).depth = Some(depth);
+    }
+    pub fn depth_at(&self, x: usize, y: usize) -> Option<Scalar> {
+        //! How far in front of the camera whatever draw drew in the cell last frame is, or None if nothing was drawn there. Only kept while Renderer::record_depth is set, ex: to hide labels behind what's drawn.
+        lock(&self.scratch.0).depth.as_ref()?.get(x, y)
+    }
+    fn draw_depth_line(
+        &self,
+        buffer: &mut CharBuffer,
+        depth: &mut DepthBuffer,
+        mut start: Vector3,
+        mut end: Vector3,
+        char: char,
+    ) {
+        if start.z <= NEAR && end.z <= NEAR {
+            return;
+        }
+        if start.z <= NEAR {
+            start = end.lerp(start, (end.z - NEAR) / (end.z - start.z));
+        } else if end.z <= NEAR {
+            end = start.lerp(end, (start.z - NEAR) / (start.z - end.z));
+        }
+        let dimensions = buffer.dimensions;
+        let points = (
+            self.project_view(start, dimensions),
+            self.project_view(end, dimensions),
+        );
+        let along = points.1 - points.0;
+        let length_squared = along.length_squared();
+        for_each_cell(Line { char, points }, buffer.region(), |x, y| {
+            //How far along the line the cell is on screen, then the depth there (1 / z is what's linear on screen)
+            let t = if length_squared == 0.0 {
+                0.0
+            } else {
+                (Vector2::new(x as Scalar, y as Scalar) - points.0).dot(along) / length_squared
+            };
+            let t = t.clamp(0.0, 1.0);
+            let z = 1.0 / ((1.0 - t) / start.z + t / end.z);
+            if depth.test(x, y, z) {
+                let _ = buffer.set_char(x, y, char);
+            }
+        });
+    }
+}
+
+impl DepthBuffer {
+    pub fn is_edge(&self, x: usize, y: usize, threshold: Scalar) -> bool {
+        //! True if something is drawn in the cell and it's at the edge of what's in front, because a neighbouring cell (left, right, above, or below) is empty or further away by more than threshold times the cell's depth. Only the nearer side of a jump is an edge, so outlines are drawn on the thing in front. The edges of the buffer aren't edges.
+        let Some(depth) = self.get(x, y) else {
+            return false;
+        };
+        let neighbours = [
+            x.checked_sub(1).map(|x| (x, y)),
+            Some((x + 1, y)),
+            y.checked_sub(1).map(|y| (x, y)),
+            Some((x, y + 1)),
+        ];
+        neighbours.into_iter().flatten().any(|(x, y)| {
+            self.index(x, y).is_some()
+                && self
+                    .get(x, y)
+                    .is_none_or(|neighbour| neighbour - depth > threshold * depth)
+        })
+    }
+}
+
+/// A post effect drawing outlines where the depth of the frame jumps, see DepthBuffer::is_edge. The depth buffer has to be kept up to date with the frame, ex: by drawing with Renderer::draw_with_depth into DepthEdges::depth every frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthEdges {
+    pub depth: DepthBuffer,
+    /// How big a jump in depth is an edge, as a fraction of the nearer depth.
+    pub threshold: Scalar,
+    /// What the outlines are drawn with.
+    pub char: char,
+    /// When set, every cell that isn't an edge is replaced with this, so only the outlines are left.
+    pub background: Option<char>,
+}
+
+impl DepthEdges {
+    pub fn new(depth: DepthBuffer) -> Self {
+        //! Outlines with '#' wherever the depth jumps by more than 10%, drawn over the rest of the frame.
+        Self {
+            depth,
+            threshold: 0.1,
+            char: '#',
+            background: None,
+        }
+    }
+    pub fn with_threshold(mut self, threshold: Scalar) -> Self {
+        self.threshold = threshold;
+        self
+    }
+    pub fn with_char(mut self, char: char) -> Self {
+        self.char = char;
+        self
+    }
+    pub fn with_background(mut self, background: char) -> Self {
+        self.background = Some(background);
+        self
+    }
+}
+
+impl PostEffect for DepthEdges {
+    fn apply(&mut self, frame: &mut CharBuffer, _delta: f32) {
+        for y in 0..frame.dimensions.1 {
+            for x in 0..frame.dimensions.0 {
+                if self.depth.is_edge(x, y, self.threshold) {
+                    let _ = frame.set_char(x, y, self.char);
+                } else if let Some(background) = self.background {
+                    let _ = frame.set_char(x, y, background);
+                }
+            }
+        }
+    }
+}
+
+pub fn composite(renderers: &[&Renderer], buffer: &mut CharBuffer) -> DepthBuffer {
+    //! Draws every renderer into buffer, merged by depth instead of the order they're in, returning the depth buffer they shared (ex: to test labels against).
+    let mut depth = DepthBuffer::new(buffer.dimensions.0, buffer.dimensions.1);
+    for renderer in renderers {
+        renderer.draw_with_depth(buffer, &mut depth);
+    }
+    depth
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::{Camera, Mesh};
+    use crate::{vec2, vec3};
+    use alloc::string::ToString;
+
+    fn renderer(char: char, z: Scalar) -> Renderer {
+        let mut mesh = Mesh::default();
+        mesh.insert_vertex(0, vec3!(-5.0, 0.0, z));
+        mesh.insert_vertex(1, vec3!(5.0, 0.0, z));
+        mesh.add_edge((0, 1));
+        mesh.char = char;
+        Renderer::new(
+            vec![mesh],
+            Camera {
+                position: vec3!(0.0, 0.0, -10.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(1.0, 1.0),
+            },
+        )
+    }
+
+    #[test]
+    fn depth_merging() {
+        let (near, far) = (renderer('n', 0.0), renderer('f', 5.0));
+        //Whichever order they're drawn in, the nearer line wins where they overlap
+        for order in [[&near, &far], [&far, &near]] {
+            let mut buf = CharBuffer::new(20, 4);
+            let depth = composite(&order, &mut buf);
+            assert_eq!(buf.get_char(10, 2), Some('n'));
+            //The far line is shorter on screen, so the near one covers all of it
+            assert!(!buf.to_string().contains('f'));
+            assert!((depth.get(10, 2).unwrap() - 10.0).abs() < 1e-3);
+            assert_eq!(depth.get(10, 0), None);
+        }
+        //Draw order decides ties, and clearing lets anything draw again
+        let mut depth = DepthBuffer::new(1, 1);
+        assert!(depth.test(0, 0, 2.0) && depth.test(0, 0, 2.0) && !depth.test(0, 0, 3.0));
+        depth.clear();
+        assert!(depth.test(0, 0, 3.0));
+    }
+
+    #[test]
+    fn depth_along_lines() {
+        let mut slanted = renderer('s', 0.0);
+        slanted.meshs[0].insert_vertex(1, vec3!(5.0, 0.0, 10.0));
+        //Behind the camera is cut off
+        slanted.meshs[0].insert_vertex(0, vec3!(-5.0, 0.0, -20.0));
+        let mut buf = CharBuffer::new(40, 4);
+        let mut depth = DepthBuffer::new(40, 4);
+        slanted.draw_with_depth(&mut buf, &mut depth);
+        let depths: Vec<Scalar> = (0..40).filter_map(|x| depth.get(x, 2)).collect();
+        assert!(depths.windows(2).all(|x| x[0] <= x[1]));
+        assert!(depths[0] < 1.0 && *depths.last().unwrap() > 19.0);
+    }
+
+    #[test]
+    fn reading_depth_back() {
+        let mut renderer = renderer('n', 0.0);
+        let mut buf = CharBuffer::new(20, 4);
+        renderer.draw(&mut buf);
+        assert_eq!(renderer.depth_at(10, 2), None);
+        renderer.record_depth = true;
+        renderer.draw(&mut buf);
+        assert!((renderer.depth_at(10, 2).unwrap() - 10.0).abs() < 1e-3);
+        assert_eq!(renderer.depth_at(10, 0), None);
+        //Each frame replaces the last, at whatever size it's drawn
+        renderer.meshs[0].position.z = 5.0;
+        renderer.draw(&mut CharBuffer::new(30, 6));
+        assert!((renderer.depth_at(15, 3).unwrap() - 15.0).abs() < 1e-3);
+        assert_eq!(renderer.depth_at(10, 2), None);
+    }
+
+    #[test]
+    fn outlining_depth() {
+        //A wall, with a box in front of it and a small bump on it
+        let mut depth = DepthBuffer::new(7, 5);
+        for y in 0..5 {
+            for x in 0..6 {
+                depth.test(x, y, 10.0);
+            }
+        }
+        for y in 1..4 {
+            for x in 1..4 {
+                depth.test(x, y, 2.0);
+            }
+        }
+        depth.test(4, 2, 9.5);
+        let mut buf = CharBuffer::new(7, 5);
+        buf.fill('@');
+        DepthEdges::new(depth.clone())
+            .with_background('.')
+            .apply(&mut buf, 0.0);
+        //The wall is outlined where it ends, but not at the edges of the buffer
+        assert_eq!(
+            &buf.to_string(),
+            concat!(
+                ". . . . . # . \n",
+                ". # # # . # . \n",
+                ". # . # . # . \n",
+                ". # # # . # . \n",
+                ". . . . . # . \n",
+            )
+        );
+        //A low enough threshold catches the bump too, and the rest of the frame is kept without a background
+        let mut buf = CharBuffer::new(7, 5);
+        buf.fill('@');
+        DepthEdges::new(depth)
+            .with_threshold(0.01)
+            .apply(&mut buf, 0.0);
+        assert_eq!(buf.get_char(4, 2), Some('#'));
+        assert_eq!(buf.get_char(4, 1), Some('@'));
+    }
+}
@@ -0,0 +1,228 @@
+//! Text descriptions of what's on screen, so visually impaired users can follow a scene with a screen reader, and tests can check what a scene shows rather than which chars it draws. A Describer sums up a renderer's meshes: roughly where each one is on screen, how far away it is, and which way it moved since the last description. A runner with a DescriptionMode passes the logic's descriptions to its backend (see Logic::describe and Backend::announce), either instead of every frame or when a key is pressed.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::describe::Describer;
+//! let mut cube = Mesh::default();
+//! cube.position = vec3!(0.0, 0.0, 5.0);
+//! let mut renderer = Renderer::new(
+//!     vec![cube],
+//!     Camera {
+//!         position: vec3!(0.0, 0.0, 0.0),
+//!         rotation: vec3!(0.0, 0.0, 0.0),
+//!         fov: vec2!(1.0, 1.0),
+//!     },
+//! );
+//! let mut describer = Describer::new().with_name(0, "cube");
+//! describer.describe(&renderer, (40, 20));
+//! renderer.meshs[0].position.x += 1.0;
+//! assert_eq!(
+//!     describer.describe(&renderer, (40, 20)).to_string(),
+//!     "Frame 2, 1 object.\ncube: right, 5.1 away, moving right."
+//! );
+//! ```
+
+use super::rendering::Renderer;
+use super::{Scalar, Vector2, Vector3};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Motion smaller than this along an axis isn't mentioned.
+const STILL: Scalar = 0.001;
+
+/// One mesh in a FrameDescription.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectDescription {
+    /// The mesh's index in Renderer::meshs.
+    pub index: usize,
+    /// The mesh's name from Describer::names, or "mesh" and its index.
+    pub name: String,
+    pub position: Vector3,
+    /// Where the mesh's position is drawn, in the same coordinates as Camera::project. None if it's behind the camera.
+    pub cell: Option<Vector2>,
+    /// How far the mesh's position is from the camera.
+    pub distance: Scalar,
+    /// How far the mesh moved relative to the camera since the last description, with +x being right, +y down, and +z away from the camera. None the first time it's described.
+    pub motion: Option<Vector3>,
+    /// The size of the buffer the cell is in.
+    dimensions: (usize, usize),
+}
+
+impl ObjectDescription {
+    pub fn region(&self) -> &'static str {
+        //! Which ninth of the screen the mesh is in, ex: "top left" or "center", or "off screen" / "behind the camera".
+        let Some(cell) = self.cell else {
+            return "behind the camera";
+        };
+        let (width, height) = (self.dimensions.0 as Scalar, self.dimensions.1 as Scalar);
+        if !(0.0..width).contains(&cell.x) || !(0.0..height).contains(&cell.y) {
+            return "off screen";
+        }
+        let column = (cell.x * 3.0 / width) as usize;
+        let row = (cell.y * 3.0 / height) as usize;
+        [
+            ["top left", "top", "top right"],
+            ["left", "center", "right"],
+            ["bottom left", "bottom", "bottom right"],
+        ][row.min(2)][column.min(2)]
+    }
+    pub fn directions(&self) -> Vec<&'static str> {
+        //! Which ways the mesh moved, ex: ["left", "closer"], or nothing if it didn't.
+        let Some(motion) = self.motion else {
+            return vec![];
+        };
+        [
+            (motion.x, "left", "right"),
+            (motion.y, "up", "down"),
+            (motion.z, "closer", "away"),
+        ]
+        .into_iter()
+        .filter(|(x, _, _)| x.abs() > STILL)
+        .map(|(x, negative, positive)| if x < 0.0 { negative } else { positive })
+        .collect()
+    }
+}
+
+impl fmt::Display for ObjectDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}, {:.1} away",
+            self.name,
+            self.region(),
+            self.distance
+        )?;
+        let directions = self.directions();
+        if !directions.is_empty() {
+            write!(f, ", moving {}", directions.join(" and "))?;
+        } else if self.motion.is_some() {
+            write!(f, ", not moving")?;
+        }
+        write!(f, ".")
+    }
+}
+
+/// Every mesh in a frame, which is shown as a line for the frame and one line per mesh.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameDescription {
+    /// How many descriptions the Describer has made, including this one.
+    pub frame: usize,
+    pub objects: Vec<ObjectDescription>,
+}
+
+impl fmt::Display for FrameDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = if self.objects.len() == 1 { "" } else { "s" };
+        write!(
+            f,
+            "Frame {}, {} object{plural}.",
+            self.frame,
+            self.objects.len()
+        )?;
+        for object in &self.objects {
+            write!(f, "\n{object}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Describes a renderer's meshes, remembering where they were to tell which way they moved.
+#[derive(Debug, Clone, Default)]
+pub struct Describer {
+    /// What meshes are called, by their index in Renderer::meshs.
+    pub names: HashMap<usize, String>,
+    /// Where each mesh was relative to the camera last time.
+    previous: HashMap<usize, Vector3>,
+    frame: usize,
+}
+
+impl Describer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with_name(mut self, mesh: usize, name: impl Into<String>) -> Self {
+        self.names.insert(mesh, name.into());
+        self
+    }
+    pub fn describe(
+        &mut self,
+        renderer: &Renderer,
+        dimensions: (usize, usize),
+    ) -> FrameDescription {
+        //! Describes every mesh as drawn into a buffer with the given dimensions.
+        self.frame += 1;
+        let camera = &renderer.camera;
+        let view = camera.view_matrix();
+        let mut previous = HashMap::with_capacity(renderer.meshs.len());
+        let objects = renderer
+            .meshs
+            .iter()
+            .enumerate()
+            .map(|(index, mesh)| {
+                let relative = view.transform_point(mesh.position);
+                previous.insert(index, relative);
+                ObjectDescription {
+                    index,
+                    name: self
+                        .names
+                        .get(&index)
+                        .cloned()
+                        .unwrap_or_else(|| format!("mesh {index}")),
+                    position: mesh.position,
+                    cell: camera.project(mesh.position, dimensions),
+                    distance: mesh.position.distance(camera.position),
+                    motion: self.previous.get(&index).map(|x| relative - *x),
+                    dimensions,
+                }
+            })
+            .collect();
+        self.previous = previous;
+        FrameDescription {
+            frame: self.frame,
+            objects,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::{Camera, Mesh};
+    use crate::{vec2, vec3};
+
+    #[test]
+    fn describing_frames() {
+        let mut renderer = Renderer::new(
+            vec![Mesh::default(), Mesh::default(), Mesh::default()],
+            Camera {
+                position: vec3!(0.0, 0.0, -10.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(1.0, 1.0),
+            },
+        );
+        renderer.meshs[0].position = vec3!(-2.0, -2.0, 0.0);
+        renderer.meshs[2].position = vec3!(0.0, 0.0, -20.0);
+        let mut describer = Describer::new().with_name(0, "ship");
+        let first = describer.describe(&renderer, (40, 20));
+        assert_eq!(first.objects[0].region(), "top left");
+        assert_eq!(first.objects[1].cell, Some(vec2!(20.0, 10.0)));
+        assert_eq!(first.objects[1].distance, 10.0);
+        assert_eq!(
+            first.to_string(),
+            "Frame 1, 3 objects.\nship: top left, 10.4 away.\nmesh 1: center, 10.0 away.\nmesh 2: behind the camera, 10.0 away."
+        );
+
+        renderer.meshs[0].position += vec3!(1.0, 1.0, -1.0);
+        renderer.meshs[1].position.x = 100.0;
+        let second = describer.describe(&renderer, (40, 20));
+        assert_eq!(
+            second.objects[0].directions(),
+            vec!["right", "down", "closer"]
+        );
+        assert_eq!(second.objects[1].region(), "off screen");
+        assert_eq!(
+            second.objects[2].to_string(),
+            "mesh 2: behind the camera, 10.0 away, not moving."
+        );
+    }
+}
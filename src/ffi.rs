@@ -0,0 +1,352 @@
+//! A C ABI, behind the `ffi` feature, so C and C++ terminal applications can embed the renderer. The declarations are in include/ascii_renderer.h, and a library to link against can be built with `cargo rustc --release --features ffi --crate-type staticlib` (or `cdylib`). A renderer is made with ar_renderer_new, given meshes as arrays of vertices and edges, and drawn into buffers the caller owns: one Unicode code point per cell, and optionally the depth of what's drawn in each cell.
+//!
+//! Functions taking a renderer do nothing and return false (or -1) when it's null, and so do ones given arrays that are null or don't make sense.
+//! # Example
+//! ```c
+//! #include "ascii_renderer.h"
+//!
+//! float vertices[] = {-1, 0, 0, 1, 0, 0};
+//! uint32_t edges[] = {0, 1};
+//! uint32_t cells[40 * 20];
+//! float depth[40 * 20];
+//! float position[] = {0, 0, -5};
+//! float rotation[] = {0, 0, 0};
+//!
+//! ArRenderer *renderer = ar_renderer_new();
+//! ar_renderer_set_camera(renderer, position, rotation, 1.0f, 1.0f);
+//! ar_renderer_add_mesh(renderer, vertices, 2, edges, 1, '#');
+//! ar_renderer_render(renderer, 40, 20, cells, depth);
+//! ar_renderer_free(renderer);
+//! ```
+
+use super::char_buffer::CharBuffer;
+use super::rendering::{Camera, Mesh, Renderer};
+use super::{Scalar, Vector2, Vector3};
+
+/// A renderer and the buffer it draws into, which C sees as an opaque ArRenderer.
+pub struct ArRenderer {
+    renderer: Renderer,
+    buffer: CharBuffer,
+}
+
+unsafe fn slice<'a, T>(pointer: *const T, len: usize) -> Option<&'a [T]> {
+    //Empty arrays are allowed to be null
+    if len == 0 {
+        return Some(&[]);
+    }
+    (!pointer.is_null()).then(|| std::slice::from_raw_parts(pointer, len))
+}
+
+unsafe fn vector(pointer: *const f32) -> Option<Vector3> {
+    let xyz = slice(pointer, 3)?;
+    Some(Vector3::new(
+        Scalar::from(xyz[0]),
+        Scalar::from(xyz[1]),
+        Scalar::from(xyz[2]),
+    ))
+}
+
+#[allow(clippy::unnecessary_cast)]
+fn to_f32(x: Scalar) -> f32 {
+    x as f32
+}
+
+#[no_mangle]
+pub extern "C" fn ar_renderer_new() -> *mut ArRenderer {
+    //! A renderer with no meshes and a camera at the origin looking down +z with a fov of 1 radian. It has to be freed with ar_renderer_free.
+    let camera = Camera {
+        position: Vector3::new(0.0, 0.0, 0.0),
+        rotation: Vector3::new(0.0, 0.0, 0.0),
+        fov: Vector2::new(1.0, 1.0),
+    };
+    Box::into_raw(Box::new(ArRenderer {
+        renderer: Renderer::new(vec![], camera),
+        buffer: CharBuffer::new(0, 0),
+    }))
+}
+
+/// Frees a renderer made by ar_renderer_new. Freeing null does nothing.
+/// # Safety
+/// renderer has to be null or from ar_renderer_new, and not used again afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn ar_renderer_free(renderer: *mut ArRenderer) {
+    if !renderer.is_null() {
+        drop(Box::from_raw(renderer));
+    }
+}
+
+/// Moves the camera. position and rotation are arrays of 3 floats (x, y, z), with rotation in radians, and the fov is in radians.
+/// # Safety
+/// renderer has to be null or from ar_renderer_new, and position and rotation null or pointing to 3 floats.
+#[no_mangle]
+pub unsafe extern "C" fn ar_renderer_set_camera(
+    renderer: *mut ArRenderer,
+    position: *const f32,
+    rotation: *const f32,
+    fov_x: f32,
+    fov_y: f32,
+) -> bool {
+    let (Some(renderer), Some(position), Some(rotation)) =
+        (renderer.as_mut(), vector(position), vector(rotation))
+    else {
+        return false;
+    };
+    renderer.renderer.camera = Camera {
+        position,
+        rotation,
+        fov: Vector2::new(Scalar::from(fov_x), Scalar::from(fov_y)),
+    };
+    true
+}
+
+/// Adds a mesh drawn with the code point char, returning its index, or -1 if the counts overflow or char isn't a valid code point. vertices holds vertex_count (x, y, z) triples, and edges holds edge_count pairs of indices into them. Edges to indices past the vertices aren't drawn.
+/// # Safety
+/// renderer has to be null or from ar_renderer_new, vertices null or pointing to vertex_count * 3 floats, and edges null or pointing to edge_count * 2 integers.
+#[no_mangle]
+pub unsafe extern "C" fn ar_renderer_add_mesh(
+    renderer: *mut ArRenderer,
+    vertices: *const f32,
+    vertex_count: usize,
+    edges: *const u32,
+    edge_count: usize,
+    char: u32,
+) -> isize {
+    let (Some(renderer), Some(vertices), Some(edges), Some(char)) = (
+        renderer.as_mut(),
+        vertex_count
+            .checked_mul(3)
+            .and_then(|len| slice(vertices, len)),
+        edge_count.checked_mul(2).and_then(|len| slice(edges, len)),
+        char::from_u32(char),
+    ) else {
+        return -1;
+    };
+    let mut mesh = Mesh::default();
+    mesh.insert_vertices(
+        vertices
+            .chunks_exact(3)
+            .map(|x| Vector3::new(Scalar::from(x[0]), Scalar::from(x[1]), Scalar::from(x[2])))
+            .enumerate()
+            .collect(),
+    );
+    mesh.add_edges(
+        edges
+            .chunks_exact(2)
+            .map(|x| (x[0] as usize, x[1] as usize))
+            .collect(),
+    );
+    mesh.char = char;
+    renderer.renderer.meshs.push(mesh);
+    renderer.renderer.meshs.len() as isize - 1
+}
+
+/// Sets the position, rotation (in radians), and scale of the mesh with the given index, each an array of 3 floats. Returns false if there's no mesh with that index.
+/// # Safety
+/// renderer has to be null or from ar_renderer_new, and position, rotation, and scale null or pointing to 3 floats.
+#[no_mangle]
+pub unsafe extern "C" fn ar_renderer_set_transform(
+    renderer: *mut ArRenderer,
+    mesh: usize,
+    position: *const f32,
+    rotation: *const f32,
+    scale: *const f32,
+) -> bool {
+    let (Some(renderer), Some(position), Some(rotation), Some(scale)) = (
+        renderer.as_mut(),
+        vector(position),
+        vector(rotation),
+        vector(scale),
+    ) else {
+        return false;
+    };
+    let Some(mesh) = renderer.renderer.meshs.get_mut(mesh) else {
+        return false;
+    };
+    mesh.position = position;
+    mesh.rotation = rotation;
+    mesh.scale = scale;
+    true
+}
+
+/// Draws every mesh into cells, an array of width * height code points, row by row from the top left, with ' ' where nothing is drawn. If depth isn't null, it's filled with how far in front of the camera what's drawn in each cell is, or infinity where nothing is, and lines are depth tested like Renderer::draw_with_depth.
+/// # Safety
+/// renderer has to be null or from ar_renderer_new, cells null or pointing to width * height integers, and depth null or pointing to width * height floats.
+#[no_mangle]
+pub unsafe extern "C" fn ar_renderer_render(
+    renderer: *mut ArRenderer,
+    width: usize,
+    height: usize,
+    cells: *mut u32,
+    depth: *mut f32,
+) -> bool {
+    let Some(renderer) = renderer.as_mut() else {
+        return false;
+    };
+    let Some(len) = width.checked_mul(height) else {
+        return false;
+    };
+    if len > 0 && cells.is_null() {
+        return false;
+    }
+    let ArRenderer { renderer, buffer } = renderer;
+    if buffer.dimensions != (width, height) {
+        *buffer = CharBuffer::new(width, height);
+    } else {
+        buffer.fill(' ');
+    }
+    renderer.record_depth = !depth.is_null();
+    renderer.draw(buffer);
+    for y in 0..height {
+        for x in 0..width {
+            let index = y * width + x;
+            *cells.add(index) = buffer.get_char(x, y).unwrap_or(' ') as u32;
+            if !depth.is_null() {
+                *depth.add(index) = renderer.depth_at(x, y).map_or(f32::INFINITY, to_f32);
+            }
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::depth::DepthBuffer;
+    use crate::{vec2, vec3};
+
+    #[test]
+    fn rendering_through_ffi() {
+        let vertices = [-1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let edges = [0, 1, 1, 2];
+        let (position, rotation, scale) = ([0.0, 0.0, -5.0], [0.0; 3], [2.0, 1.0, 1.0]);
+        let mut cells = [0u32; 20 * 10];
+        let mut depth = [0.0f32; 20 * 10];
+        unsafe {
+            let renderer = ar_renderer_new();
+            assert!(ar_renderer_set_camera(
+                renderer,
+                position.as_ptr(),
+                rotation.as_ptr(),
+                1.0,
+                1.0
+            ));
+            let mesh = ar_renderer_add_mesh(
+                renderer,
+                vertices.as_ptr(),
+                3,
+                edges.as_ptr(),
+                2,
+                '#' as u32,
+            );
+            assert_eq!(mesh, 0);
+            assert!(ar_renderer_set_transform(
+                renderer,
+                0,
+                [0.0, 0.0, 1.0].as_ptr(),
+                rotation.as_ptr(),
+                scale.as_ptr()
+            ));
+            assert!(ar_renderer_render(
+                renderer,
+                20,
+                10,
+                cells.as_mut_ptr(),
+                depth.as_mut_ptr()
+            ));
+
+            //The same as drawing the mesh directly
+            let mut mesh = Mesh::default();
+            mesh.insert_vertices(vec![
+                (0, vec3!(-1.0, 0.0, 0.0)),
+                (1, vec3!(1.0, 0.0, 0.0)),
+                (2, vec3!(0.0, 1.0, 0.0)),
+            ]);
+            mesh.add_edges(vec![(0, 1), (1, 2)]);
+            mesh.position = vec3!(0.0, 0.0, 1.0);
+            mesh.scale = vec3!(2.0, 1.0, 1.0);
+            mesh.char = '#';
+            let renderer_direct = Renderer::new(
+                vec![mesh],
+                Camera {
+                    position: vec3!(0.0, 0.0, -5.0),
+                    rotation: vec3!(0.0, 0.0, 0.0),
+                    fov: vec2!(1.0, 1.0),
+                },
+            );
+            let mut buf = CharBuffer::new(20, 10);
+            let mut expected_depth = DepthBuffer::new(20, 10);
+            renderer_direct.draw_with_depth(&mut buf, &mut expected_depth);
+            for y in 0..10 {
+                for x in 0..20 {
+                    assert_eq!(char::from_u32(cells[y * 20 + x]), buf.get_char(x, y));
+                    let expected = expected_depth.get(x, y).map_or(f32::INFINITY, to_f32);
+                    assert_eq!(depth[y * 20 + x], expected);
+                }
+            }
+            assert!(cells.contains(&('#' as u32)));
+
+            //Without a depth buffer it's a normal draw
+            assert!(ar_renderer_render(
+                renderer,
+                20,
+                10,
+                cells.as_mut_ptr(),
+                std::ptr::null_mut()
+            ));
+            let mut buf = CharBuffer::new(20, 10);
+            renderer_direct.draw(&mut buf);
+            assert!((0..200).all(|i| char::from_u32(cells[i]) == buf.get_char(i % 20, i / 20)));
+
+            //Bad input is refused
+            assert_eq!(
+                ar_renderer_add_mesh(renderer, std::ptr::null(), 3, edges.as_ptr(), 2, '#' as u32),
+                -1
+            );
+            assert_eq!(
+                ar_renderer_add_mesh(renderer, vertices.as_ptr(), 3, edges.as_ptr(), 2, 0xD800),
+                -1
+            );
+            assert_eq!(
+                ar_renderer_add_mesh(
+                    renderer,
+                    vertices.as_ptr(),
+                    usize::MAX,
+                    edges.as_ptr(),
+                    2,
+                    '#' as u32
+                ),
+                -1
+            );
+            assert!(!ar_renderer_render(
+                renderer,
+                usize::MAX,
+                2,
+                cells.as_mut_ptr(),
+                std::ptr::null_mut()
+            ));
+            assert!(!ar_renderer_set_transform(
+                renderer,
+                1,
+                position.as_ptr(),
+                rotation.as_ptr(),
+                scale.as_ptr()
+            ));
+            assert!(!ar_renderer_render(
+                renderer,
+                20,
+                10,
+                std::ptr::null_mut(),
+                std::ptr::null_mut()
+            ));
+            assert!(!ar_renderer_render(
+                std::ptr::null_mut(),
+                20,
+                10,
+                cells.as_mut_ptr(),
+                std::ptr::null_mut()
+            ));
+            ar_renderer_free(renderer);
+            ar_renderer_free(std::ptr::null_mut());
+        }
+    }
+}
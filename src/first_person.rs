@@ -0,0 +1,295 @@
+//! A ready-made first-person camera controller: walking and strafing with WASD or the left stick, and looking around with the mouse, the arrow keys, or the right stick. With a ground set, gravity keeps the camera at eye height above it and it can jump, otherwise it flies.
+//!
+//! Terminals can't lock the mouse in place, so mouse look turns by how many cells the pointer moved since the last update, and stops when the pointer reaches the edge of the screen.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::first_person::FirstPerson;
+//! # use ascii_renderer::input::InputMap;
+//! struct Walk {
+//!     renderer: Renderer,
+//!     controller: FirstPerson,
+//!     input: InputMap,
+//! }
+//! impl Logic for Walk {
+//!     fn process(&mut self, screen_buf: &mut CharBuffer, delta: f32) -> ProcessReturn {
+//!         self.controller.update(&self.input, &mut self.renderer.camera, delta);
+//!         self.input.end_frame();
+//!         screen_buf.fill(' ');
+//!         self.renderer.draw(screen_buf);
+//!         ProcessReturn::Continue
+//!     }
+//!     fn input(&mut self, event: &InputEvent) {
+//!         self.input.handle(event);
+//!     }
+//! }
+//! let mut input = InputMap::new();
+//! FirstPerson::bind_defaults(&mut input);
+//! let mut controller = FirstPerson::new();
+//! controller.ground = Some(0.0);
+//! let camera = Camera {
+//!     position: vec3!(0.0, -1.7, -10.0),
+//!     rotation: vec3!(0.0, 0.0, 0.0),
+//!     fov: vec2!(1.2, 1.0),
+//! };
+//! let renderer = Renderer::new(vec![ascii_renderer::create_cube()], camera);
+//! let walk = Walk { renderer, controller, input };
+//! ```
+
+use super::input::{Binding, InputMap};
+use super::rendering::Camera;
+use super::{vec3, Mat4, Scalar, Vector3};
+
+/// How far from straight up or down the camera can look, so it never flips over.
+const PITCH_LIMIT: Scalar = 1.55;
+
+/// Moves and turns a camera from an InputMap like a first-person game, see the module docs. Every setting is a public field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FirstPerson {
+    /// How far the camera is turned to the right of +z, in radians.
+    pub yaw: Scalar,
+    /// How far the camera is looking up, in radians.
+    pub pitch: Scalar,
+    /// In units per second.
+    pub speed: Scalar,
+    /// How many radians the camera turns for each cell the mouse moves, or None to ignore the mouse.
+    pub mouse_sensitivity: Option<Scalar>,
+    /// How many radians per second the look actions turn the camera.
+    pub turn_speed: Scalar,
+    /// Makes moving the mouse or look stick up look down instead.
+    pub invert_y: bool,
+    /// The y of a flat ground to walk on, or None to fly. +y is down, so the camera stays above (at a smaller y than) the ground.
+    pub ground: Option<Scalar>,
+    /// How far above the ground the camera is when standing on it.
+    pub eye_height: Scalar,
+    /// Added to the falling speed each second while above the ground. Positive, since +y is down.
+    pub gravity: Scalar,
+    /// How fast jumping launches the camera upwards, in units per second.
+    pub jump_speed: Scalar,
+    falling: Scalar,
+    last_pointer: Option<(usize, usize)>,
+}
+
+impl FirstPerson {
+    pub const FORWARD: &'static str = "fp_forward";
+    pub const BACK: &'static str = "fp_back";
+    pub const LEFT: &'static str = "fp_left";
+    pub const RIGHT: &'static str = "fp_right";
+    pub const LOOK_LEFT: &'static str = "fp_look_left";
+    pub const LOOK_RIGHT: &'static str = "fp_look_right";
+    pub const LOOK_UP: &'static str = "fp_look_up";
+    pub const LOOK_DOWN: &'static str = "fp_look_down";
+    pub const JUMP: &'static str = "fp_jump";
+    /// Flies up when there's no ground.
+    pub const UP: &'static str = "fp_up";
+    /// Flies down when there's no ground.
+    pub const DOWN: &'static str = "fp_down";
+
+    pub fn new() -> Self {
+        //! A controller facing +z that flies at 4 units per second, with mouse look on.
+        Self {
+            yaw: 0.0,
+            pitch: 0.0,
+            speed: 4.0,
+            mouse_sensitivity: Some(0.05),
+            turn_speed: 2.0,
+            invert_y: false,
+            ground: None,
+            eye_height: 1.7,
+            gravity: 9.8,
+            jump_speed: 5.0,
+            falling: 0.0,
+            last_pointer: None,
+        }
+    }
+    pub fn from_camera(camera: &Camera) -> Self {
+        //! A controller facing the same way as camera, ignoring any roll.
+        let forward = Mat4::rotation(camera.rotation)
+            .transpose()
+            .transform_vector(vec3!(0.0, 0.0, 1.0));
+        Self {
+            yaw: forward.x.atan2(forward.z),
+            pitch: (-forward.y).clamp(-1.0, 1.0).asin(),
+            ..Self::new()
+        }
+    }
+    pub fn bind_defaults(input: &mut InputMap) {
+        //! Binds moving to WASD and the left stick, looking to the arrow keys and right stick, jumping to space and the gamepad's South button, and flying up and down to e and q.
+        let bindings = [
+            (
+                Self::FORWARD,
+                vec![Binding::key("w"), Binding::gamepad_axis("LeftStickY")],
+            ),
+            (Self::BACK, vec![Binding::key("s")]),
+            (Self::LEFT, vec![Binding::key("a")]),
+            (
+                Self::RIGHT,
+                vec![Binding::key("d"), Binding::gamepad_axis("LeftStickX")],
+            ),
+            (Self::LOOK_LEFT, vec![Binding::key("ArrowLeft")]),
+            (
+                Self::LOOK_RIGHT,
+                vec![
+                    Binding::key("ArrowRight"),
+                    Binding::gamepad_axis("RightStickX"),
+                ],
+            ),
+            (
+                Self::LOOK_UP,
+                vec![
+                    Binding::key("ArrowUp"),
+                    Binding::gamepad_axis("RightStickY"),
+                ],
+            ),
+            (Self::LOOK_DOWN, vec![Binding::key("ArrowDown")]),
+            (
+                Self::JUMP,
+                vec![Binding::key(" "), Binding::gamepad_button("South")],
+            ),
+            (Self::UP, vec![Binding::key("e")]),
+            (Self::DOWN, vec![Binding::key("q")]),
+        ];
+        for (action, bindings) in bindings {
+            for binding in bindings {
+                input.bind(action, binding);
+            }
+        }
+    }
+    pub fn forward(&self) -> Vector3 {
+        //! The direction the camera faces.
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        vec3!(sin_yaw * cos_pitch, -sin_pitch, cos_yaw * cos_pitch)
+    }
+    pub fn is_on_ground(&self, camera: &Camera) -> bool {
+        self.ground
+            .is_some_and(|x| camera.position.y + self.eye_height >= x - 0.001)
+    }
+    pub fn update(&mut self, input: &InputMap, camera: &mut Camera, delta: f32) {
+        //! Turns and moves camera by delta seconds of the input's actions and how far the mouse moved since the last update. Call this before InputMap::end_frame, so jumps aren't missed.
+        let delta = delta as Scalar;
+        let axis = |positive: &str, negative: &str| {
+            (input.axis(positive) - input.axis(negative)) as Scalar
+        };
+        let flip = if self.invert_y { -1.0 } else { 1.0 };
+
+        //Looking
+        self.yaw += axis(Self::LOOK_RIGHT, Self::LOOK_LEFT) * self.turn_speed * delta;
+        self.pitch += axis(Self::LOOK_UP, Self::LOOK_DOWN) * self.turn_speed * delta * flip;
+        let pointer = input.get_pointer();
+        if let (Some(sensitivity), Some(now), Some(last)) =
+            (self.mouse_sensitivity, pointer, self.last_pointer)
+        {
+            self.yaw += (now.0 as Scalar - last.0 as Scalar) * sensitivity;
+            self.pitch -= (now.1 as Scalar - last.1 as Scalar) * sensitivity * flip;
+        }
+        self.last_pointer = pointer;
+        self.pitch = self.pitch.clamp(-PITCH_LIMIT, PITCH_LIMIT);
+
+        //Moving along the ground, however far up or down the camera looks
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (forward, right) = (vec3!(sin_yaw, 0.0, cos_yaw), vec3!(cos_yaw, 0.0, -sin_yaw));
+        let mut movement =
+            forward * axis(Self::FORWARD, Self::BACK) + right * axis(Self::RIGHT, Self::LEFT);
+        if self.ground.is_none() {
+            movement.y = axis(Self::DOWN, Self::UP);
+        }
+        if movement.len() > 1.0 {
+            movement = movement.normalize();
+        }
+        camera.position += movement * self.speed * delta;
+
+        if let Some(ground) = self.ground {
+            if self.is_on_ground(camera) && input.is_pressed(Self::JUMP) {
+                self.falling = -self.jump_speed;
+            }
+            self.falling += self.gravity * delta;
+            camera.position.y += self.falling * delta;
+            if camera.position.y + self.eye_height >= ground {
+                camera.position.y = ground - self.eye_height;
+                self.falling = 0.0;
+            }
+        }
+        camera.rotation = Camera::look_rotation(self.forward());
+    }
+}
+
+impl std::default::Default for FirstPerson {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::InputEvent;
+    use crate::{vec2, Vector2};
+
+    fn close(a: Vector3, b: Vector3) -> bool {
+        (a - b).len() < 0.001
+    }
+
+    #[test]
+    fn walking_and_looking() {
+        let mut input = InputMap::new();
+        FirstPerson::bind_defaults(&mut input);
+        let mut camera = Camera {
+            position: vec3!(0.0, -1.7, 0.0),
+            rotation: vec3!(0.0, 0.0, 0.0),
+            fov: vec2!(1.0, 1.0),
+        };
+        let mut controller = FirstPerson::from_camera(&camera);
+        controller.ground = Some(0.0);
+        controller.mouse_sensitivity = Some(0.1);
+
+        //Forward is +z, and strafing right is +x
+        input.handle(&InputEvent::KeyDown("w".to_string()));
+        controller.update(&input, &mut camera, 0.5);
+        assert!(close(camera.position, vec3!(0.0, -1.7, 2.0)));
+        input.handle(&InputEvent::KeyUp("w".to_string()));
+        input.handle(&InputEvent::KeyDown("d".to_string()));
+        controller.update(&input, &mut camera, 0.5);
+        assert!(close(camera.position, vec3!(2.0, -1.7, 2.0)));
+        input.handle(&InputEvent::KeyUp("d".to_string()));
+
+        //The first mouse position only sets where moves are measured from
+        input.handle(&InputEvent::MouseMove(10, 10));
+        controller.update(&input, &mut camera, 0.0);
+        assert_eq!(controller.yaw, 0.0);
+        input.handle(&InputEvent::MouseMove(20, 5));
+        controller.update(&input, &mut camera, 0.0);
+        assert!((controller.yaw - 1.0).abs() < 0.001 && (controller.pitch - 0.5).abs() < 0.001);
+        assert!(close(
+            camera.rotation,
+            Camera::look_rotation(controller.forward())
+        ));
+        assert!(close(
+            FirstPerson::from_camera(&camera).forward(),
+            controller.forward()
+        ));
+        //Looking up doesn't walk into the air
+        input.handle(&InputEvent::KeyDown("w".to_string()));
+        controller.update(&input, &mut camera, 0.1);
+        assert_eq!(camera.position.y, -1.7);
+        input.handle(&InputEvent::KeyUp("w".to_string()));
+
+        //Jumping goes up (-y) and lands back at eye height
+        input.end_frame();
+        input.handle(&InputEvent::KeyDown(" ".to_string()));
+        controller.update(&input, &mut camera, 0.1);
+        assert!(camera.position.y < -1.7 && !controller.is_on_ground(&camera));
+        input.end_frame();
+        for _ in 0..20 {
+            controller.update(&input, &mut camera, 0.1);
+        }
+        assert_eq!(camera.position.y, -1.7);
+        assert!(controller.is_on_ground(&camera));
+
+        //Without a ground, the camera flies
+        controller.ground = None;
+        input.handle(&InputEvent::KeyDown("e".to_string()));
+        controller.update(&input, &mut camera, 0.5);
+        assert!((camera.position.y + 3.7).abs() < 0.001);
+    }
+}
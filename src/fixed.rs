@@ -0,0 +1,379 @@
+//! Deterministic fixed-point math, for renders that come out exactly the same on every platform. Float sin, cos, and atan2 can give slightly different results on different platforms and standard libraries, which can change which cells a line lands on, so lockstep networked demos can drift apart and golden tests can fail on another machine. Setting Renderer::fixed_point makes draw run the transform pipeline with 16.16 Fixed numbers and integer-only trig instead, so the same scene always draws the same cells.
+//!
+//! The fixed-point path draws every mesh's full detail edges (LODs are picked by float distances) and draws lines one cell thick, ignoring Renderer::thickness, record_depth, and supersampling.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! let mut renderer = Renderer::new(
+//!     vec![ascii_renderer::create_cube()],
+//!     Camera {
+//!         position: vec3!(0.0, 0.0, -6.0),
+//!         rotation: vec3!(0.4, 0.6, 0.0),
+//!         fov: vec2!(1.0, 1.0),
+//!     },
+//! );
+//! renderer.fixed_point = true;
+//! let mut buf = CharBuffer::new(30, 30);
+//! renderer.draw(&mut buf);
+//! //The same on every machine, so it can be compared against a saved frame
+//! assert_eq!(buf.to_string(), render_frame(&renderer, 30, 30));
+//! ```
+
+use super::cache::lock;
+use super::char_buffer::CharBuffer;
+#[cfg(not(feature = "std"))]
+use super::float::Float;
+use super::rendering::{push_mesh_lines, Camera, Renderer};
+use super::{Scalar, Vector2, Vector3};
+use core::iter::Sum;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+const FRACTION_BITS: u32 = 16;
+//Trig is worked out with 30 fraction bits, so rounding doesn't pile up before the result is rounded back to 16
+const PRECISE_BITS: u32 = 30;
+const PRECISE_ONE: i64 = 1 << PRECISE_BITS;
+//Minimax coefficients for atan on 0 to 1, from the lowest power of x up, with 30 fraction bits
+const ATAN_COEFFICIENTS: [i64; 6] = [
+    1073717407, -357151731, 207815708, -125018842, 56536072, -12585543,
+];
+
+/// A 16.16 fixed-point number: an i32 counting 65536ths. Overflowing arithmetic saturates instead of wrapping, and dividing by 0 gives the largest number with the dividend's sign.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Fixed(pub i32);
+
+impl Fixed {
+    pub const ZERO: Fixed = Fixed(0);
+    pub const ONE: Fixed = Fixed(1 << FRACTION_BITS);
+    pub const HALF: Fixed = Fixed(1 << (FRACTION_BITS - 1));
+    pub const PI: Fixed = Fixed(205887);
+    pub const FRAC_PI_2: Fixed = Fixed(102944);
+    pub const TAU: Fixed = Fixed(411775);
+
+    pub fn from_int(x: i32) -> Self {
+        Self::saturate((x as i64) << FRACTION_BITS)
+    }
+    pub fn from_scalar(x: Scalar) -> Self {
+        //! The closest Fixed to x, saturating if it's out of range. NaN becomes 0.
+        #[allow(clippy::unnecessary_cast)]
+        Fixed((x as f64 * Self::ONE.0 as f64).round() as i32)
+    }
+    pub fn to_scalar(self) -> Scalar {
+        (self.0 as f64 / Self::ONE.0 as f64) as Scalar
+    }
+    fn saturate(x: i64) -> Self {
+        Fixed(x.clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+    }
+    pub fn abs(self) -> Self {
+        Fixed(self.0.saturating_abs())
+    }
+    pub fn sin_cos(self) -> (Fixed, Fixed) {
+        //! The sine and cosine of an angle in radians, to within about 0.00002.
+        (self.sin(), (self + Self::FRAC_PI_2).sin())
+    }
+    pub fn sin(self) -> Fixed {
+        //Into -pi to pi, then -pi/2 to pi/2 where sin is symmetric around pi/2
+        let mut angle = self.0.rem_euclid(Self::TAU.0);
+        if angle > Self::PI.0 {
+            angle -= Self::TAU.0;
+        }
+        if angle > Self::FRAC_PI_2.0 {
+            angle = Self::PI.0 - angle;
+        } else if angle < -Self::FRAC_PI_2.0 {
+            angle = -Self::PI.0 - angle;
+        }
+        //The Taylor series up to x^9, as x(1 - x²/6(1 - x²/20(1 - x²/42(1 - x²/72))))
+        let x = (angle as i64) << (PRECISE_BITS - FRACTION_BITS);
+        let x2 = (x * x) >> PRECISE_BITS;
+        let mut series = PRECISE_ONE;
+        for divisor in [72, 42, 20, 6] {
+            series = PRECISE_ONE - ((x2 * series) >> PRECISE_BITS) / divisor;
+        }
+        Fixed(round_precise((x * series) >> PRECISE_BITS) as i32)
+    }
+    pub fn atan2(self, x: Fixed) -> Fixed {
+        //! The angle of the point (x, self) from the +x axis, between -pi and pi, the same as Scalar::atan2. To within about 0.00002.
+        if self.0 == 0 && x.0 == 0 {
+            return Self::ZERO;
+        }
+        let (abs_x, abs_y) = ((x.0 as i64).abs(), (self.0 as i64).abs());
+        //atan of a ratio between 0 and 1, using atan(a) = pi/2 - atan(1/a) when y is bigger
+        let (numerator, denominator) = if abs_x >= abs_y {
+            (abs_y, abs_x)
+        } else {
+            (abs_x, abs_y)
+        };
+        let z = (numerator << PRECISE_BITS) / denominator;
+        let z2 = (z * z) >> PRECISE_BITS;
+        let mut series = 0;
+        for coefficient in ATAN_COEFFICIENTS.iter().rev() {
+            series = ((series * z2) >> PRECISE_BITS) + coefficient;
+        }
+        let mut angle = round_precise((series * z) >> PRECISE_BITS) as i32;
+        if abs_x < abs_y {
+            angle = Self::FRAC_PI_2.0 - angle;
+        }
+        if x.0 < 0 {
+            angle = Self::PI.0 - angle;
+        }
+        if self.0 < 0 {
+            angle = -angle;
+        }
+        Fixed(angle)
+    }
+    pub fn round(self) -> i32 {
+        //! The nearest integer, with halves rounded up.
+        ((self.0 as i64 + Self::HALF.0 as i64) >> FRACTION_BITS) as i32
+    }
+}
+
+fn round_precise(x: i64) -> i64 {
+    //From 30 fraction bits to 16
+    let shift = PRECISE_BITS - FRACTION_BITS;
+    (x + (1 << (shift - 1))) >> shift
+}
+
+impl Add for Fixed {
+    type Output = Fixed;
+    fn add(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Fixed;
+    fn sub(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Fixed;
+    fn mul(self, rhs: Fixed) -> Fixed {
+        Fixed::saturate((self.0 as i64 * rhs.0 as i64) >> FRACTION_BITS)
+    }
+}
+
+impl Div for Fixed {
+    type Output = Fixed;
+    fn div(self, rhs: Fixed) -> Fixed {
+        if rhs.0 == 0 {
+            return Fixed(if self.0 < 0 { i32::MIN } else { i32::MAX });
+        }
+        Fixed::saturate(((self.0 as i64) << FRACTION_BITS) / rhs.0 as i64)
+    }
+}
+
+impl Sum for Fixed {
+    fn sum<I: Iterator<Item = Fixed>>(iter: I) -> Fixed {
+        iter.fold(Fixed::ZERO, |sum, x| sum + x)
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Fixed;
+    fn neg(self) -> Fixed {
+        Fixed(self.0.saturating_neg())
+    }
+}
+
+/// A Vector3 of Fixed numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct FixedVector3 {
+    pub x: Fixed,
+    pub y: Fixed,
+    pub z: Fixed,
+}
+
+impl FixedVector3 {
+    pub fn from_vector(vector: Vector3) -> Self {
+        Self {
+            x: Fixed::from_scalar(vector.x),
+            y: Fixed::from_scalar(vector.y),
+            z: Fixed::from_scalar(vector.z),
+        }
+    }
+    pub fn to_vector(self) -> Vector3 {
+        Vector3::new(self.x.to_scalar(), self.y.to_scalar(), self.z.to_scalar())
+    }
+}
+
+/// The top 3 rows of a Mat4 of Fixed numbers, which is all transforms without a projection need.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Affine([[Fixed; 4]; 3]);
+
+impl Affine {
+    fn new(rows: [[Fixed; 4]; 3]) -> Self {
+        Self(rows)
+    }
+    fn linear(m: [[Fixed; 3]; 3]) -> Self {
+        let zero = Fixed::ZERO;
+        Self([
+            [m[0][0], m[0][1], m[0][2], zero],
+            [m[1][0], m[1][1], m[1][2], zero],
+            [m[2][0], m[2][1], m[2][2], zero],
+        ])
+    }
+    fn translation(by: FixedVector3) -> Self {
+        let (one, zero) = (Fixed::ONE, Fixed::ZERO);
+        Self([
+            [one, zero, zero, by.x],
+            [zero, one, zero, by.y],
+            [zero, zero, one, by.z],
+        ])
+    }
+    fn scale(by: FixedVector3) -> Self {
+        let zero = Fixed::ZERO;
+        Self::linear([[by.x, zero, zero], [zero, by.y, zero], [zero, zero, by.z]])
+    }
+    fn rotation(rotation: FixedVector3) -> Self {
+        //The same as Mat4::rotation: around x, then y, then z
+        let (one, zero) = (Fixed::ONE, Fixed::ZERO);
+        let (sin, cos) = rotation.x.sin_cos();
+        let x = Self::linear([[one, zero, zero], [zero, cos, sin], [zero, -sin, cos]]);
+        let (sin, cos) = rotation.y.sin_cos();
+        let y = Self::linear([[cos, zero, -sin], [zero, one, zero], [sin, zero, cos]]);
+        let (sin, cos) = rotation.z.sin_cos();
+        let z = Self::linear([[cos, -sin, zero], [sin, cos, zero], [zero, zero, one]]);
+        z * y * x
+    }
+    fn get(&self, row: usize, column: usize) -> Fixed {
+        //The bottom row is always (0, 0, 0, 1)
+        match self.0.get(row) {
+            Some(row) => row[column],
+            None if column == 3 => Fixed::ONE,
+            None => Fixed::ZERO,
+        }
+    }
+    fn transform_point(&self, point: FixedVector3) -> FixedVector3 {
+        let row = |r: &[Fixed; 4]| r[0] * point.x + r[1] * point.y + r[2] * point.z + r[3];
+        FixedVector3 {
+            x: row(&self.0[0]),
+            y: row(&self.0[1]),
+            z: row(&self.0[2]),
+        }
+    }
+}
+
+impl Mul for Affine {
+    type Output = Affine;
+    fn mul(self, rhs: Affine) -> Affine {
+        let mut rows = [[Fixed::ZERO; 4]; 3];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = (0..4).map(|k| self.0[i][k] * rhs.get(k, j)).sum();
+            }
+        }
+        Affine::new(rows)
+    }
+}
+
+impl Camera {
+    pub fn project_fixed(&self, relative: FixedVector3, dimensions: (usize, usize)) -> Vector2 {
+        //! Projects a view space point to buffer coordinates like project_view_uv, with the same result on every platform.
+        let fov = (
+            Fixed::from_scalar(self.fov.x),
+            Fixed::from_scalar(self.fov.y),
+        );
+        let u = relative.x.atan2(relative.z) / fov.0 + Fixed::HALF;
+        let v = relative.y.atan2(relative.z) / fov.1 + Fixed::HALF;
+        Vector2::new(
+            (u * Fixed::from_int(dimensions.0 as i32)).to_scalar(),
+            (v * Fixed::from_int(dimensions.1 as i32)).to_scalar(),
+        )
+    }
+}
+
+impl Renderer {
+    pub(crate) fn draw_fixed(&self, buffer: &mut CharBuffer) {
+        let camera = &self.camera;
+        let view = Affine::rotation(FixedVector3::from_vector(camera.rotation))
+            * Affine::translation(FixedVector3::from_vector(-camera.position));
+        let mut scratch = lock(&self.scratch.0);
+        let scratch = &mut *scratch;
+        for mesh in self.meshs.iter() {
+            let mesh = &*self.shade(mesh);
+            let model = Affine::translation(FixedVector3::from_vector(mesh.position))
+                * Affine::rotation(FixedVector3::from_vector(mesh.rotation))
+                * Affine::scale(FixedVector3::from_vector(mesh.scale));
+            let model_view = view * model;
+            scratch.points.clear();
+            scratch
+                .points
+                .extend(mesh.get_verticies().iter().map(|(&k, &v)| {
+                    let relative = model_view.transform_point(FixedVector3::from_vector(v));
+                    (k, camera.project_fixed(relative, buffer.dimensions))
+                }));
+            scratch.lines.clear();
+            push_mesh_lines(mesh, mesh.get_edges(), &scratch.points, &mut scratch.lines);
+            for line in scratch.lines.iter() {
+                self.rasterize(buffer, *line);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::render_frame;
+    use crate::{vec2, vec3, Vector2, Vector3};
+    use alloc::vec;
+
+    #[test]
+    fn fixed_point_math() {
+        let f = Fixed::from_scalar;
+        assert_eq!(f(1.5) * f(-2.0), f(-3.0));
+        assert_eq!(f(1.0) / f(4.0), f(0.25));
+        assert_eq!(f(1.0) / Fixed::ZERO, Fixed(i32::MAX));
+        assert_eq!(Fixed(i32::MAX) + Fixed::ONE, Fixed(i32::MAX));
+        assert_eq!(f(2.5).round(), 3);
+        assert_eq!(f(-2.5).round(), -2);
+        for i in -40..40 {
+            let angle = i as Scalar * 0.37;
+            let (sin, cos) = f(angle).sin_cos();
+            assert!((sin.to_scalar() - angle.sin()).abs() < 0.0001, "{angle}");
+            assert!((cos.to_scalar() - angle.cos()).abs() < 0.0001, "{angle}");
+            for x in [-3.0, -0.5, 0.0, 0.2, 4.0] {
+                let atan = f(angle).atan2(f(x)).to_scalar();
+                assert!((atan - angle.atan2(x)).abs() < 0.0001, "{angle} {x}");
+            }
+        }
+        assert_eq!(Fixed::ZERO.atan2(f(-1.0)), Fixed::PI);
+    }
+
+    #[test]
+    fn drawing_with_fixed_point() {
+        let mut cube = crate::create_cube();
+        cube.rotation = vec3!(0.3, -0.7, 0.2);
+        cube.scale = vec3!(1.5, 1.0, 1.0);
+        let mut renderer = Renderer::new(
+            vec![cube],
+            Camera {
+                position: vec3!(0.0, 0.0, -6.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(1.0, 1.0),
+            },
+        );
+        let float = render_frame(&renderer, 20, 10);
+        renderer.fixed_point = true;
+        let fixed = render_frame(&renderer, 20, 10);
+        //Close enough to float math to draw the same cells
+        assert_eq!(fixed, float);
+        //Bit for bit the same everywhere
+        assert_eq!(
+            fixed,
+            concat!(
+                "                                        \n",
+                "                                        \n",
+                "                                        \n",
+                "            + + + + + + + + +           \n",
+                "        + +     + + + + + +   + +       \n",
+                "      + + +     +       + + + + + +     \n",
+                "            + + + +     +       +       \n",
+                "                    + + + +     +       \n",
+                "                            + +         \n",
+                "                                        \n",
+            )
+        );
+    }
+}
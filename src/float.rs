@@ -0,0 +1,64 @@
+//! Float math for builds without the `std` feature, where core doesn't have sqrt, sin, and the rest. Float gives f32 and f64 those methods through libm. It's only imported without std, so builds with std keep using std's own methods, which can round slightly differently.
+
+pub(crate) trait Float: Sized {
+    fn sqrt(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn tan(self) -> Self;
+    fn asin(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn floor(self) -> Self;
+    fn ceil(self) -> Self;
+    fn round(self) -> Self;
+    fn sin_cos(self) -> (Self, Self);
+    fn rem_euclid(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_float {
+    ($type: ty, $sqrt: ident, $sin: ident, $cos: ident, $tan: ident, $asin: ident, $atan2: ident, $floor: ident, $ceil: ident, $round: ident) => {
+        impl Float for $type {
+            fn sqrt(self) -> Self {
+                libm::$sqrt(self)
+            }
+            fn sin(self) -> Self {
+                libm::$sin(self)
+            }
+            fn cos(self) -> Self {
+                libm::$cos(self)
+            }
+            fn tan(self) -> Self {
+                libm::$tan(self)
+            }
+            fn asin(self) -> Self {
+                libm::$asin(self)
+            }
+            fn atan2(self, other: Self) -> Self {
+                libm::$atan2(self, other)
+            }
+            fn floor(self) -> Self {
+                libm::$floor(self)
+            }
+            fn ceil(self) -> Self {
+                libm::$ceil(self)
+            }
+            fn round(self) -> Self {
+                libm::$round(self)
+            }
+            fn sin_cos(self) -> (Self, Self) {
+                (libm::$sin(self), libm::$cos(self))
+            }
+            fn rem_euclid(self, rhs: Self) -> Self {
+                //The same as std's
+                let remainder = self % rhs;
+                if remainder < 0.0 {
+                    remainder + rhs.abs()
+                } else {
+                    remainder
+                }
+            }
+        }
+    };
+}
+
+impl_float!(f32, sqrtf, sinf, cosf, tanf, asinf, atan2f, floorf, ceilf, roundf);
+impl_float!(f64, sqrt, sin, cos, tan, asin, atan2, floor, ceil, round);
@@ -0,0 +1,294 @@
+//! Generative wireframes: L-systems drawn by a 3D turtle (trees, plants, and classic curves), Koch snowflakes, Sierpinski tetrahedra, and the Lorenz attractor. Everything is built once into a Mesh (or a list of points), so draw it like any other mesh and rotate it to show off the shape.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::fractal::{koch_snowflake, LSystem};
+//! let mut tree = LSystem::tree().mesh(4);
+//! tree.position = vec3!(0.0, 3.0, 0.0);
+//! let snowflake = koch_snowflake(3, 4.0);
+//! let renderer = Renderer::new(
+//!     vec![tree, snowflake],
+//!     Camera {
+//!         position: vec3!(0.0, 0.0, -10.0),
+//!         rotation: vec3!(0.0, 0.0, 0.0),
+//!         fov: vec2!(1.0, 1.0),
+//!     },
+//! );
+//! let mut buf = CharBuffer::new(40, 40);
+//! renderer.draw(&mut buf);
+//! ```
+
+use super::rendering::Mesh;
+use super::{Scalar, Vector3};
+use std::collections::HashMap;
+
+fn rotate_around(vector: Vector3, axis: Vector3, angle: Scalar) -> Vector3 {
+    //Rodrigues' rotation formula, axis must be normalized
+    let (sin, cos) = angle.sin_cos();
+    vector * cos + axis.cross(vector) * sin + axis * (axis.dot(vector) * (1.0 - cos))
+}
+
+/// A Lindenmayer system: a string (the axiom) that grows by replacing each char with its rule every iteration, then gets drawn by a turtle following the chars as commands:
+/// - `F` or `G`: move forward, drawing a line
+/// - `f`: move forward without drawing
+/// - `+` / `-`: turn left / right
+/// - `&` / `^`: pitch down / up
+/// - `\` / `/`: roll left / right
+/// - `|`: turn around
+/// - `[` / `]`: start a branch, scaling lines by length_scale, / go back to where it started
+///
+/// Any other chars are only used for rules. The turtle starts at the origin facing up (-y), with its left towards -x.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LSystem {
+    pub axiom: String,
+    pub rules: HashMap<char, String>,
+    /// How far each turn, pitch, and roll goes, in radians.
+    pub angle: Scalar,
+    /// How long the lines drawn by the turtle are.
+    pub length: Scalar,
+    /// What lines are scaled by inside each branch, so branches get smaller the further they are from the trunk.
+    pub length_scale: Scalar,
+}
+
+impl LSystem {
+    pub fn new(axiom: &str, angle: Scalar) -> Self {
+        //! A system without any rules, drawing lines 1.0 long.
+        Self {
+            axiom: axiom.to_string(),
+            rules: HashMap::new(),
+            angle,
+            length: 1.0,
+            length_scale: 1.0,
+        }
+    }
+    pub fn rule(mut self, char: char, replacement: &str) -> Self {
+        //! Adds a rule replacing char with replacement every iteration.
+        self.rules.insert(char, replacement.to_string());
+        self
+    }
+    pub fn tree() -> Self {
+        //! A 3D tree where every branch splits into three, spread around it.
+        let mut tree = Self::new("FX", (25.0 as Scalar).to_radians())
+            .rule('X', "[&FX]/////[&FX]/////[&FX]")
+            .rule('F', "F");
+        tree.length_scale = 0.7;
+        tree
+    }
+    pub fn expand(&self, iterations: usize) -> String {
+        //! The string after applying the rules iterations times.
+        let mut current = self.axiom.clone();
+        for _ in 0..iterations {
+            current = current
+                .chars()
+                .map(|x| match self.rules.get(&x) {
+                    Some(replacement) => replacement.clone(),
+                    None => x.to_string(),
+                })
+                .collect();
+        }
+        current
+    }
+    pub fn mesh(&self, iterations: usize) -> Mesh {
+        //! Draws the string after iterations with the turtle. Lines continuing on from each other share vertices.
+        let mut mesh = Mesh::default();
+        let mut position = Vector3::new(0.0, 0.0, 0.0);
+        let (mut heading, mut left, mut up) = (
+            Vector3::new(0.0, -1.0, 0.0),
+            Vector3::new(-1.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, -1.0),
+        );
+        let mut length = self.length;
+        //The vertex at the turtle's position, if it's drawn a line to here
+        let mut current: Option<usize> = None;
+        let mut stack = vec![];
+        let mut next_index = 0;
+        for command in self.expand(iterations).chars() {
+            match command {
+                'F' | 'G' => {
+                    let start = *current.get_or_insert_with(|| {
+                        mesh.insert_vertex(next_index, position);
+                        next_index += 1;
+                        next_index - 1
+                    });
+                    position += heading * length;
+                    mesh.insert_vertex(next_index, position);
+                    mesh.add_edge((start, next_index));
+                    current = Some(next_index);
+                    next_index += 1;
+                }
+                'f' => {
+                    position += heading * length;
+                    current = None;
+                }
+                '+' | '-' | '&' | '^' | '\\' | '/' | '|' => {
+                    let (axis, angle) = match command {
+                        '+' => (up, self.angle),
+                        '-' => (up, -self.angle),
+                        '&' => (left, self.angle),
+                        '^' => (left, -self.angle),
+                        '\\' => (heading, self.angle),
+                        '/' => (heading, -self.angle),
+                        _ => (up, std::f64::consts::PI as Scalar),
+                    };
+                    heading = rotate_around(heading, axis, angle);
+                    left = rotate_around(left, axis, angle);
+                    up = rotate_around(up, axis, angle);
+                }
+                '[' => {
+                    stack.push((position, heading, left, up, length, current));
+                    length *= self.length_scale;
+                }
+                ']' => {
+                    if let Some(state) = stack.pop() {
+                        (position, heading, left, up, length, current) = state;
+                    }
+                }
+                _ => (),
+            }
+        }
+        mesh
+    }
+}
+
+pub fn polyline_mesh(points: &[Vector3], closed: bool) -> Mesh {
+    //! A mesh of lines joining each point to the next, and the last back to the first if closed.
+    let mut mesh = Mesh::default();
+    for (i, point) in points.iter().enumerate() {
+        mesh.insert_vertex(i, *point);
+        if i > 0 {
+            mesh.add_edge((i - 1, i));
+        }
+    }
+    if closed && points.len() > 2 {
+        mesh.add_edge((points.len() - 1, 0));
+    }
+    mesh
+}
+
+pub fn koch_snowflake(iterations: usize, size: Scalar) -> Mesh {
+    //! A Koch snowflake centered on the origin and facing the camera (in the xy plane), with the triangle it starts as having sides size long. Each iteration has 4 times as many edges, starting from 3.
+    let mut system = LSystem::new("F--F--F", (60.0 as Scalar).to_radians()).rule('F', "F+F--F+F");
+    system.length = size / (3.0 as Scalar).powi(iterations as i32);
+    let mut mesh = system.mesh(iterations);
+    //The turtle starts at a corner, so move the middle to the origin
+    mesh.recenter();
+    mesh
+}
+
+pub fn sierpinski_tetrahedron(iterations: usize, size: Scalar) -> Mesh {
+    //! A tetrahedron with edges size long and its middle at the origin, split into 4 half size tetrahedra at its corners, iterations times. Each iteration has 4 times as many edges, starting from 6.
+    let half = size / 2.0;
+    let corner = half / (2.0 as Scalar).sqrt();
+    let mut tetrahedra = vec![[
+        Vector3::new(corner, corner, corner),
+        Vector3::new(corner, -corner, -corner),
+        Vector3::new(-corner, corner, -corner),
+        Vector3::new(-corner, -corner, corner),
+    ]];
+    for _ in 0..iterations {
+        tetrahedra = tetrahedra
+            .iter()
+            .flat_map(|corners| corners.map(|anchor| corners.map(|x| anchor.lerp(x, 0.5))))
+            .collect();
+    }
+    let mut mesh = Mesh::default();
+    for (i, corners) in tetrahedra.iter().enumerate() {
+        let base = i * 4;
+        for (j, corner) in corners.iter().enumerate() {
+            mesh.insert_vertex(base + j, *corner);
+        }
+        for (a, b) in [(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)] {
+            mesh.add_edge((base + a, base + b));
+        }
+    }
+    mesh
+}
+
+pub fn lorenz(start: Vector3, steps: usize, delta: Scalar) -> Vec<Vector3> {
+    //! The path of a point through the Lorenz attractor (with the classic sigma = 10, rho = 28, beta = 8/3), taking steps steps of delta each. Includes start, so it's steps + 1 points long. Around 0.01 is a good delta, and the attractor is about 50 units across, so scale it down to draw it, ex: as a renderable::Polyline3.
+    let derivative = |p: Vector3| {
+        Vector3::new(
+            10.0 * (p.y - p.x),
+            p.x * (28.0 - p.z) - p.y,
+            p.x * p.y - 8.0 / 3.0 * p.z,
+        )
+    };
+    let mut points = Vec::with_capacity(steps + 1);
+    let mut point = start;
+    points.push(point);
+    for _ in 0..steps {
+        //Runge-Kutta, since the attractor is chaotic and simpler steps drift off quickly
+        let a = derivative(point);
+        let b = derivative(point + a * (delta / 2.0));
+        let c = derivative(point + b * (delta / 2.0));
+        let d = derivative(point + c * delta);
+        point += (a + b * 2.0 + c * 2.0 + d) * (delta / 6.0);
+        points.push(point);
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec3;
+
+    #[test]
+    fn l_systems() {
+        let algae = LSystem::new("A", 0.0).rule('A', "AB").rule('B', "A");
+        assert_eq!(algae.expand(4), "ABAABABA");
+        let tree = LSystem::tree();
+        let grown = tree.expand(3);
+        let mesh = tree.mesh(3);
+        assert_eq!(mesh.get_edges().len(), grown.matches('F').count());
+        //The trunk goes straight up, and branches get shorter
+        let vertices = mesh.get_verticies();
+        assert!((vertices[&1] - vec3!(0.0, -1.0, 0.0)).length() < 1e-5);
+        assert!(vertices.values().all(|x| x.y <= 1e-5));
+        //Moving without drawing, and turning around, on the xy plane
+        let square = LSystem::new("F+F+F+F", (90.0 as Scalar).to_radians()).mesh(0);
+        assert_eq!(square.get_edges().len(), 4);
+        assert!(square.get_verticies()[&4].length() < 1e-5);
+        let gap = LSystem::new("Ff|F", 1.0).mesh(0);
+        assert_eq!(gap.get_edges(), &vec![(0, 1), (2, 3)]);
+        assert!((gap.get_verticies()[&3] - vec3!(0.0, -1.0, 0.0)).length() < 1e-5);
+    }
+
+    #[test]
+    fn fractals() {
+        let snowflake = koch_snowflake(2, 3.0);
+        assert_eq!(snowflake.get_edges().len(), 48);
+        //The snowflake closes back on itself
+        let vertices = snowflake.get_verticies();
+        assert!((vertices[&0] - vertices[&48]).length() < 1e-4);
+        let global = snowflake.get_global_verticies();
+        let middle =
+            global.values().fold(vec3!(0.0, 0.0, 0.0), |a, b| a + *b) / global.len() as Scalar;
+        assert!(middle.length() < 0.1, "{middle:?}");
+        let tetrahedron = sierpinski_tetrahedron(2, 2.0);
+        assert_eq!(tetrahedron.get_edges().len(), 96);
+        let corners = sierpinski_tetrahedron(0, 2.0);
+        assert!(
+            (corners.get_verticies()[&0].distance(corners.get_verticies()[&1]) - 2.0).abs() < 1e-5
+        );
+        assert_eq!(
+            polyline_mesh(&[vec3!(0.0, 0.0, 0.0); 3], true)
+                .get_edges()
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn lorenz_attractor() {
+        let points = lorenz(vec3!(1.0, 1.0, 1.0), 2000, 0.01);
+        assert_eq!(points.len(), 2001);
+        //It keeps circling the attractor instead of settling or flying off
+        assert!(points
+            .iter()
+            .all(|x| x.x.abs() < 30.0 && x.z > -1.0 && x.z < 60.0));
+        assert!(
+            points[1500..].iter().any(|x| x.x > 5.0) && points[1500..].iter().any(|x| x.x < -5.0)
+        );
+    }
+}
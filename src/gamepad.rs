@@ -0,0 +1,137 @@
+//! Gamepad input through [gilrs](https://docs.rs/gilrs), behind the `gilrs` feature. Gamepad events become the same InputEvents as keys, so they can be bound to actions in an InputMap alongside the keyboard, and analog sticks can be read with InputMap::axis.
+//! # Example
+//! ```no_run
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::gamepad::Gamepads;
+//! # use ascii_renderer::input::{Binding, InputMap};
+//! struct Orbit {
+//!     gamepads: Gamepads,
+//!     input: InputMap,
+//!     angle: f32,
+//! }
+//! impl Logic for Orbit {
+//!     fn process(&mut self, screen_buf: &mut CharBuffer, delta: f32) -> ProcessReturn {
+//!         for event in self.gamepads.poll() {
+//!             self.input.handle(&event);
+//!         }
+//!         self.angle += self.input.axis("orbit") * delta;
+//!         self.input.end_frame();
+//!         ProcessReturn::Continue
+//!     }
+//! }
+//! let mut input = InputMap::new();
+//! input.bind("orbit", Binding::gamepad_axis("LeftStickX"));
+//! let orbit = Orbit { gamepads: Gamepads::new().unwrap(), input, angle: 0.0 };
+//! ```
+
+use super::runner::InputEvent;
+use gilrs::{Axis, Button, EventType, Gilrs};
+
+/// Every connected gamepad. Gamepads can be plugged in or out while it's running, and each one's id stays the same while it's connected.
+pub struct Gamepads {
+    gilrs: Gilrs,
+}
+
+impl std::fmt::Debug for Gamepads {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Gamepads")
+            .field("connected", &self.gilrs.gamepads().count())
+            .finish()
+    }
+}
+
+impl Gamepads {
+    pub fn new() -> Result<Self, Box<gilrs::Error>> {
+        //! Starts listening for gamepads. This fails if the platform has no gamepad support.
+        Ok(Self {
+            gilrs: Gilrs::new().map_err(Box::new)?,
+        })
+    }
+    pub fn poll(&mut self) -> Vec<InputEvent> {
+        //! Every gamepad event since the last poll, ex: to call once at the start of Logic::process.
+        let mut events = vec![];
+        while let Some(event) = self.gilrs.next_event() {
+            let id = event.id.into();
+            match event.event {
+                EventType::ButtonPressed(button, _) => {
+                    events.extend(button_event(id, button, true))
+                }
+                EventType::ButtonReleased(button, _) => {
+                    events.extend(button_event(id, button, false))
+                }
+                EventType::ButtonChanged(button, value, _) => {
+                    events.extend(trigger_event(id, button, value))
+                }
+                EventType::AxisChanged(axis, value, _) => {
+                    events.extend(axis_event(id, axis, value))
+                }
+                _ => {}
+            }
+        }
+        events
+    }
+    pub fn connected(&self) -> Vec<usize> {
+        //! The ids of every connected gamepad.
+        self.gilrs.gamepads().map(|(id, _)| id.into()).collect()
+    }
+}
+
+fn button_event(id: usize, button: Button, down: bool) -> Option<InputEvent> {
+    if button == Button::Unknown {
+        return None;
+    }
+    let name = format!("{button:?}");
+    Some(if down {
+        InputEvent::GamepadDown(id, name)
+    } else {
+        InputEvent::GamepadUp(id, name)
+    })
+}
+
+/// Analog triggers are reported as buttons that change by how far they're pulled, so those are turned into axes. Other buttons are left to button_event.
+fn trigger_event(id: usize, button: Button, value: f32) -> Option<InputEvent> {
+    match button {
+        Button::LeftTrigger2 | Button::RightTrigger2 => {
+            Some(InputEvent::GamepadAxis(id, format!("{button:?}"), value))
+        }
+        _ => None,
+    }
+}
+
+fn axis_event(id: usize, axis: Axis, value: f32) -> Option<InputEvent> {
+    if axis == Axis::Unknown {
+        return None;
+    }
+    Some(InputEvent::GamepadAxis(id, format!("{axis:?}"), value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_events() {
+        assert_eq!(
+            button_event(2, Button::South, true),
+            Some(InputEvent::GamepadDown(2, "South".to_string()))
+        );
+        assert_eq!(
+            button_event(0, Button::DPadUp, false),
+            Some(InputEvent::GamepadUp(0, "DPadUp".to_string()))
+        );
+        assert_eq!(button_event(0, Button::Unknown, true), None);
+        assert_eq!(
+            axis_event(1, Axis::LeftStickX, -0.5),
+            Some(InputEvent::GamepadAxis(1, "LeftStickX".to_string(), -0.5))
+        );
+        assert_eq!(
+            trigger_event(0, Button::RightTrigger2, 0.25),
+            Some(InputEvent::GamepadAxis(
+                0,
+                "RightTrigger2".to_string(),
+                0.25
+            ))
+        );
+        assert_eq!(trigger_event(0, Button::South, 1.0), None);
+    }
+}
@@ -0,0 +1,328 @@
+use super::rendering::Mesh;
+use super::{Scalar, Vector3};
+
+/// Used to avoid dividing by (nearly) zero when things are parallel.
+const EPSILON: Scalar = 1e-6;
+
+/// A half-infinite line starting at origin. The direction doesn't need to be normalized, but distances returned by intersection tests are in multiples of its length.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    pub origin: Vector3,
+    pub direction: Vector3,
+}
+
+/// The line segment between two points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Segment {
+    pub start: Vector3,
+    pub end: Vector3,
+}
+
+/// An infinite plane made up of all points p where `normal.dot(p) == distance`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plane {
+    pub normal: Vector3,
+    pub distance: Scalar,
+}
+
+/// An axis aligned bounding box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vector3,
+    pub max: Vector3,
+}
+
+impl Ray {
+    pub fn new(origin: Vector3, direction: Vector3) -> Self {
+        Self { origin, direction }
+    }
+    pub fn at(&self, t: Scalar) -> Vector3 {
+        //! The point t lengths of the direction along the ray.
+        self.origin + self.direction * t
+    }
+    pub fn intersect_triangle(&self, triangle: [Vector3; 3]) -> Option<Scalar> {
+        //! Möller–Trumbore ray/triangle intersection. Returns the t of the hit (see at()), hitting both the front and back face.
+        let edge1 = triangle[1] - triangle[0];
+        let edge2 = triangle[2] - triangle[0];
+        let p = self.direction.cross(edge2);
+        let determinant = edge1.dot(p);
+        if determinant.abs() < EPSILON {
+            return None;
+        }
+        let inverse = 1.0 / determinant;
+        let offset = self.origin - triangle[0];
+        let u = offset.dot(p) * inverse;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+        let q = offset.cross(edge1);
+        let v = self.direction.dot(q) * inverse;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+        let t = edge2.dot(q) * inverse;
+        if t >= 0.0 {
+            Some(t)
+        } else {
+            None
+        }
+    }
+    pub fn intersect_aabb(&self, aabb: &Aabb) -> Option<Scalar> {
+        //! Slab test. Returns the t where the ray enters the box, or 0.0 if it starts inside of it.
+        let mut t_min: Scalar = 0.0;
+        let mut t_max = Scalar::INFINITY;
+        for (origin, direction, min, max) in [
+            (self.origin.x, self.direction.x, aabb.min.x, aabb.max.x),
+            (self.origin.y, self.direction.y, aabb.min.y, aabb.max.y),
+            (self.origin.z, self.direction.z, aabb.min.z, aabb.max.z),
+        ] {
+            if direction.abs() < EPSILON {
+                if origin < min || origin > max {
+                    return None;
+                }
+                continue;
+            }
+            let t1 = (min - origin) / direction;
+            let t2 = (max - origin) / direction;
+            t_min = t_min.max(t1.min(t2));
+            t_max = t_max.min(t1.max(t2));
+            if t_min > t_max {
+                return None;
+            }
+        }
+        Some(t_min)
+    }
+    pub fn intersect_plane(&self, plane: &Plane) -> Option<Scalar> {
+        let denominator = plane.normal.dot(self.direction);
+        if denominator.abs() < EPSILON {
+            return None;
+        }
+        let t = (plane.distance - plane.normal.dot(self.origin)) / denominator;
+        if t >= 0.0 {
+            Some(t)
+        } else {
+            None
+        }
+    }
+}
+
+impl Segment {
+    pub fn new(start: Vector3, end: Vector3) -> Self {
+        Self { start, end }
+    }
+    pub fn len(&self) -> Scalar {
+        (self.end - self.start).len()
+    }
+    pub fn intersect_plane(&self, plane: &Plane) -> Option<Vector3> {
+        //! Returns the point where the segment crosses the plane, if it does.
+        let start_distance = plane.signed_distance(self.start);
+        let end_distance = plane.signed_distance(self.end);
+        if (start_distance > 0.0 && end_distance > 0.0)
+            || (start_distance < 0.0 && end_distance < 0.0)
+        {
+            return None;
+        }
+        let denominator = start_distance - end_distance;
+        if denominator.abs() < EPSILON {
+            //The whole segment lies on the plane
+            return Some(self.start);
+        }
+        Some(self.start.lerp(self.end, start_distance / denominator))
+    }
+    pub fn clip_to_plane(&self, plane: &Plane) -> Option<Segment> {
+        //! Cuts off the part of the segment behind the plane (the side the normal points away from). Returns None if all of it is behind the plane.
+        let start_distance = plane.signed_distance(self.start);
+        let end_distance = plane.signed_distance(self.end);
+        match (start_distance >= 0.0, end_distance >= 0.0) {
+            (true, true) => Some(*self),
+            (false, false) => None,
+            _ => {
+                let crossing = self
+                    .start
+                    .lerp(self.end, start_distance / (start_distance - end_distance));
+                if start_distance >= 0.0 {
+                    Some(Segment::new(self.start, crossing))
+                } else {
+                    Some(Segment::new(crossing, self.end))
+                }
+            }
+        }
+    }
+}
+
+impl Plane {
+    pub fn new(normal: Vector3, distance: Scalar) -> Self {
+        //! The normal is normalized.
+        let len = normal.len();
+        Self {
+            normal: normal / len,
+            distance: distance / len,
+        }
+    }
+    pub fn from_point_normal(point: Vector3, normal: Vector3) -> Self {
+        let normal = normal.normalize();
+        Self {
+            normal,
+            distance: normal.dot(point),
+        }
+    }
+    pub fn signed_distance(&self, point: Vector3) -> Scalar {
+        //! Positive in front of the plane (the side the normal points to), negative behind it.
+        self.normal.dot(point) - self.distance
+    }
+    pub fn project_point(&self, point: Vector3) -> Vector3 {
+        //! The closest point on the plane.
+        point - self.normal * self.signed_distance(point)
+    }
+}
+
+impl Aabb {
+    pub fn new(min: Vector3, max: Vector3) -> Self {
+        Self { min, max }
+    }
+    pub fn from_points(points: impl IntoIterator<Item = Vector3>) -> Option<Self> {
+        //! The smallest box containing all of the points, or None if there aren't any.
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(Self::new(first, first), |mut accum, point| {
+            accum.expand(point);
+            accum
+        }))
+    }
+    pub fn expand(&mut self, point: Vector3) {
+        //! Grows the box to contain the point.
+        self.min = Vector3::new(
+            self.min.x.min(point.x),
+            self.min.y.min(point.y),
+            self.min.z.min(point.z),
+        );
+        self.max = Vector3::new(
+            self.max.x.max(point.x),
+            self.max.y.max(point.y),
+            self.max.z.max(point.z),
+        );
+    }
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        let mut ret = *self;
+        ret.expand(other.min);
+        ret.expand(other.max);
+        ret
+    }
+    pub fn center(&self) -> Vector3 {
+        (self.min + self.max) / 2.0
+    }
+    pub fn size(&self) -> Vector3 {
+        self.max - self.min
+    }
+    pub fn corners(&self) -> [Vector3; 8] {
+        let (min, max) = (self.min, self.max);
+        [
+            Vector3::new(min.x, min.y, min.z),
+            Vector3::new(max.x, min.y, min.z),
+            Vector3::new(min.x, max.y, min.z),
+            Vector3::new(max.x, max.y, min.z),
+            Vector3::new(min.x, min.y, max.z),
+            Vector3::new(max.x, min.y, max.z),
+            Vector3::new(min.x, max.y, max.z),
+            Vector3::new(max.x, max.y, max.z),
+        ]
+    }
+    pub fn contains(&self, point: Vector3) -> bool {
+        point.x >= self.min.x
+            && point.x <= self.max.x
+            && point.y >= self.min.y
+            && point.y <= self.max.y
+            && point.z >= self.min.z
+            && point.z <= self.max.z
+    }
+    pub fn closest_point(&self, point: Vector3) -> Vector3 {
+        //! The point in (or on) the box closest to the given point.
+        Vector3::new(
+            point.x.clamp(self.min.x, self.max.x),
+            point.y.clamp(self.min.y, self.max.y),
+            point.z.clamp(self.min.z, self.max.z),
+        )
+    }
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        //! True if the boxes overlap or touch.
+        self.min.x <= other.max.x
+            && self.max.x >= other.min.x
+            && self.min.y <= other.max.y
+            && self.max.y >= other.min.y
+            && self.min.z <= other.max.z
+            && self.max.z >= other.min.z
+    }
+}
+
+impl Mesh {
+    pub fn bounding_box(&self) -> Option<Aabb> {
+        //! The global space bounding box of the mesh's vertices. Returns None if the mesh has no vertices.
+        Aabb::from_points(self.get_global_verticies().into_values())
+    }
+    pub fn local_bounding_box(&self) -> Option<Aabb> {
+        //! The bounding box of the mesh's vertices before its transform is applied.
+        Aabb::from_points(self.get_verticies().values().copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec3;
+
+    #[test]
+    fn ray_triangle() {
+        let triangle = [
+            vec3!(0.0, 0.0, 5.0),
+            vec3!(1.0, 0.0, 5.0),
+            vec3!(0.0, 1.0, 5.0),
+        ];
+        let ray = Ray::new(vec3!(0.25, 0.25, 0.0), vec3!(0.0, 0.0, 1.0));
+        assert_eq!(ray.intersect_triangle(triangle), Some(5.0));
+        let miss = Ray::new(vec3!(1.0, 1.0, 0.0), vec3!(0.0, 0.0, 1.0));
+        assert_eq!(miss.intersect_triangle(triangle), None);
+        let behind = Ray::new(vec3!(0.25, 0.25, 0.0), vec3!(0.0, 0.0, -1.0));
+        assert_eq!(behind.intersect_triangle(triangle), None);
+    }
+
+    #[test]
+    fn ray_aabb() {
+        let aabb = Aabb::new(vec3!(-1.0, -1.0, -1.0), vec3!(1.0, 1.0, 1.0));
+        let ray = Ray::new(vec3!(0.0, 0.0, -5.0), vec3!(0.0, 0.0, 1.0));
+        assert_eq!(ray.intersect_aabb(&aabb), Some(4.0));
+        let inside = Ray::new(vec3!(0.0, 0.0, 0.0), vec3!(1.0, 1.0, 0.0));
+        assert_eq!(inside.intersect_aabb(&aabb), Some(0.0));
+        let miss = Ray::new(vec3!(2.0, 0.0, -5.0), vec3!(0.0, 0.0, 1.0));
+        assert_eq!(miss.intersect_aabb(&aabb), None);
+    }
+
+    #[test]
+    fn segment_plane() {
+        let plane = Plane::from_point_normal(vec3!(0.0, 1.0, 0.0), vec3!(0.0, 2.0, 0.0));
+        let segment = Segment::new(vec3!(0.0, 0.0, 0.0), vec3!(0.0, 4.0, 4.0));
+        assert_eq!(segment.intersect_plane(&plane), Some(vec3!(0.0, 1.0, 1.0)));
+        assert_eq!(
+            segment.clip_to_plane(&plane),
+            Some(Segment::new(vec3!(0.0, 1.0, 1.0), vec3!(0.0, 4.0, 4.0)))
+        );
+        let below = Segment::new(vec3!(0.0, 0.0, 0.0), vec3!(3.0, 0.5, 0.0));
+        assert_eq!(below.intersect_plane(&plane), None);
+        assert_eq!(below.clip_to_plane(&plane), None);
+    }
+
+    #[test]
+    fn aabb_overlap() {
+        let a = Aabb::new(vec3!(0.0, 0.0, 0.0), vec3!(2.0, 2.0, 2.0));
+        let b = Aabb::new(vec3!(1.0, 1.0, 1.0), vec3!(3.0, 3.0, 3.0));
+        let c = Aabb::new(vec3!(2.5, 0.0, 0.0), vec3!(3.0, 1.0, 1.0));
+        assert!(a.intersects(&b));
+        assert!(!a.intersects(&c));
+        assert_eq!(
+            a.union(&c),
+            Aabb::new(vec3!(0.0, 0.0, 0.0), vec3!(3.0, 2.0, 2.0))
+        );
+        let cube = crate::create_cube().bounding_box().unwrap();
+        assert_eq!(cube.center(), vec3!(0.0, 0.0, 0.0));
+        assert_eq!(cube.size(), vec3!(2.0, 2.0, 2.0));
+    }
+}
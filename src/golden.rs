@@ -0,0 +1,306 @@
+//! Golden-file regression tests: a scene is run headless for a few frames, and the frames are compared against ones saved from a run known to be right, failing with a readable diff of the rows that changed. This catches rendering changes no unit test would think to check, like lines disappearing while the camera zooms through an object.
+//!
+//! The first time a test runs (or whenever the UPDATE_GOLDEN environment variable is set) the frames are saved instead of compared, so after an intended change the files are updated with `UPDATE_GOLDEN=1 cargo test` and the difference can be reviewed in version control. Each frame is saved with its CharBuffer::hash and with every row between '|'s, so trailing spaces survive editors.
+//! # Example
+//! ```no_run
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::golden;
+//! struct Spin(Renderer);
+//! impl Logic for Spin {
+//!     fn process(&mut self, screen_buf: &mut CharBuffer, delta: f32) -> ProcessReturn {
+//!         self.0.meshs[0].rotation.y += Scalar::from(delta);
+//!         screen_buf.fill(' ');
+//!         self.0.draw(screen_buf);
+//!         ProcessReturn::Continue
+//!     }
+//! }
+//! let renderer = Renderer::new(
+//!     vec![ascii_renderer::create_cube()],
+//!     Camera {
+//!         position: vec3!(0.0, 0.0, -5.0),
+//!         rotation: vec3!(0.0, 0.0, 0.0),
+//!         fov: vec2!(1.0, 1.0),
+//!     },
+//! );
+//! let frames = golden::run_headless(Spin(renderer), (40, 20), 10, 0.1);
+//! golden::assert_golden("tests/golden/spin.txt", &frames);
+//! ```
+
+use super::char_buffer::CharBuffer;
+use super::runner::{CallbackBackend, Logic, ProcessReturn, Runner};
+use std::fmt;
+use std::path::Path;
+
+/// Set to anything to save frames instead of comparing them.
+pub const UPDATE_VAR: &str = "UPDATE_GOLDEN";
+
+/// Why frames didn't match a golden file.
+#[derive(Debug)]
+pub enum GoldenError {
+    Io(std::io::Error),
+    /// The golden file couldn't be read back, with the line it went wrong on.
+    Malformed(usize),
+    /// A different number of frames were made than were saved.
+    FrameCount {
+        expected: usize,
+        actual: usize,
+    },
+    /// The first frame that changed, with a diff of it from diff.
+    Mismatch {
+        frame: usize,
+        diff: String,
+    },
+}
+
+impl fmt::Display for GoldenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoldenError::Io(err) => write!(f, "couldn't read or write the golden file: {err}"),
+            GoldenError::Malformed(line) => {
+                write!(f, "the golden file is malformed at line {line}")
+            }
+            GoldenError::FrameCount { expected, actual } => {
+                write!(f, "expected {expected} frames but there were {actual}")
+            }
+            GoldenError::Mismatch { frame, diff } => {
+                write!(
+                    f,
+                    "frame {frame} doesn't match the golden file (set {UPDATE_VAR}=1 to update it):\n{diff}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for GoldenError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GoldenError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for GoldenError {
+    fn from(err: std::io::Error) -> Self {
+        GoldenError::Io(err)
+    }
+}
+
+pub fn run_headless<L: Logic>(
+    logic: L,
+    dimensions: (usize, usize),
+    frames: usize,
+    delta: f32,
+) -> Vec<CharBuffer> {
+    //! Runs the logic for up to the given number of frames with a fixed delta, without showing or waiting for anything, returning every frame. Stops early if process returns End.
+    let backend = CallbackBackend::new(|_: &str| ());
+    let mut runner = Runner::with_backend(dimensions.0, dimensions.1, 60, logic, backend);
+    let mut shown = Vec::with_capacity(frames);
+    for _ in 0..frames {
+        let ret = runner.frame(delta, true);
+        shown.push(runner.get_shown().clone());
+        if let ProcessReturn::End = ret {
+            break;
+        }
+    }
+    shown
+}
+
+pub fn diff(expected: &CharBuffer, actual: &CharBuffer) -> Option<String> {
+    //! The rows that differ between two frames, each shown as "-" for the expected row, "+" for the actual row, and "^" under the chars that changed. None if they're the same.
+    //! # Example
+    //! ```
+    //! # use ascii_renderer::prelude::*;
+    //! # use ascii_renderer::golden::diff;
+    //! let expected = CharBuffer::from_text("ab\ncd");
+    //! let actual = CharBuffer::from_text("ab\nxd");
+    //! assert_eq!(diff(&expected, &actual).unwrap(), "row 1:\n- |cd|\n+ |xd|\n   ^\n");
+    //! ```
+    if expected.dimensions == actual.dimensions && expected.data == actual.data {
+        return None;
+    }
+    let mut out = String::new();
+    if expected.dimensions != actual.dimensions {
+        out.push_str(&format!(
+            "expected {}x{} but it was {}x{}\n",
+            expected.dimensions.0, expected.dimensions.1, actual.dimensions.0, actual.dimensions.1
+        ));
+    }
+    let empty = vec![];
+    for y in 0..expected.data.len().max(actual.data.len()) {
+        let old = expected.data.get(y).unwrap_or(&empty);
+        let new = actual.data.get(y).unwrap_or(&empty);
+        if old == new {
+            continue;
+        }
+        let marks: String = (0..old.len().max(new.len()))
+            .map(|x| if old.get(x) == new.get(x) { ' ' } else { '^' })
+            .collect();
+        out.push_str(&format!(
+            "row {y}:\n- |{}|\n+ |{}|\n   {}\n",
+            old.iter().collect::<String>(),
+            new.iter().collect::<String>(),
+            marks.trim_end()
+        ));
+    }
+    Some(out)
+}
+
+pub fn to_golden(frames: &[CharBuffer]) -> String {
+    //! Frames in the golden file format, each as a "frame" line with its index and hash, then its rows between '|'s.
+    let mut out = String::new();
+    for (i, frame) in frames.iter().enumerate() {
+        out.push_str(&format!(
+            "frame {i} {}x{} {:016x}\n",
+            frame.dimensions.0,
+            frame.dimensions.1,
+            frame.hash()
+        ));
+        for row in frame.data.iter() {
+            out.push('|');
+            out.extend(row.iter());
+            out.push_str("|\n");
+        }
+    }
+    out
+}
+
+pub fn from_golden(text: &str) -> Result<Vec<CharBuffer>, GoldenError> {
+    //! Reads back frames saved with to_golden.
+    let mut frames = vec![];
+    let mut lines = text.lines().enumerate();
+    while let Some((number, line)) = lines.next() {
+        let Some((width, height)) = line
+            .split(' ')
+            .nth(2)
+            .and_then(|x| x.split_once('x'))
+            .and_then(|(x, y)| Some((x.parse().ok()?, y.parse().ok()?)))
+        else {
+            return Err(GoldenError::Malformed(number + 1));
+        };
+        let mut frame = CharBuffer::new(width, height);
+        for y in 0..height {
+            let (number, row) = lines.next().ok_or(GoldenError::Malformed(number + 1))?;
+            let Some(row) = row.strip_prefix('|').and_then(|x| x.strip_suffix('|')) else {
+                return Err(GoldenError::Malformed(number + 1));
+            };
+            if row.chars().count() != width {
+                return Err(GoldenError::Malformed(number + 1));
+            }
+            frame.write_str(0, y, row);
+        }
+        frames.push(frame);
+    }
+    Ok(frames)
+}
+
+pub fn check(path: impl AsRef<Path>, frames: &[CharBuffer]) -> Result<(), GoldenError> {
+    //! Compares frames against the golden file at path, or saves them there if it doesn't exist yet or UPDATE_GOLDEN is set. Folders in the path are made as needed.
+    check_with(
+        path.as_ref(),
+        frames,
+        std::env::var_os(UPDATE_VAR).is_some(),
+    )
+}
+
+fn check_with(path: &Path, frames: &[CharBuffer], update: bool) -> Result<(), GoldenError> {
+    if update || !path.exists() {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        return Ok(std::fs::write(path, to_golden(frames))?);
+    }
+    let expected = from_golden(&std::fs::read_to_string(path)?)?;
+    for (frame, (expected, actual)) in expected.iter().zip(frames).enumerate() {
+        //Only diffed when the hashes differ, which is the same thing but faster
+        if expected.hash() != actual.hash() {
+            if let Some(diff) = diff(expected, actual) {
+                return Err(GoldenError::Mismatch { frame, diff });
+            }
+        }
+    }
+    if expected.len() != frames.len() {
+        return Err(GoldenError::FrameCount {
+            expected: expected.len(),
+            actual: frames.len(),
+        });
+    }
+    Ok(())
+}
+
+#[track_caller]
+pub fn assert_golden(path: impl AsRef<Path>, frames: &[CharBuffer]) {
+    //! Same as check, but panics with the error, for use in tests.
+    if let Err(err) = check(path, frames) {
+        panic!("{err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::{Camera, Renderer};
+    use crate::{vec2, vec3, Vector2, Vector3};
+
+    /// Flies the camera through a cube and out the other side.
+    struct ZoomThrough(Renderer);
+
+    impl Logic for ZoomThrough {
+        fn process(&mut self, screen_buf: &mut CharBuffer, delta: f32) -> ProcessReturn {
+            screen_buf.fill(' ');
+            self.0.draw(screen_buf);
+            self.0.camera.position.z += crate::Scalar::from(delta);
+            ProcessReturn::Continue
+        }
+    }
+
+    fn zoom_through() -> Vec<CharBuffer> {
+        let renderer = Renderer::new(
+            vec![crate::create_cube()],
+            Camera {
+                position: vec3!(0.0, 0.0, -4.5),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(1.5, 1.5),
+            },
+        );
+        run_headless(ZoomThrough(renderer), (24, 12), 8, 1.0)
+    }
+
+    #[test]
+    fn zooming_through_a_cube() {
+        assert_golden(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/golden/zoom_through.txt"),
+            &zoom_through(),
+        );
+    }
+
+    #[test]
+    fn golden_files() {
+        let frames = zoom_through();
+        let text = to_golden(&frames);
+        assert_eq!(from_golden(&text).unwrap(), frames);
+        assert!(matches!(
+            from_golden("frame 0 2x2 0\n|ab|\n|c|\n"),
+            Err(GoldenError::Malformed(3))
+        ));
+
+        let path = std::env::temp_dir().join(format!("golden_{}.txt", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        check_with(&path, &frames, false).unwrap();
+        check_with(&path, &frames, false).unwrap();
+        let mut changed = frames.clone();
+        changed[2].set_char(3, 4, '%').unwrap();
+        let err = check_with(&path, &changed, false).unwrap_err();
+        assert!(matches!(err, GoldenError::Mismatch { frame: 2, .. }));
+        assert!(err.to_string().contains("row 4:\n- |"));
+        assert!(matches!(
+            check_with(&path, &frames[..3], false),
+            Err(GoldenError::FrameCount {
+                expected: 8,
+                actual: 3
+            })
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
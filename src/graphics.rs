@@ -0,0 +1,281 @@
+//! Shows frames as images on terminals that can display them, using either the Sixel or the Kitty graphics protocol. Every cell of the buffer becomes a small block of pixels instead of a char taking up two columns, so a much bigger buffer fits in the same space and lines come out smooth. On terminals that can't show images, frames are printed as chars as usual.
+//! # Example
+//! ```no_run
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::graphics::GraphicsBackend;
+//! # struct MyLogic;
+//! # impl Logic for MyLogic {
+//! #     fn process(&mut self, _: &mut CharBuffer, _: f32) -> ProcessReturn { ProcessReturn::End }
+//! # }
+//! let backend = GraphicsBackend::detect();
+//! //Images can use a much bigger buffer than chars
+//! let (width, height) = if backend.get_protocol().is_some() { (320, 200) } else { (40, 25) };
+//! let mut runner = Runner::with_backend(width, height, 30, MyLogic, backend);
+//! runner.run(true);
+//! ```
+
+use super::char_buffer::CharBuffer;
+use super::raster::Bitmap;
+use super::runner::{Backend, StdoutBackend};
+use std::io::Write;
+
+/// A way of showing images in a terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GraphicsProtocol {
+    Sixel,
+    Kitty,
+}
+
+impl GraphicsProtocol {
+    pub fn detect() -> Option<GraphicsProtocol> {
+        //! Guesses which protocol the terminal supports from the environment variables it sets, or None if it doesn't look like it supports either.
+        Self::detect_from(|name| std::env::var(name).ok())
+    }
+    pub(crate) fn detect_from(var: impl Fn(&str) -> Option<String>) -> Option<GraphicsProtocol> {
+        let term = var("TERM").unwrap_or_default();
+        let program = var("TERM_PROGRAM").unwrap_or_default();
+        if var("KITTY_WINDOW_ID").is_some()
+            || term == "xterm-kitty"
+            || term == "xterm-ghostty"
+            || ["WezTerm", "ghostty"].contains(&program.as_str())
+        {
+            Some(GraphicsProtocol::Kitty)
+        } else if term.contains("sixel")
+            || term.starts_with("foot")
+            || term.starts_with("mlterm")
+            || term.starts_with("contour")
+            || program == "iTerm.app"
+        {
+            Some(GraphicsProtocol::Sixel)
+        } else {
+            None
+        }
+    }
+}
+
+impl Bitmap {
+    pub fn write_sixel(&self, out: &mut Vec<u8>, foreground: [u8; 3], background: [u8; 3]) {
+        //! Adds the image to out as a Sixel escape sequence, with set pixels in the foreground color and the rest in the background color (both as RGB).
+        let percent = |color: [u8; 3]| color.map(|x| x as u32 * 100 / 255);
+        let [fr, fg, fb] = percent(foreground);
+        let [br, bg, bb] = percent(background);
+        let _ = write!(
+            out,
+            "\x1bPq\"1;1;{};{}#0;2;{br};{bg};{bb}#1;2;{fr};{fg};{fb}",
+            self.width, self.height
+        );
+        for band in (0..self.height).step_by(6) {
+            for (color, lit) in [(0, false), (1, true)] {
+                let _ = write!(out, "#{color}");
+                let sixels = (0..self.width).map(|x| {
+                    let bits = (0..6)
+                        .filter(|dy| band + dy < self.height)
+                        .filter(|dy| self.pixels[(band + dy) * self.width + x] == lit)
+                        .fold(0, |accum, dy| accum | 1 << dy);
+                    b'?' + bits
+                });
+                write_run_length(out, sixels);
+                //Back to the start of the band for the next color
+                out.push(if color == 0 { b'$' } else { b'-' });
+            }
+        }
+        out.extend_from_slice(b"\x1b\\");
+    }
+    pub fn write_kitty(&self, out: &mut Vec<u8>, foreground: [u8; 3], background: [u8; 3]) {
+        //! Adds the image to out as Kitty graphics protocol escape sequences, with set pixels in the foreground color and the rest in the background color (both as RGB). Any images already shown with the protocol are removed first.
+        let rgb: Vec<u8> = self
+            .pixels
+            .iter()
+            .flat_map(|&x| if x { foreground } else { background })
+            .collect();
+        let encoded = base64(&rgb);
+        out.extend_from_slice(b"\x1b_Ga=d,d=A,q=2\x1b\\");
+        //The data has to be sent in chunks of at most 4096 bytes
+        let chunks: Vec<&[u8]> = encoded.as_bytes().chunks(4096).collect();
+        for (i, chunk) in chunks.iter().enumerate() {
+            let more = (i + 1 < chunks.len()) as u8;
+            if i == 0 {
+                let _ = write!(
+                    out,
+                    "\x1b_Ga=T,f=24,q=2,s={},v={},m={more};",
+                    self.width, self.height
+                );
+            } else {
+                let _ = write!(out, "\x1b_Gm={more};");
+            }
+            out.extend_from_slice(chunk);
+            out.extend_from_slice(b"\x1b\\");
+        }
+    }
+}
+
+/// Writes sixels, replacing runs of 4 or more of the same one with Sixel's repeat introducer.
+fn write_run_length(out: &mut Vec<u8>, sixels: impl Iterator<Item = u8>) {
+    let mut run: Option<(u8, usize)> = None;
+    let flush = |out: &mut Vec<u8>, (sixel, count): (u8, usize)| {
+        if count >= 4 {
+            let _ = write!(out, "!{count}");
+            out.push(sixel);
+        } else {
+            out.extend(std::iter::repeat_n(sixel, count));
+        }
+    };
+    for sixel in sixels {
+        run = match run {
+            Some((last, count)) if last == sixel => Some((last, count + 1)),
+            Some(last_run) => {
+                flush(out, last_run);
+                Some((sixel, 1))
+            }
+            None => Some((sixel, 1)),
+        };
+    }
+    if let Some(last_run) = run {
+        flush(out, last_run);
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |accum, (i, &x)| accum | (x as u32) << (16 - i * 8));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(group >> (18 - i * 6)) as usize & 63] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// A backend that shows every frame as an image when the terminal supports it, and prints it as chars otherwise.
+#[derive(Debug, Clone)]
+pub struct GraphicsBackend {
+    protocol: Option<GraphicsProtocol>,
+    /// How many pixels wide and tall each cell of the buffer is.
+    pub pixel_size: usize,
+    pub foreground: [u8; 3],
+    pub background: [u8; 3],
+    fallback: StdoutBackend,
+    output: Vec<u8>,
+}
+
+impl GraphicsBackend {
+    pub fn detect() -> Self {
+        //! Uses whichever protocol GraphicsProtocol::detect finds, falling back to chars.
+        Self::new(GraphicsProtocol::detect())
+    }
+    pub fn new(protocol: Option<GraphicsProtocol>) -> Self {
+        //! Uses the given protocol, or prints chars if it is None. Frames are drawn white on black, with each cell 2 x 2 pixels.
+        Self {
+            protocol,
+            pixel_size: 2,
+            foreground: [255, 255, 255],
+            background: [0, 0, 0],
+            fallback: StdoutBackend::default(),
+            output: vec![],
+        }
+    }
+    pub fn get_protocol(&self) -> Option<GraphicsProtocol> {
+        self.protocol
+    }
+    fn write_frame(&mut self, buf: &CharBuffer, clear_screen: bool, protocol: GraphicsProtocol) {
+        let bitmap = Bitmap::from_cells(buf, self.pixel_size.max(1));
+        self.output.clear();
+        if clear_screen {
+            self.output.extend_from_slice(b"\x1b[H");
+        }
+        match protocol {
+            GraphicsProtocol::Sixel => {
+                bitmap.write_sixel(&mut self.output, self.foreground, self.background)
+            }
+            GraphicsProtocol::Kitty => {
+                bitmap.write_kitty(&mut self.output, self.foreground, self.background)
+            }
+        }
+        self.output.push(b'\n');
+    }
+}
+
+impl Backend for GraphicsBackend {
+    fn present(&mut self, buf: &CharBuffer, clear_screen: bool) -> std::io::Result<()> {
+        let Some(protocol) = self.protocol else {
+            return self.fallback.present(buf, clear_screen);
+        };
+        self.write_frame(buf, clear_screen, protocol);
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(&self.output)?;
+        stdout.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detection() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|x| x.0 == name).map(|x| x.1.to_string())
+        };
+        assert_eq!(
+            GraphicsProtocol::detect_from(env(&[("TERM", "xterm-kitty")])),
+            Some(GraphicsProtocol::Kitty)
+        );
+        assert_eq!(
+            GraphicsProtocol::detect_from(env(&[("TERM", "foot")])),
+            Some(GraphicsProtocol::Sixel)
+        );
+        assert_eq!(
+            GraphicsProtocol::detect_from(env(&[("TERM", "xterm-256color")])),
+            None
+        );
+    }
+
+    #[test]
+    fn sixel() {
+        let mut bitmap = Bitmap::new(5, 7);
+        bitmap.pixels[0] = true; //(0, 0)
+        bitmap.pixels[6 * 5 + 1] = true; //(1, 6), in the second band
+        let mut out = vec![];
+        bitmap.write_sixel(&mut out, [255, 0, 0], [0, 0, 0]);
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("\x1bPq\"1;1;5;7#0;2;0;0;0#1;2;100;0;0"));
+        //First band: column 0 only has its top pixel lit, then 4 unlit columns
+        assert!(out.contains("#0}!4~$#1@!4?-"));
+        //Second band only has one row
+        assert!(out.contains("#0@?@@@$#1?@???-"));
+        assert!(out.ends_with("\x1b\\"));
+    }
+
+    #[test]
+    fn kitty() {
+        assert_eq!(base64(b"Man"), "TWFu");
+        assert_eq!(base64(b"Ma"), "TWE=");
+        assert_eq!(base64(b"M"), "TQ==");
+
+        let mut out = vec![];
+        Bitmap::new(40, 40).write_kitty(&mut out, [255, 255, 255], [0, 0, 0]);
+        let out = String::from_utf8(out).unwrap();
+        //40 * 40 * 3 bytes is 6400 in base64, so two chunks
+        assert!(out.contains("\x1b_Ga=T,f=24,q=2,s=40,v=40,m=1;AAAA"));
+        assert!(out.contains("\x1b_Gm=0;AAAA"));
+        assert_eq!(out.matches("\x1b_G").count(), 3);
+    }
+
+    #[test]
+    fn fallback() {
+        assert_eq!(GraphicsBackend::new(None).get_protocol(), None);
+        let mut backend = GraphicsBackend::new(Some(GraphicsProtocol::Sixel));
+        let mut buf = CharBuffer::new(3, 3);
+        buf.set_char(1, 1, '#').unwrap();
+        backend.write_frame(&buf, true, GraphicsProtocol::Sixel);
+        assert!(backend.output.starts_with(b"\x1b[H\x1bPq\"1;1;6;6"));
+    }
+}
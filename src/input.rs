@@ -0,0 +1,333 @@
+//! Binds inputs to named actions, so games can check for "jump" instead of hardcoding key names everywhere, and players can rebind them at runtime.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::input::{Binding, InputMap};
+//! struct Game {
+//!     input: InputMap,
+//!     x: usize,
+//! }
+//! impl Logic for Game {
+//!     fn process(&mut self, screen_buf: &mut CharBuffer, _delta: f32) -> ProcessReturn {
+//!         if self.input.is_pressed("right") {
+//!             self.x += 1;
+//!         }
+//!         if self.input.is_held("quit") {
+//!             return ProcessReturn::End;
+//!         }
+//!         screen_buf.fill(' ');
+//!         let _ = screen_buf.set_char(self.x, 0, '@');
+//!         self.input.end_frame();
+//!         ProcessReturn::Continue
+//!     }
+//!     fn input(&mut self, event: &InputEvent) {
+//!         self.input.handle(event);
+//!     }
+//! }
+//! let mut input = InputMap::new();
+//! input.bind("right", Binding::key("ArrowRight"));
+//! input.bind("right", Binding::key("d"));
+//! input.bind("quit", Binding::key("Escape"));
+//! let game = Game { input, x: 0 };
+//! ```
+
+use super::runner::InputEvent;
+use std::collections::{HashMap, HashSet};
+
+/// Something that can be bound to an action.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Binding {
+    /// A key, named the same way as InputEvent, ex: "a" or "ArrowUp".
+    Key(String),
+    /// A button on any gamepad, ex: "South" or "DPadLeft".
+    GamepadButton(String),
+    /// An analog stick or trigger on any gamepad, ex: "LeftStickX". These are never held, their values are read with axis.
+    GamepadAxis(String),
+    /// A mouse button, ex: "Left".
+    MouseButton(String),
+    /// A note on any channel of a MIDI controller.
+    MidiNote(u8),
+    /// A control on any channel of a MIDI controller, ex: a knob or fader. Like gamepad axes these are never held, and axis reads them from 0.0 to 1.0.
+    MidiControl(u8),
+    /// The first argument of OSC messages sent to the address, ex: "/fader/1", read with axis.
+    Osc(String),
+}
+
+impl Binding {
+    pub fn key(name: impl Into<String>) -> Self {
+        Binding::Key(name.into())
+    }
+    pub fn gamepad_button(name: impl Into<String>) -> Self {
+        Binding::GamepadButton(name.into())
+    }
+    pub fn gamepad_axis(name: impl Into<String>) -> Self {
+        Binding::GamepadAxis(name.into())
+    }
+    pub fn mouse_button(name: impl Into<String>) -> Self {
+        Binding::MouseButton(name.into())
+    }
+    pub fn osc(address: impl Into<String>) -> Self {
+        Binding::Osc(address.into())
+    }
+}
+
+/// Tracks which bindings are down, and which actions that makes pressed, held, or released. Events are given to it with handle, and end_frame should be called once the frame's input has been checked.
+#[derive(Debug, Clone, Default)]
+pub struct InputMap {
+    bindings: HashMap<String, Vec<Binding>>,
+    down: HashSet<Binding>,
+    pressed: HashSet<Binding>,
+    released: HashSet<Binding>,
+    axes: HashMap<Binding, f32>,
+    pointer: Option<(usize, usize)>,
+    typed: Vec<String>,
+}
+
+impl InputMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn bind(&mut self, action: impl Into<String>, binding: Binding) {
+        //! Adds a binding to the action, on top of any it already has.
+        let bindings = self.bindings.entry(action.into()).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+    pub fn unbind(&mut self, action: &str, binding: &Binding) -> bool {
+        //! Removes a binding from the action, returning false if it wasn't bound to it.
+        let Some(bindings) = self.bindings.get_mut(action) else {
+            return false;
+        };
+        let len = bindings.len();
+        bindings.retain(|x| x != binding);
+        bindings.len() != len
+    }
+    pub fn rebind(&mut self, action: impl Into<String>, bindings: Vec<Binding>) {
+        //! Replaces all of the action's bindings.
+        self.bindings.insert(action.into(), bindings);
+    }
+    pub fn get_bindings(&self, action: &str) -> &[Binding] {
+        self.bindings.get(action).map_or(&[], |x| x.as_slice())
+    }
+    pub fn actions_bound_to(&self, binding: &Binding) -> Vec<&str> {
+        //! Every action the binding triggers, ex: to warn about conflicts when rebinding.
+        let mut actions: Vec<&str> = self
+            .bindings
+            .iter()
+            .filter(|(_, bindings)| bindings.contains(binding))
+            .map(|(action, _)| action.as_str())
+            .collect();
+        actions.sort_unstable();
+        actions
+    }
+    pub fn handle(&mut self, event: &InputEvent) {
+        //! Updates which bindings are down from an input event. Repeated KeyDowns from holding a key down are ignored.
+        match event {
+            InputEvent::KeyDown(key) => {
+                self.typed.push(key.clone());
+                self.press(Binding::Key(key.clone()))
+            }
+            InputEvent::KeyUp(key) => self.release(Binding::Key(key.clone())),
+            InputEvent::GamepadDown(_, button) => {
+                self.press(Binding::GamepadButton(button.clone()))
+            }
+            InputEvent::GamepadUp(_, button) => {
+                self.release(Binding::GamepadButton(button.clone()))
+            }
+            InputEvent::GamepadAxis(_, axis, value) => {
+                self.axes.insert(Binding::GamepadAxis(axis.clone()), *value);
+            }
+            InputEvent::MouseMove(x, y) => self.pointer = Some((*x, *y)),
+            InputEvent::MouseDown(button) => self.press(Binding::MouseButton(button.clone())),
+            InputEvent::MouseUp(button) => self.release(Binding::MouseButton(button.clone())),
+            InputEvent::Resize(..) => {}
+            InputEvent::MidiNoteDown(_, note, _) => self.press(Binding::MidiNote(*note)),
+            InputEvent::MidiNoteUp(_, note) => self.release(Binding::MidiNote(*note)),
+            InputEvent::MidiControl(_, control, value) => {
+                self.axes.insert(Binding::MidiControl(*control), *value);
+            }
+            InputEvent::Osc(address, args) => {
+                if let Some(x) = args.first() {
+                    self.axes.insert(Binding::Osc(address.clone()), *x);
+                }
+            }
+        }
+    }
+    pub fn press(&mut self, binding: Binding) {
+        //! Marks a binding as down, ex: for input that doesn't come as an InputEvent.
+        if self.down.insert(binding.clone()) {
+            self.pressed.insert(binding);
+        }
+    }
+    pub fn release(&mut self, binding: Binding) {
+        //! Marks a binding as up.
+        if self.down.remove(&binding) {
+            self.released.insert(binding);
+        }
+    }
+    pub fn get_pointer(&self) -> Option<(usize, usize)> {
+        //! The cell the mouse was last over, or None if it hasn't moved yet.
+        self.pointer
+    }
+    pub fn end_frame(&mut self) {
+        //! Forgets what was pressed, released, and typed this frame, so is_pressed and is_released only report changes once.
+        self.pressed.clear();
+        self.released.clear();
+        self.typed.clear();
+    }
+    pub fn get_typed(&self) -> &[String] {
+        //! Every key that went down this frame in order, including repeats from holding a key down, ex: for typing text. Unlike actions, these don't need to be bound.
+        &self.typed
+    }
+    pub fn is_held(&self, action: &str) -> bool {
+        //! True while any of the action's bindings are down.
+        self.get_bindings(action)
+            .iter()
+            .any(|x| self.down.contains(x))
+    }
+    pub fn is_pressed(&self, action: &str) -> bool {
+        //! True if any of the action's bindings went down this frame, even if they have already come back up.
+        self.get_bindings(action)
+            .iter()
+            .any(|x| self.pressed.contains(x))
+    }
+    pub fn is_released(&self, action: &str) -> bool {
+        //! True if one of the action's bindings came up this frame, and none of them are still down.
+        !self.is_held(action)
+            && self
+                .get_bindings(action)
+                .iter()
+                .any(|x| self.released.contains(x))
+    }
+    pub fn axis(&self, action: &str) -> f32 {
+        //! How far the action is pushed, from -1.0 to 1.0, ex: for steering or orbiting a camera with a stick. This is the bound axis pushed furthest, or 1.0 if one of the action's keys or buttons is held instead. Use two actions for a stick's x and y.
+        let mut value: f32 = 0.0;
+        for binding in self.get_bindings(action) {
+            let x = match binding {
+                Binding::GamepadAxis(_) | Binding::MidiControl(_) | Binding::Osc(_) => {
+                    self.axes.get(binding).copied().unwrap_or(0.0)
+                }
+                _ if self.down.contains(binding) => 1.0,
+                _ => 0.0,
+            };
+            if x.abs() > value.abs() {
+                value = x;
+            }
+        }
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn down(key: &str) -> InputEvent {
+        InputEvent::KeyDown(key.to_string())
+    }
+    fn up(key: &str) -> InputEvent {
+        InputEvent::KeyUp(key.to_string())
+    }
+
+    #[test]
+    fn action_states() {
+        let mut input = InputMap::new();
+        input.bind("jump", Binding::key(" "));
+        input.bind("jump", Binding::key("w"));
+
+        input.handle(&down(" "));
+        assert!(input.is_pressed("jump") && input.is_held("jump"));
+        input.end_frame();
+        //Key repeat doesn't press it again
+        input.handle(&down(" "));
+        assert!(!input.is_pressed("jump") && input.is_held("jump"));
+        input.handle(&down("w"));
+        input.handle(&up(" "));
+        assert!(!input.is_released("jump"));
+        input.end_frame();
+        input.handle(&up("w"));
+        assert!(input.is_released("jump") && !input.is_held("jump"));
+        input.end_frame();
+
+        //A tap within one frame still counts as a press
+        input.handle(&down("w"));
+        input.handle(&up("w"));
+        assert!(input.is_pressed("jump") && input.is_released("jump"));
+        assert!(!input.is_held("unbound"));
+        assert_eq!(input.get_typed(), &["w".to_string()]);
+    }
+
+    #[test]
+    fn rebinding() {
+        let mut input = InputMap::new();
+        input.bind("left", Binding::key("a"));
+        input.bind("strafe", Binding::key("a"));
+        assert_eq!(
+            input.actions_bound_to(&Binding::key("a")),
+            vec!["left", "strafe"]
+        );
+
+        input.rebind("left", vec![Binding::key("ArrowLeft")]);
+        input.handle(&down("a"));
+        assert!(!input.is_held("left") && input.is_held("strafe"));
+        assert!(input.unbind("strafe", &Binding::key("a")));
+        assert!(!input.unbind("strafe", &Binding::key("a")));
+        assert!(!input.is_held("strafe"));
+        assert_eq!(input.get_bindings("left"), &[Binding::key("ArrowLeft")]);
+    }
+
+    #[test]
+    fn gamepad() {
+        let mut input = InputMap::new();
+        input.bind("jump", Binding::gamepad_button("South"));
+        input.bind("steer", Binding::gamepad_axis("LeftStickX"));
+        input.bind("steer", Binding::key("d"));
+
+        input.handle(&InputEvent::GamepadDown(1, "South".to_string()));
+        assert!(input.is_pressed("jump"));
+        input.handle(&InputEvent::GamepadUp(1, "South".to_string()));
+        assert!(input.is_released("jump"));
+
+        assert_eq!(input.axis("steer"), 0.0);
+        input.handle(&InputEvent::GamepadAxis(0, "LeftStickX".to_string(), -0.5));
+        assert_eq!(input.axis("steer"), -0.5);
+        assert!(!input.is_held("steer"));
+        //The key is pushed further than the stick
+        input.handle(&down("d"));
+        assert_eq!(input.axis("steer"), 1.0);
+        assert_eq!(input.axis("unbound"), 0.0);
+    }
+
+    #[test]
+    fn midi_and_osc() {
+        let mut input = InputMap::new();
+        input.bind("kick", Binding::MidiNote(36));
+        input.bind("spin", Binding::MidiControl(1));
+        input.bind("zoom", Binding::osc("/fader/1"));
+
+        input.handle(&InputEvent::MidiNoteDown(9, 36, 0.8));
+        assert!(input.is_pressed("kick"));
+        input.handle(&InputEvent::MidiNoteUp(9, 36));
+        assert!(input.is_released("kick"));
+
+        input.handle(&InputEvent::MidiControl(0, 1, 0.25));
+        input.handle(&InputEvent::MidiControl(0, 2, 1.0));
+        assert_eq!(input.axis("spin"), 0.25);
+        input.handle(&InputEvent::Osc("/fader/1".to_string(), vec![0.5, 2.0]));
+        //Messages without arguments don't change it
+        input.handle(&InputEvent::Osc("/fader/1".to_string(), vec![]));
+        assert_eq!(input.axis("zoom"), 0.5);
+    }
+
+    #[test]
+    fn mouse() {
+        let mut input = InputMap::new();
+        input.bind("click", Binding::mouse_button("Left"));
+        assert_eq!(input.get_pointer(), None);
+        input.handle(&InputEvent::MouseMove(3, 4));
+        input.handle(&InputEvent::MouseDown("Left".to_string()));
+        assert_eq!(input.get_pointer(), Some((3, 4)));
+        assert!(input.is_pressed("click"));
+    }
+}
@@ -0,0 +1,331 @@
+//! Shaded rendering through brightness values instead of chars. An IntensityBuffer holds a brightness from 0.0 to 1.0 for each cell, which lines, fog, antialiasing, blur, and fading all add up in, and only at the end is it turned into chars with a ramp (like demos::RAMP, or a charset::Charset's ramp). Working in brightness lets effects stack: an antialiased line fading into fog and blurred still gets the right char, where doing each step on chars would round the result every time.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::demos::RAMP;
+//! # use ascii_renderer::intensity::{Fog, IntensityBuffer};
+//! let mut renderer = Renderer::new(
+//!     vec![ascii_renderer::create_cube()],
+//!     Camera {
+//!         position: vec3!(0.0, 0.0, -6.0),
+//!         rotation: vec3!(0.0, 0.0, 0.0),
+//!         fov: vec2!(1.0, 1.0),
+//!     },
+//! );
+//! //The back of the cube fades into the fog
+//! renderer.fog = Some(Fog::new(4.0, 9.0));
+//! let mut target = IntensityBuffer::new(30, 30);
+//! renderer.draw_intensity(&mut target);
+//! target.blur(0.2);
+//! let mut buf = CharBuffer::new(30, 30);
+//! target.resolve(&mut buf, RAMP);
+//! ```
+
+use super::char_buffer::CharBuffer;
+use super::demos::shade;
+#[cfg(not(feature = "std"))]
+use super::float::Float;
+use super::matrix::Mat4;
+use super::rendering::{Mesh, Renderer};
+use super::transition::density;
+use super::{Scalar, Vector2};
+use alloc::{vec, vec::Vec};
+
+/// How lines fade out with distance from the camera.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fog {
+    /// How far in front of the camera the fog starts, with everything closer at full brightness.
+    pub near: Scalar,
+    /// How far in front of the camera the fog is thick enough to hide everything.
+    pub far: Scalar,
+}
+
+impl Fog {
+    pub fn new(near: Scalar, far: Scalar) -> Self {
+        Self { near, far }
+    }
+    pub fn visibility(&self, depth: Scalar) -> Scalar {
+        //! How much of something's brightness is left depth units in front of the camera, fading linearly from 1.0 at near to 0.0 at far.
+        if self.far <= self.near {
+            return if depth < self.far { 1.0 } else { 0.0 };
+        }
+        (1.0 - (depth - self.near) / (self.far - self.near)).clamp(0.0, 1.0)
+    }
+}
+
+/// A grid of brightness values, from 0.0 (empty) to 1.0 (fully lit), to be turned into chars at the end of a frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntensityBuffer {
+    pub dimensions: (usize, usize),
+    values: Vec<Scalar>,
+}
+
+impl IntensityBuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        //! A buffer with every cell at 0.0.
+        Self {
+            dimensions: (width, height),
+            values: vec![0.0; width * height],
+        }
+    }
+    pub fn from_chars(buf: &CharBuffer) -> Self {
+        //! Each cell's brightness is how much of it buf's char covers (see transition::density), ex: to blur or fade a frame drawn as chars.
+        let (width, height) = buf.dimensions;
+        let mut out = Self::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                out.values[y * width + x] = density(buf.get_char(x, y).unwrap_or(' '));
+            }
+        }
+        out
+    }
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.dimensions.0 && y < self.dimensions.1).then_some(y * self.dimensions.0 + x)
+    }
+    pub fn get(&self, x: usize, y: usize) -> Option<Scalar> {
+        self.index(x, y).map(|x| self.values[x])
+    }
+    pub fn set(&mut self, x: usize, y: usize, value: Scalar) {
+        //! Sets a cell's brightness, doing nothing outside the buffer.
+        if let Some(index) = self.index(x, y) {
+            self.values[index] = value;
+        }
+    }
+    pub fn plot(&mut self, x: usize, y: usize, value: Scalar) {
+        //! Brightens a cell to value, keeping it as it is if it's already brighter, so overlapping lines don't darken each other.
+        if let Some(index) = self.index(x, y) {
+            self.values[index] = self.values[index].max(value);
+        }
+    }
+    pub fn fill(&mut self, value: Scalar) {
+        self.values.fill(value);
+    }
+    pub fn draw_line(
+        &mut self,
+        points: (Vector2, Vector2),
+        values: (Scalar, Scalar),
+        antialiased: bool,
+    ) {
+        //! Draws a line between two points in buffer coordinates, with its brightness going from values.0 at the first point to values.1 at the second. Antialiased lines are drawn Wu style: where the line passes between two rows (or columns, for steep lines), its brightness is split between them by how close it comes to each.
+        let (mut start, mut end) = points;
+        let (mut from, mut to) = values;
+        //Steep lines are drawn with x and y swapped, so the loop always steps along the longer axis
+        let steep = (end.y - start.y).abs() > (end.x - start.x).abs();
+        if steep {
+            start = Vector2::new(start.y, start.x);
+            end = Vector2::new(end.y, end.x);
+        }
+        if start.x > end.x {
+            core::mem::swap(&mut start, &mut end);
+            core::mem::swap(&mut from, &mut to);
+        }
+        let length = if steep {
+            self.dimensions.1
+        } else {
+            self.dimensions.0
+        };
+        let span = end.x - start.x;
+        let gradient = if span == 0.0 {
+            0.0
+        } else {
+            (end.y - start.y) / span
+        };
+        let first = start.x.round().max(0.0);
+        let last = end.x.round().min(length as Scalar - 1.0);
+        if first.is_nan() || last.is_nan() || first > last {
+            return;
+        }
+        let mut plot = |major: i64, minor: Scalar, value: Scalar| {
+            if minor >= 0.0 && major >= 0 && value > 0.0 {
+                if steep {
+                    self.plot(minor as usize, major as usize, value);
+                } else {
+                    self.plot(major as usize, minor as usize, value);
+                }
+            }
+        };
+        for major in first as i64..=last as i64 {
+            let progress = if span == 0.0 {
+                0.0
+            } else {
+                ((major as Scalar - start.x) / span).clamp(0.0, 1.0)
+            };
+            let value = from + (to - from) * progress;
+            let y = start.y + gradient * (major as Scalar - start.x);
+            let near = y.round();
+            if !antialiased {
+                plot(major, near, value);
+                continue;
+            }
+            let offset = y - near;
+            plot(major, near, value * (1.0 - offset.abs()));
+            plot(major, near + offset.signum(), value * offset.abs());
+        }
+    }
+    pub fn scale(&mut self, factor: Scalar) {
+        //! Multiplies every cell's brightness by factor, ex: to fade the whole frame out.
+        for value in self.values.iter_mut() {
+            *value *= factor;
+        }
+    }
+    pub fn blur(&mut self, amount: Scalar) {
+        //! Blends each cell with the average of it and its 8 neighbours, from 0.0 (unchanged) to 1.0 (fully blurred). Cells past the edges count as 0.0.
+        let (width, height) = self.dimensions;
+        let source = self.values.clone();
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = 0.0;
+                for ny in y.saturating_sub(1)..(y + 2).min(height) {
+                    for nx in x.saturating_sub(1)..(x + 2).min(width) {
+                        sum += source[ny * width + nx];
+                    }
+                }
+                let index = y * width + x;
+                self.values[index] += (sum / 9.0 - source[index]) * amount;
+            }
+        }
+    }
+    pub fn persist(&mut self, previous: &IntensityBuffer, decay: Scalar) {
+        //! Keeps what was in previous, dimmed by decay, wherever it's brighter than this frame, for motion blur. Does nothing if the buffers are different sizes.
+        if previous.dimensions != self.dimensions {
+            return;
+        }
+        for (value, old) in self.values.iter_mut().zip(previous.values.iter()) {
+            *value = value.max(old * decay);
+        }
+    }
+    pub fn overlay(&self, buf: &mut CharBuffer, ramp: &[char]) {
+        //! Same as resolve, but cells at 0.0 are left as they are, ex: to add faint effects over a frame drawn as chars.
+        let width = self.dimensions.0;
+        for (i, value) in self.values.iter().enumerate() {
+            if *value > 0.0 {
+                let _ = buf.set_char(i % width, i / width, shade(ramp, *value));
+            }
+        }
+    }
+    pub fn resolve(&self, buf: &mut CharBuffer, ramp: &[char]) {
+        //! Turns every cell into the char for its brightness in ramp (see demos::shade), writing them into buf from its top left corner.
+        let width = self.dimensions.0;
+        for (i, value) in self.values.iter().enumerate() {
+            let _ = buf.set_char(i % width, i / width, shade(ramp, *value));
+        }
+    }
+}
+
+impl Renderer {
+    pub fn draw_intensity(&self, target: &mut IntensityBuffer) {
+        //! Draws all the meshes into target like draw does into a CharBuffer. Each mesh's brightness is how much of its cell its char covers (see transition::density), so '@' is brightest, dimmed along each line by the renderer's fog if it has any. Lines are antialiased if antialiasing is set, with its ramp left for resolving.
+        self.draw_intensity_with(
+            target,
+            self.camera.view_matrix(),
+            |_, mesh| mesh.model_matrix(),
+            1.0,
+        );
+    }
+    pub(crate) fn draw_intensity_with(
+        &self,
+        target: &mut IntensityBuffer,
+        view: Mat4,
+        model_of: impl Fn(usize, &Mesh) -> Mat4,
+        weight: Scalar,
+    ) {
+        //! Same as draw_intensity, but from the given view, with each mesh (by its index) at the given model matrix, and everything's brightness multiplied by weight.
+        for (i, mesh) in self.meshs.iter().enumerate() {
+            let model = model_of(i, mesh);
+            let mesh = &*self.shade(mesh);
+            let model_view = view * model;
+            let brightness = density(mesh.char) * weight;
+            let vertices = mesh.get_verticies();
+            for (a, b) in self.lod_edges(mesh, &model) {
+                let (Some(a), Some(b)) = (vertices.get(a), vertices.get(b)) else {
+                    continue;
+                };
+                let (a, b) = (
+                    model_view.transform_point(*a),
+                    model_view.transform_point(*b),
+                );
+                let values = match self.fog {
+                    Some(fog) => (
+                        brightness * fog.visibility(a.z),
+                        brightness * fog.visibility(b.z),
+                    ),
+                    None => (brightness, brightness),
+                };
+                let points = (
+                    self.project_view(a, target.dimensions),
+                    self.project_view(b, target.dimensions),
+                );
+                target.draw_line(points, values, self.antialiasing.is_some());
+            }
+        }
+    }
+    pub fn draw_shaded(&self, buffer: &mut CharBuffer, ramp: &[char]) {
+        //! Draws all the meshes through an IntensityBuffer the size of buffer, then resolves it into buffer with ramp, replacing everything in it.
+        let mut target = IntensityBuffer::new(buffer.dimensions.0, buffer.dimensions.1);
+        self.draw_intensity(&mut target);
+        target.resolve(buffer, ramp);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::demos::RAMP;
+    use crate::rendering::Camera;
+    use crate::{vec2, vec3, Vector3};
+    use alloc::string::ToString;
+
+    #[test]
+    fn lines_and_effects() {
+        let mut target = IntensityBuffer::new(5, 2);
+        target.draw_line((vec2!(0.0, 0.0), vec2!(4.0, 0.0)), (1.0, 0.0), false);
+        let row: Vec<Scalar> = (0..5).map(|x| target.get(x, 0).unwrap()).collect();
+        assert_eq!(row, [1.0, 0.75, 0.5, 0.25, 0.0]);
+        //Antialiased, a line halfway between two rows lights both halfway
+        let mut target = IntensityBuffer::new(3, 2);
+        target.draw_line((vec2!(0.0, 0.5), vec2!(2.0, 0.5)), (1.0, 1.0), true);
+        assert!((0..3).all(|x| target.get(x, 0) == Some(0.5) && target.get(x, 1) == Some(0.5)));
+        let mut buf = CharBuffer::new(3, 2);
+        target.resolve(&mut buf, RAMP);
+        assert_eq!(&buf.to_string(), "+ + + \n+ + + \n");
+
+        //Effects stack before rounding to chars
+        target.scale(0.5);
+        target.persist(&IntensityBuffer::from_chars(&buf), 0.8);
+        assert!((target.get(0, 0).unwrap() - 5.0 / 9.0 * 0.8).abs() < 1e-5);
+        let mut dot = IntensityBuffer::new(3, 3);
+        dot.set(1, 1, 0.9);
+        dot.blur(1.0);
+        assert!((dot.get(1, 1).unwrap() - 0.1).abs() < 1e-5);
+        assert_eq!(dot.get(0, 0), dot.get(2, 1));
+    }
+
+    #[test]
+    fn shaded_rendering() {
+        let mut renderer = Renderer::new(
+            vec![crate::create_cube()],
+            Camera {
+                position: vec3!(0.0, 0.0, -6.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(1.0, 1.0),
+            },
+        );
+        renderer.meshs[0].char = '@';
+        //Without fog or antialiasing, it's the same shape as drawing chars
+        let (mut chars, mut shaded) = (CharBuffer::new(30, 30), CharBuffer::new(30, 30));
+        renderer.draw(&mut chars);
+        renderer.draw_shaded(&mut shaded, RAMP);
+        assert_eq!(chars, shaded);
+        //Fog dims the back of the cube more than the front
+        renderer.fog = Some(Fog::new(4.0, 8.0));
+        let mut target = IntensityBuffer::new(30, 30);
+        renderer.draw_intensity(&mut target);
+        let values: Vec<Scalar> = (0..30)
+            .flat_map(|y| (0..30).map(move |x| (x, y)))
+            .filter_map(|(x, y)| target.get(x, y))
+            .filter(|x| *x > 0.0)
+            .collect();
+        assert!(values.iter().any(|x| *x > 0.7) && values.iter().any(|x| *x < 0.4));
+        assert_eq!(Fog::new(4.0, 8.0).visibility(6.0), 0.5);
+    }
+}
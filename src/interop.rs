@@ -0,0 +1,147 @@
+//! Conversions between this crate's math types and the types of other math libraries. Each library is behind a feature of the same name, ex: `ascii_renderer = { version = "*", features = ["glam"] }`. With the `f64` feature enabled, the conversions use glam's double precision types (DVec3, etc.) and nalgebra types over f64 instead.
+//! # Example
+//! ```ignore
+//! let position: glam::Vec3 = physics_body.position;
+//! mesh.position = position.into();
+//! ```
+
+#[cfg(feature = "glam")]
+mod glam_conversions {
+    use crate::{Mat4, Vector2, Vector3};
+    #[cfg(feature = "f64")]
+    use glam::{DMat4 as GlamMat4, DVec2 as GlamVec2, DVec3 as GlamVec3};
+    #[cfg(not(feature = "f64"))]
+    use glam::{Mat4 as GlamMat4, Vec2 as GlamVec2, Vec3 as GlamVec3};
+
+    impl From<GlamVec2> for Vector2 {
+        fn from(value: GlamVec2) -> Self {
+            Vector2::new(value.x, value.y)
+        }
+    }
+
+    impl From<Vector2> for GlamVec2 {
+        fn from(value: Vector2) -> Self {
+            GlamVec2::new(value.x, value.y)
+        }
+    }
+
+    impl From<GlamVec3> for Vector3 {
+        fn from(value: GlamVec3) -> Self {
+            Vector3::new(value.x, value.y, value.z)
+        }
+    }
+
+    impl From<Vector3> for GlamVec3 {
+        fn from(value: Vector3) -> Self {
+            GlamVec3::new(value.x, value.y, value.z)
+        }
+    }
+
+    impl From<GlamMat4> for Mat4 {
+        fn from(value: GlamMat4) -> Self {
+            //glam is column-major, Mat4 is row-major
+            Mat4::new(value.transpose().to_cols_array_2d())
+        }
+    }
+
+    impl From<Mat4> for GlamMat4 {
+        fn from(value: Mat4) -> Self {
+            GlamMat4::from_cols_array_2d(&value.data).transpose()
+        }
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+mod nalgebra_conversions {
+    use crate::{Mat4, Scalar, Vector2, Vector3};
+
+    impl From<nalgebra::Vector2<Scalar>> for Vector2 {
+        fn from(value: nalgebra::Vector2<Scalar>) -> Self {
+            Vector2::new(value.x, value.y)
+        }
+    }
+
+    impl From<Vector2> for nalgebra::Vector2<Scalar> {
+        fn from(value: Vector2) -> Self {
+            nalgebra::Vector2::new(value.x, value.y)
+        }
+    }
+
+    impl From<nalgebra::Point2<Scalar>> for Vector2 {
+        fn from(value: nalgebra::Point2<Scalar>) -> Self {
+            Vector2::new(value.x, value.y)
+        }
+    }
+
+    impl From<Vector2> for nalgebra::Point2<Scalar> {
+        fn from(value: Vector2) -> Self {
+            nalgebra::Point2::new(value.x, value.y)
+        }
+    }
+
+    impl From<nalgebra::Vector3<Scalar>> for Vector3 {
+        fn from(value: nalgebra::Vector3<Scalar>) -> Self {
+            Vector3::new(value.x, value.y, value.z)
+        }
+    }
+
+    impl From<Vector3> for nalgebra::Vector3<Scalar> {
+        fn from(value: Vector3) -> Self {
+            nalgebra::Vector3::new(value.x, value.y, value.z)
+        }
+    }
+
+    impl From<nalgebra::Point3<Scalar>> for Vector3 {
+        fn from(value: nalgebra::Point3<Scalar>) -> Self {
+            Vector3::new(value.x, value.y, value.z)
+        }
+    }
+
+    impl From<Vector3> for nalgebra::Point3<Scalar> {
+        fn from(value: Vector3) -> Self {
+            nalgebra::Point3::new(value.x, value.y, value.z)
+        }
+    }
+
+    impl From<nalgebra::Matrix4<Scalar>> for Mat4 {
+        fn from(value: nalgebra::Matrix4<Scalar>) -> Self {
+            let mut data = [[0.0; 4]; 4];
+            for (row, values) in data.iter_mut().enumerate() {
+                for (col, item) in values.iter_mut().enumerate() {
+                    *item = value[(row, col)];
+                }
+            }
+            Mat4::new(data)
+        }
+    }
+
+    impl From<Mat4> for nalgebra::Matrix4<Scalar> {
+        fn from(value: Mat4) -> Self {
+            nalgebra::Matrix4::from_fn(|row, col| value.data[row][col])
+        }
+    }
+}
+
+#[cfg(all(test, feature = "glam", feature = "nalgebra"))]
+mod tests {
+    use crate::{vec3, Mat4, Vector3};
+
+    #[test]
+    fn matrices_keep_their_layout() {
+        let matrix = Mat4::translation(vec3!(1.0, 2.0, 3.0));
+        let point = vec3!(1.0, 1.0, 1.0);
+
+        #[cfg(not(feature = "f64"))]
+        let glam_matrix: glam::Mat4 = matrix.into();
+        #[cfg(feature = "f64")]
+        let glam_matrix: glam::DMat4 = matrix.into();
+        let glam_point = glam_matrix.transform_point3(point.into());
+        assert_eq!(Vector3::from(glam_point), vec3!(2.0, 3.0, 4.0));
+        assert_eq!(Mat4::from(glam_matrix), matrix);
+
+        let nalgebra_matrix: nalgebra::Matrix4<crate::Scalar> = matrix.into();
+        let nalgebra_point = nalgebra_matrix.transform_point(&point.into());
+        assert_eq!(Vector3::from(nalgebra_point), vec3!(2.0, 3.0, 4.0));
+        assert_eq!(Mat4::from(nalgebra_matrix), matrix);
+    }
+}
@@ -0,0 +1,141 @@
+//! Smooths out fast motion at low frame rates. A FrameInterpolator remembers where the camera and every mesh were last frame, and draws faint in-between subframes of the motion since then under the current frame, so a mesh that jumps several cells a frame leaves a blur along its path instead of teleporting. Subframes are added up in an intensity::IntensityBuffer, so overlapping ones get brighter chars, and older ones are fainter.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::interpolation::FrameInterpolator;
+//! let mut renderer = Renderer::new(
+//!     vec![ascii_renderer::create_cube()],
+//!     Camera {
+//!         position: vec3!(0.0, 0.0, -10.0),
+//!         rotation: vec3!(0.0, 0.0, 0.0),
+//!         fov: vec2!(1.0, 1.0),
+//!     },
+//! );
+//! let mut interpolator = FrameInterpolator::new(3);
+//! let mut buf = CharBuffer::new(40, 40);
+//! //Every frame, at 10 fps:
+//! renderer.meshs[0].rotation.y += 0.8;
+//! buf.fill(' ');
+//! interpolator.draw(&renderer, &mut buf);
+//! ```
+
+use super::char_buffer::CharBuffer;
+use super::demos::RAMP;
+use super::intensity::IntensityBuffer;
+use super::rendering::Renderer;
+use super::transform::Transform;
+use super::Scalar;
+
+/// The camera's transform and every mesh's, from the last frame drawn.
+#[derive(Debug, Clone, PartialEq)]
+struct Snapshot {
+    camera: Transform,
+    meshes: Vec<Transform>,
+}
+
+impl Snapshot {
+    fn of(renderer: &Renderer) -> Self {
+        Self {
+            camera: renderer.camera.transform(),
+            meshes: renderer.meshs.iter().map(|x| x.transform).collect(),
+        }
+    }
+}
+
+/// Draws frames with in-between subframes of the motion since the last one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameInterpolator {
+    /// How many subframes are drawn between the last frame and the current one. 0 draws frames normally.
+    pub subframes: usize,
+    /// The chars subframes are shaded with, from dark to bright.
+    pub ramp: Vec<char>,
+    previous: Option<Snapshot>,
+}
+
+impl FrameInterpolator {
+    pub fn new(subframes: usize) -> Self {
+        //! Shades subframes with demos::RAMP.
+        Self {
+            subframes,
+            ramp: RAMP.to_vec(),
+            previous: None,
+        }
+    }
+    pub fn reset(&mut self) {
+        //! Forgets the last frame, ex: after a camera cut, so the next frame doesn't blur across it.
+        self.previous = None;
+    }
+    pub fn draw(&mut self, renderer: &Renderer, buffer: &mut CharBuffer) {
+        //! Draws the subframes into buffer, then the renderer's meshes as they are now on top, and remembers where everything is for next frame. Meshes are matched up with last frame's by their index in renderer.meshs, so if meshes were added or removed there are no subframes this frame.
+        let current = Snapshot::of(renderer);
+        let previous = self
+            .previous
+            .replace(current.clone())
+            .filter(|x| x.meshes.len() == current.meshes.len() && *x != current);
+        if let (Some(previous), true) = (previous, self.subframes > 0) {
+            let mut target = IntensityBuffer::new(buffer.dimensions.0, buffer.dimensions.1);
+            let mut camera = renderer.camera.clone();
+            for i in 1..=self.subframes {
+                //Subframes closer to now are brighter
+                let t = i as Scalar / (self.subframes + 1) as Scalar;
+                camera.set_transform(&previous.camera.lerp(&current.camera, t));
+                renderer.draw_intensity_with(
+                    &mut target,
+                    camera.view_matrix(),
+                    |i, _| previous.meshes[i].lerp(&current.meshes[i], t).matrix(),
+                    t,
+                );
+            }
+            target.overlay(buffer, &self.ramp);
+        }
+        renderer.draw(buffer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::Camera;
+    use crate::{vec2, vec3, Vector2, Vector3};
+
+    #[test]
+    fn subframes() {
+        let mut renderer = Renderer::new(
+            vec![crate::create_cube()],
+            Camera {
+                position: vec3!(0.0, 0.0, -20.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(1.0, 1.0),
+            },
+        );
+        renderer.meshs[0].char = '@';
+        let mut interpolator = FrameInterpolator::new(3);
+        let count = |buf: &CharBuffer, char: char| buf.to_string().matches(char).count();
+        let mut first = CharBuffer::new(40, 40);
+        interpolator.draw(&renderer, &mut first);
+        //Nothing to blend with on the first frame, or when nothing moved
+        let mut plain = CharBuffer::new(40, 40);
+        renderer.draw(&mut plain);
+        assert_eq!(first, plain);
+        let mut still = CharBuffer::new(40, 40);
+        interpolator.draw(&renderer, &mut still);
+        assert_eq!(still, plain);
+
+        renderer.meshs[0].position.x += 8.0;
+        let mut moved = CharBuffer::new(40, 40);
+        interpolator.draw(&renderer, &mut moved);
+        let mut plain = CharBuffer::new(40, 40);
+        renderer.draw(&mut plain);
+        //The cube is drawn where it is now, with fainter copies behind it
+        assert_eq!(count(&moved, '@'), count(&plain, '@'));
+        assert!(count(&moved, ' ') < count(&plain, ' '));
+        assert_eq!(moved.get_char(22, 18), Some(':'));
+        assert_eq!(moved.get_char(26, 18), Some('+'));
+
+        interpolator.reset();
+        renderer.meshs[0].position.x -= 8.0;
+        let mut cut = CharBuffer::new(40, 40);
+        interpolator.draw(&renderer, &mut cut);
+        assert_eq!(cut, first);
+    }
+}
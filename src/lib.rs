@@ -15,7 +15,7 @@ impl Logic for MyLogic {
 There will be more on this later, but for now just make ```process()``` return ```ProcessReturn::Continue```.
 
 Next, create a ```Runner```, pass an instance of your logic struct to it, and run it.
-```rust
+```rust,no_run
 use ascii_renderer::prelude::*;
 
 struct MyLogic;
@@ -40,7 +40,7 @@ The runner will proceed to run a loop (with a maximum frequency dictated by the
 The ```delta``` parameter is the amount of time (in seconds) that has passed since the last frame was drawn to the screen. It is necesary for non-frame-dependant movement.
 
 The ```CharBuffer``` can be mutated by changing individual chars (```set_char(&mut self, x, y, char)```), filling the entire buffer (```fill(&mut self, char)```), drawing lines (```draw_line(&mut self, line)```), or by rendering 3D graphics to it (more on that later). The buffer is maintained between frames, you almost always should start ```process()``` with ```screen_buf.fill(' ');```.
-```rust
+```rust,no_run
 use ascii_renderer::prelude::*;
 
 struct MyLogic;
@@ -49,8 +49,8 @@ impl Logic for MyLogic {
     fn process(&mut self, screen_buf: &mut CharBuffer, delta: f32) -> ProcessReturn {
         screen_buf.fill(' ');
 
-        let fps_string: String = (1.0 / delta).into();
-        let mut fps_chars = fps.chars();
+        let fps_string = (1.0 / delta).to_string();
+        let mut fps_chars = fps_string.chars();
 
         screen_buf.set_char(0, 0, fps_chars.next().unwrap()).unwrap(); //Will write the fps to the screen
         screen_buf.set_char(1, 0, fps_chars.next().unwrap()).unwrap();
@@ -74,7 +74,7 @@ fn main() {
 }
 ```
 To render 3D graphics to the ```CharBuffer```, we need to use a ```Renderer```. We don't want to instantiate a new ```Renderer``` every single frame, so we should store an instance of a ```Renderer``` wtihin a field of our logic struct. To draw graphics to the ```CharBuffer```, simply call ```draw()``` on the renderer, passing a mutable reference to the ```CharBuffer``` to it. In order to have something to render, you can create a 2x2x2 cube mesh using the ```create_cube()``` function and pass the cube to the renderer within it's declaration.
-```rust
+```rust,no_run
 use ascii_renderer::prelude::*;
 
 struct MyLogic {
@@ -85,11 +85,10 @@ impl Logic for MyLogic {
     fn process(&mut self, screen_buf: &mut CharBuffer, delta: f32) -> ProcessReturn {
         screen_buf.fill(' ');
 
-        let fps_string: String = (1.0 / delta).into();
-        let mut fps_chars = fps.chars();
-
         self.renderer.draw(screen_buf);
 
+        let delta = Scalar::from(delta); //Meshes use Scalar, which is f64 with the f64 feature
+
         self.renderer.meshs[0].rotation.x += delta * 2.0;
         self.renderer.meshs[0].rotation.y += delta; //Rotates the cube. Because it's just a wireframe model, if there isn't any movement it won't look 3D.
 
@@ -103,30 +102,31 @@ fn main() {
         5, //Height
         25, //FPS Cap
         MyLogic {
-            renderer: Renderer {
-                meshs: vec![ascii_renderer::create_cube()],
-                camera: Camera {
+            renderer: Renderer::new(
+                vec![ascii_renderer::create_cube()],
+                Camera {
                     position: vec3!(0.0, 0.0, -7.0),
                     rotation: vec3!(0.0, 0.0, 0.0),
                     fov: vec2!(0.8, 0.8),   //Is in RADIANS. Make sure this is proportional to the dimensions of the CharBuffer, otherwise there will be stretching.
                 },
-            },
+            ),
         },
     ).run(true);    //true = clears the terminal between frames
 }
 ```
 For any values that need to be consistent, more fields can be added to the logic struct. For example, this logic contains a field that keeps track of how much time (in seconds) has passed since the runner started, and ```process()``` feeds that value into a sin function which determines the cube's scale in each dimension, creating a cool looking effect (as shown in [this](https://youtu.be/faViJzniUQA) video):
-```rust
+```rust,no_run
 use ascii_renderer::prelude::*;
 
 struct MyLogic {
     pub renderer: Renderer,
-    pub time_offset: f32,
+    pub time_offset: Scalar,
 }
 
 impl Logic for MyLogic {
     fn process(&mut self, screen_buf: &mut CharBuffer, delta: f32) -> ProcessReturn {
         screen_buf.fill(' ');
+        let delta = Scalar::from(delta);
 
         self.time_offset += delta; //Keeps track of time
 
@@ -149,14 +149,14 @@ fn main() {
         50,
         25,
         MyLogic {
-            renderer: Renderer {
-                meshs: vec![ascii_renderer::create_cube()],
-                camera: Camera {
+            renderer: Renderer::new(
+                vec![ascii_renderer::create_cube()],
+                Camera {
                     position: vec3!(0.0, 0.0, -7.0),
                     rotation: vec3!(0.0, 0.0, 0.0),
                     fov: vec2!(0.8, 0.8),
                 },
-            },
+            ),
             time_offset: 0.0,
         },
     );
@@ -164,7 +164,7 @@ fn main() {
 }
 ```
 Finally, to load meshes from file (currently only .OBJ is supported), run the function ```AsciiObj::load(path)```, which will return a ```Result<AsciiObj, ObjError>```. After ```unwrap()```ing it, the ```AsciiObj``` can be converted into a ```Vec<Mesh>``` using ```into()```, which all together would look like ```let my_meshes: Vec<Mesh> = AsciiObj::load("face.obj").unwrap().into();```. However, often times meshes are far from the origin, causing the mesh to appear to spin in a large circle centered around the origin rather than rotate around a point when rotated. Because of that, allways run the ```recenter()``` method on the mesh before passing it to the renderer. ```recenter()``` returns the position the mesh was originally centered at, if you wish to maintain it's in-file position. This example demonstrates overall how to load objs:
-```rust
+```rust,no_run
 use ascii_renderer::prelude::*;
 
 #[derive(Debug)]
@@ -178,7 +178,7 @@ impl Logic for MyLogic {
 
         self.renderer.draw(screen_buf);
 
-        self.renderer.meshs.first_mut().unwrap().rotation.y += delta;
+        self.renderer.meshs.first_mut().unwrap().rotation.y += Scalar::from(delta);
 
         ProcessReturn::Continue
     }
@@ -189,7 +189,7 @@ fn main() {
     my_meshes.iter_mut().for_each(|mesh| {
         // * Scales the obj down. rotates it so that it is rightside up, and recenters it.
         mesh.scale = vec3!(0.01, 0.01, 0.01);
-        mesh.rotation = vec3!(std::f32::consts::PI, 0.0, 0.0);
+        mesh.rotation = vec3!(std::f32::consts::PI as Scalar, 0.0, 0.0);
         mesh.recenter();   // * This OBJ is really far from the origin for some reason, so if it is not recentered it 
     });
     let mut runner = Runner::new(
@@ -197,14 +197,14 @@ fn main() {
         50,
         25,
         MyLogic {
-            renderer: Renderer {
-                meshs: my_meshes,
-                camera: Camera {
+            renderer: Renderer::new(
+                my_meshes,
+                Camera {
                     position: vec3!(0.0, 0.0, -3.0),
                     rotation: vec3!(0.0, 0.0, 0.0),
                     fov: vec2!(0.8, 0.8),
                 },
-            },
+            ),
         },
     );
     runner.run(true);
@@ -212,24 +212,187 @@ fn main() {
 ```
 */
 
-
+#![cfg_attr(not(feature = "std"), no_std)]
+//The test harness links std, whose float methods take precedence over float::Float's, leaving it and its imports unused in tests
+#![cfg_attr(all(test, not(feature = "std")), allow(unused_imports))]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod animation;
+#[cfg(feature = "std")]
+pub mod assets;
+#[cfg(feature = "audio")]
+pub mod audio;
+#[cfg(feature = "std")]
+pub mod budget;
+#[cfg(feature = "std")]
+pub mod bvh;
+mod cache;
+#[cfg(feature = "std")]
+pub mod capabilities;
 pub mod char_buffer;
+#[cfg(feature = "std")]
+pub mod charset;
+#[cfg(feature = "std")]
+pub mod collision;
+#[cfg(feature = "std")]
+pub mod console;
+pub mod demos;
+pub mod depth;
+#[cfg(feature = "std")]
+pub mod describe;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "std")]
+pub mod first_person;
+pub mod fixed;
+#[cfg(not(feature = "std"))]
+#[cfg_attr(test, allow(dead_code))]
+mod float;
+#[cfg(feature = "std")]
+pub mod fractal;
+#[cfg(feature = "gilrs")]
+pub mod gamepad;
+#[cfg(feature = "std")]
+pub mod geometry;
+#[cfg(feature = "std")]
+pub mod golden;
+#[cfg(feature = "std")]
+pub mod graphics;
+#[cfg(feature = "std")]
+pub mod input;
+pub mod intensity;
+#[cfg(feature = "std")]
+pub mod interop;
+#[cfg(feature = "std")]
+pub mod interpolation;
 pub mod line;
+pub mod lod;
+#[cfg(feature = "log")]
+pub mod logging;
+pub mod matrix;
+#[cfg(feature = "std")]
+pub mod midi;
+#[cfg(feature = "std")]
+pub mod minimap;
+#[cfg(feature = "std")]
+pub mod motion_blur;
+#[cfg(feature = "netcode")]
+pub mod netcode;
+pub mod noise;
+#[cfg(feature = "std")]
 pub mod obj;
+#[cfg(feature = "std")]
+pub mod occlusion;
+#[cfg(feature = "osc")]
+pub mod osc;
+#[cfg(feature = "std")]
+pub mod palette;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+#[cfg(feature = "std")]
+pub mod particles;
+#[cfg(feature = "std")]
+pub mod path;
+#[cfg(feature = "std")]
+pub mod physics;
+#[cfg(feature = "std")]
+pub mod plot;
+pub mod post;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "std")]
+pub mod rail;
+#[cfg(feature = "std")]
+pub mod raster;
+#[cfg(feature = "std")]
+pub mod recording;
+#[cfg(feature = "std")]
+pub mod reflection;
+pub mod renderable;
 pub mod rendering;
+#[cfg(feature = "std")]
 pub mod runner;
-
-pub use rendering::{Vector2, Vector3};
+#[cfg(feature = "rhai")]
+pub mod scripting;
+pub mod shader;
+#[cfg(feature = "std")]
+pub mod shadow;
+#[cfg(feature = "simd")]
+mod simd;
+#[cfg(feature = "std")]
+pub mod skeleton;
+#[cfg(feature = "std")]
+pub mod split;
+#[cfg(feature = "std")]
+pub mod sprite;
+pub mod stencil;
+#[cfg(feature = "std")]
+pub mod stl;
+pub mod supersample;
+#[cfg(feature = "std")]
+pub mod svg;
+#[cfg(feature = "std")]
+pub mod target;
+#[cfg(feature = "std")]
+mod tcp;
+#[cfg(feature = "std")]
+pub mod telnet;
+#[cfg(feature = "crossterm")]
+pub mod terminal;
+#[cfg(feature = "std")]
+pub mod text_effects;
+pub mod thickness;
+#[cfg(feature = "std")]
+pub mod tilemap;
+#[cfg(feature = "std")]
+pub mod toon;
+#[cfg(feature = "std")]
+pub mod trail;
+pub mod transform;
+pub mod transition;
+#[cfg(feature = "ratatui")]
+pub mod tui;
+pub mod turntable;
+#[cfg(feature = "std")]
+pub mod ui;
+#[cfg(feature = "std")]
+pub mod validation;
+#[cfg(feature = "std")]
+pub mod voxel;
+#[cfg(feature = "std")]
+pub mod wave;
+#[cfg(feature = "std")]
+pub mod win_console;
+
+pub use matrix::Mat4;
+
+/// The map meshes keep their vertices in: std's HashMap, or hashbrown's without the `std` feature.
+#[cfg(feature = "std")]
+pub(crate) use std::collections::HashMap;
+#[cfg(not(feature = "std"))]
+pub(crate) use hashbrown::HashMap;
+pub use rendering::{render_frame, Scalar, Vector2, Vector3};
 
 pub mod prelude {
+    #[cfg(feature = "std")]
+    pub use super::animation::{AnimationClip, Animator};
     pub use super::char_buffer::CharBuffer;
     pub use super::line::Line;
+    #[cfg(feature = "std")]
     pub use super::obj::AsciiObj;
+    #[cfg(feature = "std")]
     pub use super::obj::ObjError;
-    pub use super::rendering::{Camera, Mesh, Renderer};
+    pub use super::rendering::{render_frame, Camera, Mesh, Renderer};
+    #[cfg(feature = "std")]
     pub use super::runner::ProcessReturn;
-    pub use super::runner::{Logic, Runner};
-    pub use super::{vec2, vec3, Vector2, Vector3};
+    #[cfg(feature = "std")]
+    pub use super::runner::{InputEvent, Logic, Runner};
+    #[cfg(feature = "std")]
+    pub use super::skeleton::{Skeleton, SkinnedMesh};
+    pub use super::transform::Transform;
+    pub use super::{vec2, vec3, Mat4, Scalar, Vector2, Vector3};
 }
 
 pub fn create_cube() -> rendering::Mesh {
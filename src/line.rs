@@ -1,5 +1,8 @@
-use super::char_buffer::CharBuffer;
-use super::Vector2;
+use super::char_buffer::{CharBuffer, Region};
+#[cfg(not(feature = "std"))]
+use super::float::Float;
+use super::{Scalar, Vector2};
+use alloc::vec::Vec;
 
 /// The struct fed to a CharBuffer for drawing lines.
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
@@ -8,99 +11,111 @@ pub struct Line {
     pub points: (Vector2, Vector2),
 }
 
-impl Into<((usize, usize), (usize, usize))> for Line {
-    fn into(self) -> ((usize, usize), (usize, usize)) {
-        #[inline]
-        fn f32_to_usize(value: f32) -> usize {
-            value.round() as usize
+impl Line {
+    pub fn is_finite(&self) -> bool {
+        //! False if either point has a NaN or infinite coordinate, ex: from projecting with a fov of 0. Lines that aren't finite aren't drawn.
+        [self.points.0, self.points.1]
+            .iter()
+            .all(|x| x.x.is_finite() && x.y.is_finite())
+    }
+    pub fn clip(&self, region: Region) -> Option<Line> {
+        //! The part of the line inside region, or None if none of it is (or it isn't finite). Uses Cohen-Sutherland clipping against the area whose points round to cells in region.
+        //! # Example
+        //! ```
+        //! # use ascii_renderer::prelude::*;
+        //! # use ascii_renderer::char_buffer::Region;
+        //! let line = Line {
+        //!     char: '#',
+        //!     points: (vec2!(-10.0, 2.0), vec2!(30.0, 2.0)),
+        //! };
+        //! let clipped = line.clip(Region::new(0, 0, 20, 5)).unwrap();
+        //! assert_eq!(clipped.points, (vec2!(-0.5, 2.0), vec2!(19.5, 2.0)));
+        //! assert!(line.clip(Region::new(0, 3, 20, 5)).is_none());
+        //! ```
+        if region.width == 0 || region.height == 0 {
+            return None;
         }
+        let min = Vector2::new(region.x as Scalar - 0.5, region.y as Scalar - 0.5);
+        let max = Vector2::new(
+            (region.x + region.width) as Scalar - 0.5,
+            (region.y + region.height) as Scalar - 0.5,
+        );
+        let points = clip_to(self.points, min, max)?;
+        Some(Line {
+            char: self.char,
+            points,
+        })
+    }
+    pub fn cells(&self, region: Region) -> Option<((usize, usize), (usize, usize))> {
+        //! The first and last cells drawn for the line in region, or None if it doesn't draw any there.
+        let mut ends: Option<((usize, usize), (usize, usize))> = None;
+        for_each_cell(*self, region, |x, y| {
+            let first = ends.map_or((x, y), |ends| ends.0);
+            ends = Some((first, (x, y)));
+        });
+        ends
+    }
+}
 
-        if (self.points.0.x < 0.0 && self.points.1.x < 0.0)
-            || (self.points.0.y < 0.0 && self.points.1.y < 0.0)
-        {
-            //line is entirely offscreen, the coords are set near the 32-bit unsigned int limit
-            return (
-                (4_000_000_000, 4_000_000_000),
-                (4_000_000_000, 4_000_000_000),
-            );
+fn clip_to(points: (Vector2, Vector2), min: Vector2, max: Vector2) -> Option<(Vector2, Vector2)> {
+    //! Cohen-Sutherland clipping of the line between points to the rectangle from min to max.
+    if ![points.0, points.1]
+        .iter()
+        .all(|x| x.x.is_finite() && x.y.is_finite())
+    {
+        return None;
+    }
+    let outcode = |point: Vector2| {
+        (point.x < min.x) as u8
+            | ((point.x > max.x) as u8) << 1
+            | ((point.y < min.y) as u8) << 2
+            | ((point.y > max.y) as u8) << 3
+    };
+    let (from, to) = points;
+    let along = to - from;
+    let (mut start, mut end) = points;
+    loop {
+        let (start_code, end_code) = (outcode(start), outcode(end));
+        if start_code | end_code == 0 {
+            return Some((start, end));
         }
-        if self.points.0.x == self.points.1.x || self.points.0.y == self.points.1.y {
-            //Vertical lines and Horizontal lines - converting direcly to usize is valid as it will just shift any offscreen endpoint vertically/horizontally until they are 0
-            return (
-                (f32_to_usize(self.points.0.x), f32_to_usize(self.points.0.y)),
-                (f32_to_usize(self.points.1.x), f32_to_usize(self.points.1.y)),
-            );
+        if start_code & end_code != 0 {
+            //Both ends are past the same side
+            return None;
         }
-
-        let first = if self.points.0.x < self.points.1.x {
-            self.points.0
+        //Moves whichever end is outside onto the side it's past, along the line
+        let (code, point) = if start_code != 0 {
+            (start_code, &mut start)
         } else {
-            self.points.1
-        }; //Handles if the second point comes before the first.
-        let second = if self.points.0.x < self.points.1.x {
-            self.points.1
+            (end_code, &mut end)
+        };
+        *point = if code & 0b0011 != 0 {
+            let x = if code & 0b0001 != 0 { min.x } else { max.x };
+            Vector2::new(x, from.y + along.y * (x - from.x) / along.x)
         } else {
-            self.points.0
+            let y = if code & 0b0100 != 0 { min.y } else { max.y };
+            Vector2::new(from.x + along.x * (y - from.y) / along.y, y)
         };
-
-        let slope = (second.y - first.y) / (second.x - first.x);
-        let equation_for_y = |x: f32| slope * (x - first.x) + first.y;
-        let equation_for_x = |y: f32| (y - first.y) / slope + first.x;
-
-        if first.x < 0.0 {
-            //If the first point is offscreen to the left
-            let ret = ((0.0, equation_for_y(0.0)), (second.x, second.y));
-            if ret.0 .1 < 0.0 {
-                return (
-                    (f32_to_usize(equation_for_x(0.0)), 0),
-                    (f32_to_usize(ret.1 .0), f32_to_usize(ret.1 .1)),
-                );
-            }
-            return (
-                (f32_to_usize(ret.0 .0), f32_to_usize(ret.0 .1)),
-                (f32_to_usize(ret.1 .0), f32_to_usize(ret.1 .1)),
-            );
-        }
-        if first.y < 0.0 {
-            //If the first point is above the screen
-            let ret = ((equation_for_x(0.0), 0.0), (second.x, second.y));
-            if ret.0 .0 < 0.0 {
-                return {
-                    (
-                        (0, f32_to_usize(equation_for_y(0.0))),
-                        (f32_to_usize(ret.1 .0), f32_to_usize(ret.1 .1)),
-                    )
-                };
-            }
-            return (
-                (f32_to_usize(ret.0 .0), f32_to_usize(ret.0 .1)),
-                (f32_to_usize(ret.1 .0), f32_to_usize(ret.1 .1)),
-            );
-        } else if second.y < 0.0 {
-            //If the second point is above the screen
-            return (
-                (f32_to_usize(first.x), f32_to_usize(first.y)),
-                (f32_to_usize(equation_for_x(0.0)), 0),
-            );
-        } // We don't need to worry about if the second point is left of the screen, as we know that the first point is the leftmost one and if they are both offscreen to the left then there was an early return
-
-        (
-            (f32_to_usize(self.points.0.x), f32_to_usize(self.points.0.y)),
-            (f32_to_usize(self.points.1.x), f32_to_usize(self.points.1.y)),
-        )
     }
 }
 
 impl CharBuffer {
     pub fn draw_line(&mut self, line: Line) {
         //! Draws an individual line to the buffer
-        let coords: ((usize, usize), (usize, usize)) = line.into();
-        draw_line(line.char, self, coords.0, coords.1);
+        self.draw_line_clipped(line, self.region());
+    }
+    pub fn draw_line_clipped(&mut self, line: Line, clip: Region) {
+        //! Draws the part of a line inside clip, ex: to keep a viewport's lines out of the rest of the buffer.
+        let char = line.char;
+        for_each_cell(line, clip.clipped(self.dimensions), |x, y| {
+            let _ = self.set_char(x, y, char);
+        });
     }
     pub fn draw_lines(&mut self, lines: Vec<Line>) {
         //! Draws lines to the buffer. The first lines in the vector will be drawn first.
         //! # Example
         //! ```
+        //! # use ascii_renderer::prelude::*;
         //! let mut buf = CharBuffer::new(10, 10);
         //! let lines = vec![
         //!     Line {
@@ -121,163 +136,513 @@ impl CharBuffer {
     }
 }
 
-fn draw_line(
-    char: char,
-    buf: &mut CharBuffer,
-    mut start_coords: (usize, usize),
-    mut end_coords: (usize, usize),
-) {
-    //! The lower level function for drawing lines. Works, but its best to use higher level as it eliviates the jank of the usize params
+/// Chars for the edges of antialiased lines, from barely covered to nearly fully covered.
+pub const AA_RAMP: &[char] = &['.', ':'];
 
-    if start_coords.0 > end_coords.0 {
-        //Eliminates left-pointing lines
-        let b = start_coords;
-        start_coords = end_coords;
-        end_coords = b;
+impl CharBuffer {
+    pub fn draw_line_aa(&mut self, line: Line, ramp: &[char]) {
+        //! Draws a line like draw_line, but where it passes between two rows (or columns, for steep lines), the nearer cell gets the line's char and the farther one gets a char from ramp for how close the line comes to it, faintest first. This smooths out the stair steps of nearly horizontal and vertical lines. Edge chars only go in empty cells, or over fainter edge chars.
+        //! # Example
+        //! ```
+        //! # use ascii_renderer::prelude::*;
+        //! # use ascii_renderer::line::AA_RAMP;
+        //! let mut buf = CharBuffer::new(5, 2);
+        //! buf.draw_line_aa(
+        //!     Line {
+        //!         char: '#',
+        //!         points: (vec2!(0.0, 0.0), vec2!(4.0, 1.0)),
+        //!     },
+        //!     AA_RAMP,
+        //! );
+        //! assert_eq!(&buf.to_string(), "# # : .   \n  . # # # \n");
+        //! ```
+        if !line.is_finite() {
+            return;
+        }
+        let (mut start, mut end) = line.points;
+        //Steep lines are drawn with x and y swapped, so the loop always steps along the longer axis
+        let steep = (end.y - start.y).abs() > (end.x - start.x).abs();
+        if steep {
+            start = Vector2::new(start.y, start.x);
+            end = Vector2::new(end.y, end.x);
+        }
+        if start.x > end.x {
+            core::mem::swap(&mut start, &mut end);
+        }
+        let (length, breadth) = if steep {
+            (self.dimensions.1, self.dimensions.0)
+        } else {
+            (self.dimensions.0, self.dimensions.1)
+        };
+        let gradient = if end.x == start.x {
+            0.0
+        } else {
+            (end.y - start.y) / (end.x - start.x)
+        };
+        let mut first = start.x.round().max(0.0);
+        let mut last = end.x.round().min(length as Scalar - 1.0);
+        //Only step along the part where the line (or its edge chars) are inside the buffer
+        let (above, below) = (-1.0, breadth as Scalar);
+        if gradient != 0.0 {
+            let enter = start.x + (above - start.y) / gradient;
+            let exit = start.x + (below - start.y) / gradient;
+            first = first.max(enter.min(exit).floor());
+            last = last.min(enter.max(exit).ceil());
+        } else if start.y < above || start.y > below {
+            return;
+        }
+        if first.is_nan() || last.is_nan() || first > last {
+            return;
+        }
+        let mut plot = |major: i64, minor: Scalar, char: char, edge: bool| {
+            if minor < 0.0 || minor >= breadth as Scalar || major < 0 || major >= length as i64 {
+                return;
+            }
+            let (x, y) = if steep {
+                (minor as usize, major as usize)
+            } else {
+                (major as usize, minor as usize)
+            };
+            if edge {
+                let rank = |x: char| ramp.iter().position(|&y| y == x);
+                match self.get_char(x, y) {
+                    Some(' ') => (),
+                    Some(current) if rank(current).is_some_and(|x| Some(x) < rank(char)) => (),
+                    _ => return,
+                }
+            }
+            let _ = self.set_char(x, y, char);
+        };
+        for major in first as i64..=last as i64 {
+            let y = start.y + gradient * (major as Scalar - start.x);
+            let near = y.round();
+            plot(major, near, line.char, false);
+            let offset = y - near;
+            if offset == 0.0 || ramp.is_empty() {
+                continue;
+            }
+            //The offset is up to half a cell, so double it to get from 0.0 to 1.0
+            let index = (offset.abs() * 2.0 * (ramp.len() + 1) as Scalar) as usize;
+            if index > 0 {
+                plot(
+                    major,
+                    near + offset.signum(),
+                    ramp[(index - 1).min(ramp.len() - 1)],
+                    true,
+                );
+            }
+        }
     }
+}
 
-    let slope = (end_coords.1 as f32 - start_coords.1 as f32)
-        / (end_coords.0 as f32 - start_coords.0 as f32);
+/// How far lines can go past the edges of the buffer before they're cut shorter, which changes which cells they go through a little. Past this, Scalars can't hold every whole number anymore.
+const FAR: Scalar = 16_000_000.0;
 
-    if slope < -1.0 {
-        //Down vertical
-        draw_vertical(char, buf, start_coords, end_coords, false);
-    } else if slope > 1.0 {
-        //up vertical
-        draw_vertical(char, buf, start_coords, end_coords, true);
-    } else {
-        //right horizontal
-        draw_horizontal(char, buf, start_coords, end_coords);
+pub fn rasterize_line(
+    p0: Vector2,
+    p1: Vector2,
+    clip: Region,
+) -> impl Iterator<Item = (usize, usize)> {
+    //! Every cell CharBuffer::draw_line_clipped draws for a line from p0 to p1, in order from p0's end. These are guaranteed (and property tested):
+    //! - Every cell is inside clip, and none are repeated.
+    //! - Each cell touches the one before it, including diagonally, so there are no gaps.
+    //! - Without clipping, the first cell is p0 rounded, the last is p1 rounded, and there's exactly one cell per step along the longer axis.
+    //! - Clipping only takes cells away from the ends: the cells are the ones the line would have without clipping that are inside clip, in the same order.
+    //! - Lines with a NaN or infinite point have no cells.
+    //!
+    //! Lines going more than 16 million cells past clip are cut shorter before they're stepped through, which can move their cells slightly.
+    //! # Example
+    //! ```
+    //! # use ascii_renderer::prelude::*;
+    //! # use ascii_renderer::char_buffer::Region;
+    //! # use ascii_renderer::line::rasterize_line;
+    //! let cells: Vec<_> = rasterize_line(vec2!(0.0, 0.0), vec2!(4.0, 2.0), Region::new(0, 0, 10, 10)).collect();
+    //! assert_eq!(cells, vec![(0, 0), (1, 1), (2, 1), (3, 2), (4, 2)]);
+    //! let clipped: Vec<_> = rasterize_line(vec2!(0.0, 0.0), vec2!(4.0, 2.0), Region::new(2, 0, 2, 10)).collect();
+    //! assert_eq!(clipped, vec![(2, 1), (3, 2)]);
+    //! ```
+    let ends = clip_to((p0, p1), Vector2::new(-FAR, -FAR), Vector2::new(FAR, FAR));
+    let round = |point: Vector2| (point.x.round() as i64, point.y.round() as i64);
+    match ends {
+        Some((start, end)) => bresenham(round(start), round(end), clip),
+        None => bresenham((0, 0), (0, 0), Region::new(0, 0, 0, 0)),
     }
 }
 
-fn draw_vertical(
-    char: char,
-    buf: &mut CharBuffer,
-    start_coords: (usize, usize),
-    end_coords: (usize, usize),
-    is_up: bool,
-) {
-    let inv_slope = (end_coords.0 as f32 - start_coords.0 as f32)
-        / (end_coords.1 as f32 - start_coords.1 as f32);
-
-    if is_up {
-        let equation =
-            |y: usize| (inv_slope * (y - start_coords.1) as f32 + start_coords.0 as f32) as usize;
-        for y in start_coords.1..=end_coords.1 {
-            buf.set_char(equation(y), y, char);
-        }
-    } else {
-        let equation =
-            |y: usize| (inv_slope * (y - end_coords.1) as f32 + end_coords.0 as f32) as usize;
-        for y in end_coords.1..=start_coords.1 {
-            buf.set_char(equation(y), y, char);
-        }
+pub(crate) fn for_each_cell(line: Line, clip: Region, mut plot: impl FnMut(usize, usize)) {
+    //! Calls plot with every cell CharBuffer::draw_line_clipped would draw for the line.
+    for (x, y) in rasterize_line(line.points.0, line.points.1, clip) {
+        plot(x, y);
     }
 }
 
-fn draw_horizontal(
-    char: char,
-    buf: &mut CharBuffer,
-    start_coords: (usize, usize),
-    end_coords: (usize, usize),
-) {
-    let slope = (end_coords.1 as f32 - start_coords.1 as f32)
-        / (end_coords.0 as f32 - start_coords.0 as f32);
-
-    let equation =
-        |x: usize| (slope * (x - start_coords.0) as f32 + start_coords.1 as f32) as usize;
-
-    for x in start_coords.0..=end_coords.0 {
-        buf.set_char(x, equation(x), char);
-    }
+fn bresenham(
+    start: (i64, i64),
+    end: (i64, i64),
+    clip: Region,
+) -> impl Iterator<Item = (usize, usize)> {
+    //! Uses integer Bresenham, so every line has exactly one char per step along its longer axis and no gaps. Only the cells inside clip are stepped through: the k-th step along the longer axis moves floor((2 * k * minor + major) / (2 * major)) cells along the other one (where major and minor are how far the line goes along each), which is where Bresenham's error term puts it, so the cells are the same as stepping from the start.
+    let steep = (end.1 - start.1).abs() > (end.0 - start.0).abs();
+    let split = |point: (i64, i64)| if steep { (point.1, point.0) } else { point };
+    let (from, to) = (split(start), split(end));
+    let (major, minor) = ((to.0 - from.0).abs() as i128, (to.1 - from.1).abs() as i128);
+    let (major_step, minor_step) = ((to.0 - from.0).signum(), (to.1 - from.1).signum());
+    let offset = move |k: i64| {
+        if major == 0 {
+            0
+        } else {
+            ((2 * k as i128 * minor + major) / (2 * major)) as i64
+        }
+    };
+    let steps = 'steps: {
+        if clip.width == 0 || clip.height == 0 {
+            break 'steps None;
+        }
+        let (low, high) = (
+            split((clip.x as i64, clip.y as i64)),
+            split((
+                (clip.x + clip.width - 1) as i64,
+                (clip.y + clip.height - 1) as i64,
+            )),
+        );
+        let inside = |value: i64, low: i64, high: i64| (low..=high).contains(&value);
+        //The steps along the longer axis that are inside clip on that axis
+        let steps = |value: i64| (value - from.0) * major_step;
+        let (mut first, mut last) = if major_step == 0 {
+            (0, 0)
+        } else {
+            let (a, b) = (steps(low.0), steps(high.0));
+            (a.min(b).max(0), a.max(b).min(major as i64))
+        };
+        if major_step == 0 && !inside(from.0, low.0, high.0) {
+            break 'steps None;
+        }
+        //Then narrowed to the ones inside on the other axis, which only ever moves one way
+        let in_minor = |k: i64| inside(from.1 + offset(k) * minor_step, low.1, high.1);
+        let before = |k: i64| {
+            let value = from.1 + offset(k) * minor_step;
+            if minor_step >= 0 {
+                value < low.1
+            } else {
+                value > high.1
+            }
+        };
+        //Binary searches for the first step that isn't before clip, then the first one after it
+        let (mut lo, mut hi) = (first, last + 1);
+        while lo < hi {
+            let middle = lo + (hi - lo) / 2;
+            if before(middle) {
+                lo = middle + 1;
+            } else {
+                hi = middle;
+            }
+        }
+        first = lo;
+        let (mut lo, mut hi) = (first, last + 1);
+        while lo < hi {
+            let middle = lo + (hi - lo) / 2;
+            if in_minor(middle) {
+                lo = middle + 1;
+            } else {
+                hi = middle;
+            }
+        }
+        last = lo - 1;
+        Some((first, last))
+    };
+    //An empty range when nothing is inside clip
+    let (first, last) = steps.unwrap_or((1, 0));
+    (first..=last).map(move |k| {
+        let (a, b) = (from.0 + k * major_step, from.1 + offset(k) * minor_step);
+        let (x, y) = if steep { (b, a) } else { (a, b) };
+        (x as usize, y as usize)
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::vec2;
+    use alloc::string::ToString;
+    use alloc::vec;
+    use proptest::prelude::*;
+
+    fn draw_line(
+        char: char,
+        buf: &mut CharBuffer,
+        start_coords: (usize, usize),
+        end_coords: (usize, usize),
+    ) {
+        let region = buf.region();
+        for (x, y) in bresenham(
+            (start_coords.0 as i64, start_coords.1 as i64),
+            (end_coords.0 as i64, end_coords.1 as i64),
+            region,
+        ) {
+            let _ = buf.set_char(x, y, char);
+        }
+    }
+
     #[test]
     fn line_conversion() {
+        let screen = Region::new(0, 0, 10, 10);
         let line = Line {
             char: 'x',
             points: (vec2!(-1.0, 5.0), vec2!(3.0, 4.0)),
         };
-        assert_eq!(
-            Into::<((usize, usize), (usize, usize))>::into(line),
-            ((0, 5), (3, 4))
-        );
+        assert_eq!(line.cells(screen), Some(((0, 5), (3, 4))));
         let line = Line {
             char: 'x',
             points: (vec2!(3.0, 4.0), vec2!(1.0, -2.0)),
         };
-        assert_eq!(
-            Into::<((usize, usize), (usize, usize))>::into(line),
-            ((2, 0), (3, 4))
-        );
+        assert_eq!(line.cells(screen), Some(((3, 4), (2, 0))));
         let line = Line {
             char: 'x',
             points: (vec2!(1.0, 5.0), vec2!(3.0, -1.0)),
         };
-        assert_eq!(
-            Into::<((usize, usize), (usize, usize))>::into(line),
-            ((1, 5), (3, 0))
-        );
+        assert_eq!(line.cells(screen), Some(((1, 5), (3, 0))));
         let line = Line {
             char: 'x',
             points: (vec2!(-1.0, 5.0), vec2!(3.0, 5.0)),
         };
-        assert_eq!(
-            Into::<((usize, usize), (usize, usize))>::into(line),
-            ((0, 5), (3, 5))
-        );
+        assert_eq!(line.cells(screen), Some(((0, 5), (3, 5))));
         let line = Line {
             char: 'x',
             points: (vec2!(1.0, -2.0), vec2!(1.0, 3.0)),
         };
-        assert_eq!(
-            Into::<((usize, usize), (usize, usize))>::into(line),
-            ((1, 0), (1, 3))
-        );
+        assert_eq!(line.cells(screen), Some(((1, 0), (1, 3))));
         let line = Line {
             char: 'x',
             points: (vec2!(1.0, -2.0), vec2!(1.0, -3.0)),
         };
-        assert_eq!(
-            Into::<((usize, usize), (usize, usize))>::into(line),
-            (
-                (4_000_000_000, 4_000_000_000),
-                (4_000_000_000, 4_000_000_000)
-            )
-        );
+        assert_eq!(line.cells(screen), None);
         let line = Line {
             char: 'x',
             points: (vec2!(-1.0, 2.0), vec2!(-4.0, 3.0)),
         };
-        assert_eq!(
-            Into::<((usize, usize), (usize, usize))>::into(line),
-            (
-                (4_000_000_000, 4_000_000_000),
-                (4_000_000_000, 4_000_000_000)
-            )
-        );
+        assert_eq!(line.cells(screen), None);
         let line = Line {
             char: 'x',
             points: (vec2!(-1.0, 2.0), vec2!(-4.0, -3.0)),
         };
-        assert_eq!(
-            Into::<((usize, usize), (usize, usize))>::into(line),
-            (
-                (4_000_000_000, 4_000_000_000),
-                (4_000_000_000, 4_000_000_000)
-            )
-        );
+        assert_eq!(line.cells(screen), None);
         let line = Line {
             char: 'x',
             points: (vec2!(1.3, 2.5), vec2!(4.3, 3.9)),
         };
+        assert_eq!(line.cells(screen), Some(((1, 3), (4, 4))));
+    }
+
+    #[test]
+    fn clipping_to_regions() {
+        let mut buf = CharBuffer::new(8, 4);
+        let viewport = Region::new(2, 1, 4, 2);
+        //A diagonal crossing the whole buffer only draws inside the viewport
+        let line = Line {
+            char: '#',
+            points: (vec2!(-100.0, -101.0), vec2!(100.0, 99.0)),
+        };
+        buf.draw_line_clipped(line, viewport);
+        assert_eq!(
+            &buf.to_string(),
+            "                \n    #           \n      #         \n                \n"
+        );
+        let mut whole = CharBuffer::new(8, 4);
+        whole.draw_line(line);
+        //Clipped ends are where the whole line would have been drawn
+        for (x, y) in (0..8).flat_map(|x| (0..4).map(move |y| (x, y))) {
+            if viewport.contains(x, y) {
+                assert_eq!(buf.get_char(x, y), whole.get_char(x, y));
+            }
+        }
+        assert_eq!(line.clip(Region::new(0, 0, 0, 4)), None);
+        //Clipping keeps the line's direction
+        let clipped = line.clip(viewport).unwrap();
+        assert!(clipped.points.0.x < clipped.points.1.x);
+    }
+
+    #[test]
+    fn clipping_at_borders() {
+        //Lines leaving through each border draw the same cells as in a bigger buffer with room for all of them
+        let (width, height, margin) = (10, 6, 20);
+        let lines = [
+            (vec2!(4.0, 3.0), vec2!(-7.0, 1.3)),
+            (vec2!(4.0, 3.0), vec2!(23.0, 4.6)),
+            (vec2!(4.0, 3.0), vec2!(5.5, -9.0)),
+            (vec2!(4.0, 3.0), vec2!(2.2, 15.0)),
+            (vec2!(8.0, 5.0), vec2!(13.0, 9.0)),
+            (vec2!(-3.0, 2.0), vec2!(12.0, 4.3)),
+            (vec2!(-2.0, -1.0), vec2!(11.0, 8.0)),
+            (vec2!(9.4, -3.0), vec2!(9.4, 30.0)),
+        ];
+        for points in lines {
+            let line = Line { char: '#', points };
+            let offset = vec2!(margin as Scalar, margin as Scalar);
+            let shifted = Line {
+                char: '#',
+                points: (points.0 + offset, points.1 + offset),
+            };
+            let mut cells = vec![];
+            for_each_cell(line, Region::new(0, 0, width, height), |x, y| {
+                cells.push((x, y))
+            });
+            assert!(cells.iter().all(|&(x, y)| x < width && y < height));
+            for antialiased in [false, true] {
+                let (mut buf, mut big) = (
+                    CharBuffer::new(width, height),
+                    CharBuffer::new(width + margin * 2, height + margin * 2),
+                );
+                if antialiased {
+                    buf.draw_line_aa(line, AA_RAMP);
+                    big.draw_line_aa(shifted, AA_RAMP);
+                } else {
+                    buf.draw_line(line);
+                    big.draw_line(shifted);
+                }
+                for (x, y) in (0..width).flat_map(|x| (0..height).map(move |y| (x, y))) {
+                    assert_eq!(
+                        buf.get_char(x, y),
+                        big.get_char(x + margin, y + margin),
+                        "{points:?} at ({x}, {y}), antialiased: {antialiased}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bresenham_octants() {
+        let center = (10, 10);
+        for end in [
+            (18, 13),
+            (13, 18),
+            (7, 18),
+            (2, 13),
+            (2, 7),
+            (7, 2),
+            (13, 2),
+            (18, 7),
+            (18, 18),
+            (2, 2),
+            (10, 18),
+            (18, 10),
+        ] {
+            for (start, end) in [(center, end), (end, center)] {
+                let mut buf = CharBuffer::new(21, 21);
+                draw_line('#', &mut buf, start, end);
+                let cells: Vec<(usize, usize)> = (0..21)
+                    .flat_map(|y| (0..21).map(move |x| (x, y)))
+                    .filter(|&(x, y)| buf.get_char(x, y) == Some('#'))
+                    .collect();
+                let dx = start.0.abs_diff(end.0);
+                let dy = start.1.abs_diff(end.1);
+                assert_eq!(cells.len(), dx.max(dy) + 1, "{start:?} -> {end:?}");
+                assert!(cells.contains(&start) && cells.contains(&end));
+                //Exactly one char for each step along the longer axis, so there are no gaps or doubled up chars
+                for i in 0..=dx.max(dy) {
+                    let count = cells
+                        .iter()
+                        .filter(|&&(x, y)| {
+                            if dx >= dy {
+                                x == start.0.min(end.0) + i
+                            } else {
+                                y == start.1.min(end.1) + i
+                            }
+                        })
+                        .count();
+                    assert_eq!(count, 1, "{start:?} -> {end:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn antialiasing() {
+        //Steep lines shade the columns beside them instead
+        let mut buf = CharBuffer::new(2, 5);
+        let line = Line {
+            char: '#',
+            points: (vec2!(0.0, 0.0), vec2!(1.0, 4.0)),
+        };
+        buf.draw_line_aa(line, AA_RAMP);
+        assert_eq!(&buf.to_string(), "#   \n# . \n: # \n. # \n  # \n");
+        //The line's own cells are the same as draw_line's
+        let mut plain = CharBuffer::new(2, 5);
+        plain.draw_line(line);
+        for y in 0..5 {
+            for x in 0..2 {
+                if plain.get_char(x, y) == Some('#') {
+                    assert_eq!(buf.get_char(x, y), Some('#'));
+                }
+            }
+        }
+        //Edges don't cover other lines
+        let mut buf = CharBuffer::new(5, 2);
+        buf.fill('=');
+        buf.draw_line_aa(
+            Line {
+                char: '#',
+                points: (vec2!(0.0, 0.0), vec2!(4.0, 1.0)),
+            },
+            AA_RAMP,
+        );
+        assert!(!buf.to_string().contains(['.', ':']));
+    }
+
+    /// Where points are generated, so every line fits in a 61x61 buffer.
+    const SPAN: core::ops::Range<Scalar> = 0.0..60.0;
+
+    proptest! {
+        #[test]
+        fn rasterization_invariants(
+            x0 in SPAN, y0 in SPAN, x1 in SPAN, y1 in SPAN,
+            (x, y, width, height) in (0usize..60, 0usize..60, 0usize..30, 0usize..30),
+        ) {
+            let (p0, p1) = (vec2!(x0, y0), vec2!(x1, y1));
+            let clip = Region::new(x, y, width, height);
+            let full: Vec<_> = rasterize_line(p0, p1, Region::new(0, 0, 61, 61)).collect();
+            let round = |x: Scalar| x.round() as usize;
+            prop_assert_eq!(full[0], (round(x0), round(y0)));
+            prop_assert_eq!(full[full.len() - 1], (round(x1), round(y1)));
+            let steps = round(x0).abs_diff(round(x1)).max(round(y0).abs_diff(round(y1)));
+            prop_assert_eq!(full.len(), steps + 1);
+
+            let cells: Vec<_> = rasterize_line(p0, p1, clip).collect();
+            let inside: Vec<_> = full
+                .iter()
+                .copied()
+                .filter(|&(x, y)| clip.contains(x, y))
+                .collect();
+            prop_assert_eq!(&cells, &inside);
+            let unique: hashbrown::HashSet<_> = cells.iter().collect();
+            prop_assert_eq!(unique.len(), cells.len());
+            let touching = |cells: &[(usize, usize)]| {
+                cells
+                    .windows(2)
+                    .all(|x| x[0].0.abs_diff(x[1].0) <= 1 && x[0].1.abs_diff(x[1].1) <= 1)
+            };
+            //The cells inside are one unbroken run, so they touch too
+            prop_assert!(touching(&full) && touching(&cells));
+        }
+    }
+
+    #[test]
+    fn rasterizing_odd_lines() {
+        let clip = Region::new(0, 0, 10, 10);
+        let point = rasterize_line(vec2!(3.2, 4.7), vec2!(2.8, 5.4), clip);
+        assert_eq!(point.collect::<Vec<_>>(), vec![(3, 5)]);
+        let nan = Scalar::NAN;
+        assert_eq!(
+            rasterize_line(vec2!(nan, 0.0), vec2!(1.0, 1.0), clip).count(),
+            0
+        );
+        assert_eq!(
+            rasterize_line(vec2!(-5.0, 0.0), vec2!(-1.0, 9.0), clip).count(),
+            0
+        );
         assert_eq!(
-            Into::<((usize, usize), (usize, usize))>::into(line),
-            ((1, 3), (4, 4))
+            rasterize_line(vec2!(0.0, 0.0), vec2!(9.0, 9.0), Region::new(0, 0, 0, 5)).count(),
+            0
         );
     }
 }
@@ -0,0 +1,87 @@
+use super::rendering::{Mesh, Renderer};
+use super::{Mat4, Scalar, Vector3};
+use alloc::vec::Vec;
+
+impl Mesh {
+    pub fn add_lod(&mut self, min_distance: Scalar, edges: Vec<(usize, usize)>) {
+        //! Adds a level of detail: once the mesh is at least min_distance from the camera, these edges are drawn instead of its normal ones. The edges use the mesh's vertices, so usually they are a subset of the normal edges (see decimated_edges), but they can connect the vertices differently too.
+        //! # Example
+        //! ```
+        //! # use ascii_renderer::prelude::*;
+        //! let mut cube = ascii_renderer::create_cube();
+        //! let outline = cube.decimated_edges(2);
+        //! cube.add_lod(20.0, outline);
+        //! cube.add_lod(50.0, vec![]);   //Too far away to bother drawing
+        //! ```
+        let index = self.lods.partition_point(|x| x.0 <= min_distance);
+        alloc::sync::Arc::make_mut(&mut self.lods).insert(index, (min_distance, edges));
+    }
+    pub fn get_lods(&self) -> &Vec<(Scalar, Vec<(usize, usize)>)> {
+        &self.lods
+    }
+    pub fn clear_lods(&mut self) {
+        alloc::sync::Arc::make_mut(&mut self.lods).clear();
+    }
+    pub fn edges_at_distance(&self, distance: Scalar) -> &Vec<(usize, usize)> {
+        //! The edges drawn when the mesh is the given distance from the camera.
+        self.lods
+            .iter()
+            .rev()
+            .find(|x| x.0 <= distance)
+            .map(|x| &x.1)
+            .unwrap_or(self.get_edges())
+    }
+    pub fn decimated_edges(&self, keep_one_in: usize) -> Vec<(usize, usize)> {
+        //! Every keep_one_in'th edge, a quick way of making a cheaper level of detail for dense meshes.
+        self.get_edges()
+            .iter()
+            .step_by(keep_one_in.max(1))
+            .copied()
+            .collect()
+    }
+}
+
+impl Renderer {
+    pub(crate) fn lod_edges<'a>(&self, mesh: &'a Mesh, model: &Mat4) -> &'a [(usize, usize)] {
+        //! The edges to draw for the mesh, based on how far the origin of its model matrix is from the camera.
+        if mesh.lods.is_empty() {
+            return mesh.get_edges();
+        }
+        let origin = model.transform_point(Vector3::new(0.0, 0.0, 0.0));
+        mesh.edges_at_distance((origin - self.camera.position).len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use alloc::vec;
+
+    #[test]
+    fn lod_selection() {
+        let mut cube = crate::create_cube();
+        let all = cube.get_edges().clone();
+        cube.add_lod(50.0, vec![]);
+        cube.add_lod(10.0, cube.decimated_edges(3));
+        assert_eq!(cube.get_lods()[0].0, 10.0);
+        assert_eq!(cube.edges_at_distance(5.0), &all);
+        assert_eq!(cube.edges_at_distance(20.0).len(), 4);
+        assert!(cube.edges_at_distance(60.0).is_empty());
+
+        let mut renderer = Renderer::new(
+            vec![cube],
+            Camera {
+                position: vec3!(0.0, 0.0, -5.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(0.7, 0.7),
+            },
+        );
+        let mut near = CharBuffer::new(30, 30);
+        renderer.draw(&mut near);
+        assert_ne!(near, CharBuffer::new(30, 30));
+        renderer.meshs[0].position.z = 60.0;
+        let mut far = CharBuffer::new(30, 30);
+        renderer.draw(&mut far);
+        assert_eq!(far, CharBuffer::new(30, 30));
+    }
+}
@@ -0,0 +1,222 @@
+//! Captures records from the [log](https://docs.rs/log) crate so they can be shown on top of frames, behind the `log` feature. Printing while frames are being drawn scrambles them, so logging into a LogBuffer and showing it with a LogOverlay is the way to see what's happening in a running scene.
+//! # Example
+//! ```no_run
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::logging::{LogBuffer, LogOverlay};
+//! # use ascii_renderer::runner::StdoutBackend;
+//! # struct MyLogic;
+//! # impl Logic for MyLogic {
+//! #     fn process(&mut self, _: &mut CharBuffer, _: f32) -> ProcessReturn { ProcessReturn::End }
+//! # }
+//! let log = LogBuffer::new(100);
+//! log.install(log::LevelFilter::Info).unwrap();
+//! log::info!("starting");
+//! //The last 5 lines are shown at the bottom of every frame
+//! let backend = LogOverlay::new(StdoutBackend::default(), log, 5);
+//! let mut runner = Runner::with_backend(40, 20, 30, MyLogic, backend);
+//! runner.run(true);
+//! ```
+
+use super::cache::lock;
+use super::char_buffer::CharBuffer;
+use super::runner::Backend;
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// A captured log record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogLine {
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for LogLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:<5} {}", self.level, self.message)
+    }
+}
+
+/// The latest log lines, up to a capacity. Clones share the same lines, so one can be installed as the logger while another is used to show them.
+#[derive(Debug, Clone)]
+pub struct LogBuffer {
+    lines: Arc<Mutex<VecDeque<LogLine>>>,
+    capacity: usize,
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+    pub fn install(&self, level: LevelFilter) -> Result<(), SetLoggerError> {
+        //! Makes this buffer the logger every log macro writes to, keeping records up to level. This can only be done once per program, and fails if some other logger was installed first.
+        log::set_boxed_logger(Box::new(Capture {
+            buffer: self.clone(),
+            level,
+        }))?;
+        log::set_max_level(level);
+        Ok(())
+    }
+    pub fn push(&self, line: LogLine) {
+        //! Adds a line, forgetting the oldest one if the buffer is full.
+        let mut lines = lock(&self.lines);
+        if lines.len() >= self.capacity {
+            lines.pop_front();
+        }
+        if self.capacity > 0 {
+            lines.push_back(line);
+        }
+    }
+    pub fn last(&self, count: usize) -> Vec<LogLine> {
+        //! Up to the last count lines, oldest first.
+        let lines = lock(&self.lines);
+        lines
+            .iter()
+            .skip(lines.len().saturating_sub(count))
+            .cloned()
+            .collect()
+    }
+    pub fn len(&self) -> usize {
+        lock(&self.lines).len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    pub fn clear(&self) {
+        lock(&self.lines).clear();
+    }
+    pub fn draw(&self, buf: &mut CharBuffer, count: usize) {
+        //! Draws the last count lines over the bottom of the buffer, on a blank background. Lines too long for the buffer are cut off.
+        let lines = self.last(count.min(buf.dimensions.1));
+        let top = buf.dimensions.1 - lines.len();
+        let blank = " ".repeat(buf.dimensions.0);
+        for (i, line) in lines.iter().enumerate() {
+            buf.write_str(0, top + i, &blank);
+            //Only the first line of multi-line messages fits
+            let text = line.to_string();
+            buf.write_str(0, top + i, text.lines().next().unwrap_or(""));
+        }
+    }
+}
+
+struct Capture {
+    buffer: LogBuffer,
+    level: LevelFilter,
+}
+
+impl Log for Capture {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            self.buffer.push(LogLine {
+                level: record.level(),
+                target: record.target().to_string(),
+                message: record.args().to_string(),
+            });
+        }
+    }
+    fn flush(&self) {}
+}
+
+/// A backend that draws the last few log lines over the bottom of every frame, then passes it on to another backend to be shown. The Logic's buffer is left as it is.
+#[derive(Debug)]
+pub struct LogOverlay<B: Backend> {
+    pub inner: B,
+    pub log: LogBuffer,
+    /// How many lines are shown.
+    pub lines: usize,
+    frame: CharBuffer,
+}
+
+impl<B: Backend> LogOverlay<B> {
+    pub fn new(inner: B, log: LogBuffer, lines: usize) -> Self {
+        Self {
+            inner,
+            log,
+            lines,
+            frame: CharBuffer::new(0, 0),
+        }
+    }
+}
+
+impl<B: Backend> Backend for LogOverlay<B> {
+    fn present(&mut self, buf: &CharBuffer, clear_screen: bool) -> std::io::Result<()> {
+        if self.log.is_empty() || self.lines == 0 {
+            return self.inner.present(buf, clear_screen);
+        }
+        self.frame.clone_from(buf);
+        self.log.draw(&mut self.frame, self.lines);
+        self.inner.present(&self.frame, clear_screen)
+    }
+    fn announce(&mut self, text: &str) -> std::io::Result<()> {
+        self.inner.announce(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Last(String);
+
+    impl Backend for Last {
+        fn present(&mut self, buf: &CharBuffer, _clear_screen: bool) -> std::io::Result<()> {
+            self.0 = buf.to_string();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn captures_records() {
+        let log = LogBuffer::new(2);
+        let capture = Capture {
+            buffer: log.clone(),
+            level: LevelFilter::Info,
+        };
+        for (level, message) in [
+            (Level::Info, "one"),
+            (Level::Debug, "hidden"),
+            (Level::Warn, "two"),
+            (Level::Error, "three"),
+        ] {
+            capture.log(
+                &Record::builder()
+                    .level(level)
+                    .target("game")
+                    .args(format_args!("{message}"))
+                    .build(),
+            );
+        }
+        let lines = log.last(5);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].to_string(), "WARN  two");
+        assert_eq!(lines[1].target, "game");
+        assert_eq!(log.last(1), lines[1..]);
+    }
+
+    #[test]
+    fn overlay() {
+        let log = LogBuffer::new(10);
+        let mut overlay = LogOverlay::new(Last(String::new()), log.clone(), 1);
+        let mut buf = CharBuffer::new(6, 2);
+        buf.fill('.');
+        overlay.present(&buf, false).unwrap();
+        assert_eq!(overlay.inner.0, buf.to_string());
+
+        log.push(LogLine {
+            level: Level::Info,
+            target: String::new(),
+            message: "hi".to_string(),
+        });
+        overlay.present(&buf, false).unwrap();
+        assert_eq!(overlay.inner.0, ". . . . . . \nI N F O     \n");
+        //The logic's buffer isn't drawn on
+        assert_eq!(buf.get_char(0, 1), Some('.'));
+    }
+}
@@ -0,0 +1,235 @@
+#[cfg(not(feature = "std"))]
+use super::float::Float;
+use super::{Scalar, Vector3};
+
+/// A 4x4 matrix used for transforming points. It is stored row-major, and points are treated as column vectors, so `a * b` applies `b` first and then `a`.
+/// Rotation matrices follow the same conventions as Vector3::rotate, meaning `Mat4::rotation(r).transform_point(p) == p.rotate(r)`.
+/// # Example
+/// ```
+/// # use ascii_renderer::prelude::*;
+/// let model = Mat4::translation(vec3!(0.0, 0.0, 5.0)) * Mat4::scale(vec3!(2.0, 2.0, 2.0));
+/// assert_eq!(model.transform_point(vec3!(1.0, 0.0, 0.0)), vec3!(2.0, 0.0, 5.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mat4 {
+    pub data: [[Scalar; 4]; 4],
+}
+
+impl Mat4 {
+    pub fn new(data: [[Scalar; 4]; 4]) -> Self {
+        Self { data }
+    }
+    pub fn identity() -> Self {
+        Self::new([
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+    pub fn translation(offset: Vector3) -> Self {
+        Self::new([
+            [1.0, 0.0, 0.0, offset.x],
+            [0.0, 1.0, 0.0, offset.y],
+            [0.0, 0.0, 1.0, offset.z],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+    pub fn scale(scale: Vector3) -> Self {
+        Self::new([
+            [scale.x, 0.0, 0.0, 0.0],
+            [0.0, scale.y, 0.0, 0.0],
+            [0.0, 0.0, scale.z, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+    pub fn rotation_x(theta: Scalar) -> Self {
+        let (sin, cos) = theta.sin_cos();
+        Self::new([
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, cos, sin, 0.0],
+            [0.0, -sin, cos, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+    pub fn rotation_y(theta: Scalar) -> Self {
+        let (sin, cos) = theta.sin_cos();
+        Self::new([
+            [cos, 0.0, -sin, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [sin, 0.0, cos, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+    pub fn rotation_z(theta: Scalar) -> Self {
+        let (sin, cos) = theta.sin_cos();
+        Self::new([
+            [cos, -sin, 0.0, 0.0],
+            [sin, cos, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+    pub fn rotation(rotation: Vector3) -> Self {
+        //! Rotates around x, then y, then z, the same as Vector3::rotate.
+        Self::rotation_z(rotation.z) * Self::rotation_y(rotation.y) * Self::rotation_x(rotation.x)
+    }
+    pub fn perspective(fov_x: Scalar, fov_y: Scalar, near: Scalar, far: Scalar) -> Self {
+        //! A perspective projection looking down +z, the same direction as the Camera. Visible points end up with x and y between -1.0 and 1.0 and z between 0.0 (near) and 1.0 (far) after transform_point.
+        let depth = far - near;
+        Self::new([
+            [1.0 / (fov_x * 0.5).tan(), 0.0, 0.0, 0.0],
+            [0.0, 1.0 / (fov_y * 0.5).tan(), 0.0, 0.0],
+            [0.0, 0.0, far / depth, -far * near / depth],
+            [0.0, 0.0, 1.0, 0.0],
+        ])
+    }
+    pub fn transpose(&self) -> Self {
+        let mut ret = *self;
+        for (row, values) in ret.data.iter_mut().enumerate() {
+            for (col, value) in values.iter_mut().enumerate() {
+                *value = self.data[col][row];
+            }
+        }
+        ret
+    }
+    pub fn inverse(&self) -> Option<Self> {
+        //! Inverts the matrix using Gauss-Jordan elimination. Returns None if the matrix is singular (ex: a scale of 0 on any axis).
+        let mut left = self.data;
+        let mut right = Self::identity().data;
+
+        for col in 0..4 {
+            let pivot = (col..4).max_by(|&a, &b| {
+                left[a][col]
+                    .abs()
+                    .partial_cmp(&left[b][col].abs())
+                    .unwrap_or(core::cmp::Ordering::Equal)
+            })?;
+            if left[pivot][col].abs() < Scalar::EPSILON {
+                return None;
+            }
+            left.swap(col, pivot);
+            right.swap(col, pivot);
+
+            let divisor = left[col][col];
+            for i in 0..4 {
+                left[col][i] /= divisor;
+                right[col][i] /= divisor;
+            }
+            for row in 0..4 {
+                if row == col {
+                    continue;
+                }
+                let factor = left[row][col];
+                for i in 0..4 {
+                    left[row][i] -= factor * left[col][i];
+                    right[row][i] -= factor * right[col][i];
+                }
+            }
+        }
+        Some(Self::new(right))
+    }
+    pub fn transform_point(&self, point: Vector3) -> Vector3 {
+        //! Transforms a point, including translation and the perspective divide.
+        let m = &self.data;
+        let x = m[0][0] * point.x + m[0][1] * point.y + m[0][2] * point.z + m[0][3];
+        let y = m[1][0] * point.x + m[1][1] * point.y + m[1][2] * point.z + m[1][3];
+        let z = m[2][0] * point.x + m[2][1] * point.y + m[2][2] * point.z + m[2][3];
+        let w = m[3][0] * point.x + m[3][1] * point.y + m[3][2] * point.z + m[3][3];
+        if w == 1.0 || w == 0.0 {
+            Vector3::new(x, y, z)
+        } else {
+            Vector3::new(x / w, y / w, z / w)
+        }
+    }
+    pub fn transform_vector(&self, vector: Vector3) -> Vector3 {
+        //! Transforms a direction, ignoring translation.
+        let m = &self.data;
+        Vector3::new(
+            m[0][0] * vector.x + m[0][1] * vector.y + m[0][2] * vector.z,
+            m[1][0] * vector.x + m[1][1] * vector.y + m[1][2] * vector.z,
+            m[2][0] * vector.x + m[2][1] * vector.y + m[2][2] * vector.z,
+        )
+    }
+}
+
+impl core::default::Default for Mat4 {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl core::ops::Mul for Mat4 {
+    type Output = Mat4;
+    fn mul(self, rhs: Self) -> Self::Output {
+        let mut ret = [[0.0; 4]; 4];
+        for (row, values) in ret.iter_mut().enumerate() {
+            for (col, value) in values.iter_mut().enumerate() {
+                *value = (0..4).map(|i| self.data[row][i] * rhs.data[i][col]).sum();
+            }
+        }
+        Mat4::new(ret)
+    }
+}
+
+impl core::ops::MulAssign for Mat4 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl core::ops::Mul<Vector3> for Mat4 {
+    type Output = Vector3;
+    fn mul(self, rhs: Vector3) -> Self::Output {
+        self.transform_point(rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec3;
+
+    fn assert_close(a: Vector3, b: Vector3) {
+        assert!((a - b).len() < 0.0001, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn rotation_matches_vector_rotate() {
+        let rotation = vec3!(0.3, -1.2, 2.5);
+        let point = vec3!(1.0, 2.0, -3.0);
+        assert_close(
+            Mat4::rotation(rotation).transform_point(point),
+            point.rotate(rotation),
+        );
+    }
+
+    #[test]
+    fn inverse_undoes_transform() {
+        let matrix = Mat4::translation(vec3!(1.0, -2.0, 3.0))
+            * Mat4::rotation(vec3!(0.5, 0.25, -1.0))
+            * Mat4::scale(vec3!(2.0, 0.5, 4.0));
+        let point = vec3!(-4.0, 1.5, 2.0);
+        let inverse = matrix.inverse().unwrap();
+        assert_close(
+            inverse.transform_point(matrix.transform_point(point)),
+            point,
+        );
+        assert!(Mat4::scale(vec3!(1.0, 0.0, 1.0)).inverse().is_none());
+    }
+
+    #[test]
+    fn perspective_bounds() {
+        let projection = Mat4::perspective(1.0, 1.0, 1.0, 10.0);
+        assert_close(
+            projection.transform_point(vec3!(0.0, 0.0, 1.0)),
+            vec3!(0.0, 0.0, 0.0),
+        );
+        assert_close(
+            projection.transform_point(vec3!(0.0, 0.0, 10.0)),
+            vec3!(0.0, 0.0, 1.0),
+        );
+        let edge = Scalar::tan(0.5) * 5.0;
+        assert!((projection.transform_point(vec3!(edge, 0.0, 5.0)).x - 1.0).abs() < 0.0001);
+    }
+}
@@ -0,0 +1,172 @@
+//! MIDI input for driving visuals from controllers, ex: mapping a fader to a mesh's rotation or pads to effects during a live performance. parse turns raw MIDI messages into InputEvents, and with the `midi` feature a MidiListener connects to a controller through [midir](https://docs.rs/midir) and sends what it plays to a runner's event_sender, so it reaches the logic's input alongside the keyboard and can be bound in an InputMap.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::input::{Binding, InputMap};
+//! # use ascii_renderer::midi;
+//! //A note on from channel 1 at full velocity, then its first knob halfway up
+//! assert_eq!(midi::parse(&[0x90, 60, 127]), Some(InputEvent::MidiNoteDown(0, 60, 1.0)));
+//! let mut input = InputMap::new();
+//! input.bind("spin", Binding::MidiControl(1));
+//! input.handle(&midi::parse(&[0xB0, 1, 64]).unwrap());
+//! assert!((input.axis("spin") - 0.5).abs() < 0.01);
+//! ```
+
+use super::runner::InputEvent;
+
+pub fn parse(message: &[u8]) -> Option<InputEvent> {
+    //! The event for a note on, note off, or control change message, or None for anything else (ex: clock or sysex messages). Velocities and values are scaled to 0.0 to 1.0, and a note on with a velocity of 0 is a note off, as many controllers send them that way.
+    let (&status, data) = message.split_first()?;
+    let channel = status & 0x0F;
+    let (&a, &b) = (data.first()?, data.get(1)?);
+    if a > 0x7F || b > 0x7F {
+        return None;
+    }
+    let scaled = b as f32 / 127.0;
+    match status & 0xF0 {
+        0x90 if b > 0 => Some(InputEvent::MidiNoteDown(channel, a, scaled)),
+        0x80 | 0x90 => Some(InputEvent::MidiNoteUp(channel, a)),
+        0xB0 => Some(InputEvent::MidiControl(channel, a, scaled)),
+        _ => None,
+    }
+}
+
+/// An error from connecting to a MIDI controller.
+#[cfg(feature = "midi")]
+#[derive(Debug)]
+pub enum MidiError {
+    /// The system's MIDI API couldn't be opened.
+    Init(midir::InitError),
+    /// No connected port's name contains this.
+    NoPort(String),
+    Connect(midir::ConnectErrorKind),
+}
+
+#[cfg(feature = "midi")]
+impl std::fmt::Display for MidiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MidiError::Init(err) => write!(f, "couldn't open MIDI: {err}"),
+            MidiError::NoPort(name) => write!(f, "no MIDI port named like \"{name}\""),
+            MidiError::Connect(err) => write!(f, "couldn't connect to the MIDI port: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "midi")]
+impl std::error::Error for MidiError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MidiError::Init(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "midi")]
+impl From<midir::InitError> for MidiError {
+    fn from(err: midir::InitError) -> Self {
+        MidiError::Init(err)
+    }
+}
+
+/// A connection to a MIDI controller, which sends each message it parses to a runner from midir's own thread. It stays connected until it's dropped.
+/// # Example
+/// ```no_run
+/// # use ascii_renderer::prelude::*;
+/// # use ascii_renderer::midi::MidiListener;
+/// # struct MyLogic;
+/// # impl Logic for MyLogic {
+/// #     fn process(&mut self, _screen_buf: &mut CharBuffer, _delta: f32) -> ProcessReturn {
+/// #         ProcessReturn::Continue
+/// #     }
+/// # }
+/// let mut runner = Runner::new(80, 40, 30, MyLogic);
+/// println!("{:?}", MidiListener::ports().unwrap());
+/// let _listener = MidiListener::connect("nanoKONTROL", runner.event_sender()).unwrap();
+/// runner.run(true);
+/// ```
+#[cfg(feature = "midi")]
+pub struct MidiListener {
+    _connection: midir::MidiInputConnection<()>,
+    port_name: String,
+}
+
+#[cfg(feature = "midi")]
+impl std::fmt::Debug for MidiListener {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MidiListener")
+            .field("port_name", &self.port_name)
+            .finish()
+    }
+}
+
+#[cfg(feature = "midi")]
+impl MidiListener {
+    pub fn ports() -> Result<Vec<String>, MidiError> {
+        //! The names of every MIDI input port, ex: to pick which one to connect to.
+        let input = midir::MidiInput::new("ascii_renderer")?;
+        Ok(input
+            .ports()
+            .iter()
+            .filter_map(|x| input.port_name(x).ok())
+            .collect())
+    }
+    pub fn connect(
+        port: &str,
+        events: std::sync::mpsc::Sender<InputEvent>,
+    ) -> Result<Self, MidiError> {
+        //! Connects to the first port whose name contains port, or the first port at all if it's empty. Messages stop being sent once the receiving runner is dropped.
+        let input = midir::MidiInput::new("ascii_renderer")?;
+        let (found, port_name) = input
+            .ports()
+            .into_iter()
+            .filter_map(|x| input.port_name(&x).ok().map(|name| (x, name)))
+            .find(|(_, name)| name.contains(port))
+            .ok_or_else(|| MidiError::NoPort(port.to_string()))?;
+        let connection = input
+            .connect(
+                &found,
+                "ascii_renderer",
+                move |_, message, _| {
+                    if let Some(event) = parse(message) {
+                        let _ = events.send(event);
+                    }
+                },
+                (),
+            )
+            .map_err(|x| MidiError::Connect(x.kind()))?;
+        Ok(Self {
+            _connection: connection,
+            port_name,
+        })
+    }
+    pub fn get_port_name(&self) -> &str {
+        &self.port_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_messages() {
+        assert_eq!(
+            parse(&[0x93, 36, 127]),
+            Some(InputEvent::MidiNoteDown(3, 36, 1.0))
+        );
+        assert_eq!(parse(&[0x83, 36, 64]), Some(InputEvent::MidiNoteUp(3, 36)));
+        assert_eq!(parse(&[0x90, 36, 0]), Some(InputEvent::MidiNoteUp(0, 36)));
+        assert_eq!(
+            parse(&[0xBF, 7, 0]),
+            Some(InputEvent::MidiControl(15, 7, 0.0))
+        );
+        //Clock, pitch bend, cut off, and malformed messages
+        assert_eq!(parse(&[0xF8]), None);
+        assert_eq!(parse(&[0xE0, 0, 64]), None);
+        assert_eq!(parse(&[0x90, 36]), None);
+        assert_eq!(parse(&[0x90, 36, 0x90]), None);
+        assert_eq!(parse(&[]), None);
+    }
+}
@@ -0,0 +1,250 @@
+//! A top-down map of the scene in a corner of the screen, for finding your way around. The map looks straight down (along +y, since +y is down on screen) with an orthographic projection fitted to every mesh and the camera, so +x is right and +z is up on the map. The camera is marked where it is, with lines showing the edges of what it can see.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::minimap::Minimap;
+//! let mut renderer = Renderer::new(
+//!     vec![ascii_renderer::create_cube()],
+//!     Camera {
+//!         position: vec3!(0.0, 0.0, -5.0),
+//!         rotation: vec3!(0.0, 0.0, 0.0),
+//!         fov: vec2!(1.0, 1.0),
+//!     },
+//! );
+//! let mut buf = CharBuffer::new(60, 30);
+//! renderer.draw(&mut buf);
+//! let minimap = Minimap::top_right(buf.dimensions, 16, 8);
+//! minimap.draw(&renderer, &mut buf);
+//! //The camera is at the bottom of the map, below the cube it's looking at
+//! let camera = minimap.projection(&renderer).project(renderer.camera.position);
+//! let (x, y) = (camera.x.round() as usize, camera.y.round() as usize);
+//! assert_eq!(buf.get_char(x, y), Some('@'));
+//! ```
+
+use super::char_buffer::{CharBuffer, Region};
+use super::charset::BorderStyle;
+use super::geometry::Aabb;
+use super::line::Line;
+use super::rendering::{Camera, Renderer};
+use super::{vec2, vec3, Mat4, Scalar, Vector2, Vector3};
+
+/// Draws a top-down map of a Renderer's scene into part of a buffer, see the module docs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Minimap {
+    /// Where the map is drawn, including its border.
+    pub region: Region,
+    pub border: Option<BorderStyle>,
+    /// How many times taller cells are than wide, so the map isn't stretched. 2.0 is about right for most terminal fonts.
+    pub cell_aspect: Scalar,
+    /// The empty space left around the scene, as a fraction of its size.
+    pub margin: Scalar,
+    /// What the camera's position is marked with.
+    pub camera_char: char,
+    /// What the edges of the camera's view are drawn with.
+    pub frustum_char: char,
+    /// How far the lines showing the camera's view reach. With None, they go to the edge of the map.
+    pub frustum_length: Option<Scalar>,
+}
+
+impl Minimap {
+    pub fn new(region: Region) -> Self {
+        Self {
+            region,
+            border: Some(BorderStyle::ASCII),
+            cell_aspect: 2.0,
+            margin: 0.1,
+            camera_char: '@',
+            frustum_char: '.',
+            frustum_length: None,
+        }
+    }
+    pub fn top_right(dimensions: (usize, usize), width: usize, height: usize) -> Self {
+        //! A map width by height cells in the top right corner of a buffer with the given dimensions.
+        let width = width.min(dimensions.0);
+        Self::new(Region::new(
+            dimensions.0 - width,
+            0,
+            width,
+            height.min(dimensions.1),
+        ))
+    }
+    fn inside(&self) -> Region {
+        let inset = self.border.is_some() as usize;
+        Region::new(
+            self.region.x + inset,
+            self.region.y + inset,
+            self.region.width.saturating_sub(inset * 2),
+            self.region.height.saturating_sub(inset * 2),
+        )
+    }
+    pub fn projection(&self, renderer: &Renderer) -> MapProjection {
+        //! Where the map would put things for renderer's scene as it is now, ex: to mark other objects on it after drawing.
+        let bounds = renderer.meshs.iter().filter_map(|x| x.bounding_box()).fold(
+            Aabb::new(renderer.camera.position, renderer.camera.position),
+            |a, b| a.union(&b),
+        );
+        let inside = self.inside();
+        //Lines are drawn to the nearest cell, so the outermost cells are centered on the edge of what's shown
+        let (half_width, half_height) = (
+            inside.width.saturating_sub(1) as Scalar / 2.0,
+            inside.height.saturating_sub(1) as Scalar / 2.0 * self.cell_aspect,
+        );
+        let size = bounds.size() * (0.5 + self.margin);
+        //World units per column, fitting the bigger of the two directions
+        let scale = (size.x / half_width.max(Scalar::EPSILON))
+            .max(size.z / half_height.max(Scalar::EPSILON))
+            .max(Scalar::EPSILON);
+        let center = bounds.center();
+        MapProjection {
+            center: vec2!(center.x, center.z),
+            scale,
+            cell_aspect: self.cell_aspect,
+            origin: vec2!(
+                inside.x as Scalar + half_width,
+                inside.y as Scalar + half_height / self.cell_aspect
+            ),
+        }
+    }
+    pub fn draw(&self, renderer: &Renderer, buf: &mut CharBuffer) {
+        //! Clears the map's region and draws every mesh's edges (with the mesh's char), the edges of the camera's view, the camera, and the border.
+        let projection = self.projection(renderer);
+        let inside = self.inside();
+        let region = self.region.clipped(buf.dimensions);
+        for y in region.y..region.y + region.height {
+            for x in region.x..region.x + region.width {
+                let _ = buf.set_char(x, y, ' ');
+            }
+        }
+        for mesh in renderer.meshs.iter() {
+            let vertices = mesh.get_global_verticies();
+            for (a, b) in mesh.get_edges() {
+                if let (Some(a), Some(b)) = (vertices.get(a), vertices.get(b)) {
+                    buf.draw_line_clipped(
+                        Line {
+                            char: mesh.char,
+                            points: (projection.project(*a), projection.project(*b)),
+                        },
+                        inside,
+                    );
+                }
+            }
+        }
+
+        let camera = &renderer.camera;
+        let position = projection.project(camera.position);
+        let length = self.frustum_length.unwrap_or(
+            projection.scale
+                * (inside.width as Scalar + inside.height as Scalar * self.cell_aspect),
+        );
+        for side in [-1.0, 1.0] {
+            let end = camera.position + view_edge(camera, side) * length;
+            buf.draw_line_clipped(
+                Line {
+                    char: self.frustum_char,
+                    points: (position, projection.project(end)),
+                },
+                inside,
+            );
+        }
+        if position.x >= -0.5 && position.y >= -0.5 {
+            let (x, y) = (position.x.round() as usize, position.y.round() as usize);
+            if inside.contains(x, y) {
+                let _ = buf.set_char(x, y, self.camera_char);
+            }
+        }
+        if let Some(border) = self.border {
+            border.draw(buf, region);
+        }
+    }
+}
+
+fn view_edge(camera: &Camera, side: Scalar) -> Vector3 {
+    //The global direction along the left (-1.0) or right (1.0) edge of the camera's view, flattened onto the map
+    let angle = camera.fov.x / 2.0 * side;
+    let direction = Mat4::rotation(camera.rotation)
+        .transpose()
+        .transform_vector(vec3!(angle.sin(), 0.0, angle.cos()));
+    let flat = vec3!(direction.x, 0.0, direction.z);
+    if flat.len() > Scalar::EPSILON {
+        flat.normalize()
+    } else {
+        flat
+    }
+}
+
+/// Where a Minimap puts global points, see Minimap::projection.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MapProjection {
+    /// The global x and z in the middle of the map.
+    pub center: Vector2,
+    /// How many world units wide a column of the map is. Rows are cell_aspect times taller.
+    pub scale: Scalar,
+    pub cell_aspect: Scalar,
+    /// The middle of the map in the buffer.
+    pub origin: Vector2,
+}
+
+impl MapProjection {
+    pub fn project(&self, point: Vector3) -> Vector2 {
+        //! Where a global point is on the map, in the same coordinates lines are drawn with. Height (y) is ignored.
+        vec2!(
+            self.origin.x + (point.x - self.center.x) / self.scale,
+            self.origin.y - (point.z - self.center.y) / (self.scale * self.cell_aspect)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mapping_the_scene() {
+        let mut renderer = Renderer::new(
+            vec![crate::create_cube()],
+            Camera {
+                position: vec3!(0.0, 0.0, -3.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(1.0, 1.0),
+            },
+        );
+        renderer.meshs[0].char = '#';
+        let mut buf = CharBuffer::new(13, 7);
+        let minimap = Minimap::new(buf.region());
+        minimap.draw(&renderer, &mut buf);
+        //The camera is below the cube, looking up the map at it
+        assert_eq!(
+            &buf.to_string(),
+            concat!(
+                "+ - - - - - - - - - - - + \n",
+                "|   .   # # # # #   .   | \n",
+                "|     . #       # .     | \n",
+                "|       . # # # .       | \n",
+                "|         .   .         | \n",
+                "|           @           | \n",
+                "+ - - - - - - - - - - - + \n",
+            )
+        );
+
+        //Turned to face +x, the view points right
+        renderer.camera.look_at(vec3!(10.0, 0.0, -3.0));
+        let projection = minimap.projection(&renderer);
+        let ahead =
+            projection.project(vec3!(1.0, 0.0, -3.0)) - projection.project(vec3!(0.0, 0.0, -3.0));
+        assert!(ahead.x > 0.0 && ahead.y.abs() < 0.0001);
+        let mut buf = CharBuffer::new(13, 7);
+        minimap.draw(&renderer, &mut buf);
+        assert_eq!(
+            &buf.to_string(),
+            concat!(
+                "+ - - - - - - - - - - - + \n",
+                "|       # # # # #       | \n",
+                "|       #       #       | \n",
+                "|       # # # # #       | \n",
+                "|               . . . . | \n",
+                "|           @ .         | \n",
+                "+ - - - - - - - - - - - + \n",
+            )
+        );
+    }
+}
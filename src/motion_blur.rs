@@ -0,0 +1,144 @@
+//! Persistence of vision: chars from earlier frames linger in the empty cells of new ones, leaving streaks behind anything that moves. Apply it to each frame after everything's been drawn, right before it's shown, or add it to Runner::effects as a post::PostEffect.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::motion_blur::{BlurMode, MotionBlur};
+//! let mut blur = MotionBlur::new(BlurMode::Fade(0.6), 1);
+//! let mut buf = CharBuffer::new(10, 1);
+//! buf.set_char(2, 0, '@').unwrap();
+//! blur.apply(&mut buf);
+//! //Next frame, the '@' has moved on, but a fainter copy is left behind
+//! buf.fill(' ');
+//! buf.set_char(3, 0, '@').unwrap();
+//! blur.apply(&mut buf);
+//! assert_eq!(&buf.to_string(), "    * @             \n");
+//! ```
+
+use super::char_buffer::CharBuffer;
+use super::demos::{shade, RAMP};
+use super::noise::Rng;
+use super::post::PostEffect;
+use super::transition::density;
+use super::Scalar;
+
+/// How chars from earlier frames carry over.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlurMode {
+    /// Each lingering char has this chance (0.0 to 1.0) of staying another frame, so trails break up randomly like phosphor flicker.
+    Retain(f32),
+    /// Lingering chars get fainter each frame, multiplying how much of their cell they cover (see transition::density) by this and shading with demos::RAMP, until they fade to nothing.
+    Fade(Scalar),
+}
+
+/// Keeps the last frame shown, to blend into the next one.
+#[derive(Debug, Clone)]
+pub struct MotionBlur {
+    pub mode: BlurMode,
+    /// The char that counts as empty, which is the only thing lingering chars show up over.
+    pub background: char,
+    previous: Option<CharBuffer>,
+    rng: Rng,
+}
+
+impl MotionBlur {
+    pub fn new(mode: BlurMode, seed: u32) -> Self {
+        //! The seed is only used by BlurMode::Retain.
+        Self {
+            mode,
+            background: ' ',
+            previous: None,
+            rng: Rng::new(seed),
+        }
+    }
+    pub fn reset(&mut self) {
+        //! Forgets the last frame, ex: after a scene change, so nothing from it lingers.
+        self.previous = None;
+    }
+    fn linger(&mut self, char: char) -> Option<char> {
+        match self.mode {
+            BlurMode::Retain(probability) => self.rng.chance(probability).then_some(char),
+            BlurMode::Fade(decay) => {
+                let faded = shade(RAMP, density(char) * decay);
+                (faded != ' ').then_some(faded)
+            }
+        }
+    }
+    pub fn apply(&mut self, frame: &mut CharBuffer) {
+        //! Fills the empty cells of frame with what lingers from the last one, then remembers the result for next time. A frame of a different size than the last starts over.
+        if let Some(previous) = self.previous.take() {
+            if previous.dimensions == frame.dimensions {
+                let (width, height) = frame.dimensions;
+                for y in 0..height {
+                    for x in 0..width {
+                        let old = previous.get_char(x, y).unwrap_or(self.background);
+                        if old == self.background || frame.get_char(x, y) != Some(self.background) {
+                            continue;
+                        }
+                        if let Some(char) = self.linger(old) {
+                            let _ = frame.set_char(x, y, char);
+                        }
+                    }
+                }
+            }
+        }
+        self.previous = Some(frame.clone());
+    }
+}
+
+impl PostEffect for MotionBlur {
+    fn apply(&mut self, frame: &mut CharBuffer, _delta: f32) {
+        MotionBlur::apply(self, frame);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frames(blur: &mut MotionBlur, count: usize) -> Vec<String> {
+        //A '@' on the first frame, then empty frames after
+        (0..count)
+            .map(|i| {
+                let mut buf = CharBuffer::new(3, 1);
+                if i == 0 {
+                    buf.set_char(1, 0, '@').unwrap();
+                }
+                blur.apply(&mut buf);
+                buf.to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fading() {
+        let mut blur = MotionBlur::new(BlurMode::Fade(0.5), 0);
+        let chars: Vec<String> = frames(&mut blur, 6);
+        assert_eq!(
+            chars,
+            ["  @   \n", "  +   \n", "  :   \n", "  .   \n", "      \n", "      \n"]
+        );
+        //New chars aren't covered up by old ones
+        let mut buf = CharBuffer::new(3, 1);
+        buf.set_char(1, 0, '#').unwrap();
+        blur.apply(&mut buf);
+        buf.set_char(1, 0, 'x').unwrap();
+        blur.apply(&mut buf);
+        assert_eq!(buf.get_char(1, 0), Some('x'));
+    }
+
+    #[test]
+    fn retaining() {
+        let mut always = MotionBlur::new(BlurMode::Retain(1.0), 0);
+        assert!(frames(&mut always, 10).iter().all(|x| x == "  @   \n"));
+        let mut never = MotionBlur::new(BlurMode::Retain(0.0), 0);
+        assert_eq!(frames(&mut never, 2)[1], "      \n");
+        //Resetting, or changing size, drops the old frame
+        always.reset();
+        let mut buf = CharBuffer::new(3, 1);
+        always.apply(&mut buf);
+        assert_eq!(buf.to_string(), "      \n");
+        let mut bigger = CharBuffer::new(4, 1);
+        always.apply(&mut bigger);
+        assert_eq!(bigger.to_string(), "        \n");
+    }
+}
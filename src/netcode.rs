@@ -0,0 +1,302 @@
+//! Keeping scenes in sync over the network, behind the `netcode` feature, so several terminals can watch the same live scene from their own cameras. A SyncServer sends the transform of every mesh (and the renderer's time) to each connected SyncClient every frame, and clients apply the newest one to their own renderer, which has the same meshes but can have any camera.
+//!
+//! Only transforms are sent, so clients have to build the same meshes in the same order as the server. Like TelnetBackend, nothing blocks the runner: clients that fall behind skip frames, and ones that disconnect are dropped.
+//! # Example
+//! ```no_run
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::netcode::{SyncClient, SyncServer};
+//! struct Server {
+//!     renderer: Renderer,
+//!     sync: SyncServer,
+//! }
+//! impl Logic for Server {
+//!     fn process(&mut self, screen_buf: &mut CharBuffer, delta: f32) -> ProcessReturn {
+//!         self.renderer.meshs[0].rotation.y += delta;
+//!         self.sync.broadcast(&self.renderer).unwrap();
+//!         screen_buf.fill(' ');
+//!         self.renderer.draw(screen_buf);
+//!         ProcessReturn::Continue
+//!     }
+//! }
+//! struct Client {
+//!     renderer: Renderer,
+//!     sync: SyncClient,
+//! }
+//! impl Logic for Client {
+//!     fn process(&mut self, screen_buf: &mut CharBuffer, _: f32) -> ProcessReturn {
+//!         //The camera is this client's own, only the meshes move
+//!         self.sync.sync(&mut self.renderer).unwrap();
+//!         screen_buf.fill(' ');
+//!         self.renderer.draw(screen_buf);
+//!         ProcessReturn::Continue
+//!     }
+//! }
+//! # let renderer = Renderer::new(vec![ascii_renderer::create_cube()], Camera {
+//! #     position: vec3!(0.0, 0.0, -6.0),
+//! #     rotation: vec3!(0.0, 0.0, 0.0),
+//! #     fov: vec2!(1.0, 1.0),
+//! # });
+//! let sync = SyncClient::connect("192.168.1.5:7878").unwrap();
+//! Runner::new(40, 20, 30, Client { renderer, sync }).run(true);
+//! ```
+
+use super::rendering::Renderer;
+use super::tcp::Connection;
+use super::transform::Transform;
+use super::{Scalar, Vector3};
+use std::io::{ErrorKind, Read};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+
+/// The bytes before a state's transforms: the frame number, the time, and the number of transforms.
+const HEADER_LEN: usize = 8 + 4 + 4;
+/// The bytes each transform takes: its position, rotation, and scale.
+const TRANSFORM_LEN: usize = 9 * 4;
+
+/// The part of a scene that's sent every frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneState {
+    /// Counts up by one every state the server sends, so clients can tell how many they skipped.
+    pub frame: u64,
+    /// The renderer's time, which mesh shaders use.
+    pub time: f32,
+    /// The transform of every mesh, in order.
+    pub transforms: Vec<Transform>,
+}
+
+#[allow(clippy::unnecessary_cast)]
+fn to_f32(x: Scalar) -> f32 {
+    x as f32
+}
+
+impl SceneState {
+    pub fn capture(renderer: &Renderer, frame: u64) -> Self {
+        Self {
+            frame,
+            time: renderer.time,
+            transforms: renderer.meshs.iter().map(|x| x.transform).collect(),
+        }
+    }
+    pub fn apply(&self, renderer: &mut Renderer) {
+        //! Moves each of the renderer's meshes to its transform and sets the time. Meshes past the end of either are left alone.
+        renderer.time = self.time;
+        for (mesh, transform) in renderer.meshs.iter_mut().zip(self.transforms.iter()) {
+            mesh.transform = *transform;
+        }
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        //! The state as little endian bytes, with numbers as f32s whatever Scalar is.
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.transforms.len() * TRANSFORM_LEN);
+        bytes.extend_from_slice(&self.frame.to_le_bytes());
+        bytes.extend_from_slice(&self.time.to_le_bytes());
+        bytes.extend_from_slice(&(self.transforms.len() as u32).to_le_bytes());
+        for transform in self.transforms.iter() {
+            for vector in [transform.position, transform.rotation, transform.scale] {
+                for x in [vector.x, vector.y, vector.z] {
+                    bytes.extend_from_slice(&to_f32(x).to_le_bytes());
+                }
+            }
+        }
+        bytes
+    }
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        //! Reads a state written by to_bytes, or None if the bytes aren't one.
+        let header = bytes.get(..HEADER_LEN)?;
+        let count = u32::from_le_bytes(header[12..16].try_into().unwrap()) as usize;
+        if bytes.len() != HEADER_LEN + count.checked_mul(TRANSFORM_LEN)? {
+            return None;
+        }
+        let numbers: Vec<Scalar> = bytes[HEADER_LEN..]
+            .chunks_exact(4)
+            .map(|x| Scalar::from(f32::from_le_bytes(x.try_into().unwrap())))
+            .collect();
+        let vector = |x: &[Scalar]| Vector3::new(x[0], x[1], x[2]);
+        Some(Self {
+            frame: u64::from_le_bytes(header[..8].try_into().unwrap()),
+            time: f32::from_le_bytes(header[8..12].try_into().unwrap()),
+            transforms: numbers
+                .chunks_exact(9)
+                .map(|x| Transform::new(vector(&x[..3]), vector(&x[3..6]), vector(&x[6..])))
+                .collect(),
+        })
+    }
+}
+
+/// Sends the scene to every connected SyncClient, see the module docs.
+#[derive(Debug)]
+pub struct SyncServer {
+    listener: TcpListener,
+    clients: Vec<Connection>,
+    frame: u64,
+}
+
+impl SyncServer {
+    pub fn bind(address: impl ToSocketAddrs) -> std::io::Result<Self> {
+        //! Starts listening for clients on the address, ex: "0.0.0.0:7878".
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            clients: vec![],
+            frame: 0,
+        })
+    }
+    pub fn local_addr(&self) -> std::io::Result<SocketAddr> {
+        //! Where clients connect to, including the port the OS picked if bound to port 0.
+        self.listener.local_addr()
+    }
+    pub fn client_count(&self) -> usize {
+        self.clients.len()
+    }
+    pub fn broadcast(&mut self, renderer: &Renderer) -> std::io::Result<()> {
+        //! Accepts new clients and sends everyone the renderer's state, which should be called every frame. Clients still sending the last state skip this one.
+        while let Some(client) = Connection::accept(&self.listener)? {
+            self.clients.push(client);
+        }
+        let state = SceneState::capture(renderer, self.frame).to_bytes();
+        self.frame += 1;
+        self.clients.retain_mut(|client| {
+            if client.is_idle() {
+                client
+                    .unsent
+                    .extend_from_slice(&(state.len() as u32).to_le_bytes());
+                client.unsent.extend_from_slice(&state);
+            }
+            client.send().is_ok()
+        });
+        Ok(())
+    }
+}
+
+/// Receives the scene from a SyncServer, see the module docs.
+#[derive(Debug)]
+pub struct SyncClient {
+    stream: TcpStream,
+    received: Vec<u8>,
+}
+
+impl SyncClient {
+    pub fn connect(address: impl ToSocketAddrs) -> std::io::Result<Self> {
+        let stream = TcpStream::connect(address)?;
+        stream.set_nonblocking(true)?;
+        let _ = stream.set_nodelay(true);
+        Ok(Self {
+            stream,
+            received: vec![],
+        })
+    }
+    pub fn poll(&mut self) -> std::io::Result<Option<SceneState>> {
+        //! Reads everything the server has sent without blocking, returning the newest state, or None if no new one has arrived. Returns an error once the server has disconnected, or if it sends something that isn't a state.
+        let mut bytes = [0; 4096];
+        loop {
+            match self.stream.read(&mut bytes) {
+                Ok(0) => return Err(ErrorKind::UnexpectedEof.into()),
+                Ok(count) => self.received.extend_from_slice(&bytes[..count]),
+                Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+                Err(err) => return Err(err),
+            }
+        }
+        //Every state is its length followed by its bytes, and unfinished ones are kept for next time
+        let mut newest = None;
+        let mut used = 0;
+        while let Some(len) = self.received.get(used..used + 4) {
+            let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+            let Some(state) = self.received.get(used + 4..used + 4 + len) else {
+                break;
+            };
+            newest = Some(SceneState::from_bytes(state).ok_or(ErrorKind::InvalidData)?);
+            used += 4 + len;
+        }
+        self.received.drain(..used);
+        Ok(newest)
+    }
+    pub fn sync(&mut self, renderer: &mut Renderer) -> std::io::Result<bool> {
+        //! Applies the newest state to the renderer, returning whether there was a new one.
+        let state = self.poll()?;
+        if let Some(state) = &state {
+            state.apply(renderer);
+        }
+        Ok(state.is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::Camera;
+    use crate::{vec2, vec3, Vector2};
+
+    fn scene(camera_x: Scalar) -> Renderer {
+        Renderer::new(
+            vec![crate::create_cube(), crate::create_cube()],
+            Camera {
+                position: vec3!(camera_x, 0.0, -6.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(1.0, 1.0),
+            },
+        )
+    }
+
+    #[test]
+    fn scene_state_bytes() {
+        let mut renderer = scene(0.0);
+        renderer.meshs[1].position = vec3!(1.0, -2.5, 3.0);
+        renderer.meshs[1].rotation = vec3!(0.5, 0.0, 0.25);
+        renderer.time = 1.5;
+        let state = SceneState::capture(&renderer, 7);
+        let bytes = state.to_bytes();
+        assert_eq!(bytes.len(), HEADER_LEN + 2 * TRANSFORM_LEN);
+        assert_eq!(SceneState::from_bytes(&bytes), Some(state.clone()));
+        assert_eq!(SceneState::from_bytes(&bytes[..bytes.len() - 1]), None);
+        assert_eq!(SceneState::from_bytes(&[1, 2, 3]), None);
+
+        //Only the meshes both have are moved
+        let mut other = scene(0.0);
+        other.meshs.truncate(1);
+        SceneState {
+            transforms: state.transforms.iter().rev().copied().collect(),
+            ..state
+        }
+        .apply(&mut other);
+        assert_eq!(other.meshs[0].position, vec3!(1.0, -2.5, 3.0));
+        assert_eq!(other.time, 1.5);
+    }
+
+    #[test]
+    fn syncing_scenes() {
+        let mut server_scene = scene(0.0);
+        let mut server = SyncServer::bind("127.0.0.1:0").unwrap();
+        let mut client = SyncClient::connect(server.local_addr().unwrap()).unwrap();
+        let mut client_scene = scene(3.0);
+        for frame in 0..3 {
+            server_scene.meshs[0].rotation.y = frame as Scalar;
+            server.broadcast(&server_scene).unwrap();
+        }
+        //Waiting for the states to arrive, keeping only the newest
+        let mut state = None;
+        for _ in 0..500 {
+            state = client.poll().unwrap().or(state);
+            if state.as_ref().is_some_and(|x: &SceneState| x.frame == 2) {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+        assert_eq!(server.client_count(), 1);
+        let state = state.unwrap();
+        assert_eq!(state.frame, 2);
+        state.apply(&mut client_scene);
+        assert_eq!(client_scene.meshs[0].rotation.y, 2.0);
+        //The client keeps its own camera
+        assert_eq!(client_scene.camera.position.x, 3.0);
+        assert!(!client.sync(&mut client_scene).unwrap());
+
+        drop(client);
+        for _ in 0..500 {
+            server.broadcast(&server_scene).unwrap();
+            if server.client_count() == 0 {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+        assert_eq!(server.client_count(), 0);
+    }
+}
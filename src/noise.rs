@@ -0,0 +1,207 @@
+#[cfg(not(feature = "std"))]
+use super::float::Float;
+use super::rendering::Mesh;
+use super::{Scalar, Vector3};
+
+/// A small, fast, seedable random number generator (xorshift). Not suitable for anything security related, but plenty for visual effects, and the same seed always gives the same numbers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    state: u32,
+}
+
+impl Rng {
+    pub fn new(seed: u32) -> Self {
+        //A state of 0 would only ever produce 0
+        Self {
+            state: hash(seed) | 1,
+        }
+    }
+    pub fn next_u32(&mut self) -> u32 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+        x
+    }
+    pub fn next_f32(&mut self) -> f32 {
+        //! A number between 0.0 and 1.0.
+        (self.next_u32() >> 8) as f32 / (1 << 24) as f32
+    }
+    pub fn range(&mut self, min: Scalar, max: Scalar) -> Scalar {
+        //! A number between min and max.
+        min + (max - min) * self.next_f32() as Scalar
+    }
+    pub fn chance(&mut self, probability: f32) -> bool {
+        //! True with the given probability (0.0 to 1.0).
+        self.next_f32() < probability
+    }
+    pub fn vector3(&mut self, amplitude: Scalar) -> Vector3 {
+        //! A vector with each component between -amplitude and amplitude.
+        Vector3::new(
+            self.range(-amplitude, amplitude),
+            self.range(-amplitude, amplitude),
+            self.range(-amplitude, amplitude),
+        )
+    }
+}
+
+/// Scrambles the bits of an integer, used as the basis for all the noise functions.
+pub fn hash(mut x: u32) -> u32 {
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x7feb352d);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x846ca68b);
+    x ^= x >> 16;
+    x
+}
+
+fn lattice(x: i32, y: i32, z: i32, seed: u32) -> Scalar {
+    let h = hash(
+        (x as u32)
+            .wrapping_mul(73856093)
+            .wrapping_add(hash((y as u32).wrapping_mul(19349663)))
+            .wrapping_add(hash((z as u32).wrapping_mul(83492791) ^ seed)),
+    );
+    (h >> 8) as Scalar / (1 << 23) as Scalar - 1.0
+}
+
+/// Smooth 3D value noise between -1.0 and 1.0. Points close to each other get similar values, and integer coordinates are one "cell" apart.
+pub fn value_noise(point: Vector3, seed: u32) -> Scalar {
+    let (x0, y0, z0) = (point.x.floor(), point.y.floor(), point.z.floor());
+    let fade = |t: Scalar| t * t * (3.0 - 2.0 * t);
+    let (tx, ty, tz) = (fade(point.x - x0), fade(point.y - y0), fade(point.z - z0));
+    let (x0, y0, z0) = (x0 as i32, y0 as i32, z0 as i32);
+    let lerp = |a: Scalar, b: Scalar, t: Scalar| a + (b - a) * t;
+    let corner = |dx: i32, dy: i32, dz: i32| lattice(x0 + dx, y0 + dy, z0 + dz, seed);
+
+    lerp(
+        lerp(
+            lerp(corner(0, 0, 0), corner(1, 0, 0), tx),
+            lerp(corner(0, 1, 0), corner(1, 1, 0), tx),
+            ty,
+        ),
+        lerp(
+            lerp(corner(0, 0, 1), corner(1, 0, 1), tx),
+            lerp(corner(0, 1, 1), corner(1, 1, 1), tx),
+            ty,
+        ),
+        tz,
+    )
+}
+
+/// Value noise for each axis, giving a smoothly varying offset for a point.
+pub fn vector_noise(point: Vector3, seed: u32) -> Vector3 {
+    Vector3::new(
+        value_noise(point, seed),
+        value_noise(point, seed.wrapping_add(1)),
+        value_noise(point, seed.wrapping_add(2)),
+    )
+}
+
+impl Mesh {
+    pub fn displace_noise(&mut self, amplitude: Scalar, frequency: Scalar, seed: u32) {
+        //! Moves every vertex (in local space) by up to amplitude in each axis, using smooth noise so nearby vertices move together. Higher frequencies give more chaotic results. Changing the seed every frame (or slowly changing the frequency) animates it.
+        self.get_verticies_mut().values_mut().for_each(|vertex| {
+            *vertex += vector_noise(*vertex * frequency, seed) * amplitude;
+        });
+    }
+    pub fn displaced_noise(&self, amplitude: Scalar, frequency: Scalar, seed: u32) -> Mesh {
+        //! Same as displace_noise, but returns a displaced copy so the original can be kept for the next frame.
+        let mut ret = self.clone();
+        ret.displace_noise(amplitude, frequency, seed);
+        ret
+    }
+}
+
+/// Per-frame random glitch effects. Keep one in the logic struct and call its methods every frame (or only some frames, for occasional glitches).
+/// # Example
+/// ```
+/// # use ascii_renderer::prelude::*;
+/// # use ascii_renderer::noise::Jitter;
+/// let original = ascii_renderer::create_cube();
+/// let mut jitter = Jitter::new(42);
+/// //Every frame:
+/// let mut shown = jitter.jitter_mesh(&original, 0.1);
+/// jitter.shake(&mut shown.position, 0.05);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Jitter {
+    pub rng: Rng,
+}
+
+impl Jitter {
+    pub fn new(seed: u32) -> Self {
+        Self {
+            rng: Rng::new(seed),
+        }
+    }
+    pub fn jitter_mesh(&mut self, mesh: &Mesh, amplitude: Scalar) -> Mesh {
+        //! Returns a copy of the mesh with each vertex moved randomly by up to amplitude on each axis. Unlike displace_noise, neighboring vertices move independently.
+        let mut ret = mesh.clone();
+        ret.get_verticies_mut()
+            .values_mut()
+            .for_each(|vertex| *vertex += self.rng.vector3(amplitude));
+        ret
+    }
+    pub fn shake(&mut self, value: &mut Vector3, amplitude: Scalar) {
+        //! Moves a value (ex: a camera or mesh position) randomly by up to amplitude on each axis.
+        *value += self.rng.vector3(amplitude);
+    }
+    pub fn glitch_edges(&mut self, mesh: &mut Mesh, probability: f32) {
+        //! Randomly swaps endpoints between edges with the given probability per edge, for a broken-signal look. Apply it to a copy of the mesh.
+        let count = mesh.get_edges().len();
+        if count < 2 {
+            return;
+        }
+        for i in 0..count {
+            if self.rng.chance(probability) {
+                let other = self.rng.next_u32() as usize % count;
+                let edges = mesh.get_edges_mut();
+                let swapped = edges[other].1;
+                edges[other].1 = edges[i].1;
+                edges[i].1 = swapped;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec3;
+
+    #[test]
+    fn noise_is_deterministic_and_bounded() {
+        for i in 0..100 {
+            let point = vec3!(i as Scalar * 0.37, i as Scalar * -1.3, i as Scalar * 0.11);
+            let value = value_noise(point, 7);
+            assert_eq!(value, value_noise(point, 7));
+            assert!((-1.0..=1.0).contains(&value));
+        }
+        let a = value_noise(vec3!(0.5, 0.5, 0.5), 1);
+        let b = value_noise(vec3!(0.501, 0.5, 0.5), 1);
+        assert!((a - b).abs() < 0.01);
+    }
+
+    #[test]
+    fn rng_ranges() {
+        let mut rng = Rng::new(3);
+        let mut other = Rng::new(3);
+        for _ in 0..100 {
+            let value = rng.range(-2.0, 5.0);
+            assert!((-2.0..=5.0).contains(&value));
+            assert_eq!(value, other.range(-2.0, 5.0));
+        }
+    }
+
+    #[test]
+    fn displacement_stays_within_amplitude() {
+        let cube = crate::create_cube();
+        let displaced = cube.displaced_noise(0.25, 2.0, 9);
+        for (index, vertex) in displaced.get_verticies() {
+            let offset = *vertex - cube.get_verticies()[index];
+            assert!(offset.x.abs() <= 0.25 && offset.y.abs() <= 0.25 && offset.z.abs() <= 0.25);
+        }
+    }
+}
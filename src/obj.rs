@@ -1,7 +1,8 @@
 use super::prelude::*;
 pub use obj::ObjError;
-use obj::{Obj, Object};
-use std::{collections::HashMap, hash::Hash, ops::Deref};
+use obj::{Obj, ObjData};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone)]
 pub struct AsciiObj(Obj);
@@ -11,15 +12,112 @@ impl AsciiObj {
         self.0.save(path)
     }
     pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, ObjError> {
-        Obj::load(path).map(|x| AsciiObj(x))
+        //! Loads and parses a .obj file, see parse.
+        let path = path.as_ref();
+        let mut obj = Self::parse(&std::fs::read_to_string(path)?)?;
+        obj.0.path = path.parent().map_or_else(PathBuf::new, Path::to_owned);
+        Ok(obj)
     }
+    pub fn parse(text: &str) -> Result<Self, ObjError> {
+        //! Parses the contents of a .obj file. However malformed the text is this returns an error rather than panicking, and the meshes it makes can be drawn, so it's safe to use on files from users (and it is fuzzed, see fuzz/). Faces pointing at vertices that don't exist are a MalformedFaceGroup error, and infinite or NaN vertices an ArgumentListFailure.
+        //! # Example
+        //! ```
+        //! # use ascii_renderer::prelude::*;
+        //! let obj = AsciiObj::parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3").unwrap();
+        //! let meshes: Vec<Mesh> = obj.into();
+        //! assert_eq!(meshes[0].get_edges().len(), 3);
+        //! assert!(AsciiObj::parse("v 0 0 0\nf 1 2 3").is_err());
+        //! ```
+        check_text(text)?;
+        //The obj crate slices names out of o and g lines by byte, which splits chars after leading or non-ASCII whitespace
+        let mut cleaned = String::with_capacity(text.len());
+        for line in text.lines() {
+            let line = line.trim_start();
+            match line.split_once(char::is_whitespace) {
+                Some((command @ ("o" | "g"), name)) => {
+                    cleaned.push_str(command);
+                    cleaned.push(' ');
+                    cleaned.push_str(name.trim_start());
+                }
+                _ => cleaned.push_str(line),
+            }
+            cleaned.push('\n');
+        }
+        ObjData::load_buf(cleaned.as_bytes()).map(|data| {
+            AsciiObj(Obj {
+                data,
+                path: PathBuf::new(),
+            })
+        })
+    }
+    pub fn faces(&self) -> Vec<Vec<[usize; 3]>> {
+        //! The faces of each mesh into() makes, in the same order, as triangles of vertex indices (polygons are split into fans of triangles around their first vertex), ex: to fill them in.
+        self.0
+            .data
+            .objects
+            .iter()
+            .map(|object| {
+                object
+                    .groups
+                    .iter()
+                    .flat_map(|group| group.polys.iter())
+                    .flat_map(|polygon| {
+                        let corners: Vec<usize> = polygon.0.iter().map(|x| x.0).collect();
+                        (2..corners.len())
+                            .map(move |i| [corners[0], corners[i - 1], corners[i]])
+                            .collect::<Vec<_>>()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+fn check_text(text: &str) -> Result<(), ObjError> {
+    //! Checks every vertex is finite and every face only points at vertices that come before it, since the obj crate accepts "inf" and coordinates too big for an f32, and wraps bad indices around to ones that don't exist.
+    let mut positions = 0;
+    for (line_number, line) in text.lines().enumerate() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("v") => {
+                let coordinates: Vec<&str> = words.take(3).collect();
+                if coordinates
+                    .iter()
+                    .any(|x| x.parse::<f32>().is_ok_and(|x| !x.is_finite()))
+                {
+                    return Err(ObjError::ArgumentListFailure {
+                        line_number,
+                        list: coordinates.join(" "),
+                    });
+                }
+                positions += 1;
+            }
+            Some("f") => {
+                for group in words {
+                    //Indices that don't parse are left for the obj crate to report
+                    let index = group.split('/').next().and_then(|x| x.parse::<isize>().ok());
+                    if index.is_some_and(|x| x < -positions || x > positions) {
+                        return Err(ObjError::MalformedFaceGroup {
+                            line_number,
+                            group: group.to_string(),
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
 }
 
-impl std::convert::Into<Vec<Mesh>> for AsciiObj {
-    fn into(self) -> Vec<Mesh> {
+impl std::convert::From<AsciiObj> for Vec<Mesh> {
+    fn from(obj: AsciiObj) -> Self {
         #[inline]
         fn simple_polygon_to_edges(polygon: obj::SimplePolygon) -> Vec<(usize, usize)> {
-            let last_vertex_index = polygon.0.last().unwrap().0;
+            //An f line with no vertices
+            let Some(last_vertex_index) = polygon.0.last().map(|x| x.0) else {
+                return vec![];
+            };
             polygon
                 .0
                 .iter()
@@ -36,7 +134,7 @@ impl std::convert::Into<Vec<Mesh>> for AsciiObj {
                 })
         }
         #[inline]
-        fn edges_contains_index(edges: &Vec<(usize, usize)>, value: usize) -> bool {
+        fn edges_contains_index(edges: &[(usize, usize)], value: usize) -> bool {
             edges.iter().fold(false, |accum, (index1, index2)| {
                 if *index1 == value || *index2 == value {
                     true
@@ -46,7 +144,7 @@ impl std::convert::Into<Vec<Mesh>> for AsciiObj {
             })
         }
 
-        self.0
+        obj.0
             .data
             .objects
             .iter()
@@ -55,14 +153,12 @@ impl std::convert::Into<Vec<Mesh>> for AsciiObj {
                 let edges: Vec<(usize, usize)> = object
                     .groups
                     .iter()
-                    .map(|group| group.polys.iter())
-                    .flatten()
+                    .flat_map(|group| group.polys.iter())
                     .map(|x| x.to_owned())
-                    .map(simple_polygon_to_edges)
-                    .flatten()
+                    .flat_map(simple_polygon_to_edges)
                     .collect();
 
-                let positions: HashMap<usize, Vector3> = self
+                let positions: HashMap<usize, Vector3> = obj
                     .0
                     .data
                     .position
@@ -86,6 +182,55 @@ impl std::convert::Into<Vec<Mesh>> for AsciiObj {
 
 impl std::convert::From<&[f32; 3]> for Vector3 {
     fn from(value: &[f32; 3]) -> Self {
-        vec3!(value[0], value[1], value[2],)
+        vec3!(value[0] as Scalar, value[1] as Scalar, value[2] as Scalar,)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_malformed_files() {
+        let obj = AsciiObj::parse("v 0 0 0\nv 1 0 0\n  o  \u{3000}name\nf -1 1\nf\n").unwrap();
+        let meshes: Vec<Mesh> = obj.into();
+        assert_eq!(meshes.last().unwrap().get_edges(), &vec![(0, 1), (1, 0)]);
+
+        for text in [
+            "v 0 0 0\nf 1 2",
+            "v 0 0 0\nf -2 1",
+            "v 0 0 0\nf -9223372036854775808 1",
+            "g\u{85}x",
+            "o\u{3000}",
+            "v 1 2",
+            "f 0",
+            "f a/b/c",
+            "v 0 nan 0",
+            "v 1e39 0 0",
+        ] {
+            //Either is fine, as long as it doesn't panic
+            if let Ok(obj) = AsciiObj::parse(text) {
+                let _: Vec<Mesh> = obj.into();
+            }
+        }
+        assert!(matches!(
+            AsciiObj::parse("v 0 0 0\nv 0 0 inf"),
+            Err(ObjError::ArgumentListFailure { line_number: 1, .. })
+        ));
+        assert!(matches!(
+            AsciiObj::parse("v 0 0 0\nf 1 2"),
+            Err(ObjError::MalformedFaceGroup { line_number: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn faces() {
+        let obj =
+            AsciiObj::parse("v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\nf 1 2\no b\nf 4 3 2")
+                .unwrap();
+        assert_eq!(
+            obj.faces(),
+            vec![vec![[0, 1, 2], [0, 2, 3]], vec![[3, 2, 1]]]
+        );
     }
 }
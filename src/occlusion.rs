@@ -0,0 +1,275 @@
+//! Skips drawing meshes that are hidden behind nearer geometry, for scenes like interiors where walls hide most of the level. Which parts of the screen are covered comes from the last frame's depth (see Renderer::record_depth and Renderer::recorded_occlusion), or from solid boxes given as occluders, ex: the bounding boxes of the wall meshes, since thin wireframe lines rarely cover much of the screen on their own.
+
+use super::cache::lock;
+use super::char_buffer::CharBuffer;
+use super::depth::DepthBuffer;
+use super::geometry::{Aabb, Ray};
+use super::rendering::{Camera, Renderer};
+use super::{Mat4, Scalar, Vector2};
+
+/// How far in front of the camera each cell of a coarse grid over the screen is covered. A cell only counts as covered if all of it is, by a single occluder or by depth recorded in every char of it.
+/// # Example
+/// ```
+/// # use ascii_renderer::prelude::*;
+/// # use ascii_renderer::geometry::Aabb;
+/// # use ascii_renderer::occlusion::OcclusionGrid;
+/// # let renderer = Renderer::new(vec![ascii_renderer::create_cube()], Camera {
+/// #     position: vec3!(0.0, 0.0, -10.0),
+/// #     rotation: vec3!(0.0, 0.0, 0.0),
+/// #     fov: vec2!(0.7, 0.7),
+/// # });
+/// # let mut buf = CharBuffer::new(30, 30);
+/// let wall = Aabb::new(vec3!(-20.0, -20.0, -2.0), vec3!(20.0, 20.0, -1.5));
+/// let grid = OcclusionGrid::build(&renderer.camera, buf.dimensions, (4, 2), &[wall]);
+/// renderer.draw_occluded(&grid, &mut buf);  //The cube is behind the wall, so nothing is drawn
+/// assert_eq!(buf, CharBuffer::new(30, 30));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct OcclusionGrid {
+    cell_size: (usize, usize),
+    dimensions: (usize, usize),
+    buffer_dimensions: (usize, usize),
+    depths: Vec<Scalar>,
+}
+
+impl OcclusionGrid {
+    pub fn build(
+        camera: &Camera,
+        buffer_dimensions: (usize, usize),
+        cell_size: (usize, usize),
+        occluders: &[Aabb],
+    ) -> Self {
+        //! Builds the grid for a buffer of the given dimensions, split into cells of cell_size chars. Smaller cells hide more, but take longer to build. It needs to be rebuilt whenever the camera or the occluders move.
+        let cell_size = (cell_size.0.max(1), cell_size.1.max(1));
+        let dimensions = (
+            buffer_dimensions.0.div_ceil(cell_size.0),
+            buffer_dimensions.1.div_ceil(cell_size.1),
+        );
+        let to_world = Mat4::rotation(camera.rotation).transpose();
+        let view = camera.view_matrix();
+        let corner_ray = |x: usize, y: usize| {
+            let uv = Vector2::new(
+                (x * cell_size.0).min(buffer_dimensions.0) as Scalar
+                    / buffer_dimensions.0.max(1) as Scalar,
+                (y * cell_size.1).min(buffer_dimensions.1) as Scalar
+                    / buffer_dimensions.1.max(1) as Scalar,
+            );
+            camera
+                .view_direction(uv)
+                .map(|direction| Ray::new(camera.position, to_world.transform_vector(direction)))
+        };
+        //Rays through every corner of every cell, shared between neighboring cells
+        let rays: Vec<Option<Ray>> = (0..=dimensions.1)
+            .flat_map(|y| (0..=dimensions.0).map(move |x| (x, y)))
+            .map(|(x, y)| corner_ray(x, y))
+            .collect();
+        let ray = |x: usize, y: usize| rays[y * (dimensions.0 + 1) + x];
+
+        let mut depths = vec![Scalar::INFINITY; dimensions.0 * dimensions.1];
+        for y in 0..dimensions.1 {
+            for x in 0..dimensions.0 {
+                let corners = [ray(x, y), ray(x + 1, y), ray(x, y + 1), ray(x + 1, y + 1)];
+                depths[y * dimensions.0 + x] = occluders
+                    .iter()
+                    .filter_map(|occluder| {
+                        //The directions that hit a box are a convex shape, so if every corner hits the whole cell does
+                        corners.iter().try_fold(0.0 as Scalar, |accum, corner| {
+                            let corner = (*corner)?;
+                            let t = corner.intersect_aabb(occluder)?;
+                            Some(accum.max(view.transform_point(corner.at(t)).z))
+                        })
+                    })
+                    .fold(Scalar::INFINITY, Scalar::min);
+            }
+        }
+        Self {
+            cell_size,
+            dimensions,
+            buffer_dimensions,
+            depths,
+        }
+    }
+    pub fn from_depth(depth: &DepthBuffer, cell_size: (usize, usize)) -> Self {
+        //! Builds the grid from a frame's depth, split into cells of cell_size chars. Each cell is covered as far away as the farthest thing drawn in it, or not at all if any of its chars is empty.
+        let cell_size = (cell_size.0.max(1), cell_size.1.max(1));
+        let buffer_dimensions = depth.dimensions;
+        let dimensions = (
+            buffer_dimensions.0.div_ceil(cell_size.0),
+            buffer_dimensions.1.div_ceil(cell_size.1),
+        );
+        let mut depths = vec![Scalar::INFINITY; dimensions.0 * dimensions.1];
+        for y in 0..dimensions.1 {
+            for x in 0..dimensions.0 {
+                let ys = y * cell_size.1..((y + 1) * cell_size.1).min(buffer_dimensions.1);
+                let xs = x * cell_size.0..((x + 1) * cell_size.0).min(buffer_dimensions.0);
+                depths[y * dimensions.0 + x] = ys
+                    .flat_map(|sub_y| xs.clone().map(move |sub_x| (sub_x, sub_y)))
+                    .try_fold(0.0 as Scalar, |accum, (sub_x, sub_y)| {
+                        Some(accum.max(depth.get(sub_x, sub_y)?))
+                    })
+                    .unwrap_or(Scalar::INFINITY);
+            }
+        }
+        Self {
+            cell_size,
+            dimensions,
+            buffer_dimensions,
+            depths,
+        }
+    }
+    pub fn depth_at(&self, cell_x: usize, cell_y: usize) -> Option<Scalar> {
+        //! How far in front of the camera the cell is covered, or None if it isn't (or is outside the grid).
+        self.depths
+            .get(cell_y * self.dimensions.0 + cell_x)
+            .copied()
+            .filter(|x| x.is_finite() && cell_x < self.dimensions.0)
+    }
+    pub fn dimensions(&self) -> (usize, usize) {
+        //! The number of cells wide and tall the grid is.
+        self.dimensions
+    }
+    pub fn is_hidden(&self, camera: &Camera, aabb: &Aabb) -> bool {
+        //! True if every cell the box could cover on screen is covered by an occluder closer to the camera than any part of the box.
+        let view = camera.view_matrix();
+        let mut min = Vector2::new(Scalar::INFINITY, Scalar::INFINITY);
+        let mut max = Vector2::new(Scalar::NEG_INFINITY, Scalar::NEG_INFINITY);
+        let mut nearest = Scalar::INFINITY;
+        for corner in aabb.corners() {
+            let relative = view.transform_point(corner);
+            if relative.z <= 0.0 {
+                //Part of the box is beside or behind the camera, where its outline on screen isn't reliable
+                return false;
+            }
+            nearest = nearest.min(relative.z);
+            let uv = camera.project_view_uv(relative);
+            min = Vector2::new(min.x.min(uv.x), min.y.min(uv.y));
+            max = Vector2::new(max.x.max(uv.x), max.y.max(uv.y));
+        }
+        let to_cell = |uv: Scalar, buffer: usize, cell: usize, cells: usize| {
+            ((uv * buffer as Scalar / cell as Scalar).floor().max(0.0) as usize).min(cells - 1)
+        };
+        if self.depths.is_empty() {
+            return false;
+        }
+        let (width, height) = self.buffer_dimensions;
+        let (cell_width, cell_height) = self.cell_size;
+        let (cells_x, cells_y) = self.dimensions;
+        let x_range =
+            to_cell(min.x, width, cell_width, cells_x)..=to_cell(max.x, width, cell_width, cells_x);
+        let y_range = to_cell(min.y, height, cell_height, cells_y)
+            ..=to_cell(max.y, height, cell_height, cells_y);
+
+        y_range.into_iter().all(|y| {
+            x_range
+                .clone()
+                .all(|x| self.depths[y * cells_x + x] < nearest)
+        })
+    }
+}
+
+impl Renderer {
+    pub fn draw_occluded(&self, grid: &OcclusionGrid, buffer: &mut CharBuffer) {
+        //! Same as draw, but skips meshes the grid says are hidden. The grid should be built for this renderer's camera and buffer. With record_depth set, the depth of what's drawn is recorded like draw does, for building the next frame's grid with recorded_occlusion. Supersampling and fixed_point are ignored.
+        let visible = self.meshs.iter().filter(|mesh| {
+            mesh.bounding_box()
+                .is_none_or(|aabb| !grid.is_hidden(&self.camera, &aabb))
+        });
+        if self.record_depth {
+            let mut depth =
+                DepthBuffer::reuse(lock(&self.scratch.0).depth.take(), buffer.dimensions);
+            for mesh in visible {
+                self.draw_mesh_with_depth(mesh, buffer, &mut depth);
+            }
+            lock(&self.scratch.0).depth = Some(depth);
+        } else {
+            for mesh in visible {
+                self.draw_mesh(mesh, buffer);
+            }
+        }
+    }
+    pub fn recorded_occlusion(&self, cell_size: (usize, usize)) -> Option<OcclusionGrid> {
+        //! A grid built with OcclusionGrid::from_depth from the depth the last frame recorded, or None if it wasn't drawn with record_depth set. Meshes hidden last frame are assumed to still be hidden, so it works best when the camera moves a little each frame.
+        //! # Example
+        //! ```
+        //! # use ascii_renderer::prelude::*;
+        //! # let mut renderer = Renderer::new(vec![ascii_renderer::create_cube()], Camera {
+        //! #     position: vec3!(0.0, 0.0, -10.0),
+        //! #     rotation: vec3!(0.0, 0.0, 0.0),
+        //! #     fov: vec2!(0.7, 0.7),
+        //! # });
+        //! # let mut buf = CharBuffer::new(30, 30);
+        //! renderer.record_depth = true;
+        //! renderer.draw(&mut buf);
+        //! //Every frame after the first
+        //! if let Some(grid) = renderer.recorded_occlusion((2, 2)) {
+        //!     buf.fill(' ');
+        //!     renderer.draw_occluded(&grid, &mut buf);
+        //! }
+        //! ```
+        let scratch = lock(&self.scratch.0);
+        Some(OcclusionGrid::from_depth(
+            scratch.depth.as_ref()?,
+            cell_size,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{vec2, vec3, Vector3};
+
+    #[test]
+    fn hidden_behind_wall() {
+        let camera = Camera {
+            position: vec3!(0.0, 0.0, 0.0),
+            rotation: vec3!(0.0, 0.0, 0.0),
+            fov: vec2!(1.0, 1.0),
+        };
+        //A wall covering the left half of the view
+        let wall = Aabb::new(vec3!(-50.0, -50.0, 10.0), vec3!(0.0, 50.0, 11.0));
+        let grid = OcclusionGrid::build(&camera, (40, 20), (4, 2), &[wall]);
+        assert_eq!(grid.dimensions(), (10, 10));
+        assert!(grid.depth_at(1, 5).unwrap() >= 10.0);
+        assert_eq!(grid.depth_at(8, 5), None);
+
+        let unit = |center: Vector3| {
+            Aabb::new(center - vec3!(0.5, 0.5, 0.5), center + vec3!(0.5, 0.5, 0.5))
+        };
+        assert!(grid.is_hidden(&camera, &unit(vec3!(-5.0, 0.0, 20.0))));
+        //In front of the wall
+        assert!(!grid.is_hidden(&camera, &unit(vec3!(-2.0, 0.0, 5.0))));
+        //Off to the right, where there is no wall
+        assert!(!grid.is_hidden(&camera, &unit(vec3!(5.0, 0.0, 20.0))));
+        //Sticking out from behind the wall
+        assert!(!grid.is_hidden(&camera, &unit(vec3!(0.0, 0.0, 20.0))));
+    }
+
+    #[test]
+    fn hidden_behind_last_frame() {
+        let camera = Camera {
+            position: vec3!(0.0, 0.0, 0.0),
+            rotation: vec3!(0.0, 0.0, 0.0),
+            fov: vec2!(1.0, 1.0),
+        };
+        //Something 10 away drawn over the left half of the frame, and one char of the right half
+        let mut depth = DepthBuffer::new(40, 20);
+        for y in 0..20 {
+            for x in 0..20 {
+                depth.test(x, y, 10.0);
+            }
+        }
+        depth.test(30, 10, 10.0);
+        let grid = OcclusionGrid::from_depth(&depth, (4, 2));
+        assert_eq!(grid.dimensions(), (10, 10));
+        assert_eq!(grid.depth_at(1, 5), Some(10.0));
+        assert_eq!(grid.depth_at(7, 5), None);
+
+        let unit = |center: Vector3| {
+            Aabb::new(center - vec3!(0.5, 0.5, 0.5), center + vec3!(0.5, 0.5, 0.5))
+        };
+        assert!(grid.is_hidden(&camera, &unit(vec3!(-5.0, 0.0, 20.0))));
+        assert!(!grid.is_hidden(&camera, &unit(vec3!(-2.0, 0.0, 5.0))));
+        assert!(!grid.is_hidden(&camera, &unit(vec3!(5.0, 0.0, 20.0))));
+    }
+}
@@ -0,0 +1,230 @@
+//! OSC (Open Sound Control) input over UDP, behind the `osc` feature, for driving visuals from tools like TouchOSC, Max, or a sequencer during a live performance. An OscListener receives packets on its own thread and sends each message in them to a runner's event_sender as an InputEvent::Osc, so it reaches the logic's input alongside the keyboard and can be bound in an InputMap with Binding::Osc.
+//! # Example
+//! ```no_run
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::input::{Binding, InputMap};
+//! # use ascii_renderer::osc::OscListener;
+//! struct Visuals {
+//!     input: InputMap,
+//! }
+//! impl Logic for Visuals {
+//!     fn process(&mut self, screen_buf: &mut CharBuffer, _delta: f32) -> ProcessReturn {
+//!         let size = self.input.axis("size");
+//!         screen_buf.fill(if size > 0.5 { '#' } else { '.' });
+//!         self.input.end_frame();
+//!         ProcessReturn::Continue
+//!     }
+//!     fn input(&mut self, event: &InputEvent) {
+//!         self.input.handle(event);
+//!     }
+//! }
+//! let mut input = InputMap::new();
+//! input.bind("size", Binding::osc("/fader/1"));
+//! let mut runner = Runner::new(40, 20, 30, Visuals { input });
+//! let _listener = OscListener::bind("0.0.0.0:9000", runner.event_sender()).unwrap();
+//! runner.run(true);
+//! ```
+
+use super::runner::InputEvent;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Reads OSC's 4 byte aligned pieces from the front of a packet.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let padded = len.checked_add(3)? & !3;
+        if padded > self.0.len() {
+            return None;
+        }
+        let (taken, rest) = self.0.split_at(padded);
+        self.0 = rest;
+        Some(&taken[..len])
+    }
+    fn string(&mut self) -> Option<&'a str> {
+        let len = self.0.iter().position(|x| *x == 0)?;
+        //The terminating null is part of the padding
+        let bytes = self.take(len + 1)?;
+        std::str::from_utf8(&bytes[..len]).ok()
+    }
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.take(N)?.try_into().ok()
+    }
+    fn int(&mut self) -> Option<i32> {
+        self.array().map(i32::from_be_bytes)
+    }
+}
+
+pub fn parse(packet: &[u8]) -> Vec<InputEvent> {
+    //! Every message in a packet, including ones nested in bundles (whose time tags are ignored, so everything happens right away). Malformed messages are skipped, and arguments after one with an unknown type tag are left out.
+    let mut events = vec![];
+    parse_into(packet, &mut events);
+    events
+}
+
+fn parse_into(packet: &[u8], events: &mut Vec<InputEvent>) {
+    let mut reader = Reader(packet);
+    if packet.starts_with(b"#bundle\0") {
+        //The "#bundle" string and the time tag
+        if reader.take(16).is_none() {
+            return;
+        }
+        while let Some(len) = reader.int() {
+            let Some(element) = usize::try_from(len).ok().and_then(|x| reader.take(x)) else {
+                return;
+            };
+            parse_into(element, events);
+        }
+        return;
+    }
+    let Some(address) = reader.string().filter(|x| x.starts_with('/')) else {
+        return;
+    };
+    //Very old senders leave out the type tags, which is the same as no arguments
+    let tags = reader.string().unwrap_or(",");
+    let mut args = vec![];
+    for tag in tags.chars().skip(1) {
+        let arg = match tag {
+            'i' => reader.int().map(|x| Some(x as f32)),
+            'f' => reader.array().map(|x| Some(f32::from_be_bytes(x))),
+            'h' => reader.array().map(|x| Some(i64::from_be_bytes(x) as f32)),
+            'd' => reader.array().map(|x| Some(f64::from_be_bytes(x) as f32)),
+            'T' => Some(Some(1.0)),
+            'F' => Some(Some(0.0)),
+            'N' | 'I' => Some(None),
+            's' | 'S' => reader.string().map(|_| None),
+            'b' => reader
+                .int()
+                .and_then(|x| usize::try_from(x).ok())
+                .and_then(|x| reader.take(x))
+                .map(|_| None),
+            'c' | 'r' | 'm' => reader.int().map(|_| None),
+            't' => reader.array::<8>().map(|_| None),
+            _ => None,
+        };
+        match arg {
+            Some(Some(x)) => args.push(x),
+            Some(None) => {}
+            None => break,
+        }
+    }
+    events.push(InputEvent::Osc(address.to_string(), args));
+}
+
+/// A UDP socket receiving OSC packets on a background thread. The thread stops once this is dropped, or once the receiving runner is.
+#[derive(Debug)]
+pub struct OscListener {
+    local_addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+}
+
+impl OscListener {
+    pub fn bind(address: impl ToSocketAddrs, events: Sender<InputEvent>) -> std::io::Result<Self> {
+        //! Listens on the address, ex: "0.0.0.0:9000" to accept packets from other machines on the network, or port 0 to let the system pick one.
+        let socket = UdpSocket::bind(address)?;
+        //So the thread notices when it should stop even if nothing's being sent
+        socket.set_read_timeout(Some(Duration::from_millis(100)))?;
+        let local_addr = socket.local_addr()?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = stop.clone();
+        std::thread::spawn(move || {
+            let mut packet = [0; 65536];
+            while !stopped.load(Ordering::Relaxed) {
+                let Ok(len) = socket.recv(&mut packet) else {
+                    continue;
+                };
+                for event in parse(&packet[..len]) {
+                    if events.send(event).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        Ok(Self { local_addr, stop })
+    }
+    pub fn local_addr(&self) -> SocketAddr {
+        //! Where packets should be sent, ex: to find the port picked when binding to port 0.
+        self.local_addr
+    }
+}
+
+impl Drop for OscListener {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn padded(string: &str) -> Vec<u8> {
+        let mut bytes = string.as_bytes().to_vec();
+        bytes.resize((bytes.len() + 4) & !3, 0);
+        bytes
+    }
+
+    fn message(address: &str, tags: &str, args: &[u8]) -> Vec<u8> {
+        [padded(address), padded(tags), args.to_vec()].concat()
+    }
+
+    #[test]
+    fn parsing_packets() {
+        let args = [
+            &7i32.to_be_bytes()[..],
+            &0.5f32.to_be_bytes(),
+            &padded("name"),
+            &2.5f64.to_be_bytes(),
+        ]
+        .concat();
+        let fader = message("/fader/1", ",ifsdTN", &args);
+        assert_eq!(
+            parse(&fader),
+            vec![InputEvent::Osc(
+                "/fader/1".to_string(),
+                vec![7.0, 0.5, 2.5, 1.0]
+            )]
+        );
+
+        let button = message("/a", ",", &[]);
+        let bundle = [
+            &padded("#bundle")[..],
+            &[0, 0, 0, 0, 0, 0, 0, 1],
+            &(fader.len() as i32).to_be_bytes(),
+            &fader,
+            &(button.len() as i32).to_be_bytes(),
+            &button,
+        ]
+        .concat();
+        let events = parse(&bundle);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1], InputEvent::Osc("/a".to_string(), vec![]));
+
+        //Cut off arguments are left out, and garbage is ignored
+        assert_eq!(
+            parse(&message("/b", ",ff", &1.0f32.to_be_bytes())),
+            vec![InputEvent::Osc("/b".to_string(), vec![1.0])]
+        );
+        assert!(parse(b"not osc").is_empty());
+        assert!(parse(&bundle[..20]).is_empty());
+    }
+
+    #[test]
+    fn listening() {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let listener = OscListener::bind("127.0.0.1:0", sender).unwrap();
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket
+            .send_to(
+                &message("/spin", ",f", &0.25f32.to_be_bytes()),
+                listener.local_addr(),
+            )
+            .unwrap();
+        let event = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(event, InputEvent::Osc("/spin".to_string(), vec![0.25]));
+    }
+}
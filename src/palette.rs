@@ -0,0 +1,193 @@
+//! Color themes, so apps can look right on dark, light, and monochrome terminals without chars and colors written all over their code. A Palette gives each Role (the background, wireframes, highlights, and the HUD) a char and optionally a color, and apps draw with whatever the current palette says. Palettes can be switched while running, and remapping a buffer swaps the chars of one palette for another's, ex: for frames that were already drawn.
+//!
+//! CharBuffers only hold chars, so colors aren't drawn, but backends that show color can look them up by char with Palette::role_of.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::palette::{Palette, Role};
+//! let mut palette = Palette::dark();
+//! let mut cube = ascii_renderer::create_cube();
+//! cube.char = palette.get_char(Role::Wireframe);
+//! let mut buf = CharBuffer::new(20, 10);
+//! buf.fill(palette.get_char(Role::Background));
+//! buf.set_char(0, 0, palette.get_char(Role::Hud)).unwrap();
+//!
+//! //Switching to another theme, ex: picked by name in a settings menu
+//! let monochrome = Palette::named("monochrome").unwrap();
+//! palette.remap(&monochrome, &mut buf);
+//! cube.char = monochrome.get_char(Role::Wireframe);
+//! palette = monochrome;
+//! assert_eq!(buf.get_char(0, 0), Some(palette.get_char(Role::Hud)));
+//! ```
+
+use super::char_buffer::CharBuffer;
+
+/// What something drawn is for, which decides how a Palette draws it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Role {
+    Background,
+    /// Meshes drawn by the renderer.
+    Wireframe,
+    /// Whatever should stand out, ex: the selected object.
+    Highlight,
+    /// Text and borders drawn over the scene.
+    Hud,
+}
+
+impl Role {
+    pub const ALL: [Role; 4] = [
+        Role::Background,
+        Role::Wireframe,
+        Role::Highlight,
+        Role::Hud,
+    ];
+}
+
+/// The char and color a Palette draws a role with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Swatch {
+    pub char: char,
+    /// The color as RGB, if it has one.
+    pub color: Option<[u8; 3]>,
+}
+
+impl Swatch {
+    pub fn new(char: char) -> Self {
+        Self { char, color: None }
+    }
+    pub fn color(mut self, color: [u8; 3]) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+/// A named theme giving every Role a Swatch, see the module docs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Palette {
+    pub name: String,
+    pub background: Swatch,
+    pub wireframe: Swatch,
+    pub highlight: Swatch,
+    pub hud: Swatch,
+}
+
+impl Palette {
+    pub fn dark() -> Self {
+        //! Light gray wireframes on black, with yellow highlights and a cyan HUD.
+        Self {
+            name: "dark".to_string(),
+            background: Swatch::new(' ').color([0, 0, 0]),
+            wireframe: Swatch::new('#').color([200, 200, 200]),
+            highlight: Swatch::new('@').color([255, 200, 0]),
+            hud: Swatch::new('+').color([0, 200, 255]),
+        }
+    }
+    pub fn light() -> Self {
+        //! Dark gray wireframes on white, with red highlights and a blue HUD.
+        Self {
+            name: "light".to_string(),
+            background: Swatch::new(' ').color([255, 255, 255]),
+            wireframe: Swatch::new('#').color([40, 40, 40]),
+            highlight: Swatch::new('@').color([200, 0, 0]),
+            hud: Swatch::new('+').color([0, 0, 160]),
+        }
+    }
+    pub fn monochrome() -> Self {
+        //! No colors, so every role has a char of its own that tells it apart.
+        Self {
+            name: "monochrome".to_string(),
+            background: Swatch::new(' '),
+            wireframe: Swatch::new('*'),
+            highlight: Swatch::new('#'),
+            hud: Swatch::new('='),
+        }
+    }
+    pub fn named(name: &str) -> Option<Self> {
+        //! One of the built in palettes by name ("dark", "light", or "monochrome"), ignoring case.
+        match name.to_lowercase().as_str() {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "monochrome" => Some(Self::monochrome()),
+            _ => None,
+        }
+    }
+    pub fn get(&self, role: Role) -> Swatch {
+        match role {
+            Role::Background => self.background,
+            Role::Wireframe => self.wireframe,
+            Role::Highlight => self.highlight,
+            Role::Hud => self.hud,
+        }
+    }
+    pub fn get_char(&self, role: Role) -> char {
+        self.get(role).char
+    }
+    pub fn get_color(&self, role: Role) -> Option<[u8; 3]> {
+        self.get(role).color
+    }
+    pub fn set(&mut self, role: Role, swatch: Swatch) {
+        match role {
+            Role::Background => self.background = swatch,
+            Role::Wireframe => self.wireframe = swatch,
+            Role::Highlight => self.highlight = swatch,
+            Role::Hud => self.hud = swatch,
+        }
+    }
+    pub fn role_of(&self, char: char) -> Option<Role> {
+        //! Which role is drawn with char, or None if none are. If more than one is, the first in Role::ALL is returned.
+        Role::ALL.into_iter().find(|x| self.get_char(*x) == char)
+    }
+    pub fn remap(&self, to: &Palette, buf: &mut CharBuffer) {
+        //! Replaces every char in buf that this palette draws a role with by the char to draws that role with. Other chars are left alone.
+        for y in 0..buf.dimensions.1 {
+            for x in 0..buf.dimensions.0 {
+                let role = buf.get_char(x, y).and_then(|x| self.role_of(x));
+                if let Some(role) = role {
+                    let _ = buf.set_char(x, y, to.get_char(role));
+                }
+            }
+        }
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn switching_palettes() {
+        let mut dark = Palette::named("Dark").unwrap();
+        assert_eq!(dark, Palette::dark());
+        assert_eq!(Palette::named("sepia"), None);
+        assert_eq!(dark.role_of('@'), Some(Role::Highlight));
+        assert_eq!(dark.role_of('x'), None);
+        assert_eq!(dark.get_color(Role::Hud), Some([0, 200, 255]));
+
+        let mut buf = CharBuffer::from_text("#@x\n+  ");
+        dark.remap(&Palette::monochrome(), &mut buf);
+        assert_eq!(&buf.to_string(), "* # x \n=     \n");
+        //Remapping to the same palette changes nothing
+        let before = buf.clone();
+        Palette::monochrome().remap(&Palette::monochrome(), &mut buf);
+        assert_eq!(buf, before);
+
+        dark.set(Role::Highlight, Swatch::new('!').color([255, 0, 0]));
+        assert_eq!(
+            dark.get(Role::Highlight),
+            Swatch::new('!').color([255, 0, 0])
+        );
+        assert_eq!(Palette::light().get_char(Role::Wireframe), '#');
+        for role in Role::ALL {
+            assert_eq!(
+                Palette::monochrome().role_of(Palette::monochrome().get_char(role)),
+                Some(role)
+            );
+        }
+    }
+}
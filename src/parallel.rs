@@ -0,0 +1,155 @@
+//! Multithreaded rendering for scenes with lots of edges (ex: large OBJ models), behind the `rayon` feature. Vertices are transformed in parallel, then the edges are split into chunks that are each drawn into their own buffer on a separate thread, and the buffers are merged in order. The result is exactly the same as Renderer::draw, just faster for big scenes (and usually slower for small ones).
+
+use super::cache::{lock, Scratch};
+use super::char_buffer::CharBuffer;
+use super::line::Line;
+use super::rendering::{push_mesh_lines, Mesh, Renderer};
+use rayon::prelude::*;
+
+/// The fewest lines worth giving to a thread, smaller chunks spend more time merging buffers than drawing.
+const MIN_LINES_PER_CHUNK: usize = 512;
+
+/// Marks cells of the per-thread buffers that no line touched. Lines are never drawn with it, so it is safe to skip when merging.
+const UNTOUCHED: char = '\0';
+
+impl Renderer {
+    pub fn par_draw(&self, buffer: &mut CharBuffer) {
+        //! Same as draw, but spreads the work over rayon's thread pool. Thick edges, and frames drawn with record_depth, supersampling, or fixed_point set, aren't drawn in parallel.
+        //! # Example
+        //! ```
+        //! # use ascii_renderer::prelude::*;
+        //! # use ascii_renderer::create_cube;
+        //! let mut buf = CharBuffer::new(30, 30);
+        //! let renderer = Renderer::new(
+        //!     vec![create_cube()],
+        //!     Camera {
+        //!         position: vec3!(0.0, 0.0, -10.0),
+        //!         rotation: vec3!(0.0, 0.0, 0.0),
+        //!         fov: vec2!(0.7, 0.7),
+        //!     },
+        //! );
+        //! renderer.par_draw(&mut buf);
+        //! ```
+        if self.thickness.is_some()
+            || self.record_depth
+            || self.supersampling.is_some()
+            || self.fixed_point
+        {
+            return self.draw(buffer);
+        }
+        let mut scratch = lock(&self.scratch.0);
+        scratch.lines.clear();
+        for mesh in self.meshs.iter() {
+            self.par_push_mesh_lines(mesh, buffer.dimensions, &mut scratch);
+        }
+        par_draw_lines(self, buffer, &scratch.lines);
+    }
+    pub fn par_draw_mesh(&self, mesh: &Mesh, buffer: &mut CharBuffer) {
+        //! Same as draw_mesh, but spreads the work over rayon's thread pool.
+        if self.thickness.is_some() {
+            return self.draw_mesh(mesh, buffer);
+        }
+        let mut scratch = lock(&self.scratch.0);
+        scratch.lines.clear();
+        self.par_push_mesh_lines(mesh, buffer.dimensions, &mut scratch);
+        par_draw_lines(self, buffer, &scratch.lines);
+    }
+    fn par_push_mesh_lines(&self, mesh: &Mesh, dimensions: (usize, usize), scratch: &mut Scratch) {
+        let mesh = &*self.shade(mesh);
+        let model = mesh.model_matrix();
+        let key = self.projection_key(model, dimensions);
+        let Scratch { lines, points, .. } = scratch;
+        mesh.cache.with_projected(
+            key,
+            points,
+            |point_map| {
+                point_map.par_extend(
+                    mesh.get_verticies().par_iter().map(|(&k, &v)| {
+                        (k, self.project_to_buffer(&key.model_view, v, dimensions))
+                    }),
+                )
+            },
+            |point_map| push_mesh_lines(mesh, self.lod_edges(mesh, &model), point_map, lines),
+        );
+    }
+}
+
+/// Draws the lines into separate buffers in parallel, then copies them into the buffer in the same order they would have been drawn in.
+fn par_draw_lines(renderer: &Renderer, buffer: &mut CharBuffer, lines: &[Line]) {
+    let threads = rayon::current_num_threads().max(1);
+    let chunk_size = lines.len().div_ceil(threads).max(MIN_LINES_PER_CHUNK);
+    //Antialiased edges depend on what's already under them, so they can't be drawn into separate layers
+    if lines.len() <= chunk_size || renderer.antialiasing.is_some() {
+        for line in lines {
+            renderer.rasterize(buffer, *line);
+        }
+        return;
+    }
+    let (width, height) = buffer.dimensions;
+    let layers: Vec<CharBuffer> = lines
+        .par_chunks(chunk_size)
+        .map(|chunk| {
+            let mut layer = CharBuffer::new(width, height);
+            layer.fill(UNTOUCHED);
+            for line in chunk {
+                renderer.rasterize(&mut layer, *line);
+            }
+            layer
+        })
+        .collect();
+    for layer in layers {
+        for (y, layer_row) in layer.data.into_iter().enumerate() {
+            for (x, layer_item) in layer_row.into_iter().enumerate() {
+                if layer_item != UNTOUCHED {
+                    let _ = buffer.set_char(x, y, layer_item);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::Camera;
+    use crate::{vec2, vec3, Scalar, Vector2, Vector3};
+
+    #[test]
+    fn matches_serial_draw() {
+        let mut meshs = vec![];
+        for i in 0..300 {
+            let mut cube = crate::create_cube();
+            cube.position = vec3!((i % 20) as Scalar - 10.0, (i / 20) as Scalar - 7.0, 0.0);
+            cube.rotation = vec3!(i as Scalar * 0.1, i as Scalar * 0.2, 0.0);
+            cube.char = ['#', '+', '@'][i % 3];
+            meshs.push(cube);
+        }
+        let mut renderer = Renderer::new(
+            meshs,
+            Camera {
+                position: vec3!(0.0, 0.0, -20.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(1.2, 1.2),
+            },
+        );
+        let mut serial = CharBuffer::new(80, 80);
+        let mut parallel = serial.clone();
+        renderer.draw(&mut serial);
+        //More threads than the machine might have, so the buffers are always split and merged
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap()
+            .install(|| renderer.par_draw(&mut parallel));
+        assert_eq!(serial, parallel);
+
+        //Modes that can't be drawn in parallel are drawn like draw
+        renderer.record_depth = true;
+        let mut serial = CharBuffer::new(80, 80);
+        let mut parallel = serial.clone();
+        renderer.draw(&mut serial);
+        renderer.par_draw(&mut parallel);
+        assert_eq!(serial, parallel);
+        assert!(renderer.depth_at(40, 40).is_some());
+    }
+}
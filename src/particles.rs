@@ -0,0 +1,216 @@
+//! Particle effects like explosions, rain, and sparks. An emitter spawns particles over time, moves them by their velocity and gravity, and draws each one as a char that changes over its life. Particles can be drawn in screen space, where x and y are cells, or projected from 3D through a camera.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::particles::ParticleEmitter;
+//! let mut sparks = ParticleEmitter::new(vec3!(10.0, 5.0, 0.0), 1);
+//! sparks.velocity = vec3!(0.0, -4.0, 0.0);
+//! sparks.spread = 3.0;
+//! sparks.gravity = vec3!(0.0, 9.8, 0.0);
+//! sparks.ramp = vec!['*', '+', '.'];
+//! sparks.burst(20);
+//! sparks.update(0.1);
+//! let mut buf = CharBuffer::new(20, 10);
+//! sparks.draw_2d(&mut buf);
+//! ```
+
+use super::char_buffer::CharBuffer;
+use super::noise::Rng;
+use super::rendering::Camera;
+use super::{Scalar, Vector3};
+
+/// A single particle. Its age and lifetime are in seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Particle {
+    pub position: Vector3,
+    pub velocity: Vector3,
+    pub age: f32,
+    pub lifetime: f32,
+}
+
+impl Particle {
+    pub fn life(&self) -> f32 {
+        //! How far through its life the particle is, from 0.0 when spawned to 1.0 when it dies.
+        if self.lifetime > 0.0 {
+            (self.age / self.lifetime).clamp(0.0, 1.0)
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Spawns, moves, and draws particles. Every setting is a public field, and changes to them affect the particles spawned after.
+#[derive(Debug, Clone)]
+pub struct ParticleEmitter {
+    /// Where new particles spawn.
+    pub position: Vector3,
+    /// How many particles spawn per second while the emitter is updated. 0.0 only spawns particles with burst.
+    pub rate: f32,
+    /// How many seconds particles live for, picked between the two.
+    pub lifetime: (f32, f32),
+    /// The velocity new particles start with.
+    pub velocity: Vector3,
+    /// How far each component of new particles' velocities can randomly be from velocity.
+    pub spread: Scalar,
+    /// Added to every particle's velocity each second.
+    pub gravity: Vector3,
+    /// The chars particles are drawn with over their life, from the first when they spawn to the last when they die.
+    pub ramp: Vec<char>,
+    /// Particles aren't spawned while there are this many alive.
+    pub max_particles: usize,
+    particles: Vec<Particle>,
+    spawn_debt: f32,
+    rng: Rng,
+}
+
+impl ParticleEmitter {
+    pub fn new(position: Vector3, seed: u32) -> Self {
+        //! An emitter that doesn't spawn anything until rate is set or burst is called. Particles live for a second and are drawn as '*', then '.' for the second half of their life.
+        Self {
+            position,
+            rate: 0.0,
+            lifetime: (1.0, 1.0),
+            velocity: Vector3::new(0.0, 0.0, 0.0),
+            spread: 0.0,
+            gravity: Vector3::new(0.0, 0.0, 0.0),
+            ramp: vec!['*', '.'],
+            max_particles: 1000,
+            particles: vec![],
+            spawn_debt: 0.0,
+            rng: Rng::new(seed),
+        }
+    }
+    pub fn get_particles(&self) -> &[Particle] {
+        &self.particles
+    }
+    pub fn clear(&mut self) {
+        //! Removes every particle.
+        self.particles.clear();
+    }
+    pub fn burst(&mut self, count: usize) {
+        //! Spawns count particles at once, ex: for an explosion.
+        for _ in 0..count.min(self.max_particles.saturating_sub(self.particles.len())) {
+            let (shortest, longest) = self.lifetime;
+            let lifetime = shortest + (longest - shortest) * self.rng.next_f32();
+            self.particles.push(Particle {
+                position: self.position,
+                velocity: self.velocity + self.rng.vector3(self.spread),
+                age: 0.0,
+                lifetime,
+            });
+        }
+    }
+    pub fn update(&mut self, delta: f32) {
+        //! Moves every particle forward by delta seconds, removing the ones that died and spawning new ones at the emitter's rate.
+        let step = delta as Scalar;
+        for particle in self.particles.iter_mut() {
+            particle.velocity += self.gravity * step;
+            particle.position += particle.velocity * step;
+            particle.age += delta;
+        }
+        self.particles.retain(|x| x.age < x.lifetime);
+
+        //Fractions of a particle are carried over, so low rates still spawn at high frame rates
+        self.spawn_debt += self.rate.max(0.0) * delta;
+        let count = self.spawn_debt.floor();
+        self.spawn_debt -= count;
+        self.burst(count as usize);
+    }
+    pub fn char_for(&self, particle: &Particle) -> Option<char> {
+        //! The char the particle is drawn with at its age, from the ramp.
+        let index = (particle.life() * self.ramp.len() as f32) as usize;
+        self.ramp
+            .get(index.min(self.ramp.len().saturating_sub(1)))
+            .copied()
+    }
+    pub fn draw_2d(&self, buf: &mut CharBuffer) {
+        //! Draws particles in screen space, where a particle's x and y are the cell it's drawn in. Their z is ignored.
+        for particle in self.particles.iter() {
+            let (x, y) = (particle.position.x.round(), particle.position.y.round());
+            if let (Some(char), true) = (self.char_for(particle), x >= 0.0 && y >= 0.0) {
+                let _ = buf.set_char(x as usize, y as usize, char);
+            }
+        }
+    }
+    pub fn draw_3d(&self, buf: &mut CharBuffer, camera: &Camera) {
+        //! Draws particles as points in the 3D scene seen by camera, the same way a renderer would place them. Particles behind the camera aren't drawn.
+        for particle in self.particles.iter() {
+            let (Some(char), Some(cell)) = (
+                self.char_for(particle),
+                camera.project(particle.position, buf.dimensions),
+            ) else {
+                continue;
+            };
+            let (x, y) = (cell.x.floor(), cell.y.floor());
+            if x >= 0.0 && y >= 0.0 {
+                let _ = buf.set_char(x as usize, y as usize, char);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{vec2, vec3, Vector2};
+
+    #[test]
+    fn spawning_and_dying() {
+        let mut emitter = ParticleEmitter::new(vec3!(0.0, 0.0, 0.0), 7);
+        emitter.rate = 10.0;
+        emitter.lifetime = (0.5, 0.5);
+        //A quarter of a particle every update still adds up
+        for _ in 0..4 {
+            emitter.update(0.025);
+        }
+        assert_eq!(emitter.get_particles().len(), 1);
+        for _ in 0..20 {
+            emitter.update(0.1);
+        }
+        //Particles that lived out their lifetime are gone
+        assert_eq!(emitter.get_particles().len(), 5);
+
+        emitter.rate = 0.0;
+        emitter.max_particles = 8;
+        emitter.burst(10);
+        assert_eq!(emitter.get_particles().len(), 8);
+        emitter.clear();
+        assert!(emitter.get_particles().is_empty());
+    }
+
+    #[test]
+    fn motion_and_ramp() {
+        let mut emitter = ParticleEmitter::new(vec3!(1.0, 1.0, 0.0), 7);
+        emitter.velocity = vec3!(2.0, 0.0, 0.0);
+        emitter.gravity = vec3!(0.0, 4.0, 0.0);
+        emitter.lifetime = (2.0, 2.0);
+        emitter.ramp = vec!['a', 'b', 'c', 'd'];
+        emitter.burst(1);
+        emitter.update(0.5);
+        let particle = emitter.get_particles()[0];
+        assert_eq!(particle.position, vec3!(2.0, 2.0, 0.0));
+        assert_eq!(emitter.char_for(&particle), Some('b'));
+
+        let mut buf = CharBuffer::new(4, 4);
+        emitter.draw_2d(&mut buf);
+        assert_eq!(buf.get_char(2, 2), Some('b'));
+    }
+
+    #[test]
+    fn projected() {
+        let mut emitter = ParticleEmitter::new(vec3!(0.0, 0.0, 5.0), 7);
+        emitter.burst(1);
+        emitter.position = vec3!(0.0, 0.0, -5.0);
+        emitter.burst(1);
+        let camera = Camera {
+            position: vec3!(0.0, 0.0, 0.0),
+            rotation: vec3!(0.0, 0.0, 0.0),
+            fov: vec2!(1.0, 1.0),
+        };
+        let mut buf = CharBuffer::new(5, 5);
+        emitter.draw_3d(&mut buf, &camera);
+        //Only the one in front of the camera is drawn, in the middle
+        assert_eq!(buf.get_char(2, 2), Some('*'));
+        assert_eq!(buf.to_string().matches('*').count(), 1);
+    }
+}
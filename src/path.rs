@@ -0,0 +1,300 @@
+use super::rendering::Camera;
+use super::{Scalar, Vector3};
+
+/// How a Path3 curves between its control points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathKind {
+    /// Passes through every control point.
+    CatmullRom,
+    /// A chain of cubic Bezier curves. The points go anchor, handle, handle, anchor, handle, handle, anchor... so a path with n curves has 3n + 1 points. Only passes through the anchors.
+    Bezier,
+}
+
+/// The number of straight pieces each curve is split into when measuring the length of a path.
+const SAMPLES_PER_SEGMENT: usize = 32;
+
+/// A smooth 3D curve through a handful of control points, for moving cameras and meshes along. Positions along the path are looked up by distance, so moving along it at a constant rate gives a constant speed no matter how the control points are spaced.
+/// # Example
+/// ```
+/// # use ascii_renderer::prelude::*;
+/// # use ascii_renderer::path::*;
+/// let path = Path3::new(
+///     vec![vec3!(0.0, 0.0, -10.0), vec3!(5.0, 0.0, 0.0), vec3!(0.0, 0.0, 10.0)],
+///     PathKind::CatmullRom,
+/// );
+/// let mut camera = Camera {
+///     position: vec3!(0.0, 0.0, 0.0),
+///     rotation: vec3!(0.0, 0.0, 0.0),
+///     fov: vec2!(0.8, 0.8),
+/// };
+/// let time = 2.5;
+/// camera.position = path.position_at_distance(time * 3.0);  //3 units per second
+/// camera.rotation = path.look_rotation_at_distance(time * 3.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path3 {
+    points: Vec<Vector3>,
+    rotations: Option<Vec<Vector3>>,
+    kind: PathKind,
+    closed: bool,
+    /// Cumulative length at each sample, SAMPLES_PER_SEGMENT + 1 samples per segment
+    lengths: Vec<Scalar>,
+}
+
+impl Path3 {
+    pub fn new(points: Vec<Vector3>, kind: PathKind) -> Self {
+        let mut ret = Self {
+            points,
+            rotations: None,
+            kind,
+            closed: false,
+            lengths: vec![],
+        };
+        ret.rebuild();
+        ret
+    }
+    pub fn closed(mut self) -> Self {
+        //! Makes the path loop back around to its first point. Only affects Catmull-Rom paths.
+        self.closed = true;
+        self.rebuild();
+        self
+    }
+    pub fn with_rotations(mut self, rotations: Vec<Vector3>) -> Self {
+        //! Gives the path a rotation at each point it passes through (every point for Catmull-Rom, every anchor for Bezier), which rotation_at_distance() interpolates between.
+        self.rotations = Some(rotations);
+        self
+    }
+    pub fn get_points(&self) -> &Vec<Vector3> {
+        &self.points
+    }
+    pub fn set_points(&mut self, points: Vec<Vector3>) {
+        self.points = points;
+        self.rebuild();
+    }
+    pub fn kind(&self) -> PathKind {
+        self.kind
+    }
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+    pub fn segment_count(&self) -> usize {
+        match self.kind {
+            PathKind::CatmullRom if self.points.len() < 2 => 0,
+            PathKind::CatmullRom if self.closed => self.points.len(),
+            PathKind::CatmullRom => self.points.len() - 1,
+            PathKind::Bezier => self.points.len().saturating_sub(1) / 3,
+        }
+    }
+    pub fn length(&self) -> Scalar {
+        self.lengths.last().copied().unwrap_or(0.0)
+    }
+    pub fn position_at_distance(&self, distance: Scalar) -> Vector3 {
+        //! The point the given distance along the path, clamped to the ends of the path (or wrapped around if it is closed).
+        let (segment, t) = self.locate(distance);
+        self.evaluate(segment, t)
+    }
+    pub fn position_at(&self, t: Scalar) -> Vector3 {
+        //! Same as position_at_distance, but t goes from 0.0 at the start of the path to 1.0 at the end.
+        self.position_at_distance(t * self.length())
+    }
+    pub fn tangent_at_distance(&self, distance: Scalar) -> Vector3 {
+        //! The normalized direction the path is heading at the given distance.
+        let (segment, t) = self.locate(distance);
+        let step = 0.001;
+        let (a, b) = if t + step <= 1.0 {
+            (self.evaluate(segment, t), self.evaluate(segment, t + step))
+        } else {
+            (self.evaluate(segment, t - step), self.evaluate(segment, t))
+        };
+        (b - a).normalize()
+    }
+    pub fn look_rotation_at_distance(&self, distance: Scalar) -> Vector3 {
+        //! The camera rotation that looks along the path at the given distance.
+        Camera::look_rotation(self.tangent_at_distance(distance))
+    }
+    pub fn rotation_at_distance(&self, distance: Scalar) -> Option<Vector3> {
+        //! Interpolates the rotations passed to with_rotations(). Returns None if there are none, or there aren't enough for the point on the path.
+        let rotations = self.rotations.as_ref()?;
+        let (segment, t) = self.locate(distance);
+        let start = *rotations.get(segment)?;
+        let end = match rotations.get(segment + 1) {
+            Some(x) => *x,
+            None if self.closed => *rotations.first()?,
+            None => return None,
+        };
+        Some(start.lerp(end, t))
+    }
+
+    fn rebuild(&mut self) {
+        //! Recalculates the arc length table.
+        let mut lengths = Vec::with_capacity(self.segment_count() * (SAMPLES_PER_SEGMENT + 1));
+        let mut total = 0.0;
+        for segment in 0..self.segment_count() {
+            let mut prev = self.evaluate(segment, 0.0);
+            lengths.push(total);
+            for i in 1..=SAMPLES_PER_SEGMENT {
+                let point = self.evaluate(segment, i as Scalar / SAMPLES_PER_SEGMENT as Scalar);
+                total += (point - prev).len();
+                lengths.push(total);
+                prev = point;
+            }
+        }
+        self.lengths = lengths;
+    }
+    fn locate(&self, distance: Scalar) -> (usize, Scalar) {
+        //! Converts a distance along the path into a segment and how far (0.0 to 1.0) through that segment it is.
+        let length = self.length();
+        if self.lengths.is_empty() || length <= 0.0 {
+            return (0, 0.0);
+        }
+        let distance = if self.closed {
+            distance.rem_euclid(length)
+        } else {
+            distance.clamp(0.0, length)
+        };
+        let index = self
+            .lengths
+            .partition_point(|&x| x < distance)
+            .clamp(1, self.lengths.len() - 1);
+        let (before, after) = (self.lengths[index - 1], self.lengths[index]);
+        let fraction = if after > before {
+            (distance - before) / (after - before)
+        } else {
+            0.0
+        };
+        let samples = SAMPLES_PER_SEGMENT + 1;
+        let segment = (index - 1) / samples;
+        let sample = (index - 1) % samples;
+        if sample == SAMPLES_PER_SEGMENT {
+            //Between the last sample of one segment and the first of the next, which are the same point
+            return (segment + 1, 0.0);
+        }
+        (
+            segment,
+            (sample as Scalar + fraction) / SAMPLES_PER_SEGMENT as Scalar,
+        )
+    }
+    fn evaluate(&self, segment: usize, t: Scalar) -> Vector3 {
+        let count = self.points.len();
+        match count {
+            0 => return Vector3::new(0.0, 0.0, 0.0),
+            1 => return self.points[0],
+            _ => {}
+        }
+        match self.kind {
+            PathKind::CatmullRom => {
+                let point = |i: isize| {
+                    if self.closed {
+                        self.points[i.rem_euclid(count as isize) as usize]
+                    } else {
+                        self.points[i.clamp(0, count as isize - 1) as usize]
+                    }
+                };
+                let i = segment as isize;
+                let (p0, p1, p2, p3) = (point(i - 1), point(i), point(i + 1), point(i + 2));
+                let (t2, t3) = (t * t, t * t * t);
+                (p1 * 2.0
+                    + (p2 - p0) * t
+                    + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2
+                    + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * t3)
+                    * 0.5
+            }
+            PathKind::Bezier => {
+                let i = (segment * 3).min(count.saturating_sub(4));
+                if count < 4 {
+                    return self.points[0].lerp(self.points[count - 1], t);
+                }
+                let (p0, p1, p2, p3) = (
+                    self.points[i],
+                    self.points[i + 1],
+                    self.points[i + 2],
+                    self.points[i + 3],
+                );
+                let u = 1.0 - t;
+                p0 * (u * u * u)
+                    + p1 * (3.0 * u * u * t)
+                    + p2 * (3.0 * u * t * t)
+                    + p3 * (t * t * t)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec3;
+
+    fn assert_close(a: Vector3, b: Vector3) {
+        assert!((a - b).len() < 0.01, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn catmull_rom_passes_through_points() {
+        let points = vec![
+            vec3!(0.0, 0.0, 0.0),
+            vec3!(1.0, 2.0, 0.0),
+            vec3!(3.0, 0.0, 1.0),
+        ];
+        let path = Path3::new(points.clone(), PathKind::CatmullRom);
+        assert_eq!(path.segment_count(), 2);
+        assert_close(path.position_at(0.0), points[0]);
+        assert_close(path.position_at(1.0), points[2]);
+        assert_close(path.evaluate(1, 0.0), points[1]);
+        assert_eq!(path.clone().closed().segment_count(), 3);
+    }
+
+    #[test]
+    fn arc_length_parameterization() {
+        let path = Path3::new(
+            vec![
+                vec3!(0.0, 0.0, 0.0),
+                vec3!(5.0, 0.0, 0.0),
+                vec3!(10.0, 0.0, 0.0),
+            ],
+            PathKind::CatmullRom,
+        );
+        assert!((path.length() - 10.0).abs() < 0.01);
+        //The curve speeds up and slows down within each segment, but distances are still even
+        assert_close(path.position_at_distance(2.5), vec3!(2.5, 0.0, 0.0));
+        assert_close(path.position_at_distance(7.5), vec3!(7.5, 0.0, 0.0));
+        assert_close(path.position_at_distance(-3.0), vec3!(0.0, 0.0, 0.0));
+        assert_close(path.tangent_at_distance(2.5), vec3!(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn bezier_and_rotations() {
+        let path = Path3::new(
+            vec![
+                vec3!(0.0, 0.0, 0.0),
+                vec3!(0.0, 1.0, 0.0),
+                vec3!(1.0, 1.0, 0.0),
+                vec3!(1.0, 0.0, 0.0),
+            ],
+            PathKind::Bezier,
+        )
+        .with_rotations(vec![vec3!(0.0, 0.0, 0.0), vec3!(0.0, 2.0, 0.0)]);
+        assert_eq!(path.segment_count(), 1);
+        assert_close(path.position_at(0.5), vec3!(0.5, 0.75, 0.0));
+        assert_close(path.position_at(1.0), vec3!(1.0, 0.0, 0.0));
+        assert_close(
+            path.rotation_at_distance(path.length()).unwrap(),
+            vec3!(0.0, 2.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn look_rotation_faces_tangent() {
+        let mut camera = Camera {
+            position: vec3!(1.0, 2.0, 3.0),
+            rotation: vec3!(0.0, 0.0, 0.0),
+            fov: crate::Vector2::new(1.0, 1.0),
+        };
+        let target = vec3!(4.0, -1.0, 7.0);
+        camera.look_at(target);
+        let uv = camera.map_point_uv(target);
+        assert!((uv.x - 0.5).abs() < 0.001 && (uv.y - 0.5).abs() < 0.001);
+        let below = camera.map_point_uv(target + vec3!(0.0, 1.0, 0.0));
+        assert!((below.x - 0.5).abs() < 0.001 && below.y > 0.5);
+    }
+}
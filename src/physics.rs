@@ -0,0 +1,183 @@
+//! Simple rigid body motion for bouncing-cube style demos. A RigidBody moves the Transform it's stepped with (ex: a mesh's) by its velocity, which gravity and its acceleration change over time, and bounces it off a flat ground. Physics steps bodies at a fixed timestep however long frames take, so they move the same at any frame rate.
+//!
+//! +y is down, the same as the camera draws it, so gravity is positive and the ground is the largest y bodies can reach.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::physics::{Physics, RigidBody};
+//! let mut cube = ascii_renderer::create_cube();
+//! let mut body = RigidBody::new();
+//! body.radius = 1.0;
+//! let mut physics = Physics::new(1.0 / 60.0);
+//! physics.ground = Some(5.0);
+//! //In Logic::process, with the frame's delta
+//! physics.update(0.5, [(&mut body, &mut cube.transform)]);
+//! assert!(cube.position.y > 0.0 && body.velocity.y > 0.0);
+//! ```
+
+use super::transform::Transform;
+use super::{Scalar, Vector3};
+
+/// How a body moves. Every setting is a public field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RigidBody {
+    /// In units per second.
+    pub velocity: Vector3,
+    /// Added to the velocity each second, on top of gravity, ex: for thrust.
+    pub acceleration: Vector3,
+    /// Added to the rotation each second, in radians.
+    pub angular_velocity: Vector3,
+    /// How much gravity affects the body, ex: 0.0 for something floating.
+    pub gravity_scale: Scalar,
+    /// How much speed is kept when bouncing off the ground, from 0.0 (stops dead) to 1.0 (bounces back as high as it fell from).
+    pub restitution: Scalar,
+    /// How much of its velocity the body loses each second to air resistance, from 0.0 to 1.0.
+    pub damping: Scalar,
+    /// How far the bottom of the body is below its position, so it touches the ground instead of sinking halfway into it.
+    pub radius: Scalar,
+}
+
+impl RigidBody {
+    pub fn new() -> Self {
+        //! A body at rest, affected by gravity, that keeps half its speed when it bounces.
+        Self {
+            velocity: Vector3::new(0.0, 0.0, 0.0),
+            acceleration: Vector3::new(0.0, 0.0, 0.0),
+            angular_velocity: Vector3::new(0.0, 0.0, 0.0),
+            gravity_scale: 1.0,
+            restitution: 0.5,
+            damping: 0.0,
+            radius: 0.0,
+        }
+    }
+    pub fn apply_impulse(&mut self, impulse: Vector3) {
+        //! Instantly changes the velocity, ex: to launch the body or knock it sideways.
+        self.velocity += impulse;
+    }
+}
+
+impl std::default::Default for RigidBody {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The settings shared by every body, and the clock that steps them at a fixed timestep.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Physics {
+    /// Added to every body's velocity each second, scaled by its gravity_scale.
+    pub gravity: Vector3,
+    /// The y of a flat ground bodies bounce off, or None for no ground.
+    pub ground: Option<Scalar>,
+    /// How many seconds each step moves bodies forward.
+    pub timestep: f32,
+    /// The most steps taken in one update, so a long frame doesn't make the next one even longer.
+    pub max_steps: usize,
+    accumulator: f32,
+}
+
+impl Physics {
+    pub fn new(timestep: f32) -> Self {
+        //! Physics with a gravity of 9.8 units per second squared and no ground.
+        Self {
+            gravity: Vector3::new(0.0, 9.8, 0.0),
+            ground: None,
+            timestep,
+            max_steps: 8,
+            accumulator: 0.0,
+        }
+    }
+    pub fn update<'a>(
+        &mut self,
+        delta: f32,
+        bodies: impl IntoIterator<Item = (&'a mut RigidBody, &'a mut Transform)>,
+    ) -> usize {
+        //! Steps the bodies as many times as fit in delta seconds, plus what was left over from earlier updates, and returns how many steps were taken. Time past max_steps is dropped.
+        if self.timestep <= 0.0 {
+            return 0;
+        }
+        self.accumulator += delta.max(0.0);
+        let steps = ((self.accumulator / self.timestep) as usize).min(self.max_steps);
+        self.accumulator = if steps == self.max_steps {
+            0.0
+        } else {
+            self.accumulator - steps as f32 * self.timestep
+        };
+        let mut bodies: Vec<_> = bodies.into_iter().collect();
+        for _ in 0..steps {
+            for (body, transform) in bodies.iter_mut() {
+                self.step(body, transform, self.timestep);
+            }
+        }
+        steps
+    }
+    pub fn get_accumulator(&self) -> f32 {
+        //! The time left over from the last update that wasn't enough for a full step.
+        self.accumulator
+    }
+    pub fn step(&self, body: &mut RigidBody, transform: &mut Transform, delta: f32) {
+        //! Moves a single body forward by delta seconds, bouncing it off the ground. Use update instead to step at the fixed timestep.
+        let delta = delta as Scalar;
+        body.velocity += (self.gravity * body.gravity_scale + body.acceleration) * delta;
+        body.velocity *= (1.0 - body.damping * delta).max(0.0);
+        transform.position += body.velocity * delta;
+        transform.rotation += body.angular_velocity * delta;
+
+        if let Some(ground) = self.ground {
+            let bottom = transform.position.y + body.radius;
+            if bottom > ground {
+                transform.position.y = ground - body.radius;
+                if body.velocity.y > 0.0 {
+                    body.velocity.y *= -body.restitution;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec3;
+
+    #[test]
+    fn fixed_timestep() {
+        let mut physics = Physics::new(0.1);
+        physics.gravity = vec3!(0.0, 0.0, 0.0);
+        let mut body = RigidBody::new();
+        body.velocity = vec3!(1.0, 0.0, 0.0);
+        let mut transform = Transform::identity();
+        //Time carries over until there's enough for a step
+        assert_eq!(physics.update(0.05, [(&mut body, &mut transform)]), 0);
+        assert_eq!(physics.update(0.1, [(&mut body, &mut transform)]), 1);
+        assert!((physics.get_accumulator() - 0.05).abs() < 1e-6);
+        assert!((transform.position.x - 0.1).abs() < 1e-6);
+        //A huge frame only takes max_steps steps
+        assert_eq!(physics.update(10.0, [(&mut body, &mut transform)]), 8);
+        assert_eq!(physics.get_accumulator(), 0.0);
+    }
+
+    #[test]
+    fn gravity_and_bouncing() {
+        let mut physics = Physics::new(0.01);
+        physics.ground = Some(4.0);
+        let mut body = RigidBody::new();
+        body.radius = 1.0;
+        body.restitution = 1.0;
+        body.angular_velocity = vec3!(0.0, 1.0, 0.0);
+        let mut transform = Transform::identity();
+        physics.step(&mut body, &mut transform, 0.5);
+        assert!((body.velocity.y - 4.9).abs() < 1e-4);
+        assert!((transform.rotation.y - 0.5).abs() < 1e-6);
+
+        //Falling onto the ground, the body rests on it and bounces back up
+        physics.step(&mut body, &mut transform, 0.5);
+        assert_eq!(transform.position.y, 3.0);
+        assert!(body.velocity.y < 0.0);
+
+        body.restitution = 0.0;
+        body.velocity = vec3!(0.0, 3.0, 0.0);
+        physics.step(&mut body, &mut transform, 0.1);
+        assert_eq!(body.velocity.y, 0.0);
+    }
+}
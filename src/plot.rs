@@ -0,0 +1,140 @@
+//! Quick math visualization: 3D wireframes of functions of two variables, and 2D line charts drawn straight into a region of a buffer.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::char_buffer::Region;
+//! # use ascii_renderer::plot::{plot_line_chart, plot_surface};
+//! let ripple = plot_surface(|x, z| (x * x + z * z).sqrt().sin(), -6.0..=6.0, 25);
+//! let renderer = Renderer::new(
+//!     vec![ripple],
+//!     Camera {
+//!         position: vec3!(0.0, -8.0, -12.0),
+//!         rotation: vec3!(-0.5, 0.0, 0.0),
+//!         fov: vec2!(1.2, 1.0),
+//!     },
+//! );
+//! let mut buf = CharBuffer::new(60, 40);
+//! renderer.draw(&mut buf);
+//! let samples: Vec<Scalar> = (0..60).map(|x| (x as Scalar * 0.2).sin()).collect();
+//! plot_line_chart(&mut buf, Region::new(0, 30, 60, 10), &samples, '*');
+//! ```
+
+use super::char_buffer::{CharBuffer, Region};
+use super::line::Line;
+use super::rendering::Mesh;
+use super::{Scalar, Vector2, Vector3};
+use std::ops::RangeInclusive;
+
+pub fn plot_surface(
+    f: impl Fn(Scalar, Scalar) -> Scalar,
+    range: RangeInclusive<Scalar>,
+    resolution: usize,
+) -> Mesh {
+    //! A wireframe of y = f(x, z), sampled resolution times along each axis over range, as a grid. Bigger values of f go up (-y, since +y is down on screen). Samples where f isn't finite (ex: dividing by 0) are left out, along with their edges.
+    let mut mesh = Mesh::default();
+    let (start, end) = (*range.start(), *range.end());
+    let resolution = resolution.max(2);
+    let step = (end - start) / (resolution - 1) as Scalar;
+    let mut present = vec![false; resolution * resolution];
+    for row in 0..resolution {
+        for column in 0..resolution {
+            let (x, z) = (
+                start + column as Scalar * step,
+                start + row as Scalar * step,
+            );
+            let y = f(x, z);
+            if !y.is_finite() {
+                continue;
+            }
+            let index = row * resolution + column;
+            present[index] = true;
+            mesh.insert_vertex(index, Vector3::new(x, -y, z));
+            if column > 0 && present[index - 1] {
+                mesh.add_edge((index - 1, index));
+            }
+            if row > 0 && present[index - resolution] {
+                mesh.add_edge((index - resolution, index));
+            }
+        }
+    }
+    mesh
+}
+
+pub fn plot_line_chart(buf: &mut CharBuffer, region: Region, values: &[Scalar], char: char) {
+    //! Draws values as a line from left to right across the region, scaled so the smallest is at the bottom and the biggest at the top. Values that aren't finite leave a gap.
+    let region = region.clipped(buf.dimensions);
+    if region.width == 0 || region.height == 0 || values.is_empty() {
+        return;
+    }
+    let finite = values.iter().copied().filter(|x| x.is_finite());
+    let min = finite.clone().fold(Scalar::INFINITY, Scalar::min);
+    let max = finite.fold(Scalar::NEG_INFINITY, Scalar::max);
+    let bottom = (region.y + region.height - 1) as Scalar;
+    let point = |i: usize, value: Scalar| {
+        let x = if values.len() == 1 {
+            0.0
+        } else {
+            i as Scalar * (region.width - 1) as Scalar / (values.len() - 1) as Scalar
+        };
+        //A flat line goes through the middle
+        let height = if max > min {
+            (value - min) / (max - min)
+        } else {
+            0.5
+        };
+        Vector2::new(
+            region.x as Scalar + x,
+            bottom - height * (region.height - 1) as Scalar,
+        )
+    };
+    let mut previous: Option<Vector2> = None;
+    for (i, &value) in values.iter().enumerate() {
+        if !value.is_finite() {
+            previous = None;
+            continue;
+        }
+        let current = point(i, value);
+        buf.draw_line(Line {
+            char,
+            points: (previous.unwrap_or(current), current),
+        });
+        previous = Some(current);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn surfaces() {
+        let plane = plot_surface(|x, z| x + z, -1.0..=1.0, 3);
+        assert_eq!(plane.get_verticies().len(), 9);
+        assert_eq!(plane.get_edges().len(), 12);
+        assert_eq!(plane.get_verticies()[&8], crate::vec3!(1.0, -2.0, 1.0));
+        //The middle sample divides by 0, so it's left out along with its 4 edges
+        let hole = plot_surface(|x, z| 1.0 / (x * x + z * z), -1.0..=1.0, 3);
+        assert_eq!(hole.get_verticies().len(), 8);
+        assert_eq!(hole.get_edges().len(), 8);
+    }
+
+    #[test]
+    fn line_charts() {
+        let mut buf = CharBuffer::new(5, 3);
+        plot_line_chart(
+            &mut buf,
+            Region::new(0, 0, 5, 3),
+            &[0.0, 1.0, 2.0, 1.0, 0.0],
+            '*',
+        );
+        assert_eq!(&buf.to_string(), "    *     \n  *   *   \n*       * \n");
+        let mut buf = CharBuffer::new(4, 3);
+        plot_line_chart(
+            &mut buf,
+            Region::new(0, 0, 4, 3),
+            &[5.0, 5.0, Scalar::NAN, 5.0],
+            '-',
+        );
+        assert_eq!(&buf.to_string(), "        \n- -   - \n        \n");
+    }
+}
@@ -0,0 +1,245 @@
+//! Post effects change a finished frame right before it's shown, ex: for a retro CRT look. Add them to Runner::effects and they're applied to a copy of every frame, so the buffer process draws into is left alone. Effects that dim chars swap them for fainter ones from demos::RAMP.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::post::{PostEffect, Scanlines, Vignette};
+//! struct MyLogic;
+//! impl Logic for MyLogic {
+//!     fn process(&mut self, screen_buf: &mut CharBuffer, _delta: f32) -> ProcessReturn {
+//!         screen_buf.fill('#');
+//!         ProcessReturn::Continue
+//!     }
+//! }
+//! let mut runner = Runner::new(40, 20, 30, MyLogic);
+//! runner.effects.push(Box::new(Scanlines::new()));
+//! runner.effects.push(Box::new(Vignette::new(0.8)));
+//! //Effects can also be applied to any buffer directly
+//! let mut buf = CharBuffer::new(10, 4);
+//! buf.fill('@');
+//! Scanlines::new().apply(&mut buf, 0.0);
+//! assert_eq!(buf.get_char(0, 1), Some('+'));
+//! ```
+
+use super::char_buffer::CharBuffer;
+#[cfg(not(feature = "std"))]
+use super::float::Float;
+use super::noise::Rng;
+use super::transition::faded;
+use super::Scalar;
+use alloc::vec::Vec;
+
+/// Something that changes a frame after it's been drawn.
+pub trait PostEffect {
+    /// Changes frame, delta seconds after the last one, for effects that animate.
+    fn apply(&mut self, frame: &mut CharBuffer, delta: f32);
+}
+
+fn dim_row(frame: &mut CharBuffer, y: usize, amount: Scalar) {
+    for x in 0..frame.dimensions.0 {
+        if let Some(char) = frame.get_char(x, y) {
+            let _ = frame.set_char(x, y, faded(char, amount));
+        }
+    }
+}
+
+/// Dims every few rows, like the gaps between the lines of a CRT.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scanlines {
+    /// Every how many rows are dimmed, starting from the second.
+    pub spacing: usize,
+    /// How bright dimmed rows are, from 0.0 (gone) to 1.0 (unchanged).
+    pub brightness: Scalar,
+}
+
+impl Scanlines {
+    pub fn new() -> Self {
+        //! Dims every other row to half brightness.
+        Self {
+            spacing: 2,
+            brightness: 0.5,
+        }
+    }
+}
+
+impl Default for Scanlines {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PostEffect for Scanlines {
+    fn apply(&mut self, frame: &mut CharBuffer, _delta: f32) {
+        let spacing = self.spacing.max(1);
+        for y in (spacing - 1..frame.dimensions.1).step_by(spacing) {
+            dim_row(frame, y, self.brightness);
+        }
+    }
+}
+
+/// A dim band rolling down the screen and wrapping around, like a badly synced CRT.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RollingFlicker {
+    /// How many rows the band moves each second.
+    pub speed: Scalar,
+    /// How many rows tall the band is.
+    pub height: usize,
+    /// How bright the band is, from 0.0 to 1.0.
+    pub brightness: Scalar,
+    position: Scalar,
+}
+
+impl RollingFlicker {
+    pub fn new(speed: Scalar, height: usize) -> Self {
+        //! A band at the top of the screen, dimming to 60% brightness.
+        Self {
+            speed,
+            height,
+            brightness: 0.6,
+            position: 0.0,
+        }
+    }
+    pub fn get_position(&self) -> Scalar {
+        //! The row the top of the band is on.
+        self.position
+    }
+}
+
+impl PostEffect for RollingFlicker {
+    fn apply(&mut self, frame: &mut CharBuffer, delta: f32) {
+        let rows = frame.dimensions.1;
+        if rows == 0 {
+            return;
+        }
+        self.position = (self.position + self.speed * delta as Scalar).rem_euclid(rows as Scalar);
+        let top = self.position as usize;
+        for offset in 0..self.height.min(rows) {
+            dim_row(frame, (top + offset) % rows, self.brightness);
+        }
+    }
+}
+
+/// Randomly shifts rows sideways, like a signal with bad horizontal sync. Cells shifted in from past the edge are ' '.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HorizontalJitter {
+    /// The chance (0.0 to 1.0) of each row being shifted each frame.
+    pub probability: f32,
+    /// The furthest a row is shifted, in cells.
+    pub max_offset: usize,
+    rng: Rng,
+}
+
+impl HorizontalJitter {
+    pub fn new(probability: f32, max_offset: usize, seed: u32) -> Self {
+        Self {
+            probability,
+            max_offset,
+            rng: Rng::new(seed),
+        }
+    }
+}
+
+impl PostEffect for HorizontalJitter {
+    fn apply(&mut self, frame: &mut CharBuffer, _delta: f32) {
+        let (width, height) = frame.dimensions;
+        for y in 0..height {
+            if self.max_offset == 0 || !self.rng.chance(self.probability) {
+                continue;
+            }
+            let span = self.max_offset as u32 * 2 + 1;
+            let offset = (self.rng.next_u32() % span) as isize - self.max_offset as isize;
+            let row: Vec<char> = (0..width)
+                .map(|x| frame.get_char(x, y).unwrap_or(' '))
+                .collect();
+            for x in 0..width {
+                let source = x as isize - offset;
+                let char = usize::try_from(source)
+                    .ok()
+                    .and_then(|x| row.get(x).copied())
+                    .unwrap_or(' ');
+                let _ = frame.set_char(x, y, char);
+            }
+        }
+    }
+}
+
+/// Dims cells more the further they are from the middle of the screen, darkening the corners.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vignette {
+    /// How much the corners are dimmed, from 0.0 (not at all) to 1.0 (to nothing).
+    pub strength: Scalar,
+}
+
+impl Vignette {
+    pub fn new(strength: Scalar) -> Self {
+        Self { strength }
+    }
+}
+
+impl PostEffect for Vignette {
+    fn apply(&mut self, frame: &mut CharBuffer, _delta: f32) {
+        let (width, height) = frame.dimensions;
+        let half = (width as Scalar / 2.0, height as Scalar / 2.0);
+        for y in 0..height {
+            for x in 0..width {
+                //0.0 in the middle, 1.0 in the corners
+                let dx = (x as Scalar + 0.5 - half.0) / half.0;
+                let dy = (y as Scalar + 0.5 - half.1) / half.1;
+                let distance = (dx * dx + dy * dy) / 2.0;
+                if let Some(char) = frame.get_char(x, y) {
+                    let _ = frame.set_char(x, y, faded(char, 1.0 - self.strength * distance));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    fn filled(width: usize, height: usize) -> CharBuffer {
+        let mut buf = CharBuffer::new(width, height);
+        buf.fill('@');
+        buf
+    }
+
+    #[test]
+    fn scanlines_and_flicker() {
+        let mut buf = filled(2, 4);
+        Scanlines::new().apply(&mut buf, 0.0);
+        assert_eq!(buf.to_string(), "@ @ \n+ + \n@ @ \n+ + \n");
+        let mut flicker = RollingFlicker::new(2.0, 1);
+        let mut buf = filled(1, 4);
+        flicker.apply(&mut buf, 1.5);
+        assert_eq!(flicker.get_position(), 3.0);
+        assert_eq!(buf.to_string(), "@ \n@ \n@ \n* \n");
+        //It wraps back around to the top
+        flicker.apply(&mut filled(1, 4), 0.5);
+        assert_eq!(flicker.get_position(), 0.0);
+    }
+
+    #[test]
+    fn jitter() {
+        let mut buf = CharBuffer::from_text("abc\nabc");
+        HorizontalJitter::new(0.0, 1, 3).apply(&mut buf, 0.0);
+        assert_eq!(buf, CharBuffer::from_text("abc\nabc"));
+        let mut jitter = HorizontalJitter::new(1.0, 1, 3);
+        for _ in 0..20 {
+            let mut buf = CharBuffer::from_text("abc");
+            jitter.apply(&mut buf, 0.0);
+            assert!(["a b c \n", "  a b \n", "b c   \n"]
+                .iter()
+                .any(|x| buf.to_string() == *x));
+        }
+    }
+
+    #[test]
+    fn vignette() {
+        let mut buf = filled(5, 5);
+        Vignette::new(1.0).apply(&mut buf, 0.0);
+        assert_eq!(buf.get_char(2, 2), Some('@'));
+        assert!(buf.get_char(0, 0).unwrap() != '@');
+        assert_eq!(buf.get_char(0, 0), buf.get_char(4, 4));
+    }
+}
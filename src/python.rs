@@ -0,0 +1,387 @@
+//! Python bindings, behind the `python` feature, so scenes can be driven from notebooks and scripts. Building the crate as a Python extension (ex: with `maturin develop`, which pyproject.toml sets the features up for) makes a module named `ascii_renderer` with CharBuffer, Mesh, Camera, and Renderer classes, and a Pump that draws a frame whenever it's called, so Python code keeps its own loop instead of handing it to a Runner.
+//!
+//! Vectors are tuples, ex: `mesh.position = (0.0, 1.0, 0.0)`. A Renderer holds the Mesh and Camera objects it was given rather than copies, so changing them changes the next frame.
+//! # Example
+//! ```python
+//! import time
+//! from ascii_renderer import Camera, Mesh, Pump, Renderer
+//!
+//! cube = Mesh.cube()
+//! pump = Pump(Renderer([cube], Camera(position=(0.0, 0.0, -6.0))), 40, 20)
+//! while True:
+//!     cube.rotation = (0.0, pump.renderer.time, 0.0)
+//!     pump.pump(show=True)  #Draws and prints the frame, using the time since the last call
+//!     time.sleep(1 / 30)
+//! ```
+
+use super::assets::{Asset, AssetError};
+use super::char_buffer::CharBuffer;
+use super::rendering::{Camera, Mesh, Renderer};
+use super::runner::{Backend, StdoutBackend};
+use super::{Scalar, Vector2, Vector3};
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::prelude::*;
+use std::path::PathBuf;
+use std::time::Instant;
+
+type Tuple2 = (Scalar, Scalar);
+type Tuple3 = (Scalar, Scalar, Scalar);
+
+fn vec2(v: Tuple2) -> Vector2 {
+    Vector2::new(v.0, v.1)
+}
+
+fn vec3(v: Tuple3) -> Vector3 {
+    Vector3::new(v.0, v.1, v.2)
+}
+
+fn tuple3(v: Vector3) -> Tuple3 {
+    (v.x, v.y, v.z)
+}
+
+/// CharBuffer as a Python class.
+#[pyclass(name = "CharBuffer", module = "ascii_renderer")]
+#[derive(Debug, Clone)]
+pub struct PyCharBuffer(pub CharBuffer);
+
+#[pymethods]
+impl PyCharBuffer {
+    #[new]
+    fn new(width: usize, height: usize) -> Self {
+        Self(CharBuffer::new(width, height))
+    }
+    #[getter]
+    fn width(&self) -> usize {
+        self.0.dimensions.0
+    }
+    #[getter]
+    fn height(&self) -> usize {
+        self.0.dimensions.1
+    }
+    fn fill(&mut self, char: char) {
+        self.0.fill(char);
+    }
+    fn set_char(&mut self, x: usize, y: usize, char: char) -> PyResult<()> {
+        self.0
+            .set_char(x, y, char)
+            .map_err(|_| PyIndexError::new_err(format!("({x}, {y}) is outside the buffer")))
+    }
+    fn get_char(&self, x: usize, y: usize) -> Option<char> {
+        self.0.get_char(x, y)
+    }
+    fn write(&mut self, x: usize, y: usize, text: &str) -> usize {
+        self.0.write_str(x, y, text)
+    }
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+    fn __repr__(&self) -> String {
+        format!(
+            "CharBuffer({}, {})",
+            self.0.dimensions.0, self.0.dimensions.1
+        )
+    }
+}
+
+/// Mesh as a Python class.
+#[pyclass(name = "Mesh", module = "ascii_renderer")]
+#[derive(Debug, Clone)]
+pub struct PyMesh(pub Mesh);
+
+#[pymethods]
+impl PyMesh {
+    #[new]
+    fn new() -> Self {
+        Self(Mesh::default())
+    }
+    #[staticmethod]
+    fn cube() -> Self {
+        Self(super::create_cube())
+    }
+    #[staticmethod]
+    fn load(path: PathBuf) -> PyResult<Self> {
+        match Mesh::load(&path) {
+            Ok(mesh) => Ok(Self(mesh)),
+            Err(AssetError::Io(err)) => Err(err.into()),
+            Err(err) => Err(PyValueError::new_err(err.to_string())),
+        }
+    }
+    #[getter]
+    fn position(&self) -> Tuple3 {
+        tuple3(self.0.position)
+    }
+    #[setter]
+    fn set_position(&mut self, position: Tuple3) {
+        self.0.position = vec3(position);
+    }
+    #[getter]
+    fn rotation(&self) -> Tuple3 {
+        tuple3(self.0.rotation)
+    }
+    #[setter]
+    fn set_rotation(&mut self, rotation: Tuple3) {
+        self.0.rotation = vec3(rotation);
+    }
+    #[getter]
+    fn scale(&self) -> Tuple3 {
+        tuple3(self.0.scale)
+    }
+    #[setter]
+    fn set_scale(&mut self, scale: Tuple3) {
+        self.0.scale = vec3(scale);
+    }
+    #[getter]
+    fn char(&self) -> char {
+        self.0.char
+    }
+    #[setter]
+    fn set_char(&mut self, char: char) {
+        self.0.char = char;
+    }
+    fn add_vertex(&mut self, index: usize, vertex: Tuple3) {
+        self.0.insert_vertex(index, vec3(vertex));
+    }
+    fn add_edge(&mut self, a: usize, b: usize) {
+        self.0.add_edge((a, b));
+    }
+}
+
+/// Camera as a Python class.
+#[pyclass(name = "Camera", module = "ascii_renderer")]
+#[derive(Debug, Clone)]
+pub struct PyCamera(pub Camera);
+
+#[pymethods]
+impl PyCamera {
+    #[new]
+    #[pyo3(signature = (position = (0.0, 0.0, 0.0), rotation = (0.0, 0.0, 0.0), fov = (1.0, 1.0)))]
+    fn new(position: Tuple3, rotation: Tuple3, fov: Tuple2) -> Self {
+        Self(Camera {
+            position: vec3(position),
+            rotation: vec3(rotation),
+            fov: vec2(fov),
+        })
+    }
+    #[getter]
+    fn position(&self) -> Tuple3 {
+        tuple3(self.0.position)
+    }
+    #[setter]
+    fn set_position(&mut self, position: Tuple3) {
+        self.0.position = vec3(position);
+    }
+    #[getter]
+    fn rotation(&self) -> Tuple3 {
+        tuple3(self.0.rotation)
+    }
+    #[setter]
+    fn set_rotation(&mut self, rotation: Tuple3) {
+        self.0.rotation = vec3(rotation);
+    }
+    #[getter]
+    fn fov(&self) -> Tuple2 {
+        (self.0.fov.x, self.0.fov.y)
+    }
+    #[setter]
+    fn set_fov(&mut self, fov: Tuple2) {
+        self.0.fov = vec2(fov);
+    }
+    fn look_at(&mut self, target: Tuple3) {
+        self.0.look_at(vec3(target));
+    }
+    fn project(&self, point: Tuple3, width: usize, height: usize) -> Option<Tuple2> {
+        //! Where a point is drawn in a buffer of the given size, or None if it's behind the camera.
+        let cell = self.0.project(vec3(point), (width, height))?;
+        Some((cell.x, cell.y))
+    }
+}
+
+/// Renderer as a Python class, holding Python Mesh and Camera objects.
+#[pyclass(name = "Renderer", module = "ascii_renderer")]
+pub struct PyRenderer {
+    meshes: Vec<Py<PyMesh>>,
+    camera: Py<PyCamera>,
+    //Drawn with, after copying the meshes and camera in, so its caches last between frames
+    renderer: Renderer,
+}
+
+impl PyRenderer {
+    fn sync(&mut self, py: Python<'_>) {
+        self.renderer.meshs.clear();
+        self.renderer
+            .meshs
+            .extend(self.meshes.iter().map(|x| x.borrow(py).0.clone()));
+        self.renderer.camera = self.camera.borrow(py).0.clone();
+    }
+}
+
+#[pymethods]
+impl PyRenderer {
+    #[new]
+    fn new(py: Python<'_>, meshes: Vec<Py<PyMesh>>, camera: Py<PyCamera>) -> Self {
+        let renderer = Renderer::new(vec![], camera.borrow(py).0.clone());
+        Self {
+            meshes,
+            camera,
+            renderer,
+        }
+    }
+    #[getter]
+    fn meshes(&self, py: Python<'_>) -> Vec<Py<PyMesh>> {
+        self.meshes.iter().map(|x| x.clone_ref(py)).collect()
+    }
+    #[setter]
+    fn set_meshes(&mut self, meshes: Vec<Py<PyMesh>>) {
+        self.meshes = meshes;
+    }
+    #[getter]
+    fn camera(&self, py: Python<'_>) -> Py<PyCamera> {
+        self.camera.clone_ref(py)
+    }
+    #[setter]
+    fn set_camera(&mut self, camera: Py<PyCamera>) {
+        self.camera = camera;
+    }
+    #[getter]
+    fn time(&self) -> f32 {
+        self.renderer.time
+    }
+    #[setter]
+    fn set_time(&mut self, time: f32) {
+        self.renderer.time = time;
+    }
+    fn add(&mut self, mesh: Py<PyMesh>) {
+        self.meshes.push(mesh);
+    }
+    fn __len__(&self) -> usize {
+        self.meshes.len()
+    }
+    fn draw(&mut self, py: Python<'_>, buffer: &mut PyCharBuffer) {
+        self.sync(py);
+        self.renderer.draw(&mut buffer.0);
+    }
+    fn render(&mut self, py: Python<'_>, width: usize, height: usize) -> String {
+        //! Draws a frame into a new buffer of the given size, returning it the same way printing it would.
+        self.sync(py);
+        super::rendering::render_frame(&self.renderer, width, height)
+    }
+}
+
+/// Draws a renderer's scene every time it's pumped, for Python code running its own loop.
+#[pyclass(name = "Pump", module = "ascii_renderer")]
+pub struct PyPump {
+    renderer: Py<PyRenderer>,
+    buffer: Py<PyCharBuffer>,
+    backend: StdoutBackend,
+    last_pump: Option<Instant>,
+}
+
+#[pymethods]
+impl PyPump {
+    #[new]
+    fn new(
+        py: Python<'_>,
+        renderer: Py<PyRenderer>,
+        width: usize,
+        height: usize,
+    ) -> PyResult<Self> {
+        Ok(Self {
+            renderer,
+            buffer: Py::new(py, PyCharBuffer::new(width, height))?,
+            backend: StdoutBackend::default(),
+            last_pump: None,
+        })
+    }
+    #[getter]
+    fn renderer(&self, py: Python<'_>) -> Py<PyRenderer> {
+        self.renderer.clone_ref(py)
+    }
+    #[getter]
+    fn buffer(&self, py: Python<'_>) -> Py<PyCharBuffer> {
+        self.buffer.clone_ref(py)
+    }
+    #[pyo3(signature = (delta = None, show = false))]
+    fn pump(&mut self, py: Python<'_>, delta: Option<f32>, show: bool) -> PyResult<String> {
+        //! Moves the renderer's time forward by delta (or the time since the last pump if it's None, which is 0 the first time), clears the buffer and draws the scene into it, and returns the frame the same way printing it would. With show set, the frame is also printed over the last one.
+        let now = Instant::now();
+        let delta = delta.unwrap_or_else(|| {
+            self.last_pump
+                .map_or(0.0, |x| now.duration_since(x).as_secs_f32())
+        });
+        self.last_pump = Some(now);
+        let mut renderer = self.renderer.borrow_mut(py);
+        let mut buffer = self.buffer.borrow_mut(py);
+        renderer.renderer.time += delta;
+        buffer.0.fill(' ');
+        renderer.draw(py, &mut buffer);
+        if show {
+            self.backend.present(&buffer.0, true)?;
+        }
+        Ok(buffer.0.to_string())
+    }
+}
+
+#[pymodule]
+pub fn ascii_renderer(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    //! The Python module, which building the crate as a Python extension exports.
+    module.add_class::<PyCharBuffer>()?;
+    module.add_class::<PyMesh>()?;
+    module.add_class::<PyCamera>()?;
+    module.add_class::<PyRenderer>()?;
+    module.add_class::<PyPump>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::ffi::c_str;
+
+    #[test]
+    fn driving_from_python() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = pyo3::wrap_pymodule!(ascii_renderer)(py);
+            py.import("sys")
+                .unwrap()
+                .getattr("modules")
+                .unwrap()
+                .set_item("ascii_renderer", module)
+                .unwrap();
+            py.run(
+                c_str!(
+                    r#"
+from ascii_renderer import Camera, CharBuffer, Mesh, Pump, Renderer
+cube = Mesh.cube()
+cube.char = "@"
+camera = Camera(position=(0.0, 0.0, -6.0))
+renderer = Renderer([cube], camera)
+pump = Pump(renderer, 20, 10)
+first = pump.pump(0.5)
+assert "@" in first and len(first.splitlines()) == 10
+assert renderer.time == 0.5
+#Changes to the objects the renderer holds show up in the next frame
+cube.position = (100.0, 0.0, 0.0)
+assert "@" not in pump.pump(0.5)
+camera.look_at((100.0, 0.0, 0.0))
+assert "@" in pump.pump()
+assert str(pump.buffer) == renderer.render(20, 10)
+
+buf = CharBuffer(3, 2)
+buf.set_char(2, 1, "x")
+assert buf.get_char(2, 1) == "x" and buf.get_char(3, 0) is None
+try:
+    buf.set_char(3, 0, "x")
+    assert False
+except IndexError:
+    pass
+assert len(renderer) == 1 and camera.project((-100.0, 0.0, -6.0), 20, 10) is None
+"#
+                ),
+                None,
+                None,
+            )
+            .unwrap();
+        });
+    }
+}
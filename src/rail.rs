@@ -0,0 +1,185 @@
+//! Camera rails for scripted fly-throughs. A CameraRail moves the camera along a Path3 over a set time while it looks at targets that change over time, ex: circling a loaded model while looking at its head, then its feet. Rails can be sampled directly, or baked into an AnimationClip and played with an Animator like any other animation.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::animation::{Animator, Interpolation};
+//! # use ascii_renderer::path::{Path3, PathKind};
+//! # use ascii_renderer::rail::CameraRail;
+//! let path = Path3::new(
+//!     vec![vec3!(-8.0, -2.0, -8.0), vec3!(0.0, -4.0, -10.0), vec3!(8.0, -2.0, -8.0)],
+//!     PathKind::CatmullRom,
+//! );
+//! let rail = CameraRail::new(path, 6.0)
+//!     .with_easing(Interpolation::EaseInOut)
+//!     .look_at(0.0, vec3!(0.0, -1.0, 0.0), Interpolation::EaseInOut)
+//!     .look_at(6.0, vec3!(0.0, 1.0, 0.0), Interpolation::EaseInOut);
+//! let mut camera = Camera {
+//!     position: vec3!(0.0, 0.0, 0.0),
+//!     rotation: vec3!(0.0, 0.0, 0.0),
+//!     fov: vec2!(1.0, 1.0),
+//! };
+//! rail.apply_to_camera(3.0, &mut camera);
+//!
+//! //Or played like any other animation
+//! let mut animator = Animator::new(rail.bake(30.0));
+//! animator.update(3.0);
+//! animator.apply_to_camera(&mut camera);
+//! ```
+
+use super::animation::{AnimationClip, Interpolation, LoopMode, Track};
+use super::path::Path3;
+use super::rendering::Camera;
+use super::{Scalar, Vector3};
+
+/// Moves a camera along a path while it looks at timed targets, see the module docs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraRail {
+    pub path: Path3,
+    /// How many seconds the camera takes to get from the start of the path to the end.
+    pub duration: f32,
+    /// How the camera speeds up and slows down along the path. Linear keeps it at the same speed the whole way, and Step holds it at the start until the end.
+    pub easing: Interpolation,
+    /// What the camera looks at over time, blended between keyframes with their interpolation. With no keyframes, the camera looks along the path.
+    pub targets: Track,
+    pub loop_mode: LoopMode,
+}
+
+impl CameraRail {
+    pub fn new(path: Path3, duration: f32) -> Self {
+        //! A rail played once at a constant speed, looking along the path.
+        Self {
+            path,
+            duration,
+            easing: Interpolation::Linear,
+            targets: Track::new(),
+            loop_mode: LoopMode::Once,
+        }
+    }
+    pub fn with_easing(mut self, easing: Interpolation) -> Self {
+        self.easing = easing;
+        self
+    }
+    pub fn with_loop_mode(mut self, loop_mode: LoopMode) -> Self {
+        self.loop_mode = loop_mode;
+        self
+    }
+    pub fn look_at(mut self, time: f32, target: Vector3, interpolation: Interpolation) -> Self {
+        //! Makes the camera look at target at the given time, blending to the next target with interpolation.
+        self.targets.insert(time, target, interpolation);
+        self
+    }
+    pub fn total_duration(&self) -> f32 {
+        //! How long the rail plays before looping, which is until the end of the path or the last target, whichever is later.
+        self.duration.max(self.targets.duration())
+    }
+    pub fn sample(&self, time: f32) -> (Vector3, Vector3) {
+        //! The camera's position and rotation at a time since the rail started playing, according to the loop mode.
+        let time = self.loop_mode.local_time(time, self.total_duration());
+        let t = if self.duration > 0.0 {
+            (time / self.duration).min(1.0)
+        } else {
+            1.0
+        };
+        let t = match self.easing {
+            Interpolation::Step if t < 1.0 => 0.0,
+            Interpolation::Step | Interpolation::Linear => t,
+            Interpolation::EaseInOut => t * t * (3.0 - 2.0 * t),
+        };
+        let distance = t as Scalar * self.path.length();
+        let position = self.path.position_at_distance(distance);
+        let rotation = match self.targets.sample(time) {
+            Some(target) if target != position => Camera::look_rotation(target - position),
+            _ => self.path.look_rotation_at_distance(distance),
+        };
+        (position, rotation)
+    }
+    pub fn apply_to_camera(&self, time: f32, camera: &mut Camera) {
+        let (position, rotation) = self.sample(time);
+        camera.position = position;
+        camera.rotation = rotation;
+    }
+    pub fn bake(&self, samples_per_second: f32) -> AnimationClip {
+        //! An AnimationClip with linear position and rotation keyframes sampled from the rail, with the same loop mode. Rotations are kept from jumping a whole turn between keyframes, so they blend the short way around.
+        let mut clip = AnimationClip::new(self.loop_mode);
+        let duration = self.total_duration();
+        let count = (duration * samples_per_second).ceil().max(1.0) as usize;
+        let turn = 2.0 * std::f64::consts::PI as Scalar;
+        let mut previous: Option<Vector3> = None;
+        for i in 0..=count {
+            let time = duration * i as f32 / count as f32;
+            let (position, mut rotation) = self.sample(time);
+            if let Some(previous) = previous {
+                let unwrap = |angle: Scalar, previous: Scalar| {
+                    angle - ((angle - previous) / turn).round() * turn
+                };
+                rotation = Vector3::new(
+                    unwrap(rotation.x, previous.x),
+                    unwrap(rotation.y, previous.y),
+                    unwrap(rotation.z, previous.z),
+                );
+            }
+            previous = Some(rotation);
+            clip.position.insert(time, position, Interpolation::Linear);
+            clip.rotation.insert(time, rotation, Interpolation::Linear);
+        }
+        clip
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animation::Animator;
+    use crate::path::PathKind;
+    use crate::{vec2, vec3, Vector2};
+
+    fn close(a: Vector3, b: Vector3) -> bool {
+        (a - b).len() < 0.01
+    }
+
+    #[test]
+    fn riding_the_rail() {
+        let path = Path3::new(
+            vec![vec3!(0.0, 0.0, -10.0), vec3!(10.0, 0.0, -10.0)],
+            PathKind::CatmullRom,
+        );
+        let mut rail = CameraRail::new(path, 2.0);
+        //Without targets, the camera looks along the path
+        let (position, rotation) = rail.sample(1.0);
+        assert!(close(position, vec3!(5.0, 0.0, -10.0)));
+        assert!(close(rotation, Camera::look_rotation(vec3!(1.0, 0.0, 0.0))));
+        assert!(close(rail.sample(5.0).0, vec3!(10.0, 0.0, -10.0)));
+
+        //Eased, it's slower near the ends but still halfway at the middle
+        rail = rail.with_easing(Interpolation::EaseInOut);
+        assert!(rail.sample(0.2).0.x < 1.0);
+        assert!(close(rail.sample(1.0).0, vec3!(5.0, 0.0, -10.0)));
+
+        //Targets turn the camera, and can outlast the path
+        rail = rail
+            .look_at(0.0, vec3!(0.0, 0.0, 0.0), Interpolation::Linear)
+            .look_at(4.0, vec3!(10.0, 0.0, 0.0), Interpolation::Linear)
+            .with_loop_mode(LoopMode::Loop);
+        assert_eq!(rail.total_duration(), 4.0);
+        let (position, rotation) = rail.sample(2.0);
+        assert!(close(position, vec3!(10.0, 0.0, -10.0)));
+        assert!(close(
+            rotation,
+            Camera::look_rotation(vec3!(-5.0, 0.0, 10.0))
+        ));
+        assert!(close(rail.sample(5.0).0, rail.sample(1.0).0));
+
+        //Baked, an Animator plays the same thing
+        let mut animator = Animator::new(rail.bake(20.0));
+        let mut camera = Camera {
+            position: vec3!(0.0, 0.0, 0.0),
+            rotation: vec3!(0.0, 0.0, 0.0),
+            fov: vec2!(1.0, 1.0),
+        };
+        animator.update(3.0);
+        animator.apply_to_camera(&mut camera);
+        let (position, rotation) = rail.sample(3.0);
+        assert!(close(camera.position, position));
+        assert!(close(camera.rotation, rotation));
+    }
+}
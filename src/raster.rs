@@ -0,0 +1,195 @@
+//! Black and white images of frames. With the `font` feature, buffers can be drawn into them with a bitmap font, for exporting frames as pictures and animations. The font is the 6 x 10 pixel one from [embedded-graphics](https://docs.rs/embedded-graphics), covering Latin-1, and chars it doesn't have are drawn as '?'. Like when printing, every char is followed by a space, so each cell of the buffer is about square in the image.
+
+use super::char_buffer::CharBuffer;
+#[cfg(feature = "font")]
+use embedded_graphics::mono_font::{iso_8859_1::FONT_6X10, MonoTextStyle};
+#[cfg(feature = "font")]
+use embedded_graphics::pixelcolor::BinaryColor;
+#[cfg(feature = "font")]
+use embedded_graphics::prelude::*;
+#[cfg(feature = "font")]
+use embedded_graphics::text::{Baseline, Text};
+
+/// How many pixels wide each cell of a buffer is in its image (the char, then the space after it).
+#[cfg(feature = "font")]
+pub const CELL_WIDTH: usize = 12;
+/// How many pixels tall each cell of a buffer is in its image.
+#[cfg(feature = "font")]
+pub const CELL_HEIGHT: usize = 10;
+
+/// A black and white image, where true is a pixel that is part of a char.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Bitmap {
+    pub width: usize,
+    pub height: usize,
+    /// The pixels, row by row from the top left.
+    pub pixels: Vec<bool>,
+}
+
+impl Bitmap {
+    pub fn new(width: usize, height: usize) -> Self {
+        //! An image with nothing drawn on it.
+        Self {
+            width,
+            height,
+            pixels: vec![false; width * height],
+        }
+    }
+    pub fn get(&self, x: usize, y: usize) -> Option<bool> {
+        if x >= self.width {
+            return None;
+        }
+        self.pixels.get(y * self.width + x).copied()
+    }
+    pub fn padded(&self, width: usize, height: usize) -> Bitmap {
+        //! A copy resized to the given size, adding empty pixels to the right and bottom or cutting them off.
+        let mut padded = Bitmap::new(width, height);
+        for (y, row) in self
+            .pixels
+            .chunks(self.width.max(1))
+            .take(height)
+            .enumerate()
+        {
+            let len = row.len().min(width);
+            padded.pixels[y * width..y * width + len].copy_from_slice(&row[..len]);
+        }
+        padded
+    }
+    pub fn from_cells(buf: &CharBuffer, pixel_size: usize) -> Bitmap {
+        //! An image where every cell of the buffer that isn't a space is a pixel_size x pixel_size block of pixels, for when chars are too coarse, ex: to show a big buffer on a terminal that can display images.
+        let (width, height) = buf.dimensions;
+        let mut bitmap = Bitmap::new(width * pixel_size, height * pixel_size);
+        for (y, row) in buf.data.iter().enumerate() {
+            for (x, &char) in row.iter().enumerate() {
+                if char == ' ' {
+                    continue;
+                }
+                for py in y * pixel_size..(y + 1) * pixel_size {
+                    let start = py * bitmap.width + x * pixel_size;
+                    bitmap.pixels[start..start + pixel_size].fill(true);
+                }
+            }
+        }
+        bitmap
+    }
+}
+
+#[cfg(feature = "font")]
+impl OriginDimensions for Bitmap {
+    fn size(&self) -> Size {
+        Size::new(self.width as u32, self.height as u32)
+    }
+}
+
+#[cfg(feature = "font")]
+impl DrawTarget for Bitmap {
+    type Color = BinaryColor;
+    type Error = std::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            let (x, y) = (point.x as usize, point.y as usize);
+            if point.x >= 0 && point.y >= 0 && x < self.width && y < self.height {
+                self.pixels[y * self.width + x] = color.is_on();
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "font")]
+impl CharBuffer {
+    pub fn rasterize(&self) -> Bitmap {
+        //! Draws the buffer into an image CELL_WIDTH times as wide and CELL_HEIGHT times as tall.
+        let (width, height) = self.dimensions;
+        let mut bitmap = Bitmap::new(width * CELL_WIDTH, height * CELL_HEIGHT);
+        let style = MonoTextStyle::new(&FONT_6X10, BinaryColor::On);
+        let mut line = String::with_capacity(width * 2);
+        for (y, row) in self.data.iter().enumerate() {
+            line.clear();
+            for &char in row.iter() {
+                line.push(char);
+                line.push(' ');
+            }
+            let position = Point::new(0, (y * CELL_HEIGHT) as i32);
+            let _ = Text::with_baseline(&line, position, style, Baseline::Top).draw(&mut bitmap);
+        }
+        bitmap
+    }
+}
+
+#[cfg(feature = "apng")]
+impl Bitmap {
+    pub fn write_png(
+        &self,
+        writer: impl std::io::Write,
+        foreground: [u8; 3],
+        background: [u8; 3],
+    ) -> std::io::Result<()> {
+        //! Writes the image as a PNG, with set pixels in the foreground color and the rest in the background color (both as RGB).
+        let mut encoder = png::Encoder::new(writer, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_palette([background, foreground].concat());
+        let mut writer = encoder.write_header().map_err(std::io::Error::other)?;
+        let indices: Vec<u8> = self.pixels.iter().map(|&x| x as u8).collect();
+        writer
+            .write_image_data(&indices)
+            .map_err(std::io::Error::other)?;
+        writer.finish().map_err(std::io::Error::other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cells_become_blocks() {
+        let mut buf = CharBuffer::new(2, 2);
+        buf.set_char(1, 0, '#').unwrap();
+        let bitmap = Bitmap::from_cells(&buf, 2);
+        assert_eq!((bitmap.width, bitmap.height), (4, 4));
+        let lit: Vec<(usize, usize)> = (0..4)
+            .flat_map(|y| (0..4).map(move |x| (x, y)))
+            .filter(|&(x, y)| bitmap.get(x, y).unwrap())
+            .collect();
+        assert_eq!(lit, vec![(2, 0), (3, 0), (2, 1), (3, 1)]);
+    }
+
+    #[cfg(feature = "apng")]
+    #[test]
+    fn png() {
+        let mut bytes = Vec::new();
+        Bitmap::new(3, 2)
+            .write_png(&mut bytes, [255, 255, 255], [0, 0, 0])
+            .unwrap();
+        let reader = png::Decoder::new(bytes.as_slice()).read_info().unwrap();
+        assert_eq!((reader.info().width, reader.info().height), (3, 2));
+    }
+
+    #[cfg(feature = "font")]
+    #[test]
+    fn chars_become_pixels() {
+        let mut buf = CharBuffer::new(3, 2);
+        buf.set_char(1, 1, '#').unwrap();
+        let bitmap = buf.rasterize();
+        assert_eq!((bitmap.width, bitmap.height), (36, 20));
+        let lit = |x_range: std::ops::Range<usize>, y_range: std::ops::Range<usize>| {
+            y_range
+                .flat_map(|y| x_range.clone().map(move |x| (x, y)))
+                .filter(|&(x, y)| bitmap.get(x, y).unwrap())
+                .count()
+        };
+        assert!(lit(12..18, 10..20) > 10);
+        assert_eq!(lit(0..36, 0..20), lit(12..18, 10..20));
+
+        let padded = bitmap.padded(40, 10);
+        assert_eq!(padded.get(38, 0), Some(false));
+        assert_eq!(padded.get(0, 10), None);
+        assert_eq!(&padded.pixels[..36], &bitmap.pixels[..36]);
+    }
+}
@@ -0,0 +1,266 @@
+//! Records frames along with how long each was shown for, so they can be replayed or exported. With the `gif` or `apng` features, recordings can be saved as animated images.
+//! # Example
+//! ```no_run
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::recording::Recorder;
+//! # use ascii_renderer::runner::StdoutBackend;
+//! # struct MyLogic;
+//! # impl Logic for MyLogic {
+//! #     fn process(&mut self, _: &mut CharBuffer, _: f32) -> ProcessReturn { ProcessReturn::End }
+//! # }
+//! let recorder = Recorder::new(StdoutBackend::default());
+//! let mut runner = Runner::with_backend(40, 20, 30, MyLogic, recorder);
+//! runner.run(true);
+//! let recording = runner.backend.finish();
+//! # #[cfg(feature = "gif")]
+//! recording.save_gif("demo.gif", [255, 255, 255], [0, 0, 0]).unwrap();
+//! ```
+
+use super::char_buffer::CharBuffer;
+use super::runner::Backend;
+use std::time::{Duration, Instant};
+
+/// How long the last frame of a recording is shown for when there is no later frame to go by.
+const DEFAULT_DELAY: Duration = Duration::from_millis(100);
+
+/// A list of frames and how long each one is shown for.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Recording {
+    pub frames: Vec<(CharBuffer, Duration)>,
+}
+
+impl Recording {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn push(&mut self, frame: CharBuffer, delay: Duration) {
+        //! Adds a frame to the end, shown for delay.
+        self.frames.push((frame, delay));
+    }
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+    pub fn duration(&self) -> Duration {
+        //! How long the whole recording takes to play.
+        self.frames.iter().map(|x| x.1).sum()
+    }
+}
+
+/// A backend that records every frame, then passes it on to another backend to be shown as usual. Each frame's delay is the time until the next frame was presented.
+#[derive(Debug)]
+pub struct Recorder<B: Backend> {
+    pub inner: B,
+    recording: Recording,
+    last_frame: Option<Instant>,
+}
+
+impl<B: Backend> Recorder<B> {
+    pub fn new(inner: B) -> Self {
+        Self {
+            inner,
+            recording: Recording::new(),
+            last_frame: None,
+        }
+    }
+    pub fn get_recording(&self) -> &Recording {
+        //! The frames so far. The last frame's delay is a guess until another frame comes after it.
+        &self.recording
+    }
+    pub fn finish(&mut self) -> Recording {
+        //! Takes the frames so far, starting a new recording.
+        self.last_frame = None;
+        std::mem::take(&mut self.recording)
+    }
+}
+
+impl<B: Backend> Backend for Recorder<B> {
+    fn present(&mut self, buf: &CharBuffer, clear_screen: bool) -> std::io::Result<()> {
+        let now = Instant::now();
+        let mut delay = DEFAULT_DELAY;
+        if let (Some(last_frame), Some(last)) = (self.last_frame, self.recording.frames.last_mut())
+        {
+            last.1 = now - last_frame;
+            delay = last.1;
+        }
+        self.last_frame = Some(now);
+        self.recording.push(buf.clone(), delay);
+        self.inner.present(buf, clear_screen)
+    }
+    fn announce(&mut self, text: &str) -> std::io::Result<()> {
+        self.inner.announce(text)
+    }
+}
+
+#[cfg(any(feature = "gif", feature = "apng"))]
+impl Recording {
+    fn rasterize_frames(&self) -> (usize, usize, Vec<(super::raster::Bitmap, Duration)>) {
+        //Every frame is padded to the size of the biggest one
+        let frames: Vec<_> = self
+            .frames
+            .iter()
+            .map(|(x, delay)| (x.rasterize(), *delay))
+            .collect();
+        let width = frames.iter().map(|x| x.0.width).max().unwrap_or(0).max(1);
+        let height = frames.iter().map(|x| x.0.height).max().unwrap_or(0).max(1);
+        let frames = frames
+            .into_iter()
+            .map(|(bitmap, delay)| (bitmap.padded(width, height), delay))
+            .collect();
+        (width, height, frames)
+    }
+}
+
+#[cfg(feature = "gif")]
+impl Recording {
+    pub fn write_gif(
+        &self,
+        writer: impl std::io::Write,
+        foreground: [u8; 3],
+        background: [u8; 3],
+    ) -> std::io::Result<()> {
+        //! Writes the recording as a looping animated GIF, with the chars drawn in the foreground color (as RGB). GIF delays are in hundredths of a second, so they are rounded to the nearest one.
+        let (width, height, frames) = self.rasterize_frames();
+        let palette = [background, foreground].concat();
+        let mut encoder = gif::Encoder::new(writer, width as u16, height as u16, &palette)
+            .map_err(std::io::Error::other)?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(std::io::Error::other)?;
+        for (bitmap, delay) in frames {
+            let indices: Vec<u8> = bitmap.pixels.iter().map(|&x| x as u8).collect();
+            let mut frame =
+                gif::Frame::from_indexed_pixels(width as u16, height as u16, indices, None);
+            frame.delay = (delay.as_millis() as f64 / 10.0)
+                .round()
+                .min(u16::MAX as f64) as u16;
+            encoder.write_frame(&frame).map_err(std::io::Error::other)?;
+        }
+        Ok(())
+    }
+    pub fn save_gif(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        foreground: [u8; 3],
+        background: [u8; 3],
+    ) -> std::io::Result<()> {
+        //! Same as write_gif, but to a new file at path.
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        self.write_gif(file, foreground, background)
+    }
+}
+
+#[cfg(feature = "apng")]
+impl Recording {
+    pub fn write_apng(
+        &self,
+        writer: impl std::io::Write,
+        foreground: [u8; 3],
+        background: [u8; 3],
+    ) -> std::io::Result<()> {
+        //! Writes the recording as a looping animated PNG, with the chars drawn in the foreground color (as RGB). Delays are kept to the millisecond.
+        let (width, height, frames) = self.rasterize_frames();
+        let mut encoder = png::Encoder::new(writer, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_palette([background, foreground].concat());
+        encoder
+            .set_animated(frames.len().max(1) as u32, 0)
+            .map_err(std::io::Error::other)?;
+        let mut writer = encoder.write_header().map_err(std::io::Error::other)?;
+        for (bitmap, delay) in frames {
+            let millis = delay.as_millis().min(u16::MAX as u128) as u16;
+            writer
+                .set_frame_delay(millis, 1000)
+                .map_err(std::io::Error::other)?;
+            let indices: Vec<u8> = bitmap.pixels.iter().map(|&x| x as u8).collect();
+            writer
+                .write_image_data(&indices)
+                .map_err(std::io::Error::other)?;
+        }
+        writer.finish().map_err(std::io::Error::other)
+    }
+    pub fn save_apng(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        foreground: [u8; 3],
+        background: [u8; 3],
+    ) -> std::io::Result<()> {
+        //! Same as write_apng, but to a new file at path.
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        self.write_apng(file, foreground, background)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Count(usize);
+
+    impl Backend for Count {
+        fn present(&mut self, _buf: &CharBuffer, _clear_screen: bool) -> std::io::Result<()> {
+            self.0 += 1;
+            Ok(())
+        }
+    }
+
+    fn two_frames() -> Recording {
+        let mut recording = Recording::new();
+        let mut buf = CharBuffer::new(4, 3);
+        recording.push(buf.clone(), Duration::from_millis(50));
+        buf.set_char(2, 1, '@').unwrap();
+        recording.push(buf, Duration::from_millis(120));
+        recording
+    }
+
+    #[test]
+    fn records_frames() {
+        let mut recorder = Recorder::new(Count::default());
+        let mut buf = CharBuffer::new(2, 2);
+        recorder.present(&buf, true).unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        buf.set_char(0, 0, '#').unwrap();
+        recorder.present(&buf, true).unwrap();
+        assert_eq!(recorder.inner.0, 2);
+
+        let recording = recorder.finish();
+        assert_eq!(recording.len(), 2);
+        assert!(recording.frames[0].1 >= Duration::from_millis(20));
+        assert_eq!(recording.frames[1].0, buf);
+        assert!(recorder.get_recording().is_empty());
+        assert_eq!(two_frames().duration(), Duration::from_millis(170));
+    }
+
+    #[cfg(feature = "gif")]
+    #[test]
+    fn gif() {
+        let mut bytes = Vec::new();
+        two_frames()
+            .write_gif(&mut bytes, [255, 255, 255], [0, 0, 0])
+            .unwrap();
+        let mut decoder = gif::DecodeOptions::new()
+            .read_info(bytes.as_slice())
+            .unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (48, 30));
+        assert_eq!(decoder.read_next_frame().unwrap().unwrap().delay, 5);
+        assert_eq!(decoder.read_next_frame().unwrap().unwrap().delay, 12);
+        assert!(decoder.read_next_frame().unwrap().is_none());
+    }
+
+    #[cfg(feature = "apng")]
+    #[test]
+    fn apng() {
+        let mut bytes = Vec::new();
+        two_frames()
+            .write_apng(&mut bytes, [255, 255, 255], [0, 0, 0])
+            .unwrap();
+        let reader = png::Decoder::new(bytes.as_slice()).read_info().unwrap();
+        let info = reader.info();
+        assert_eq!((info.width, info.height), (48, 30));
+        assert_eq!(info.animation_control.unwrap().num_frames, 2);
+    }
+}
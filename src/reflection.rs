@@ -0,0 +1,143 @@
+//! Planar reflections, for shiny floors in showcase scenes. Every mesh is mirrored across a plane and drawn with fainter chars (from demos::RAMP), before the meshes themselves so the real scene is drawn over its reflection.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::reflection::PlanarReflection;
+//! let renderer = Renderer::new(
+//!     vec![ascii_renderer::create_cube()],
+//!     Camera {
+//!         position: vec3!(0.0, -3.0, -8.0),
+//!         rotation: vec3!(-0.3, 0.0, 0.0),
+//!         fov: vec2!(1.0, 1.0),
+//!     },
+//! );
+//! //A floor just under the cube (+y is down), showing it at half brightness
+//! let floor = PlanarReflection::ground(1.5);
+//! let mut buf = CharBuffer::new(60, 30);
+//! floor.draw(&renderer, &mut buf);
+//! renderer.draw(&mut buf);
+//! ```
+
+use super::char_buffer::CharBuffer;
+use super::geometry::Plane;
+use super::rendering::Renderer;
+use super::transition::faded;
+use super::{vec3, Mat4, Scalar, Vector3};
+
+/// Draws the reflections of a Renderer's meshes in a plane, see the module docs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlanarReflection {
+    /// The mirror.
+    pub plane: Plane,
+    /// How bright reflections are compared to the meshes, from 0.0 (not drawn) to 1.0 (drawn with the meshes' own chars).
+    pub brightness: Scalar,
+}
+
+impl PlanarReflection {
+    pub fn new(plane: Plane) -> Self {
+        //! Reflections at half brightness.
+        Self {
+            plane,
+            brightness: 0.5,
+        }
+    }
+    pub fn ground(y: Scalar) -> Self {
+        //! Reflections in flat ground at the given y.
+        Self::new(Plane::from_point_normal(
+            vec3!(0.0, y, 0.0),
+            vec3!(0.0, -1.0, 0.0),
+        ))
+    }
+    pub fn with_brightness(mut self, brightness: Scalar) -> Self {
+        self.brightness = brightness;
+        self
+    }
+    pub fn matrix(&self) -> Mat4 {
+        //! The matrix mirroring global points across the plane.
+        let n = self.plane.normal;
+        let n = [n.x, n.y, n.z];
+        let mut data = Mat4::identity().data;
+        for (i, row) in data.iter_mut().take(3).enumerate() {
+            for (j, cell) in row.iter_mut().take(3).enumerate() {
+                *cell -= 2.0 * n[i] * n[j];
+            }
+            row[3] = 2.0 * n[i] * self.plane.distance;
+        }
+        Mat4::new(data)
+    }
+    pub fn draw(&self, renderer: &Renderer, buf: &mut CharBuffer) {
+        //! Draws the reflection of every mesh in renderer, with renderer's camera. Draw the renderer after this, so the meshes are on top.
+        if self.brightness <= 0.0 {
+            return;
+        }
+        let matrix = self.matrix();
+        for mesh in renderer.meshs.iter() {
+            let mut reflection = mesh.clone();
+            reflection.char = faded(mesh.char, self.brightness);
+            renderer.draw_mesh_with_matrix(&reflection, matrix * mesh.model_matrix(), buf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::Camera;
+    use crate::{vec2, Vector2};
+
+    fn close(a: Vector3, b: Vector3) -> bool {
+        (a - b).len() < 0.001
+    }
+
+    #[test]
+    fn mirroring() {
+        let floor = PlanarReflection::ground(2.0);
+        assert!(close(
+            floor.matrix().transform_point(vec3!(1.0, 1.0, 1.0)),
+            vec3!(1.0, 3.0, 1.0)
+        ));
+        let wall = PlanarReflection::new(Plane::new(vec3!(2.0, 0.0, 0.0), 2.0));
+        assert!(close(
+            wall.matrix().transform_point(vec3!(0.0, 5.0, 3.0)),
+            vec3!(2.0, 5.0, 3.0)
+        ));
+
+        //Seen from the side, the cube's reflection is fainter and below the floor
+        let mut renderer = Renderer::new(
+            vec![crate::create_cube()],
+            Camera {
+                position: vec3!(0.0, 0.0, -10.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(1.0, 1.0),
+            },
+        );
+        renderer.meshs[0].char = '#';
+        let floor = PlanarReflection::ground(1.5);
+        let mut buf = CharBuffer::new(40, 40);
+        floor.draw(&renderer, &mut buf);
+        renderer.draw(&mut buf);
+        let dim = faded('#', 0.5);
+        assert!(dim != '#');
+        let corner = renderer
+            .camera
+            .project(vec3!(1.0, 2.0, -1.0), buf.dimensions)
+            .unwrap();
+        let (x, y) = (corner.x.round() as usize, corner.y.round() as usize);
+        assert_eq!(buf.get_char(x, y), Some(dim));
+        let floor_row = renderer
+            .camera
+            .project(vec3!(0.0, 1.5, -1.0), buf.dimensions)
+            .unwrap()
+            .y
+            .round() as usize;
+        assert!(!buf
+            .to_string()
+            .lines()
+            .take(floor_row)
+            .any(|x| x.contains(dim)));
+
+        let mut buf = CharBuffer::new(40, 40);
+        floor.with_brightness(0.0).draw(&renderer, &mut buf);
+        assert_eq!(buf, CharBuffer::new(40, 40));
+    }
+}
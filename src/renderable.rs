@@ -0,0 +1,420 @@
+//! Custom things a Renderer can draw besides meshes, ex: procedural curves, voxel chunks, or debug shapes. A Renderable is given a RenderContext, which projects 3D points and lines through the renderer's camera and draws them into the buffer, so nothing needs to be baked into a Mesh first.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::renderable::{RenderContext, Renderable};
+//! struct Axes;
+//! impl Renderable for Axes {
+//!     fn draw(&self, ctx: &mut RenderContext) {
+//!         let origin = vec3!(0.0, 0.0, 0.0);
+//!         ctx.line(origin, vec3!(3.0, 0.0, 0.0), 'x');
+//!         ctx.line(origin, vec3!(0.0, -3.0, 0.0), 'y');
+//!         ctx.line(origin, vec3!(0.0, 0.0, 3.0), 'z');
+//!     }
+//! }
+//! let renderer = Renderer::new(
+//!     vec![],
+//!     Camera {
+//!         position: vec3!(2.0, -2.0, -10.0),
+//!         rotation: vec3!(0.0, 0.0, 0.0),
+//!         fov: vec2!(1.0, 1.0),
+//!     },
+//! );
+//! let mut buf = CharBuffer::new(30, 30);
+//! renderer.draw_renderables(&[&Axes, &ascii_renderer::create_cube()], &mut buf);
+//! ```
+
+use super::char_buffer::CharBuffer;
+use super::line::Line;
+use super::rendering::{Camera, Mesh, Renderer};
+use super::transform::Transform;
+use super::{Mat4, Scalar, Vector2, Vector3};
+use alloc::vec::Vec;
+
+/// Parts of lines closer to the camera than this are cut off, so lines passing behind it don't wrap around the screen.
+pub(crate) const NEAR: Scalar = 0.001;
+
+/// Something that draws itself through a RenderContext.
+pub trait Renderable {
+    fn draw(&self, ctx: &mut RenderContext);
+}
+
+/// What a Renderable draws with: the renderer, the buffer being drawn into, and a model matrix points are moved by first.
+#[derive(Debug)]
+pub struct RenderContext<'a> {
+    renderer: &'a Renderer,
+    buffer: &'a mut CharBuffer,
+    view: Mat4,
+    model: Mat4,
+}
+
+impl<'a> RenderContext<'a> {
+    pub fn new(renderer: &'a Renderer, buffer: &'a mut CharBuffer) -> Self {
+        Self {
+            renderer,
+            buffer,
+            view: renderer.camera.view_matrix(),
+            model: Mat4::identity(),
+        }
+    }
+    pub fn get_camera(&self) -> &Camera {
+        &self.renderer.camera
+    }
+    pub fn get_renderer(&self) -> &Renderer {
+        self.renderer
+    }
+    pub fn buffer(&mut self) -> &mut CharBuffer {
+        //! The buffer being drawn into, ex: to write a label next to a projected point.
+        self.buffer
+    }
+    pub fn get_model(&self) -> Mat4 {
+        self.model
+    }
+    pub fn set_model(&mut self, model: Mat4) {
+        //! Sets the matrix every point is moved by before being projected, ex: to draw in an object's local space. It starts out as the identity.
+        self.model = model;
+    }
+    pub fn with_model(&mut self, model: Mat4, draw: impl FnOnce(&mut Self)) {
+        //! Runs draw with model applied on top of the current model matrix, then puts the old one back. Good for nesting, ex: a part of an object relative to the object.
+        let previous = self.model;
+        self.model = previous * model;
+        draw(self);
+        self.model = previous;
+    }
+    pub fn with_buffer(&mut self, buffer: &mut CharBuffer, draw: impl FnOnce(&mut RenderContext)) {
+        //! Runs draw with the same camera and model matrix, but drawing into another buffer, ex: a layer that's blended into this one afterwards. The buffer should be the same size as this one, since that's what points are projected for.
+        draw(&mut RenderContext {
+            renderer: self.renderer,
+            buffer,
+            view: self.view,
+            model: self.model,
+        });
+    }
+    pub fn to_view(&self, point: Vector3) -> Vector3 {
+        //! Where a point is relative to the camera after the model matrix, with x right, y down, and z the distance in front of it. Good for sorting things by depth or checking which way a face points.
+        self.view.transform_point(self.model.transform_point(point))
+    }
+    fn to_buffer(&self, relative: Vector3) -> Vector2 {
+        let uv = self.renderer.camera.project_view_uv(relative);
+        Vector2::new(
+            uv.x * self.buffer.dimensions.0 as Scalar,
+            uv.y * self.buffer.dimensions.1 as Scalar,
+        )
+    }
+    pub fn project(&self, point: Vector3) -> Option<Vector2> {
+        //! Where a point is drawn in the buffer, or None if it's behind the camera. See Camera::project.
+        let relative = self.to_view(point);
+        (relative.z > NEAR).then(|| self.to_buffer(relative))
+    }
+    pub fn point(&mut self, point: Vector3, char: char) {
+        //! Draws a single char where the point is.
+        if let Some(cell) = self.project(point) {
+            if cell.x >= 0.0 && cell.y >= 0.0 {
+                let _ = self.buffer.set_char(cell.x as usize, cell.y as usize, char);
+            }
+        }
+    }
+    pub fn line(&mut self, start: Vector3, end: Vector3, char: char) {
+        //! Draws a line between two points, cutting off any part of it behind the camera.
+        let (mut start, mut end) = (self.to_view(start), self.to_view(end));
+        if start.z <= NEAR && end.z <= NEAR {
+            return;
+        }
+        //Moves the end behind the camera forward along the line, to where it crosses the near plane
+        if start.z <= NEAR {
+            start = end.lerp(start, (end.z - NEAR) / (end.z - start.z));
+        } else if end.z <= NEAR {
+            end = start.lerp(end, (start.z - NEAR) / (start.z - end.z));
+        }
+        let line = Line {
+            char,
+            points: (self.to_buffer(start), self.to_buffer(end)),
+        };
+        self.renderer.rasterize(self.buffer, line);
+    }
+    pub fn polyline(&mut self, points: &[Vector3], char: char, closed: bool) {
+        //! Draws lines between each point and the next, and from the last back to the first if closed.
+        for pair in points.windows(2) {
+            self.line(pair[0], pair[1], char);
+        }
+        if let (true, [first, .., last]) = (closed, points) {
+            self.line(*last, *first, char);
+        }
+    }
+    pub fn polygon(&mut self, points: &[Vector3], char: char) {
+        //! Fills every cell whose middle is inside a flat, convex polygon, ex: a face of a solid object. Nothing is drawn if any corner is behind the camera. Later polygons are drawn over earlier ones, so draw from back to front.
+        let Some(cells) = points
+            .iter()
+            .map(|&x| self.project(x))
+            .collect::<Option<Vec<Vector2>>>()
+        else {
+            return;
+        };
+        if cells.len() < 3 {
+            return;
+        }
+        let (width, height) = self.buffer.dimensions;
+        let (min, max) = cells.iter().fold((cells[0], cells[0]), |(min, max), cell| {
+            (
+                Vector2::new(min.x.min(cell.x), min.y.min(cell.y)),
+                Vector2::new(max.x.max(cell.x), max.y.max(cell.y)),
+            )
+        });
+        let (start_x, end_x) = (
+            min.x.max(0.0) as usize,
+            (max.x.max(0.0) as usize + 1).min(width),
+        );
+        let (start_y, end_y) = (
+            min.y.max(0.0) as usize,
+            (max.y.max(0.0) as usize + 1).min(height),
+        );
+        for y in start_y..end_y {
+            for x in start_x..end_x {
+                let middle = Vector2::new(x as Scalar + 0.5, y as Scalar + 0.5);
+                //Inside a convex polygon, the middle is on the same side of every edge, whichever way it winds
+                let mut sides = cells
+                    .iter()
+                    .zip(cells.iter().cycle().skip(1))
+                    .map(|(a, b)| (b.x - a.x) * (middle.y - a.y) - (b.y - a.y) * (middle.x - a.x));
+                let inside = sides.clone().all(|x| x >= 0.0) || sides.all(|x| x <= 0.0);
+                if inside {
+                    let _ = self.buffer.set_char(x, y, char);
+                }
+            }
+        }
+    }
+    pub fn mesh(&mut self, mesh: &Mesh) {
+        //! Draws a mesh the same way Renderer::draw does (using its cache and level of detail), with the context's model matrix applied on top of the mesh's own.
+        self.renderer
+            .draw_mesh_with_matrix(mesh, self.model * mesh.model_matrix(), self.buffer);
+    }
+    pub fn draw(&mut self, renderable: &dyn Renderable) {
+        //! Draws another renderable, ex: a part of this one.
+        renderable.draw(self);
+    }
+}
+
+impl Renderable for Mesh {
+    fn draw(&self, ctx: &mut RenderContext) {
+        ctx.mesh(self);
+    }
+}
+
+/// A line through a list of points, ex: an orbit, a plotted function, or the path something took. Unlike a Mesh, the points are kept in order and can be added to or changed freely.
+/// # Example
+/// ```
+/// # use ascii_renderer::prelude::*;
+/// # use ascii_renderer::renderable::Polyline3;
+/// let mut orbit: Polyline3 = (0..32)
+///     .map(|i| {
+///         let angle = i as Scalar / 32.0 * core::f64::consts::TAU as Scalar;
+///         vec3!(angle.cos() * 3.0, 0.0, angle.sin() * 3.0)
+///     })
+///     .collect();
+/// orbit.closed = true;
+/// orbit.marker = Some('o');
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polyline3 {
+    pub points: Vec<Vector3>,
+    /// The char the lines are drawn with.
+    pub char: char,
+    /// Drawn on top of each point, if set, ex: to show the samples in a plot.
+    pub marker: Option<char>,
+    /// Whether to draw a line from the last point back to the first.
+    pub closed: bool,
+    /// Moves the points into the world.
+    pub transform: Transform,
+}
+
+impl Polyline3 {
+    pub fn new(points: Vec<Vector3>) -> Self {
+        //! An open line drawn with '*', without markers.
+        Self {
+            points,
+            char: '*',
+            marker: None,
+            closed: false,
+            transform: Transform::identity(),
+        }
+    }
+    pub fn push(&mut self, point: Vector3) {
+        self.points.push(point);
+    }
+    pub fn length(&self) -> Scalar {
+        //! The total length of the lines, in local space.
+        let open: Scalar = self.points.windows(2).map(|x| x[0].distance(x[1])).sum();
+        match (self.closed, self.points.as_slice()) {
+            (true, [first, .., last]) => open + last.distance(*first),
+            _ => open,
+        }
+    }
+}
+
+impl From<Vec<Vector3>> for Polyline3 {
+    fn from(points: Vec<Vector3>) -> Self {
+        Self::new(points)
+    }
+}
+
+impl FromIterator<Vector3> for Polyline3 {
+    fn from_iter<I: IntoIterator<Item = Vector3>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+impl Renderable for Polyline3 {
+    fn draw(&self, ctx: &mut RenderContext) {
+        ctx.with_model(self.transform.matrix(), |ctx| {
+            ctx.polyline(&self.points, self.char, self.closed);
+            if let Some(marker) = self.marker {
+                for point in self.points.iter() {
+                    ctx.point(*point, marker);
+                }
+            }
+        });
+    }
+}
+
+impl Renderer {
+    pub fn draw_renderable(&self, renderable: &dyn Renderable, buffer: &mut CharBuffer) {
+        //! Draws a renderable through this renderer's camera.
+        renderable.draw(&mut RenderContext::new(self, buffer));
+    }
+    pub fn draw_renderables(&self, renderables: &[&dyn Renderable], buffer: &mut CharBuffer) {
+        //! Draws every renderable in order, on top of what's already in the buffer. The renderer's own meshes aren't drawn, so call draw as well to include them.
+        let mut ctx = RenderContext::new(self, buffer);
+        for renderable in renderables {
+            renderable.draw(&mut ctx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{vec2, vec3};
+    use alloc::string::{String, ToString};
+    use alloc::vec;
+
+    fn renderer() -> Renderer {
+        Renderer::new(
+            vec![],
+            Camera {
+                position: vec3!(0.0, 0.0, -10.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(1.0, 1.0),
+            },
+        )
+    }
+
+    struct Square;
+
+    impl Renderable for Square {
+        fn draw(&self, ctx: &mut RenderContext) {
+            let corners = [
+                vec3!(-1.0, -1.0, 0.0),
+                vec3!(1.0, -1.0, 0.0),
+                vec3!(1.0, 1.0, 0.0),
+                vec3!(-1.0, 1.0, 0.0),
+            ];
+            ctx.polyline(&corners, '#', true);
+        }
+    }
+
+    #[test]
+    fn meshes_match_renderer() {
+        let mut renderer = renderer();
+        let mut cube = crate::create_cube();
+        cube.rotation = vec3!(0.3, 0.6, 0.0);
+        renderer.meshs.push(cube.clone());
+        let mut expected = CharBuffer::new(20, 20);
+        renderer.draw(&mut expected);
+        let mut buf = CharBuffer::new(20, 20);
+        renderer.draw_renderable(&cube, &mut buf);
+        assert_eq!(buf, expected);
+    }
+
+    #[test]
+    fn custom_renderables() {
+        let renderer = renderer();
+        let mut buf = CharBuffer::new(21, 21);
+        renderer.draw_renderables(&[&Square], &mut buf);
+        let text = buf.to_string();
+        assert!(text.contains('#'));
+        //The square is around the middle of the screen, which stays empty
+        assert_eq!(buf.get_char(10, 10), Some(' '));
+
+        //Moved to the side by the model matrix
+        let mut moved = CharBuffer::new(21, 21);
+        let mut ctx = RenderContext::new(&renderer, &mut moved);
+        ctx.with_model(Mat4::translation(vec3!(-2.0, 0.0, 0.0)), |ctx| {
+            ctx.draw(&Square)
+        });
+        assert_eq!(ctx.get_model(), Mat4::identity());
+        assert_eq!(ctx.project(vec3!(0.0, 0.0, 0.0)), Some(vec2!(10.5, 10.5)));
+        assert_ne!(moved, buf);
+    }
+
+    #[test]
+    fn polygons() {
+        let renderer = renderer();
+        let mut buf = CharBuffer::new(21, 21);
+        let mut ctx = RenderContext::new(&renderer, &mut buf);
+        let square = [
+            vec3!(-1.0, -1.0, 0.0),
+            vec3!(1.0, -1.0, 0.0),
+            vec3!(1.0, 1.0, 0.0),
+            vec3!(-1.0, 1.0, 0.0),
+        ];
+        ctx.polygon(&square, '#');
+        //Filled in the middle, but not near the edges of the screen
+        assert_eq!(buf.get_char(10, 10), Some('#'));
+        assert_eq!(buf.get_char(3, 10), Some(' '));
+        let mut behind = CharBuffer::new(21, 21);
+        let mut ctx = RenderContext::new(&renderer, &mut behind);
+        ctx.set_model(Mat4::translation(vec3!(0.0, 0.0, -20.0)));
+        ctx.polygon(&square, '#');
+        assert_eq!(behind, CharBuffer::new(21, 21));
+    }
+
+    #[test]
+    fn polylines() {
+        let renderer = renderer();
+        let mut line: Polyline3 = [vec3!(-2.0, 0.0, 0.0), vec3!(2.0, 0.0, 0.0)]
+            .into_iter()
+            .collect();
+        line.push(vec3!(2.0, 2.0, 0.0));
+        assert_eq!(line.length(), 6.0);
+        line.closed = true;
+        assert!((line.length() - (6.0 + (20.0 as Scalar).sqrt())).abs() < 1e-5);
+        line.marker = Some('o');
+        let mut buf = CharBuffer::new(21, 21);
+        renderer.draw_renderable(&line, &mut buf);
+        let text = buf.to_string();
+        assert_eq!(text.matches('o').count(), 3);
+        assert!(text.contains('*'));
+        //Moving it moves what's drawn
+        line.transform.position = vec3!(0.0, -3.0, 0.0);
+        let mut moved = CharBuffer::new(21, 21);
+        renderer.draw_renderable(&line, &mut moved);
+        assert_ne!(moved, buf);
+    }
+
+    #[test]
+    fn clipping() {
+        let renderer = renderer();
+        let mut buf = CharBuffer::new(21, 21);
+        let mut ctx = RenderContext::new(&renderer, &mut buf);
+        assert_eq!(ctx.project(vec3!(0.0, 0.0, -20.0)), None);
+        ctx.point(vec3!(0.0, 0.0, -20.0), '@');
+        ctx.line(vec3!(0.0, 0.0, -20.0), vec3!(0.0, 0.0, -11.0), '@');
+        assert_eq!(buf, CharBuffer::new(21, 21));
+
+        //A line from behind the camera to in front of it only draws the part in front, going off the bottom of the screen
+        let mut ctx = RenderContext::new(&renderer, &mut buf);
+        ctx.line(vec3!(0.0, 5.0, -20.0), vec3!(0.0, 5.0, 10.0), '|');
+        let rows: Vec<String> = buf.to_string().lines().map(String::from).collect();
+        assert!(rows[20].contains('|') && !rows[0].contains('|'));
+    }
+}
@@ -1,6 +1,26 @@
+use super::cache::{lock, lock_mut, ProjectionKey, Scratch, ScratchBuffers, VertexCache};
 use super::char_buffer::CharBuffer;
+#[cfg(not(feature = "std"))]
+use super::float::Float;
+use super::intensity::Fog;
 use super::line::Line;
-use std::collections::HashMap;
+use super::matrix::Mat4;
+use super::shader::VertexShader;
+use super::supersample::Supersampling;
+use super::thickness::EdgeThickness;
+use super::transform::{euler_from_matrix, Transform};
+use super::turntable::{Turntable, TurntableTarget};
+use super::HashMap;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::{vec, vec::Vec};
+
+/// The floating point type used by the vectors, matrices, and the rest of the transform pipeline. This is f32 unless the `f64` feature is enabled, which makes scenes with very large coordinates (ex: OBJs far from the origin) render without jitter.
+#[cfg(not(feature = "f64"))]
+pub type Scalar = f32;
+/// The floating point type used by the vectors, matrices, and the rest of the transform pipeline. This is f32 unless the `f64` feature is enabled, which makes scenes with very large coordinates (ex: OBJs far from the origin) render without jitter.
+#[cfg(feature = "f64")]
+pub type Scalar = f64;
 
 /// Slightly more concise way of declaring a Vector3
 #[macro_export]
@@ -24,64 +44,258 @@ macro_rules! vec2 {
     };
 }
 
-/// Used for rendering meshs to a CharBuffer.
+/// Used for rendering meshs to a CharBuffer. It has private fields, so it's made with Renderer::new and its options are set after.
 #[derive(Debug, Clone)]
 pub struct Renderer {
     pub meshs: Vec<Mesh>,
     pub camera: Camera,
+    /// When set, lines are drawn antialiased with this ramp (see CharBuffer::draw_line_aa and line::AA_RAMP).
+    pub antialiasing: Option<Vec<char>>,
+    /// Fades lines out with distance when drawing shaded, see Renderer::draw_intensity.
+    pub fog: Option<Fog>,
+    /// When set, edges nearer the camera are drawn thicker, see thickness::EdgeThickness.
+    pub thickness: Option<EdgeThickness>,
+    /// Turn meshes or the camera every update_turntables, see turntable::Turntable.
+    pub turntables: Vec<(TurntableTarget, Turntable)>,
+    /// The time in seconds passed to mesh shaders, which should be moved forward every frame.
+    pub time: f32,
+    /// When set, draw keeps the depth of every cell for Renderer::depth_at, drawing with depth testing like Renderer::draw_with_depth.
+    pub record_depth: bool,
+    /// When set, draw draws at a higher resolution and shrinks it back down, see supersample::Supersampling. With record_depth also set, depth is tested at the higher resolution and each cell keeps the nearest depth drawn in it.
+    pub supersampling: Option<Supersampling>,
+    /// When set, draw transforms and projects with fixed-point math, so frames are the same on every platform, see fixed::Fixed. This ignores record_depth and supersampling.
+    pub fixed_point: bool,
+    pub(crate) scratch: ScratchBuffers,
 }
 
 impl Renderer {
+    pub fn new(meshs: Vec<Mesh>, camera: Camera) -> Self {
+        Self {
+            meshs,
+            camera,
+            antialiasing: None,
+            fog: None,
+            thickness: None,
+            turntables: Vec::new(),
+            time: 0.0,
+            record_depth: false,
+            supersampling: None,
+            fixed_point: false,
+            scratch: ScratchBuffers::default(),
+        }
+    }
+    pub fn reserve(&mut self, vertices: usize, edges: usize) {
+        //! Makes room in the renderer's scratch buffers for meshes with up to this many vertices and frames with up to this many edges, so the first frames don't need to allocate as they grow. The buffers are reused between frames either way.
+        let scratch = lock_mut(&mut self.scratch.0);
+        scratch.lines.clear();
+        scratch.lines.reserve(edges);
+        scratch.points.clear();
+        scratch.points.reserve(vertices);
+    }
     ///Draws all the meshs to the CharBuffer
     /// # Example
     /// ```
-    /// let buf = CharBuffer::new(30, 30);  //Make sure to use a char buffer that has dimensions proportional to the camera's FOV, otherwise everything will be stretched oddly...
-    /// let renderer = Renderer {
-    ///     meshs: vec![create_cube()],
-    ///     camera: Camera {
+    /// # use ascii_renderer::prelude::*;
+    /// # use ascii_renderer::create_cube;
+    /// let mut buf = CharBuffer::new(30, 30);  //Make sure to use a char buffer that has dimensions proportional to the camera's FOV, otherwise everything will be stretched oddly...
+    /// let renderer = Renderer::new(
+    ///     vec![create_cube()],
+    ///     Camera {
     ///         position: vec3!(0.0, 0.0, -10.0),
     ///         rotation: vec3!(0.0, 0.0, 0.0),
-    ///         fov: vec2!(0.7, 0.7);   //FOV is in radians
+    ///         fov: vec2!(0.7, 0.7),   //FOV is in radians
     ///     },
-    /// };
+    /// );
     /// renderer.draw(&mut buf);
     /// println!("{buf}");
     /// ```
     pub fn draw(&self, buffer: &mut CharBuffer) {
+        if self.fixed_point {
+            self.draw_fixed(buffer);
+            return;
+        }
+        if let Some(supersampling) = &self.supersampling {
+            self.draw_supersampled(buffer, supersampling);
+            return;
+        }
+        if self.record_depth {
+            self.draw_recording_depth(buffer);
+            return;
+        }
+        let mut scratch = lock(&self.scratch.0);
         for mesh in self.meshs.iter() {
-            self.draw_mesh(mesh, buffer);
+            self.draw_mesh_scratch(mesh, mesh.model_matrix(), buffer, &mut scratch);
         }
     }
     /// Draws an individual mesh.
     pub fn draw_mesh(&self, mesh: &Mesh, buffer: &mut CharBuffer) {
-        let point_map: HashMap<usize, Vector2> = mesh
-            .get_global_verticies()
-            .iter()
-            .map(|(&k, &v)| {
-                let mut pnt = self.camera.map_point_uv(v);
-                pnt.x *= buffer.dimensions.0 as f32;
-                pnt.y *= buffer.dimensions.1 as f32;
-                (k, pnt)
-            })
-            .fold(HashMap::new(), |mut accum, (k, v)| {
-                accum.insert(k, v);
-                accum
-            });
-
-        let lines: Vec<Line> = mesh
-            .edges
-            .iter()
-            .map(|&point_indexs| Line {
-                char: mesh.char,
-                points: (
-                    (*point_map.get(&point_indexs.0).unwrap()).into(),
-                    (*point_map.get(&point_indexs.1).unwrap()).into(),
-                ),
-            })
-            .collect();
-
-        buffer.draw_lines(lines);
+        self.draw_mesh_with_matrix(mesh, mesh.model_matrix(), buffer);
+    }
+    /// Draws an individual mesh using the given model matrix instead of the mesh's own position, rotation, and scale.
+    pub fn draw_mesh_with_matrix(&self, mesh: &Mesh, model: Mat4, buffer: &mut CharBuffer) {
+        self.draw_mesh_scratch(mesh, model, buffer, &mut lock(&self.scratch.0));
+    }
+    /// The lines draw would draw for every mesh, in the coordinates of a buffer with the given dimensions, without drawing them. Lines can go past the edges of the buffer.
+    pub fn project_lines(&self, dimensions: (usize, usize)) -> Vec<Line> {
+        let mut scratch = lock(&self.scratch.0);
+        let mut lines = vec![];
+        for mesh in self.meshs.iter() {
+            self.project_mesh_lines(mesh, mesh.model_matrix(), dimensions, &mut scratch);
+            lines.extend_from_slice(&scratch.lines);
+        }
+        lines
+    }
+    pub(crate) fn draw_mesh_scratch(
+        &self,
+        mesh: &Mesh,
+        model: Mat4,
+        buffer: &mut CharBuffer,
+        scratch: &mut Scratch,
+    ) {
+        let mesh = &*self.shade(mesh);
+        if let Some(thickness) = self.thickness {
+            self.draw_thick_mesh(mesh, model, buffer, thickness);
+            return;
+        }
+        self.project_mesh_lines(mesh, model, buffer.dimensions, scratch);
+        for line in scratch.lines.iter() {
+            self.rasterize(buffer, *line);
+        }
+    }
+    pub(crate) fn rasterize(&self, buffer: &mut CharBuffer, line: Line) {
+        //! Draws a projected line, antialiased if the renderer is set to.
+        match &self.antialiasing {
+            Some(ramp) => buffer.draw_line_aa(line, ramp),
+            None => buffer.draw_line(line),
+        }
+    }
+    /// Replaces the lines in scratch with the mesh's lines, in buffer coordinates.
+    pub(crate) fn project_mesh_lines(
+        &self,
+        mesh: &Mesh,
+        model: Mat4,
+        dimensions: (usize, usize),
+        scratch: &mut Scratch,
+    ) {
+        let mesh = &*self.shade(mesh);
+        let key = self.projection_key(model, dimensions);
+        let Scratch { lines, points, .. } = scratch;
+        lines.clear();
+        mesh.cache.with_projected(
+            key,
+            points,
+            |point_map| {
+                #[cfg(feature = "simd")]
+                super::simd::project_into(point_map, &mesh.vertices, &key);
+                #[cfg(not(feature = "simd"))]
+                point_map.extend(mesh.vertices.iter().map(|(&k, &v)| {
+                    (
+                        k,
+                        self.project_to_buffer(&key.model_view, v, key.dimensions),
+                    )
+                }))
+            },
+            |point_map| push_mesh_lines(mesh, self.lod_edges(mesh, &model), point_map, lines),
+        );
+    }
+    pub(crate) fn projection_key(&self, model: Mat4, dimensions: (usize, usize)) -> ProjectionKey {
+        ProjectionKey {
+            model_view: self.camera.view_matrix() * model,
+            fov: self.camera.fov,
+            dimensions,
+        }
     }
+    pub(crate) fn project_view(&self, point: Vector3, dimensions: (usize, usize)) -> Vector2 {
+        //! Projects a view space point to buffer coordinates.
+        let uv = self.camera.project_view_uv(point);
+        Vector2::new(uv.x * dimensions.0 as Scalar, uv.y * dimensions.1 as Scalar)
+    }
+    //The simd feature replaces this in draw, leaving it to the rayon path and tests
+    #[cfg_attr(feature = "simd", allow(dead_code))]
+    pub(crate) fn project_to_buffer(
+        &self,
+        model_view: &Mat4,
+        vertex: Vector3,
+        dimensions: (usize, usize),
+    ) -> Vector2 {
+        //! Projects a local space vertex to buffer coordinates.
+        let mut pnt = self
+            .camera
+            .project_view_uv(model_view.transform_point(vertex));
+        pnt.x *= dimensions.0 as Scalar;
+        pnt.y *= dimensions.1 as Scalar;
+        pnt
+    }
+}
+
+/// Draws a single frame of the renderer's scene into a new buffer of the given size, returning it in the same format as printing the buffer. This is for programs that just need a frame (ex: web services, bots, or build scripts), without setting up a Logic and Runner.
+/// # Example
+/// ```
+/// # use ascii_renderer::prelude::*;
+/// let renderer = Renderer::new(
+///     vec![ascii_renderer::create_cube()],
+///     Camera {
+///         position: vec3!(0.0, 0.0, -10.0),
+///         rotation: vec3!(0.0, 0.0, 0.0),
+///         fov: vec2!(0.7, 0.7),
+///     },
+/// );
+/// let frame = render_frame(&renderer, 30, 30);
+/// assert_eq!(frame.lines().count(), 30);
+/// assert!(!frame.trim().is_empty());  //The cube is in view
+/// ```
+pub fn render_frame(renderer: &Renderer, width: usize, height: usize) -> String {
+    let mut buffer = CharBuffer::new(width, height);
+    renderer.draw(&mut buffer);
+    buffer.to_string()
+}
+
+/// Turns a mesh's edges into lines between its projected vertices, adding them to the end of lines.
+pub(crate) fn push_mesh_lines(
+    mesh: &Mesh,
+    edges: &[(usize, usize)],
+    point_map: &HashMap<usize, Vector2>,
+    lines: &mut Vec<Line>,
+) {
+    lines.extend(edges.iter().filter_map(|&point_indexs| {
+        //An edge to a vertex that doesn't exist is skipped, see Mesh::validate
+        let (Some(&a), Some(&b)) = (
+            point_map.get(&point_indexs.0),
+            point_map.get(&point_indexs.1),
+        ) else {
+            debug_assert!(
+                false,
+                "mesh drawn with {:?} has edge {:?} to a vertex that doesn't exist",
+                mesh.char, point_indexs
+            );
+            return None;
+        };
+        let line = Line {
+            char: mesh.char,
+            points: (a, b),
+        };
+        //Lines projected to NaN or infinity would end up anywhere, so they're left out. A camera that can't project (ex: a fov of 0) is allowed, but NaN in the mesh itself is a bug
+        if line.is_finite() {
+            return Some(line);
+        }
+        debug_assert!(
+            mesh_is_finite(mesh, point_indexs),
+            "mesh drawn with {:?} has a non-finite vertex or transform in edge {:?}",
+            mesh.char,
+            point_indexs
+        );
+        None
+    }));
+}
+
+fn mesh_is_finite(mesh: &Mesh, edge: (usize, usize)) -> bool {
+    let finite = |x: &Vector3| x.x.is_finite() && x.y.is_finite() && x.z.is_finite();
+    let transform = &mesh.transform;
+    [edge.0, edge.1]
+        .iter()
+        .filter_map(|x| mesh.vertices.get(x))
+        .chain([transform.position, transform.rotation, transform.scale].iter())
+        .all(finite)
 }
 
 #[derive(Debug, Clone)]
@@ -96,8 +310,50 @@ impl Camera {
     pub fn map_point_uv(&self, point: Vector3) -> Vector2 {
         //Maps a three dimensional GLOBAL point to UV point dictating its location on screen
         //EX: (0.0, 0.0) is top left of screen and (1.0, 1.0) is bottom right of screen
-        let relative = (point - self.position).rotate(self.rotation);
-
+        self.project_view_uv(self.view_matrix().transform_point(point))
+    }
+    /// The matrix that moves global points into the camera's space, where the camera is at the origin looking down +z.
+    pub fn view_matrix(&self) -> Mat4 {
+        Mat4::rotation(self.rotation) * Mat4::translation(-self.position)
+    }
+    /// The camera's position and rotation as a Transform (with a scale of 1).
+    pub fn transform(&self) -> Transform {
+        Transform {
+            position: self.position,
+            rotation: self.rotation,
+            ..Transform::identity()
+        }
+    }
+    /// Sets the camera's position and rotation from a Transform. The scale is ignored.
+    pub fn set_transform(&mut self, transform: &Transform) {
+        self.position = transform.position;
+        self.rotation = transform.rotation;
+    }
+    /// Points the camera at a global point, without any roll.
+    pub fn look_at(&mut self, target: Vector3) {
+        if target != self.position {
+            self.rotation = Camera::look_rotation(target - self.position);
+        }
+    }
+    /// The camera rotation that faces the given direction, without any roll. Note that +y is down on screen.
+    pub fn look_rotation(direction: Vector3) -> Vector3 {
+        let forward = direction.normalize();
+        let mut right = vec3!(0.0, 1.0, 0.0).cross(forward);
+        if right.len() < 0.0001 {
+            //Looking straight up or down, any right vector works
+            right = vec3!(1.0, 0.0, 0.0);
+        }
+        let right = right.normalize();
+        let down = forward.cross(right);
+        euler_from_matrix(&Mat4::new([
+            [right.x, right.y, right.z, 0.0],
+            [down.x, down.y, down.z, 0.0],
+            [forward.x, forward.y, forward.z, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]))
+    }
+    /// Same as map_point_uv, but for a point that has already been transformed by the view matrix.
+    pub fn project_view_uv(&self, relative: Vector3) -> Vector2 {
         let thetas = vec2!(
             vec2!(relative.z, relative.x).to_polar().y,
             vec2!(relative.z, relative.y).to_polar().y
@@ -105,26 +361,91 @@ impl Camera {
 
         vec2!(thetas.x / self.fov.x + 0.5, thetas.y / self.fov.y + 0.5)
     }
+    /// Where a global point is drawn in a buffer with the given dimensions, in the same coordinates lines are drawn with, ex: to put a HUD marker over an object. The cell (x, y) covers from x to x + 1. Returns None for points behind the camera, which aren't drawn.
+    /// # Example
+    /// ```
+    /// # use ascii_renderer::prelude::*;
+    /// let camera = Camera {
+    ///     position: vec3!(0.0, 0.0, -10.0),
+    ///     rotation: vec3!(0.0, 0.0, 0.0),
+    ///     fov: vec2!(1.0, 1.0),
+    /// };
+    /// let cell = camera.project(vec3!(0.0, 0.0, 0.0), (40, 20)).unwrap();
+    /// assert_eq!(cell, vec2!(20.0, 10.0));
+    /// let world = camera.unproject(cell, 10.0, (40, 20)).unwrap();
+    /// assert!(world.length() < 0.001);
+    /// ```
+    pub fn project(&self, point: Vector3, dimensions: (usize, usize)) -> Option<Vector2> {
+        let relative = self.view_matrix().transform_point(point);
+        if relative.z <= 0.0 {
+            return None;
+        }
+        let uv = self.project_view_uv(relative);
+        Some(vec2!(
+            uv.x * dimensions.0 as Scalar,
+            uv.y * dimensions.1 as Scalar
+        ))
+    }
+    /// The global point drawn at a spot in a buffer with the given dimensions, depth units in front of the camera (measured along the way it faces), ex: to turn a click into a position in the world. This undoes project, so use the middle of a cell (x + 0.5, y + 0.5) to get the point in the middle of it. Returns None for spots more than 90 degrees off to the side, which nothing can be drawn at.
+    pub fn unproject(
+        &self,
+        cell: Vector2,
+        depth: Scalar,
+        dimensions: (usize, usize),
+    ) -> Option<Vector3> {
+        let uv = vec2!(
+            cell.x / dimensions.0.max(1) as Scalar,
+            cell.y / dimensions.1.max(1) as Scalar
+        );
+        let relative = self.view_direction(uv)? * depth;
+        Some(
+            Mat4::rotation(self.rotation)
+                .transpose()
+                .transform_point(relative)
+                + self.position,
+        )
+    }
+    /// The view space direction through a UV point on screen, with a z of 1, or None if it is more than 90 degrees off to the side.
+    pub(crate) fn view_direction(&self, uv: Vector2) -> Option<Vector3> {
+        let theta_x = (uv.x - 0.5) * self.fov.x;
+        let theta_y = (uv.y - 0.5) * self.fov.y;
+        let limit = core::f64::consts::FRAC_PI_2 as Scalar;
+        if theta_x.abs() >= limit || theta_y.abs() >= limit {
+            return None;
+        }
+        Some(vec3!(theta_x.tan(), theta_y.tan(), 1.0))
+    }
 }
 
+/// A mesh's levels of detail, each with the distance it starts at.
+pub(crate) type Lods = Vec<(Scalar, Vec<(usize, usize)>)>;
+
 /// A struct containing all the data for a mesh. Rotation, as with everything in this crate, is in radians, with each value determining the amount that the mesh should be rotated around the given axis.
 /// Note that vertices are stored on a hashmap, not a vector.
+/// The mesh's position, rotation, and scale are stored in its transform, but can still be accessed directly (ex: `mesh.position`) as Mesh derefs to its Transform.
 #[derive(Debug, Clone)]
 pub struct Mesh {
-    vertices: HashMap<usize, Vector3>,
-    edges: Vec<(usize, usize)>,
-    pub rotation: Vector3,
-    pub position: Vector3,
-    pub scale: Vector3,
+    //The geometry is shared between clones until one of them changes it, so clones (and clones of renderers) are cheap
+    vertices: Arc<HashMap<usize, Vector3>>,
+    edges: Arc<Vec<(usize, usize)>>,
+    pub transform: Transform,
     pub char: char,
+    /// Critical meshes are always drawn, even when Renderer::draw_with_budget runs out of time.
+    pub critical: bool,
+    /// Moves the vertices every frame before the model transform, see shader::VertexShader.
+    pub shader: Option<VertexShader>,
+    /// Simpler edge sets and the distance from the camera they start being used at, sorted by distance. See Mesh::add_lod.
+    pub(crate) lods: Arc<Lods>,
+    pub(crate) cache: VertexCache,
 }
 
 impl Mesh {
     pub fn insert_vertex(&mut self, index: usize, vertex: Vector3) -> Option<Vector3> {
-        self.vertices.insert(index, vertex)
+        self.cache.clear();
+        Arc::make_mut(&mut self.vertices).insert(index, vertex)
     }
     pub fn get_vertex(&mut self, index: usize) -> Option<Vector3> {
-        self.vertices.get(&index).map(|&x| x)
+        self.vertices.get(&index).copied()
     }
     pub fn insert_vertices(
         &mut self,
@@ -136,49 +457,69 @@ impl Mesh {
             .collect()
     }
     pub fn remove_vertex(&mut self, index: usize) -> Option<Vector3> {
-        self.vertices.remove(&index)
+        self.cache.clear();
+        Arc::make_mut(&mut self.vertices).remove(&index)
     }
     pub fn get_verticies(&self) -> &HashMap<usize, Vector3> {
         &self.vertices
     }
     pub fn get_verticies_mut(&mut self) -> &mut HashMap<usize, Vector3> {
-        &mut self.vertices
+        self.cache.clear();
+        Arc::make_mut(&mut self.vertices)
     }
     pub fn add_edge(&mut self, edge: (usize, usize)) {
-        self.edges.push(edge)
+        Arc::make_mut(&mut self.edges).push(edge)
     }
     pub fn add_edges(&mut self, edges: Vec<(usize, usize)>) {
-        for edge in edges {
-            self.edges.push(edge);
-        }
+        Arc::make_mut(&mut self.edges).extend(edges);
     }
     pub fn remove_edge(&mut self, edge: (usize, usize)) -> Option<(usize, usize)> {
         let i = self.edges.iter().enumerate().find(|(_, &x)| x == edge)?.0;
-        Some(self.edges.remove(i))
+        Some(Arc::make_mut(&mut self.edges).remove(i))
     }
     pub fn get_edges(&self) -> &Vec<(usize, usize)> {
         &self.edges
     }
     pub fn get_edges_mut(&mut self) -> &mut Vec<(usize, usize)> {
-        &mut self.edges
+        Arc::make_mut(&mut self.edges)
+    }
+    pub fn shares_geometry(&self, other: &Mesh) -> bool {
+        //! True if the two meshes use the same vertices and edges in memory, which clones do until one of them is changed. Their transforms and chars can still be different.
+        //! # Example
+        //! ```
+        //! # use ascii_renderer::prelude::*;
+        //! let cube = ascii_renderer::create_cube();
+        //! let mut copy = cube.clone();
+        //! copy.position.x = 5.0;
+        //! assert!(copy.shares_geometry(&cube));
+        //! copy.insert_vertex(100, vec3!(0.0, 0.0, 0.0));
+        //! assert!(!copy.shares_geometry(&cube));
+        //! ```
+        Arc::ptr_eq(&self.vertices, &other.vertices) && Arc::ptr_eq(&self.edges, &other.edges)
     }
     pub fn get_global_verticies(&self) -> HashMap<usize, Vector3> {
-        let mut ret = self.vertices.clone();
-        ret.iter_mut().for_each(|(_, item)| {
-            item.x *= self.scale.x;
-            item.y *= self.scale.y;
-            item.z *= self.scale.z;
-
-            *item = item.rotate(self.rotation);
-
-            *item = *item + self.position;
-        });
-        ret
+        let model = self.model_matrix();
+        self.cache.world(model, || {
+            let mut ret = HashMap::clone(&self.vertices);
+            ret.iter_mut()
+                .for_each(|(_, item)| *item = model.transform_point(*item));
+            ret
+        })
+    }
+    /// Frees the transformed vertices cached from the last time the mesh was drawn (or had get_global_verticies called). The cache is kept up to date automatically, so this is only needed to save memory on big meshes that won't be drawn again for a while.
+    pub fn clear_vertex_cache(&mut self) {
+        self.cache.clear();
+    }
+    /// The matrix that scales, rotates, and then moves the mesh's vertices into global space.
+    pub fn model_matrix(&self) -> Mat4 {
+        self.transform.matrix()
     }
     /// Gets the average position of all the vertices and centers the mesh to be centered around that point. Good for meshes you want to rotate.
     /// returns the global coords to where the mesh was previously centered. If the mesh's position is set to this, then the mesh will go back to it's previous position, only now it's center is appropriatly placed so rotation won't look broken.
     /// EX:
     /// ```
+    /// # use ascii_renderer::prelude::*;
+    /// # let mut my_mesh = ascii_renderer::create_cube();
     /// let new_position = my_mesh.recenter();
     /// my_mesh.position = new_position;
     /// ```
@@ -187,37 +528,53 @@ impl Mesh {
             .vertices
             .values()
             .fold(vec3!(0.0, 0.0, 0.0), |accum, vertex| accum + *vertex)
-            / self.vertices.values().count() as f32;
-        self.vertices
+            / self.vertices.values().count() as Scalar;
+        self.cache.clear();
+        Arc::make_mut(&mut self.vertices)
             .values_mut()
             .for_each(|vertex| *vertex -= avg_pos);
         avg_pos
     }
 }
 
-impl std::default::Default for Mesh {
+impl core::default::Default for Mesh {
     fn default() -> Self {
         Self {
-            vertices: HashMap::new(),
-            edges: vec![],
-            rotation: vec3!(0.0, 0.0, 0.0),
-            position: vec3!(0.0, 0.0, 0.0),
-            scale: vec3!(1.0, 1.0, 1.0),
+            vertices: Arc::new(HashMap::new()),
+            edges: Arc::new(vec![]),
+            transform: Transform::identity(),
             char: '+',
+            critical: false,
+            shader: None,
+            lods: Arc::new(vec![]),
+            cache: VertexCache::default(),
         }
     }
 }
 
+impl core::ops::Deref for Mesh {
+    type Target = Transform;
+    fn deref(&self) -> &Self::Target {
+        &self.transform
+    }
+}
+
+impl core::ops::DerefMut for Mesh {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.transform
+    }
+}
+
 /// A struct used for storing 3d points, rotation vectors, etc. It is easiest to create using vec3!(x, y, z)
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub struct Vector3 {
-    pub x: f32,
-    pub y: f32,
-    pub z: f32,
+    pub x: Scalar,
+    pub y: Scalar,
+    pub z: Scalar,
 }
 
 impl Vector3 {
-    pub fn new(x: f32, y: f32, z: f32) -> Self {
+    pub fn new(x: Scalar, y: Scalar, z: Scalar) -> Self {
         Self { x, y, z }
     }
     pub fn rotate(self, rotation_vec: Vector3) -> Self {
@@ -241,20 +598,44 @@ impl Vector3 {
 
         ret
     }
-    pub fn len(self) -> f32 {
+    pub fn len(self) -> Scalar {
         (self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
     }
+    /// Same as len()
+    pub fn length(self) -> Scalar {
+        self.len()
+    }
+    pub fn length_squared(self) -> Scalar {
+        self.dot(self)
+    }
     pub fn normalize(self) -> Self {
         let len = self.len();
         if len == 1.0 || len == 0.0 {
-            return self;
+            self
         } else {
             vec3!(self.x / len, self.y / len, self.z / len)
         }
     }
+    pub fn dot(self, other: Self) -> Scalar {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+    pub fn cross(self, other: Self) -> Self {
+        vec3!(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x
+        )
+    }
+    pub fn distance(self, other: Self) -> Scalar {
+        (other - self).len()
+    }
+    pub fn lerp(self, other: Self, t: Scalar) -> Self {
+        //! Linearly interpolates between self (t = 0.0) and other (t = 1.0).
+        self + (other - self) * t
+    }
 }
 
-impl std::ops::Add for Vector3 {
+impl core::ops::Add for Vector3 {
     type Output = Vector3;
     fn add(self, other: Self) -> Self::Output {
         Vector3 {
@@ -265,7 +646,7 @@ impl std::ops::Add for Vector3 {
     }
 }
 
-impl std::ops::Sub for Vector3 {
+impl core::ops::Sub for Vector3 {
     type Output = Self;
     fn sub(self, other: Self) -> Self::Output {
         Vector3 {
@@ -276,58 +657,93 @@ impl std::ops::Sub for Vector3 {
     }
 }
 
-impl std::convert::Into<(f32, f32, f32)> for Vector3 {
-    fn into(self) -> (f32, f32, f32) {
-        (self.x, self.y, self.z)
+impl core::convert::From<Vector3> for (Scalar, Scalar, Scalar) {
+    fn from(vector: Vector3) -> Self {
+        (vector.x, vector.y, vector.z)
     }
 }
 
-impl std::ops::AddAssign for Vector3 {
+impl core::ops::AddAssign for Vector3 {
     fn add_assign(&mut self, rhs: Self) {
         *self = vec3!(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z);
     }
 }
 
-impl std::ops::SubAssign for Vector3 {
+impl core::ops::SubAssign for Vector3 {
     fn sub_assign(&mut self, rhs: Self) {
         *self = *self - rhs;
     }
 }
 
-impl std::ops::Mul<f32> for Vector3 {
+impl core::ops::Mul<Scalar> for Vector3 {
     type Output = Vector3;
-    fn mul(self, rhs: f32) -> Self::Output {
+    fn mul(self, rhs: Scalar) -> Self::Output {
         vec3!(self.x * rhs, self.y * rhs, self.z * rhs,)
     }
 }
 
-impl std::ops::Div<f32> for Vector3 {
+impl core::ops::Div<Scalar> for Vector3 {
     type Output = Vector3;
-    fn div(self, rhs: f32) -> Self::Output {
+    fn div(self, rhs: Scalar) -> Self::Output {
         vec3!(self.x / rhs, self.y / rhs, self.z / rhs,)
     }
 }
 
-impl std::ops::MulAssign<f32> for Vector3 {
-    fn mul_assign(&mut self, rhs: f32) {
+impl core::ops::MulAssign<Scalar> for Vector3 {
+    fn mul_assign(&mut self, rhs: Scalar) {
+        *self = *self * rhs;
+    }
+}
+
+impl core::ops::DivAssign<Scalar> for Vector3 {
+    fn div_assign(&mut self, rhs: Scalar) {
+        *self = *self / rhs;
+    }
+}
+
+impl core::ops::Mul for Vector3 {
+    type Output = Vector3;
+    /// Component-wise multiplication
+    fn mul(self, rhs: Self) -> Self::Output {
+        vec3!(self.x * rhs.x, self.y * rhs.y, self.z * rhs.z)
+    }
+}
+
+impl core::ops::Div for Vector3 {
+    type Output = Vector3;
+    /// Component-wise division
+    fn div(self, rhs: Self) -> Self::Output {
+        vec3!(self.x / rhs.x, self.y / rhs.y, self.z / rhs.z)
+    }
+}
+
+impl core::ops::MulAssign for Vector3 {
+    fn mul_assign(&mut self, rhs: Self) {
         *self = *self * rhs;
     }
 }
 
-impl std::ops::DivAssign<f32> for Vector3 {
-    fn div_assign(&mut self, rhs: f32) {
+impl core::ops::DivAssign for Vector3 {
+    fn div_assign(&mut self, rhs: Self) {
         *self = *self / rhs;
     }
 }
 
-impl std::ops::Neg for Vector3 {
+impl core::ops::Mul<Vector3> for Scalar {
+    type Output = Vector3;
+    fn mul(self, rhs: Vector3) -> Self::Output {
+        rhs * self
+    }
+}
+
+impl core::ops::Neg for Vector3 {
     type Output = Self;
     fn neg(self) -> Self::Output {
         vec3!(-self.x, -self.y, -self.z)
     }
 }
 
-impl std::ops::Add<Vector2> for Vector3 {
+impl core::ops::Add<Vector2> for Vector3 {
     type Output = Vector3;
 
     fn add(self, rhs: Vector2) -> Self::Output {
@@ -335,20 +751,20 @@ impl std::ops::Add<Vector2> for Vector3 {
     }
 }
 
-impl std::ops::Sub<Vector2> for Vector3 {
+impl core::ops::Sub<Vector2> for Vector3 {
     type Output = Vector3;
     fn sub(self, rhs: Vector2) -> Self::Output {
         vec3!(self.x - rhs.x, self.y - rhs.y, self.z)
     }
 }
 
-impl std::ops::AddAssign<Vector2> for Vector3 {
+impl core::ops::AddAssign<Vector2> for Vector3 {
     fn add_assign(&mut self, rhs: Vector2) {
         *self = *self + rhs;
     }
 }
 
-impl std::ops::SubAssign<Vector2> for Vector3 {
+impl core::ops::SubAssign<Vector2> for Vector3 {
     fn sub_assign(&mut self, rhs: Vector2) {
         *self = *self - rhs;
     }
@@ -357,12 +773,12 @@ impl std::ops::SubAssign<Vector2> for Vector3 {
 /// A struct used for storing 2d points, rotation vectors, etc. It is easiest to create using vec2!(x, y)
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub struct Vector2 {
-    pub x: f32,
-    pub y: f32,
+    pub x: Scalar,
+    pub y: Scalar,
 }
 
 impl Vector2 {
-    pub fn new(x: f32, y: f32) -> Self {
+    pub fn new(x: Scalar, y: Scalar) -> Self {
         Self { x, y }
     }
     pub fn to_polar(self) -> Self {
@@ -375,25 +791,46 @@ impl Vector2 {
     pub fn to_cartesian(self) -> Self {
         vec2!(self.x * self.y.cos(), self.x * self.y.sin())
     }
-    pub fn rotate(self, delta_theta: f32) -> Self {
+    pub fn rotate(self, delta_theta: Scalar) -> Self {
         let mut polar = self.to_polar();
         polar.y += delta_theta;
         polar.to_cartesian()
     }
-    pub fn len(self) -> f32 {
+    pub fn len(self) -> Scalar {
         (self.x * self.x + self.y * self.y).sqrt()
     }
+    /// Same as len()
+    pub fn length(self) -> Scalar {
+        self.len()
+    }
+    pub fn length_squared(self) -> Scalar {
+        self.dot(self)
+    }
     pub fn normalize(self) -> Self {
         let len = self.len();
         if len == 1.0 || len == 0.0 {
-            return self;
+            self
         } else {
             vec2!(self.x / len, self.y / len)
         }
     }
+    pub fn dot(self, other: Self) -> Scalar {
+        self.x * other.x + self.y * other.y
+    }
+    pub fn cross(self, other: Self) -> Scalar {
+        //! The z component of the cross product of the two vectors (treating them as 3d vectors with z = 0). Positive when other is counterclockwise of self.
+        self.x * other.y - self.y * other.x
+    }
+    pub fn distance(self, other: Self) -> Scalar {
+        (other - self).len()
+    }
+    pub fn lerp(self, other: Self, t: Scalar) -> Self {
+        //! Linearly interpolates between self (t = 0.0) and other (t = 1.0).
+        self + (other - self) * t
+    }
 }
 
-impl std::ops::Add for Vector2 {
+impl core::ops::Add for Vector2 {
     type Output = Self;
     fn add(self, other: Self) -> Self::Output {
         Self {
@@ -403,7 +840,7 @@ impl std::ops::Add for Vector2 {
     }
 }
 
-impl std::ops::Sub for Vector2 {
+impl core::ops::Sub for Vector2 {
     type Output = Self;
     fn sub(self, other: Self) -> Self::Output {
         Self {
@@ -413,66 +850,274 @@ impl std::ops::Sub for Vector2 {
     }
 }
 
-impl std::convert::Into<(f32, f32)> for Vector2 {
-    fn into(self) -> (f32, f32) {
-        (self.x, self.y)
+impl core::convert::From<Vector2> for (Scalar, Scalar) {
+    fn from(vector: Vector2) -> Self {
+        (vector.x, vector.y)
     }
 }
 
-impl std::ops::AddAssign for Vector2 {
+impl core::ops::AddAssign for Vector2 {
     fn add_assign(&mut self, rhs: Self) {
-        *self = *self - rhs;
+        *self = *self + rhs;
     }
 }
 
-impl std::ops::SubAssign for Vector2 {
+impl core::ops::SubAssign for Vector2 {
     fn sub_assign(&mut self, rhs: Self) {
         *self = *self - rhs;
     }
 }
 
-impl std::ops::Mul<f32> for Vector2 {
+impl core::ops::Mul<Scalar> for Vector2 {
     type Output = Vector2;
-    fn mul(self, rhs: f32) -> Self::Output {
+    fn mul(self, rhs: Scalar) -> Self::Output {
         vec2!(self.x * rhs, self.y * rhs,)
     }
 }
 
-impl std::ops::Div<f32> for Vector2 {
+impl core::ops::Div<Scalar> for Vector2 {
     type Output = Vector2;
-    fn div(self, rhs: f32) -> Self::Output {
+    fn div(self, rhs: Scalar) -> Self::Output {
         vec2!(self.x / rhs, self.y / rhs,)
     }
 }
 
-impl std::ops::MulAssign<f32> for Vector2 {
-    fn mul_assign(&mut self, rhs: f32) {
+impl core::ops::MulAssign<Scalar> for Vector2 {
+    fn mul_assign(&mut self, rhs: Scalar) {
         *self = *self * rhs;
     }
 }
 
-impl std::ops::DivAssign<f32> for Vector2 {
-    fn div_assign(&mut self, rhs: f32) {
+impl core::ops::DivAssign<Scalar> for Vector2 {
+    fn div_assign(&mut self, rhs: Scalar) {
         *self = *self / rhs;
     }
 }
 
-impl std::ops::Neg for Vector2 {
+impl core::ops::Mul for Vector2 {
+    type Output = Vector2;
+    /// Component-wise multiplication
+    fn mul(self, rhs: Self) -> Self::Output {
+        vec2!(self.x * rhs.x, self.y * rhs.y)
+    }
+}
+
+impl core::ops::Div for Vector2 {
+    type Output = Vector2;
+    /// Component-wise division
+    fn div(self, rhs: Self) -> Self::Output {
+        vec2!(self.x / rhs.x, self.y / rhs.y)
+    }
+}
+
+impl core::ops::MulAssign for Vector2 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl core::ops::DivAssign for Vector2 {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl core::ops::Mul<Vector2> for Scalar {
+    type Output = Vector2;
+    fn mul(self, rhs: Vector2) -> Self::Output {
+        rhs * self
+    }
+}
+
+impl core::ops::Neg for Vector2 {
     type Output = Self;
     fn neg(self) -> Self::Output {
         vec2!(-self.x, -self.y,)
     }
 }
 
-impl std::ops::Sub<Vector3> for Vector2 {
+impl core::ops::Sub<Vector3> for Vector2 {
     type Output = Self;
     fn sub(self, rhs: Vector3) -> Self::Output {
         vec2!(self.x - rhs.x, self.y - rhs.y)
     }
 }
 
-impl std::ops::SubAssign<Vector3> for Vector2 {
+impl core::ops::SubAssign<Vector3> for Vector2 {
     fn sub_assign(&mut self, rhs: Vector3) {
         *self = *self - rhs;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vector3_math() {
+        let a = vec3!(1.0, 0.0, 0.0);
+        let b = vec3!(0.0, 1.0, 0.0);
+        assert_eq!(a.cross(b), vec3!(0.0, 0.0, 1.0));
+        assert_eq!(a.dot(b), 0.0);
+        assert_eq!(a.distance(b), Scalar::sqrt(2.0));
+        assert_eq!(a.lerp(b, 0.5), vec3!(0.5, 0.5, 0.0));
+        assert_eq!(
+            vec3!(1.0, 2.0, 3.0) * vec3!(2.0, 2.0, 2.0),
+            vec3!(2.0, 4.0, 6.0)
+        );
+        assert_eq!(2.0 * a, vec3!(2.0, 0.0, 0.0));
+        assert_eq!(vec3!(3.0, 4.0, 0.0).length(), 5.0);
+    }
+
+    #[test]
+    fn vector2_math() {
+        let a = vec2!(1.0, 0.0);
+        let b = vec2!(0.0, 1.0);
+        assert_eq!(a.cross(b), 1.0);
+        assert_eq!(a.dot(b), 0.0);
+        assert_eq!(a.lerp(b, 0.25), vec2!(0.75, 0.25));
+        let mut c = a;
+        c += b;
+        assert_eq!(c, vec2!(1.0, 1.0));
+        assert_eq!(c / vec2!(2.0, 4.0), vec2!(0.5, 0.25));
+    }
+
+    #[test]
+    fn projecting_points() {
+        let camera = Camera {
+            position: vec3!(1.0, 2.0, -5.0),
+            rotation: vec3!(0.3, -0.5, 0.1),
+            fov: vec2!(1.2, 0.8),
+        };
+        let point = vec3!(-1.5, 2.5, 3.0);
+        let cell = camera.project(point, (80, 40)).unwrap();
+        let depth = camera.view_matrix().transform_point(point).z;
+        let back = camera.unproject(cell, depth, (80, 40)).unwrap();
+        assert!(back.distance(point) < 0.001);
+        //The same place lines are drawn
+        let uv = camera.map_point_uv(point);
+        assert_eq!(cell, vec2!(uv.x * 80.0, uv.y * 40.0));
+        //Behind the camera
+        assert_eq!(
+            camera.project(camera.position - camera.transform().forward(), (80, 40)),
+            None
+        );
+        assert_eq!(camera.unproject(vec2!(-1000.0, 0.0), 1.0, (80, 40)), None);
+    }
+
+    #[test]
+    fn scratch_buffers_are_reused() {
+        let mut renderer = Renderer::new(
+            vec![crate::create_cube(), crate::create_cube()],
+            Camera {
+                position: vec3!(0.0, 0.0, -10.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(0.7, 0.7),
+            },
+        );
+        renderer.reserve(64, 64);
+        let capacity = |renderer: &Renderer| lock(&renderer.scratch.0).lines.capacity();
+        let before = capacity(&renderer);
+        assert!(before >= 64);
+        let mut buf = CharBuffer::new(30, 30);
+        for i in 0..3 {
+            renderer.meshs[0].rotation.y = i as Scalar;
+            renderer.draw(&mut buf);
+        }
+        assert_eq!(capacity(&renderer), before);
+    }
+
+    #[test]
+    fn non_finite_projections() {
+        let mut renderer = Renderer::new(
+            vec![crate::create_cube()],
+            Camera {
+                position: vec3!(0.0, 0.0, -10.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(0.0, 0.0),
+            },
+        );
+        //Nothing can be projected with no fov, but drawing still finishes without drawing garbage
+        let mut buf = CharBuffer::new(10, 10);
+        renderer.draw(&mut buf);
+        assert!(renderer.project_lines((10, 10)).is_empty());
+        assert!(buf.to_string().trim().is_empty());
+        buf.draw_line(Line {
+            char: '#',
+            points: (vec2!(0.0, 0.0), vec2!(Scalar::INFINITY, 5.0)),
+        });
+        assert!(buf.to_string().trim().is_empty());
+        //The camera sitting on a vertex, or a mesh scaled to nothing, is fine
+        renderer.camera.fov = vec2!(1.0, 1.0);
+        renderer.camera.position = vec3!(1.0, 1.0, 1.0);
+        renderer.meshs.push(crate::create_cube());
+        renderer.meshs[1].scale = vec3!(0.0, 0.0, 0.0);
+        renderer.draw(&mut buf);
+        assert!(renderer.project_lines((10, 10)).iter().all(Line::is_finite));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "non-finite vertex or transform in edge")]
+    fn non_finite_meshes_are_reported() {
+        let mut cube = crate::create_cube();
+        cube.insert_vertex(0, vec3!(Scalar::NAN, 0.0, 0.0));
+        let renderer = Renderer::new(
+            vec![cube],
+            Camera {
+                position: vec3!(0.0, 0.0, -10.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(1.0, 1.0),
+            },
+        );
+        renderer.draw(&mut CharBuffer::new(10, 10));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "has edge (0, 99) to a vertex that doesn't exist")]
+    fn missing_vertices_are_reported() {
+        let mut cube = crate::create_cube();
+        cube.add_edge((0, 99));
+        let renderer = Renderer::new(
+            vec![cube],
+            Camera {
+                position: vec3!(0.0, 0.0, -10.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(1.0, 1.0),
+            },
+        );
+        renderer.draw(&mut CharBuffer::new(10, 10));
+    }
+
+    #[test]
+    fn clones_share_geometry() {
+        let renderer = Renderer::new(
+            vec![crate::create_cube()],
+            Camera {
+                position: vec3!(0.0, 0.0, -10.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(0.7, 0.7),
+            },
+        );
+        //A second view of the same scene from another camera
+        let mut side = renderer.clone();
+        side.camera.position = vec3!(-10.0, 0.0, 0.0);
+        side.camera.rotation.y = 1.5;
+        side.meshs[0].char = '#';
+        assert!(side.meshs[0].shares_geometry(&renderer.meshs[0]));
+        let mut buf = CharBuffer::new(30, 30);
+        side.draw(&mut buf);
+        assert!(buf.to_string().contains('#'));
+        //Changing one copy's geometry leaves the other alone
+        side.meshs[0].add_edge((0, 6));
+        assert!(!side.meshs[0].shares_geometry(&renderer.meshs[0]));
+        assert_eq!(
+            side.meshs[0].get_edges().len(),
+            renderer.meshs[0].get_edges().len() + 1
+        );
+        let mut vertex_copy = renderer.meshs[0].clone();
+        vertex_copy.recenter();
+        assert!(!vertex_copy.shares_geometry(&renderer.meshs[0]));
+    }
+}
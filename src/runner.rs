@@ -1,5 +1,12 @@
 use super::char_buffer::CharBuffer;
-use std::time::Instant;
+use super::post::PostEffect;
+use super::win_console;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 ///The enum returned by the process fn of a logic class. If End is returned, the runner will cease, otherwise it will continue.
 pub enum ProcessReturn {
@@ -11,59 +18,310 @@ pub enum ProcessReturn {
 pub trait Logic {
     ///This method runs every frame, is passed the the char buffer (which is maintained from frame to frame, so remember to clear it), delta (the time in secods since the last frame), and returns a ProcessReturn. After being running process every single frame, the runner will print the buffer to the screen. If ProcessReturn::Continue is returned the runner will then continue to the next frame, otherwise it will stop.
     fn process(&mut self, screen_buf: &mut CharBuffer, delta: f32) -> ProcessReturn;
+    ///This method runs whenever an input event is given to the runner, before the next frame. By default it ignores every event.
+    fn input(&mut self, _event: &InputEvent) {}
+    ///A text summary of what's on screen for the runner's DescriptionMode, ex: from describe::Describer. By default there is none, and the frame is shown as normal.
+    fn describe(&mut self) -> Option<String> {
+        None
+    }
 }
 
-///The struct that runs every thing. When ran, every single frame it will run the process method from it's logic (which will mutate the CharBuffer), print the char buffer to the screen, and if process returned continue it will wait for the next frame.
+///An input event given to a runner from outside, ex: forwarded from a browser's keyboard events. Keys are named the same way as the browser's KeyboardEvent.key, ex: "a", "ArrowUp", or "Enter".
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputEvent {
+    KeyDown(String),
+    KeyUp(String),
+    ///A button on the gamepad with the given id went down. Buttons are named by where they are rather than what they're labeled, ex: "South" (A on an Xbox controller), "DPadUp", or "LeftTrigger".
+    GamepadDown(usize, String),
+    GamepadUp(usize, String),
+    ///An analog stick or trigger on the gamepad with the given id moved, ex: "LeftStickX" to -1.0 when pushed all the way left. Sticks go from -1.0 to 1.0 (with up being positive), and triggers from 0.0 to 1.0.
+    GamepadAxis(usize, String, f32),
+    ///The mouse moved over the cell at the given x and y.
+    MouseMove(usize, usize),
+    ///A mouse button went down, named the same way as crossterm's MouseButton, ex: "Left", "Right", or "Middle".
+    MouseDown(String),
+    MouseUp(String),
+    ///The space the frames are shown in changed to fit the given number of chars wide and tall. The runner resizes its buffer before passing this on.
+    Resize(usize, usize),
+    ///A note on a MIDI controller went down, with its channel (0 to 15), note number (0 to 127, with 60 being middle C), and velocity from 0.0 to 1.0.
+    MidiNoteDown(u8, u8, f32),
+    ///A note on a MIDI controller came up, with its channel and note number.
+    MidiNoteUp(u8, u8),
+    ///A knob, fader, or other control change on a MIDI controller, with its channel, control number, and value from 0.0 to 1.0.
+    MidiControl(u8, u8, f32),
+    ///An OSC message arrived, with its address (ex: "/fader/1") and its numeric arguments as floats. True and false become 1.0 and 0.0, and strings and blobs are left out.
+    Osc(String, Vec<f32>),
+}
+
+///A multiplier for the delta a runner passes to process and its effects, for slow motion (below 1.0), fast forward (above 1.0), or pausing (0.0). Anything animated by that delta, like an Animator, speeds up or slows down with it. Clones share the same value, so the logic can keep one to change it while the runner is running.
 /// # Example
 /// ```
+/// # use ascii_renderer::prelude::*;
+/// # use ascii_renderer::runner::TimeScale;
+/// struct SlowMo {
+///     time_scale: TimeScale,
+/// }
+/// impl Logic for SlowMo {
+///     fn process(&mut self, _screen_buf: &mut CharBuffer, _delta: f32) -> ProcessReturn {
+///         ProcessReturn::Continue
+///     }
+///     fn input(&mut self, event: &InputEvent) {
+///         if *event == InputEvent::KeyDown("s".to_string()) {
+///             self.time_scale.set(0.25);
+///         }
+///     }
+/// }
+/// let time_scale = TimeScale::new(1.0);
+/// let mut runner = Runner::new(10, 10, 30, SlowMo { time_scale: time_scale.clone() });
+/// runner.time_scale = time_scale;
+/// runner.input(InputEvent::KeyDown("s".to_string()));
+/// assert_eq!(runner.time_scale.get(), 0.25);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TimeScale(Arc<AtomicU32>);
+
+impl TimeScale {
+    pub fn new(scale: f32) -> Self {
+        Self(Arc::new(AtomicU32::new(scale.to_bits())))
+    }
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+    pub fn set(&self, scale: f32) {
+        //! Changes the scale for this and every clone of it, from the next frame on.
+        self.0.store(scale.to_bits(), Ordering::Relaxed);
+    }
+}
+
+impl Default for TimeScale {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+///A key that saves the frame on screen to a new file when pressed, see Runner::screenshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScreenshotHotkey {
+    ///The key, named like InputEvent::KeyDown's, ex: "F12". It isn't passed on to the logic.
+    pub key: String,
+    ///The folder screenshots are saved in, which must already exist.
+    pub directory: PathBuf,
+    ///Saves .png images instead of .txt files. Needs the `apng` feature.
+    pub png: bool,
+}
+
+impl ScreenshotHotkey {
+    pub fn new(key: impl Into<String>, directory: impl Into<PathBuf>) -> Self {
+        //! Saves .txt files.
+        Self {
+            key: key.into(),
+            directory: directory.into(),
+            png: false,
+        }
+    }
+}
+
+///When a runner describes frames as text for screen readers, see the describe module.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum DescriptionMode {
+    #[default]
+    Off,
+    ///Every frame is described instead of shown.
+    EveryFrame,
+    ///Frames are shown, and the next one is also described after the key (named like InputEvent::KeyDown's) is pressed. The key isn't passed on to the logic.
+    OnKey(String),
+}
+
+///The struct that runs every thing. When ran, every single frame it will run the process method from it's logic (which will mutate the CharBuffer), print the char buffer to the screen, and if process returned continue it will wait for the next frame.
+/// # Example
+/// ```no_run
+/// # use ascii_renderer::prelude::*;
 /// struct MyLogic;
 /// impl Logic for MyLogic {
 ///     fn process(&mut self, screen_buf: &mut CharBuffer, delta: f32) -> ProcessReturn {
-///         let fps_string = delta.to_string();
-///         let chars = fps_string.chars();
+///         let fps_string = (1.0 / delta).to_string();
+///         let mut chars = fps_string.chars();
 ///         screen_buf.set_char(1, 1, chars.next().unwrap()).unwrap();
-///         screen_buf.set_char(2, 1, chars.nest().unwrap()).unwrap();
+///         screen_buf.set_char(2, 1, chars.next().unwrap()).unwrap();
+///         ProcessReturn::Continue
 ///     }
 /// }
 /// let mut my_runner = Runner::new(3, 3, 25, MyLogic);   //Should print the real fps to the screen every frame.
 /// my_runner.run(true);
 /// ```
-pub struct Runner<L: Logic> {
+pub struct Runner<L: Logic, B: Backend = StdoutBackend> {
     pub buf: CharBuffer,
     logic: L,
     pub fps_cap: usize,
-    last_timpoint: Instant,
+    pub backend: B,
+    /// Applied in order to a copy of each frame before it's shown, see the post module.
+    pub effects: Vec<Box<dyn PostEffect>>,
+    /// What the time between frames is multiplied by before it's passed on as delta, 1.0 by default.
+    pub time_scale: TimeScale,
+    /// When set, pressing its key saves a screenshot.
+    pub screenshot: Option<ScreenshotHotkey>,
+    /// When the logic's descriptions are passed to the backend's announce.
+    pub description: DescriptionMode,
+    /// Set by the DescriptionMode::OnKey key.
+    describe_next: bool,
+    /// The copy of buf effects are applied to.
+    post_buf: CharBuffer,
+    last_timpoint: Option<Instant>,
+    /// Cloned by event_sender, with what's sent ending up in received.
+    events: Sender<InputEvent>,
+    received: Receiver<InputEvent>,
 }
 
 impl<L: Logic> Runner<L> {
     pub fn new(width: usize, height: usize, fps_cap: usize, logic: L) -> Self {
+        Self::with_backend(width, height, fps_cap, logic, StdoutBackend::default())
+    }
+    pub fn set_clear_mode(&mut self, clear_mode: ClearMode) {
+        //! How frames replace each other when running with clear_screen, see ClearMode.
+        self.backend.set_clear_mode(clear_mode);
+    }
+}
+
+impl<L: Logic, B: Backend> Runner<L, B> {
+    pub fn with_backend(width: usize, height: usize, fps_cap: usize, logic: L, backend: B) -> Self {
+        //! Same as new, but frames are shown with the given backend instead of printed to stdout.
+        let (events, received) = mpsc::channel();
         Self {
             buf: CharBuffer::new(width, height),
             logic,
             fps_cap,
-            last_timpoint: Instant::now(),
+            backend,
+            effects: vec![],
+            time_scale: TimeScale::default(),
+            screenshot: None,
+            description: DescriptionMode::Off,
+            describe_next: false,
+            post_buf: CharBuffer::new(0, 0),
+            last_timpoint: None,
+            events,
+            received,
         }
     }
     pub fn step(&mut self, clear_screen: bool) -> ProcessReturn {
         //! Steps the runner one frame. If the time since the last frame (or the creation of the runner) is less than 1.0 / fps_cap, it will instead wait until enough time has passed and then return continue, so typically it is better to run this twice to ensure a frame will actually render.
-        let time_elapsed = self.last_timpoint.elapsed().as_secs_f32();
+        let time_elapsed = self
+            .last_timpoint
+            .get_or_insert_with(Instant::now)
+            .elapsed()
+            .as_secs_f32();
         if time_elapsed < (1.0 / self.fps_cap as f32) {
             std::thread::sleep(std::time::Duration::from_secs_f32(
                 (1.0 / self.fps_cap as f32) - time_elapsed,
             ));
             return ProcessReturn::Continue;
         }
-        self.last_timpoint = Instant::now();
-
-        let ret = self.logic.process(&mut self.buf, time_elapsed);
-        if clear_screen {
-            clear_screen::clear();
+        self.last_timpoint = Some(Instant::now());
+        self.frame(time_elapsed, clear_screen)
+    }
+    pub fn frame(&mut self, delta: f32, clear_screen: bool) -> ProcessReturn {
+        //! Runs process and shows the frame right away with the given delta (scaled by time_scale), without checking the fps cap or the time. This is for when something else decides when frames happen, ex: a browser's requestAnimationFrame, since wasm32-unknown-unknown has no clock or sleeping to run the normal loop with.
+        let delta = delta * self.time_scale.get();
+        let events: Vec<InputEvent> = self.received.try_iter().collect();
+        for event in events {
+            self.input(event);
+        }
+        let ret = self.logic.process(&mut self.buf, delta);
+        let describe = match self.description {
+            DescriptionMode::Off => false,
+            DescriptionMode::EveryFrame => true,
+            DescriptionMode::OnKey(_) => std::mem::take(&mut self.describe_next),
+        };
+        if let Some(text) = describe.then(|| self.logic.describe()).flatten() {
+            let _ = self.backend.announce(&text);
+            if self.description == DescriptionMode::EveryFrame {
+                return ret;
+            }
+        }
+        if self.effects.is_empty() {
+            let _ = self.backend.present(&self.buf, clear_screen);
+        } else {
+            self.post_buf.clone_from(&self.buf);
+            for effect in self.effects.iter_mut() {
+                effect.apply(&mut self.post_buf, delta);
+            }
+            let _ = self.backend.present(&self.post_buf, clear_screen);
         }
-        println!("{buf}", buf = self.buf);
         ret
     }
+    pub fn input(&mut self, event: InputEvent) {
+        //! Passes an input event on to the logic, resizing the buffer first if it is a resize. The screenshot key takes a screenshot instead, and the DescriptionMode::OnKey key has the next frame described.
+        if let InputEvent::Resize(width, height) = event {
+            self.buf = CharBuffer::new(width, height);
+        }
+        if let (InputEvent::KeyDown(key), Some(hotkey)) = (&event, &self.screenshot) {
+            if *key == hotkey.key {
+                let _ = self.screenshot();
+                return;
+            }
+        }
+        if let (InputEvent::KeyDown(key), DescriptionMode::OnKey(hotkey)) =
+            (&event, &self.description)
+        {
+            if key == hotkey {
+                self.describe_next = true;
+                return;
+            }
+        }
+        self.logic.input(&event);
+    }
+    pub fn event_sender(&self) -> Sender<InputEvent> {
+        //! A sender for input events from other threads, ex: the MIDI and OSC listeners. Events sent through it are passed to input at the start of the next frame, in the order they were sent.
+        self.events.clone()
+    }
+    pub fn get_shown(&self) -> &CharBuffer {
+        //! The last frame shown, after the effects were applied to it.
+        if self.effects.is_empty() {
+            &self.buf
+        } else {
+            &self.post_buf
+        }
+    }
+    pub fn save_frame(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        //! Saves the last frame shown to a file, as a white on black image if path ends with .png (which needs the `apng` feature), or otherwise as text, the same as it was printed.
+        let path = path.as_ref();
+        let frame = self.get_shown();
+        if path
+            .extension()
+            .is_some_and(|x| x.eq_ignore_ascii_case("png"))
+        {
+            #[cfg(feature = "apng")]
+            {
+                let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+                return frame
+                    .rasterize()
+                    .write_png(file, [255, 255, 255], [0, 0, 0]);
+            }
+            #[cfg(not(feature = "apng"))]
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "saving frames as .png needs the apng feature",
+            ));
+        }
+        std::fs::write(path, frame.to_string())
+    }
+    pub fn screenshot(&self) -> std::io::Result<PathBuf> {
+        //! Saves the last frame shown with the screenshot hotkey's settings, named after the time since the Unix epoch in milliseconds (ex: "frame_1700000000000.txt"), returning where it went. Returns a NotFound error without a hotkey set.
+        let Some(hotkey) = &self.screenshot else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "no screenshot hotkey is set",
+            ));
+        };
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |x| x.as_millis());
+        let extension = if hotkey.png { "png" } else { "txt" };
+        let path = hotkey.directory.join(format!("frame_{millis}.{extension}"));
+        self.save_frame(&path)?;
+        Ok(path)
+    }
     pub fn run(&mut self, clear_screen: bool) {
-        //! Runs the runner. If clear_screen is true, it will attempt to clear the terminal every frame. Otherwise, it will just print out every frame normally. It requires &mut self as the char buffer and logic will likely mutate every frame.
+        //! Runs the runner. If clear_screen is true, it will attempt to clear the terminal every frame (how depends on the backend, see set_clear_mode for the default one). Otherwise, it will just print out every frame normally. It requires &mut self as the char buffer and logic will likely mutate every frame.
         loop {
             if let ProcessReturn::End = self.step(clear_screen) {
                 break;
@@ -71,3 +329,448 @@ impl<L: Logic> Runner<L> {
         }
     }
 }
+
+///Where a runner shows each frame once process has run.
+pub trait Backend {
+    ///Shows the buffer. If clear_screen is true, the previous frame should be replaced rather than scrolled past.
+    fn present(&mut self, buf: &CharBuffer, clear_screen: bool) -> std::io::Result<()>;
+    ///Passes on a text description of a frame, see DescriptionMode. By default it's dropped.
+    fn announce(&mut self, _text: &str) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+///How StdoutBackend replaces the last frame when the runner is told to clear the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ClearMode {
+    ///Clears the whole terminal before every frame. Works everywhere, but flickers on some terminals.
+    #[default]
+    FullClear,
+    ///Moves the cursor back to the top left and prints over the last frame, without clearing anything first.
+    CursorHome,
+    ///Switches to the terminal's alternate screen (leaving the scrollback alone, and restored when the backend is dropped) and only redraws the rows that changed since the last frame.
+    AltScreenDiff,
+    ///Never clears, so frames scroll past like printing them would.
+    None,
+}
+
+///The default backend, which prints every frame to stdout (plus a blank line, as println! would) with a single write, reusing the same String every frame.
+///When stdout isn't a terminal (ex: it is piped to a file or another program), the screen is never cleared, and frames are instead separated by a delimiter, so the output can be split back into frames.
+///On Windows consoles that can't handle escape sequences, ClearMode::CursorHome and AltScreenDiff move the cursor with console API calls instead, redrawing every row (see the win_console module).
+#[derive(Debug, Clone)]
+pub struct StdoutBackend {
+    output: String,
+    delimiter: String,
+    piped: bool,
+    virtual_terminal: bool,
+    clear_mode: ClearMode,
+    ///The last frame shown, for ClearMode::AltScreenDiff.
+    previous: Option<CharBuffer>,
+    alt_screen: bool,
+}
+
+impl Default for StdoutBackend {
+    fn default() -> Self {
+        Self {
+            output: String::new(),
+            delimiter: "\n".to_string(),
+            piped: !std::io::stdout().is_terminal(),
+            virtual_terminal: win_console::enable_virtual_terminal(),
+            clear_mode: ClearMode::default(),
+            previous: None,
+            alt_screen: false,
+        }
+    }
+}
+
+impl StdoutBackend {
+    pub fn delimiter(mut self, delimiter: impl Into<String>) -> Self {
+        //! What is written after every frame when piped, ex: "\x0c" (form feed) or "\n---\n". Defaults to a blank line, the same as when printing to a terminal.
+        self.delimiter = delimiter.into();
+        self
+    }
+    pub fn piped(mut self, piped: bool) -> Self {
+        //! Overrides whether stdout is treated as piped instead of a terminal, which is otherwise detected when the backend is made.
+        self.piped = piped;
+        self
+    }
+    pub fn is_piped(&self) -> bool {
+        self.piped
+    }
+    pub fn virtual_terminal(mut self, virtual_terminal: bool) -> Self {
+        //! Overrides whether escape sequences can be printed, which is otherwise detected (and turned on for Windows consoles) when the backend is made.
+        self.virtual_terminal = virtual_terminal;
+        self
+    }
+    pub fn is_virtual_terminal(&self) -> bool {
+        self.virtual_terminal
+    }
+    pub fn clear_mode(mut self, clear_mode: ClearMode) -> Self {
+        //! How the last frame is replaced, ClearMode::FullClear by default.
+        self.set_clear_mode(clear_mode);
+        self
+    }
+    pub fn set_clear_mode(&mut self, clear_mode: ClearMode) {
+        self.clear_mode = clear_mode;
+        self.previous = None;
+    }
+    pub fn get_clear_mode(&self) -> ClearMode {
+        self.clear_mode
+    }
+    fn write_diff(&mut self, buf: &CharBuffer) {
+        self.output.clear();
+        if !self.alt_screen {
+            self.output.push_str("\x1b[?1049h\x1b[?25l");
+            self.alt_screen = true;
+        }
+        //Everything is redrawn on the first frame, and whenever the size changes
+        let previous = self
+            .previous
+            .take()
+            .filter(|x| x.dimensions == buf.dimensions);
+        if previous.is_none() {
+            self.output.push_str("\x1b[2J");
+        }
+        for (y, row) in buf.data.iter().enumerate() {
+            if previous.as_ref().is_some_and(|x| x.data[y] == *row) {
+                continue;
+            }
+            self.output.push_str(&format!("\x1b[{};1H", y + 1));
+            for &char in row.iter() {
+                self.output.push(char);
+                self.output.push(' ');
+            }
+        }
+        let mut previous = previous.unwrap_or_else(|| CharBuffer::new(0, 0));
+        previous.clone_from(buf);
+        self.previous = Some(previous);
+    }
+    fn write_frame(
+        &mut self,
+        writer: &mut impl Write,
+        buf: &CharBuffer,
+        clear_screen: bool,
+    ) -> std::io::Result<()> {
+        let mut clear_mode = if clear_screen {
+            self.clear_mode
+        } else {
+            ClearMode::None
+        };
+        if !self.piped
+            && !self.virtual_terminal
+            && matches!(clear_mode, ClearMode::CursorHome | ClearMode::AltScreenDiff)
+        {
+            //Legacy Windows consoles would print escape sequences as text
+            win_console::move_cursor_home();
+            clear_mode = ClearMode::None;
+        }
+        if self.alt_screen && (self.piped || clear_mode != ClearMode::AltScreenDiff) {
+            //Switched away from AltScreenDiff, so the terminal goes back to how it was
+            writer.write_all(b"\x1b[?25h\x1b[?1049l")?;
+            self.alt_screen = false;
+        }
+        if self.piped {
+            buf.write_to_string(&mut self.output);
+            self.output.push_str(&self.delimiter);
+        } else if clear_mode == ClearMode::AltScreenDiff {
+            self.write_diff(buf);
+        } else {
+            buf.write_to_string(&mut self.output);
+            match clear_mode {
+                #[cfg(not(target_arch = "wasm32"))]
+                ClearMode::FullClear => clear_screen::clear(),
+                #[cfg(target_arch = "wasm32")]
+                ClearMode::FullClear => self.output.insert_str(0, "\x1b[2J\x1b[H"),
+                ClearMode::CursorHome => self.output.insert_str(0, "\x1b[H"),
+                _ => (),
+            }
+            self.output.push('\n');
+        }
+        writer.write_all(self.output.as_bytes())?;
+        writer.flush()
+    }
+}
+
+impl Backend for StdoutBackend {
+    fn present(&mut self, buf: &CharBuffer, clear_screen: bool) -> std::io::Result<()> {
+        self.write_frame(&mut std::io::stdout().lock(), buf, clear_screen)
+    }
+    fn announce(&mut self, text: &str) -> std::io::Result<()> {
+        //Printed as plain lines that are never cleared, so screen readers can read them back
+        let mut stdout = std::io::stdout().lock();
+        writeln!(stdout, "{text}")?;
+        stdout.flush()
+    }
+}
+
+impl Drop for StdoutBackend {
+    fn drop(&mut self) {
+        if self.alt_screen {
+            let _ = std::io::stdout().write_all(b"\x1b[?25h\x1b[?1049l");
+        }
+    }
+}
+
+///A backend that passes every frame as a string to a callback instead of printing it, ex: to write it to xterm.js or a <pre> element in the browser. The string is in the same format as printing the buffer, and is only valid for the call.
+/// # Example
+/// ```ignore
+/// #[wasm_bindgen]
+/// pub struct Demo(Runner<MyLogic, CallbackBackend<Box<dyn FnMut(&str)>>>);
+///
+/// #[wasm_bindgen]
+/// impl Demo {
+///     #[wasm_bindgen(constructor)]
+///     pub fn new(on_frame: js_sys::Function) -> Demo {
+///         let backend = CallbackBackend::new(Box::new(move |frame: &str| {
+///             let _ = on_frame.call1(&JsValue::NULL, &JsValue::from_str(frame));
+///         }) as Box<dyn FnMut(&str)>);
+///         Demo(Runner::with_backend(40, 20, 60, MyLogic::default(), backend))
+///     }
+///     pub fn frame(&mut self, delta: f32) -> bool {  //Called from requestAnimationFrame
+///         matches!(self.0.frame(delta, true), ProcessReturn::Continue)
+///     }
+///     pub fn key_down(&mut self, key: String) {  //Called from a keydown listener
+///         self.0.input(InputEvent::KeyDown(key));
+///     }
+/// }
+/// ```
+pub struct CallbackBackend<F: FnMut(&str)> {
+    callback: F,
+    output: String,
+}
+
+impl<F: FnMut(&str)> CallbackBackend<F> {
+    pub fn new(callback: F) -> Self {
+        Self {
+            callback,
+            output: String::new(),
+        }
+    }
+}
+
+impl<F: FnMut(&str)> Backend for CallbackBackend<F> {
+    fn present(&mut self, buf: &CharBuffer, _clear_screen: bool) -> std::io::Result<()> {
+        buf.write_to_string(&mut self.output);
+        (self.callback)(&self.output);
+        Ok(())
+    }
+    fn announce(&mut self, text: &str) -> std::io::Result<()> {
+        (self.callback)(text);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Keys(Vec<String>);
+
+    impl Logic for Keys {
+        fn process(&mut self, screen_buf: &mut CharBuffer, _delta: f32) -> ProcessReturn {
+            if let Some(key) = self.0.last() {
+                screen_buf
+                    .set_char(0, 0, key.chars().next().unwrap())
+                    .unwrap();
+            }
+            ProcessReturn::Continue
+        }
+        fn input(&mut self, event: &InputEvent) {
+            if let InputEvent::KeyDown(key) = event {
+                self.0.push(key.clone());
+            }
+        }
+    }
+
+    #[test]
+    fn frames_go_to_callback() {
+        let mut frames = Vec::new();
+        {
+            let backend = CallbackBackend::new(|frame: &str| frames.push(frame.to_string()));
+            let mut runner = Runner::with_backend(2, 1, 60, Keys::default(), backend);
+            runner.frame(0.1, true);
+            runner.input(InputEvent::KeyDown("x".to_string()));
+            runner.input(InputEvent::Resize(3, 2));
+            assert_eq!(runner.buf.dimensions, (3, 2));
+            runner.frame(0.1, true);
+        }
+        assert_eq!(frames, vec!["    \n", "x     \n      \n"]);
+    }
+
+    #[test]
+    fn effects_change_shown_frames() {
+        let mut frames = Vec::new();
+        {
+            let backend = CallbackBackend::new(|frame: &str| frames.push(frame.to_string()));
+            let mut runner = Runner::with_backend(1, 2, 60, Keys::default(), backend);
+            runner.input(InputEvent::KeyDown("@".to_string()));
+            runner.effects.push(Box::new(crate::post::Scanlines::new()));
+            runner.frame(0.1, true);
+            runner.buf.set_char(0, 1, '@').unwrap();
+            runner.frame(0.1, true);
+            //The buffer process draws into isn't changed
+            assert_eq!(runner.buf.get_char(0, 1), Some('@'));
+        }
+        assert_eq!(frames, vec!["@ \n  \n", "@ \n+ \n"]);
+    }
+
+    #[test]
+    fn events_from_other_threads() {
+        let backend = CallbackBackend::new(|_: &str| ());
+        let mut runner = Runner::with_backend(1, 1, 60, Keys::default(), backend);
+        let sender = runner.event_sender();
+        std::thread::spawn(move || {
+            sender.send(InputEvent::KeyDown("a".to_string())).unwrap();
+            sender.send(InputEvent::KeyDown("b".to_string())).unwrap();
+        })
+        .join()
+        .unwrap();
+        assert!(runner.logic.0.is_empty());
+        runner.frame(0.1, true);
+        assert_eq!(runner.logic.0, vec!["a", "b"]);
+        assert_eq!(runner.buf.get_char(0, 0), Some('b'));
+    }
+
+    struct Described(usize);
+
+    impl Logic for Described {
+        fn process(&mut self, screen_buf: &mut CharBuffer, _delta: f32) -> ProcessReturn {
+            self.0 += 1;
+            screen_buf.fill('#');
+            ProcessReturn::Continue
+        }
+        fn describe(&mut self) -> Option<String> {
+            Some(format!("Frame {}.", self.0))
+        }
+    }
+
+    #[test]
+    fn describing_frames() {
+        let mut shown = Vec::new();
+        {
+            let backend = CallbackBackend::new(|frame: &str| shown.push(frame.to_string()));
+            let mut runner = Runner::with_backend(1, 1, 60, Described(0), backend);
+            runner.description = DescriptionMode::EveryFrame;
+            runner.frame(0.1, true);
+            runner.description = DescriptionMode::OnKey("?".to_string());
+            runner.frame(0.1, true);
+            runner.input(InputEvent::KeyDown("?".to_string()));
+            runner.frame(0.1, true);
+            runner.frame(0.1, true);
+            //Logic without descriptions is still shown
+            let backend = CallbackBackend::new(|frame: &str| shown.push(frame.to_string()));
+            let mut runner = Runner::with_backend(1, 1, 60, Clock(0.0), backend);
+            runner.description = DescriptionMode::EveryFrame;
+            runner.frame(0.1, true);
+        }
+        assert_eq!(
+            shown,
+            vec!["Frame 1.", "# \n", "Frame 3.", "# \n", "# \n", "  \n"]
+        );
+    }
+
+    struct Clock(f32);
+
+    impl Logic for Clock {
+        fn process(&mut self, _screen_buf: &mut CharBuffer, delta: f32) -> ProcessReturn {
+            self.0 += delta;
+            ProcessReturn::Continue
+        }
+    }
+
+    #[test]
+    fn time_scale_changes_delta() {
+        let backend = CallbackBackend::new(|_: &str| ());
+        let mut runner = Runner::with_backend(1, 1, 60, Clock(0.0), backend);
+        let handle = runner.time_scale.clone();
+        runner.frame(1.0, true);
+        handle.set(0.5);
+        runner.frame(1.0, true);
+        handle.set(0.0);
+        runner.frame(1.0, true);
+        runner.time_scale = TimeScale::new(3.0);
+        runner.frame(1.0, true);
+        assert_eq!(runner.logic.0, 4.5);
+    }
+
+    #[test]
+    fn screenshots() {
+        let directory = std::env::temp_dir().join(format!("screenshots_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let backend = CallbackBackend::new(|_: &str| ());
+        let mut runner = Runner::with_backend(2, 1, 60, Keys::default(), backend);
+        runner.input(InputEvent::KeyDown("#".to_string()));
+        runner.frame(0.1, true);
+        assert!(runner.screenshot().is_err());
+        runner.screenshot = Some(ScreenshotHotkey::new("F12", &directory));
+        let path = runner.screenshot().unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "#   \n");
+        //The hotkey isn't passed on to the logic
+        runner.input(InputEvent::KeyDown("F12".to_string()));
+        assert_eq!(runner.logic.0, vec!["#".to_string()]);
+        runner.save_frame(directory.join("frame.txt")).unwrap();
+        assert!(directory.join("frame.txt").exists());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn clear_modes() {
+        let mut out = Vec::new();
+        let mut buf = CharBuffer::new(2, 2);
+        let mut backend = StdoutBackend::default()
+            .piped(false)
+            .clear_mode(ClearMode::CursorHome);
+        backend.write_frame(&mut out, &buf, true).unwrap();
+        backend.write_frame(&mut out, &buf, false).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b[H    \n    \n\n    \n    \n\n"
+        );
+        //Only changed rows are redrawn
+        let mut out = Vec::new();
+        backend.set_clear_mode(ClearMode::AltScreenDiff);
+        backend.write_frame(&mut out, &buf, true).unwrap();
+        buf.set_char(1, 1, '#').unwrap();
+        backend.write_frame(&mut out, &buf, true).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b[?1049h\x1b[?25l\x1b[2J\x1b[1;1H    \x1b[2;1H    \x1b[2;1H  # "
+        );
+        //Switching away leaves the alternate screen and shows the cursor again
+        let mut out = Vec::new();
+        backend.set_clear_mode(ClearMode::CursorHome);
+        backend.write_frame(&mut out, &buf, true).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b[?25h\x1b[?1049l\x1b[H    \n  # \n\n"
+        );
+        assert!(!backend.alt_screen);
+    }
+
+    #[test]
+    fn legacy_consoles_get_no_escapes() {
+        let mut out = Vec::new();
+        let buf = CharBuffer::new(2, 1);
+        let mut backend = StdoutBackend::default()
+            .piped(false)
+            .virtual_terminal(false)
+            .clear_mode(ClearMode::AltScreenDiff);
+        backend.write_frame(&mut out, &buf, true).unwrap();
+        backend.set_clear_mode(ClearMode::CursorHome);
+        backend.write_frame(&mut out, &buf, true).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "    \n\n    \n\n");
+        assert!(!backend.alt_screen);
+    }
+
+    #[test]
+    fn piped_frames_are_delimited() {
+        let mut backend = StdoutBackend::default().piped(true).delimiter("--\n");
+        let mut out = Vec::new();
+        let mut buf = CharBuffer::new(2, 1);
+        backend.write_frame(&mut out, &buf, true).unwrap();
+        buf.set_char(1, 0, '#').unwrap();
+        backend.write_frame(&mut out, &buf, true).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "    \n--\n  # \n--\n");
+    }
+}
@@ -0,0 +1,528 @@
+//! Scenes written in [rhai](https://rhai.rs) scripts, behind the `rhai` feature, so per-frame logic can be changed without recompiling. A ScriptLogic runs a script's functions with `this` set to its Renderer: `setup()` once when it's made, `process(buffer, delta)` every frame, and `key_down(key)` and `key_up(key)` on key events, each only if the script has it. Returning false from process ends the runner. When the script was loaded from a file, it's reloaded whenever the file changes, keeping the scene as it is.
+//!
+//! Scripts can use:
+//! - `vec2(x, y)` and `vec3(x, y, z)`, with `x`, `y`, and `z`, `+`, `-`, `*` by a number, and `length()`.
+//! - `cube()` and `mesh()` to make meshes, with `position`, `rotation`, `scale`, and `char`, and `add_vertex(index, vec3)` and `add_edge(a, b)`.
+//! - `this.camera`, with `position`, `rotation`, `fov`, and `look_at(vec3)`.
+//! - `this[i]` for the renderer's meshes, `this.add(mesh)`, `this.len()`, `this.time`, and `this.draw(buffer)`.
+//! - `buffer.width` and `buffer.height`, `fill(char)`, `set_char(x, y, char)`, `get_char(x, y)`, and `write(x, y, text)`.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::scripting::ScriptLogic;
+//! let script = r#"
+//!     fn setup() {
+//!         this.add(cube());
+//!     }
+//!     fn process(buffer, delta) {
+//!         this[0].rotation.y += delta;
+//!         buffer.fill(' ');
+//!         this.draw(buffer);
+//!         buffer.write(0, 0, "spinning");
+//!     }
+//! "#;
+//! let renderer = Renderer::new(
+//!     vec![],
+//!     Camera {
+//!         position: vec3!(0.0, 0.0, -6.0),
+//!         rotation: vec3!(0.0, 0.0, 0.0),
+//!         fov: vec2!(1.0, 1.0),
+//!     },
+//! );
+//! let mut logic = ScriptLogic::new(script, renderer).unwrap();
+//! //Or ScriptLogic::load("scene.rhai", renderer) to reload it while running
+//! let mut buf = CharBuffer::new(20, 20);
+//! logic.process(&mut buf, 0.5);
+//! assert_eq!(logic.renderer.meshs[0].rotation.y, 0.5);
+//! ```
+
+use super::char_buffer::CharBuffer;
+use super::rendering::{Camera, Mesh, Renderer};
+use super::runner::{InputEvent, Logic, ProcessReturn};
+use super::{Scalar, Vector2, Vector3};
+use rhai::{CallFnOptions, Dynamic, Engine, EvalAltResult, ParseError, Scope, AST, FLOAT, INT};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::SystemTime;
+
+/// The error returned when a script can't be read, doesn't parse, or fails while running.
+#[derive(Debug)]
+pub enum ScriptError {
+    Io(std::io::Error),
+    Parse(ParseError),
+    Runtime(Box<EvalAltResult>),
+}
+
+impl std::fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScriptError::Io(err) => write!(f, "could not read script: {err}"),
+            ScriptError::Parse(err) => write!(f, "could not parse script: {err}"),
+            ScriptError::Runtime(err) => write!(f, "script failed: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ScriptError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ScriptError::Io(err) => Some(err),
+            ScriptError::Parse(err) => Some(err),
+            ScriptError::Runtime(err) => Some(err.as_ref()),
+        }
+    }
+}
+
+impl From<std::io::Error> for ScriptError {
+    fn from(err: std::io::Error) -> Self {
+        ScriptError::Io(err)
+    }
+}
+
+impl From<ParseError> for ScriptError {
+    fn from(err: ParseError) -> Self {
+        ScriptError::Parse(err)
+    }
+}
+
+impl From<Box<EvalAltResult>> for ScriptError {
+    fn from(err: Box<EvalAltResult>) -> Self {
+        ScriptError::Runtime(err)
+    }
+}
+
+impl From<String> for ScriptError {
+    fn from(message: String) -> Self {
+        ScriptError::Runtime(message.into())
+    }
+}
+
+/// The buffer passed to process. Scripts get arguments by value, so the buffer is shared rather than copied for changes to make it back out.
+#[derive(Debug, Clone)]
+struct ScriptBuffer(Rc<RefCell<CharBuffer>>);
+
+#[allow(clippy::unnecessary_cast)]
+fn scalar(x: FLOAT) -> Scalar {
+    x as Scalar
+}
+
+#[allow(clippy::unnecessary_cast)]
+fn float(x: Scalar) -> FLOAT {
+    x as FLOAT
+}
+
+fn index(i: INT) -> Result<usize, Box<EvalAltResult>> {
+    usize::try_from(i).map_err(|_| format!("{i} isn't a valid index").into())
+}
+
+pub fn engine() -> Engine {
+    //! An Engine with the crate's types and functions registered, see the module docs. ScriptLogic uses this, but it can also run scripts some other way.
+    let mut engine = Engine::new();
+    engine
+        .register_type_with_name::<Vector2>("Vector2")
+        .register_fn("vec2", |x: FLOAT, y: FLOAT| {
+            Vector2::new(scalar(x), scalar(y))
+        })
+        .register_get_set(
+            "x",
+            |v: &mut Vector2| float(v.x),
+            |v: &mut Vector2, x: FLOAT| v.x = scalar(x),
+        )
+        .register_get_set(
+            "y",
+            |v: &mut Vector2| float(v.y),
+            |v: &mut Vector2, y: FLOAT| v.y = scalar(y),
+        )
+        .register_fn("+", |a: Vector2, b: Vector2| a + b)
+        .register_fn("-", |a: Vector2, b: Vector2| a - b)
+        .register_fn("*", |a: Vector2, b: FLOAT| a * scalar(b))
+        .register_fn("length", |v: &mut Vector2| float(v.length()))
+        .register_fn("to_string", |v: &mut Vector2| format!("{v:?}"));
+    engine
+        .register_type_with_name::<Vector3>("Vector3")
+        .register_fn("vec3", |x: FLOAT, y: FLOAT, z: FLOAT| {
+            Vector3::new(scalar(x), scalar(y), scalar(z))
+        })
+        .register_get_set(
+            "x",
+            |v: &mut Vector3| float(v.x),
+            |v: &mut Vector3, x: FLOAT| v.x = scalar(x),
+        )
+        .register_get_set(
+            "y",
+            |v: &mut Vector3| float(v.y),
+            |v: &mut Vector3, y: FLOAT| v.y = scalar(y),
+        )
+        .register_get_set(
+            "z",
+            |v: &mut Vector3| float(v.z),
+            |v: &mut Vector3, z: FLOAT| v.z = scalar(z),
+        )
+        .register_fn("+", |a: Vector3, b: Vector3| a + b)
+        .register_fn("-", |a: Vector3, b: Vector3| a - b)
+        .register_fn("*", |a: Vector3, b: FLOAT| a * scalar(b))
+        .register_fn("length", |v: &mut Vector3| float(v.length()))
+        .register_fn("to_string", |v: &mut Vector3| format!("{v:?}"));
+    engine
+        .register_type_with_name::<Mesh>("Mesh")
+        .register_fn("cube", super::create_cube)
+        .register_fn("mesh", Mesh::default)
+        .register_get_set(
+            "position",
+            |m: &mut Mesh| m.position,
+            |m: &mut Mesh, v: Vector3| m.position = v,
+        )
+        .register_get_set(
+            "rotation",
+            |m: &mut Mesh| m.rotation,
+            |m: &mut Mesh, v: Vector3| m.rotation = v,
+        )
+        .register_get_set(
+            "scale",
+            |m: &mut Mesh| m.scale,
+            |m: &mut Mesh, v: Vector3| m.scale = v,
+        )
+        .register_get_set(
+            "char",
+            |m: &mut Mesh| m.char,
+            |m: &mut Mesh, c: char| m.char = c,
+        )
+        .register_fn(
+            "add_vertex",
+            |m: &mut Mesh, i: INT, v: Vector3| -> Result<(), Box<EvalAltResult>> {
+                m.insert_vertex(index(i)?, v);
+                Ok(())
+            },
+        )
+        .register_fn(
+            "add_edge",
+            |m: &mut Mesh, a: INT, b: INT| -> Result<(), Box<EvalAltResult>> {
+                m.add_edge((index(a)?, index(b)?));
+                Ok(())
+            },
+        );
+    engine
+        .register_type_with_name::<Camera>("Camera")
+        .register_get_set(
+            "position",
+            |c: &mut Camera| c.position,
+            |c: &mut Camera, v: Vector3| c.position = v,
+        )
+        .register_get_set(
+            "rotation",
+            |c: &mut Camera| c.rotation,
+            |c: &mut Camera, v: Vector3| c.rotation = v,
+        )
+        .register_get_set(
+            "fov",
+            |c: &mut Camera| c.fov,
+            |c: &mut Camera, v: Vector2| c.fov = v,
+        )
+        .register_fn("look_at", |c: &mut Camera, target: Vector3| {
+            c.look_at(target)
+        });
+    engine
+        .register_type_with_name::<Renderer>("Renderer")
+        .register_get_set(
+            "camera",
+            |r: &mut Renderer| r.camera.clone(),
+            |r: &mut Renderer, c: Camera| r.camera = c,
+        )
+        .register_get_set(
+            "time",
+            |r: &mut Renderer| r.time as FLOAT,
+            |r: &mut Renderer, t: FLOAT| r.time = t as f32,
+        )
+        .register_indexer_get_set(
+            |r: &mut Renderer, i: INT| -> Result<Mesh, Box<EvalAltResult>> {
+                let i = index(i)?;
+                r.meshs
+                    .get(i)
+                    .cloned()
+                    .ok_or_else(|| format!("no mesh {i}").into())
+            },
+            |r: &mut Renderer, i: INT, mesh: Mesh| -> Result<(), Box<EvalAltResult>> {
+                let i = index(i)?;
+                let slot = r.meshs.get_mut(i).ok_or_else(|| format!("no mesh {i}"))?;
+                *slot = mesh;
+                Ok(())
+            },
+        )
+        .register_fn("add", |r: &mut Renderer, mesh: Mesh| r.meshs.push(mesh))
+        .register_fn("len", |r: &mut Renderer| r.meshs.len() as INT)
+        .register_fn("draw", |r: &mut Renderer, buffer: ScriptBuffer| {
+            r.draw(&mut buffer.0.borrow_mut())
+        });
+    engine
+        .register_type_with_name::<ScriptBuffer>("CharBuffer")
+        .register_get("width", |b: &mut ScriptBuffer| {
+            b.0.borrow().dimensions.0 as INT
+        })
+        .register_get("height", |b: &mut ScriptBuffer| {
+            b.0.borrow().dimensions.1 as INT
+        })
+        .register_fn("fill", |b: &mut ScriptBuffer, c: char| {
+            b.0.borrow_mut().fill(c)
+        })
+        .register_fn(
+            "set_char",
+            |b: &mut ScriptBuffer, x: INT, y: INT, c: char| {
+                //Cells outside the buffer are ignored, the same as lines going off the edge
+                if let (Ok(x), Ok(y)) = (usize::try_from(x), usize::try_from(y)) {
+                    let _ = b.0.borrow_mut().set_char(x, y, c);
+                }
+            },
+        )
+        .register_fn("get_char", |b: &mut ScriptBuffer, x: INT, y: INT| {
+            let (Ok(x), Ok(y)) = (usize::try_from(x), usize::try_from(y)) else {
+                return Dynamic::UNIT;
+            };
+            b.0.borrow()
+                .get_char(x, y)
+                .map_or(Dynamic::UNIT, Dynamic::from)
+        })
+        .register_fn(
+            "write",
+            |b: &mut ScriptBuffer, x: INT, y: INT, text: &str| match (
+                usize::try_from(x),
+                usize::try_from(y),
+            ) {
+                (Ok(x), Ok(y)) => b.0.borrow_mut().write_str(x, y, text) as INT,
+                _ => 0,
+            },
+        );
+    engine
+}
+
+/// A Logic running a rhai script, see the module docs.
+pub struct ScriptLogic {
+    /// The scene, which the script sees as `this`.
+    pub renderer: Renderer,
+    /// The last error from reloading or running the script, which doesn't stop the runner so it can be fixed while running.
+    pub last_error: Option<ScriptError>,
+    engine: Engine,
+    ast: AST,
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+}
+
+impl ScriptLogic {
+    pub fn new(source: &str, renderer: Renderer) -> Result<Self, ScriptError> {
+        //! Compiles the script and runs its setup, if it has one.
+        let engine = engine();
+        let ast = engine.compile(source)?;
+        let mut logic = Self {
+            renderer,
+            last_error: None,
+            engine,
+            ast,
+            path: None,
+            modified: None,
+        };
+        let _ = logic.call("setup", ())?;
+        Ok(logic)
+    }
+    pub fn load(path: impl AsRef<Path>, renderer: Renderer) -> Result<Self, ScriptError> {
+        //! Same as new, but from a file, which is reloaded whenever it changes.
+        let path = path.as_ref();
+        let modified = std::fs::metadata(path)?.modified().ok();
+        let mut logic = Self::new(&std::fs::read_to_string(path)?, renderer)?;
+        logic.path = Some(path.to_path_buf());
+        logic.modified = modified;
+        Ok(logic)
+    }
+    pub fn reload(&mut self) -> Result<bool, ScriptError> {
+        //! Recompiles the script if its file changed since it was last read, returning whether it did. setup isn't run again, so the scene carries on with the new functions. If the new version doesn't compile, the old one keeps running.
+        let Some(path) = &self.path else {
+            return Ok(false);
+        };
+        let modified = std::fs::metadata(path)?.modified().ok();
+        if modified == self.modified {
+            return Ok(false);
+        }
+        //Only tried once per change, so a broken file doesn't report the same error every frame
+        self.modified = modified;
+        self.ast = self.engine.compile(std::fs::read_to_string(path)?)?;
+        Ok(true)
+    }
+    pub fn has_fn(&self, name: &str, params: usize) -> bool {
+        //! Whether the script has a function with the given name and number of parameters.
+        self.ast
+            .iter_functions()
+            .any(|x| x.name == name && x.params.len() == params)
+    }
+    pub fn call(&mut self, name: &str, args: impl rhai::FuncArgs) -> Result<Dynamic, ScriptError> {
+        //! Calls one of the script's functions with `this` set to the renderer, returning () if the script doesn't have it.
+        let mut args_vec = vec![];
+        args.parse(&mut args_vec);
+        if !self.has_fn(name, args_vec.len()) {
+            return Ok(Dynamic::UNIT);
+        }
+        //The renderer is moved in and out rather than cloned, so it keeps its caches
+        let placeholder = Renderer::new(vec![], self.renderer.camera.clone());
+        let mut this = Dynamic::from(std::mem::replace(&mut self.renderer, placeholder));
+        let options = CallFnOptions::new()
+            .eval_ast(false)
+            .bind_this_ptr(&mut this);
+        let result =
+            self.engine
+                .call_fn_with_options(options, &mut Scope::new(), &self.ast, name, args_vec);
+        match this.try_cast::<Renderer>() {
+            Some(renderer) => self.renderer = renderer,
+            None => {
+                return Err(
+                    format!("{name} replaced `this` with something other than a Renderer").into(),
+                )
+            }
+        }
+        Ok(result?)
+    }
+}
+
+impl Logic for ScriptLogic {
+    fn process(&mut self, screen_buf: &mut CharBuffer, delta: f32) -> ProcessReturn {
+        if let Err(err) = self.reload() {
+            self.last_error = Some(err);
+        }
+        let buffer = ScriptBuffer(Rc::new(RefCell::new(std::mem::replace(
+            screen_buf,
+            CharBuffer::new(0, 0),
+        ))));
+        let result = self.call("process", (buffer.clone(), delta as FLOAT));
+        *screen_buf = buffer.0.replace(CharBuffer::new(0, 0));
+        match result {
+            Ok(x) if x.as_bool() == Ok(false) => ProcessReturn::End,
+            Ok(_) => ProcessReturn::Continue,
+            Err(err) => {
+                self.last_error = Some(err);
+                ProcessReturn::Continue
+            }
+        }
+    }
+    fn input(&mut self, event: &InputEvent) {
+        let result = match event {
+            InputEvent::KeyDown(key) => self.call("key_down", (key.clone(),)),
+            InputEvent::KeyUp(key) => self.call("key_up", (key.clone(),)),
+            _ => return,
+        };
+        if let Err(err) = result {
+            self.last_error = Some(err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{vec2, vec3};
+
+    fn renderer() -> Renderer {
+        Renderer::new(
+            vec![],
+            Camera {
+                position: vec3!(0.0, 0.0, -6.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(1.0, 1.0),
+            },
+        )
+    }
+
+    #[test]
+    fn running_scripts() {
+        let script = r#"
+            fn setup() {
+                let m = cube();
+                m.char = '@';
+                this.add(m);
+                this.camera.position.z = -8.0;
+            }
+            fn process(buffer, delta) {
+                this[0].rotation.y += delta;
+                this.time += delta;
+                buffer.fill('.');
+                this.draw(buffer);
+                buffer.write(0, 0, "hi");
+                this.time < 1.0
+            }
+            fn key_down(key) {
+                this[0].position = vec3(1.0, 0.0, 0.0) * 2.0;
+            }
+        "#;
+        let mut logic = ScriptLogic::new(script, renderer()).unwrap();
+        assert_eq!(logic.renderer.meshs.len(), 1);
+        assert_eq!(logic.renderer.camera.position.z, -8.0);
+
+        let mut buf = CharBuffer::new(20, 10);
+        assert!(matches!(
+            logic.process(&mut buf, 0.5),
+            ProcessReturn::Continue
+        ));
+        assert_eq!(logic.renderer.meshs[0].rotation.y, 0.5);
+        //What the script drew is in the buffer
+        let mut expected = CharBuffer::new(20, 10);
+        expected.fill('.');
+        logic.renderer.draw(&mut expected);
+        expected.write_str(0, 0, "hi");
+        assert_eq!(buf, expected);
+        assert!(matches!(logic.process(&mut buf, 0.5), ProcessReturn::End));
+
+        logic.input(&InputEvent::KeyDown("a".to_string()));
+        assert_eq!(logic.renderer.meshs[0].position, vec3!(2.0, 0.0, 0.0));
+        //Events the script doesn't handle are ignored
+        logic.input(&InputEvent::KeyUp("a".to_string()));
+        assert!(logic.last_error.is_none());
+
+        //Errors are kept and the runner carries on
+        let mut logic = ScriptLogic::new(
+            "fn process(buffer, delta) { this[3].char = 'x'; }",
+            renderer(),
+        )
+        .unwrap();
+        assert!(matches!(
+            logic.process(&mut buf, 0.1),
+            ProcessReturn::Continue
+        ));
+        assert!(matches!(logic.last_error, Some(ScriptError::Runtime(_))));
+        assert!(matches!(
+            ScriptLogic::new("fn (", renderer()),
+            Err(ScriptError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn reloading_scripts() {
+        let path =
+            std::env::temp_dir().join(format!("ascii_renderer_script_{}.rhai", std::process::id()));
+        std::fs::write(
+            &path,
+            "fn setup() { this.add(cube()); } fn process(buffer, delta) { this[0].char = 'a'; }",
+        )
+        .unwrap();
+        let mut logic = ScriptLogic::load(&path, renderer()).unwrap();
+        let mut buf = CharBuffer::new(4, 4);
+        logic.process(&mut buf, 0.1);
+        assert_eq!(logic.renderer.meshs[0].char, 'a');
+        assert!(!logic.reload().unwrap());
+
+        //Making sure the modification time changes even on filesystems with coarse timestamps
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        std::fs::write(&path, "fn process(buffer, delta) { this[0].char = 'b'; }").unwrap();
+        file.set_modified(SystemTime::now() + std::time::Duration::from_secs(5))
+            .unwrap();
+        logic.process(&mut buf, 0.1);
+        //The scene carries on with the new process, without running setup again
+        assert_eq!(logic.renderer.meshs.len(), 1);
+        assert_eq!(logic.renderer.meshs[0].char, 'b');
+
+        //A broken version keeps the old one running
+        std::fs::write(&path, "fn process(").unwrap();
+        file.set_modified(SystemTime::now() + std::time::Duration::from_secs(10))
+            .unwrap();
+        logic.renderer.meshs[0].char = ' ';
+        logic.process(&mut buf, 0.1);
+        assert!(matches!(logic.last_error, Some(ScriptError::Parse(_))));
+        assert_eq!(logic.renderer.meshs[0].char, 'b');
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,149 @@
+//! Vertex shaders, for meshes that move on their own (ex: a flag waving, or a creature breathing) without rebuilding their geometry every frame. A mesh's shader is called with each of its vertices and Renderer::time whenever it's drawn, and the vertices are drawn wherever it puts them, before the mesh's position, rotation, and scale are applied.
+//!
+//! The shader only changes what's drawn, so everything else (ex: bounding_box and collisions) still sees the mesh's own vertices. Use Mesh::shaded to get the moved vertices as a mesh.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::plot::plot_surface;
+//! //A flat grid stood up on its side, with the pole along x = 0
+//! let mut flag = plot_surface(|_, _| 0.0, 0.0..=4.0, 9);
+//! flag.rotation = vec3!(1.57, 0.0, 0.0);
+//! //Ripples travel along the flag, growing the further they get from the pole
+//! flag.set_shader(|vertex, time| {
+//!     let p = vertex.position;
+//!     vec3!(p.x, p.y + (p.x * 2.0 - time as Scalar * 4.0).sin() * p.x * 0.1, p.z)
+//! });
+//! let mut renderer = Renderer::new(
+//!     vec![flag],
+//!     Camera {
+//!         position: vec3!(2.0, 1.0, -6.0),
+//!         rotation: vec3!(0.0, 0.0, 0.0),
+//!         fov: vec2!(1.0, 1.0),
+//!     },
+//! );
+//! let mut buf = CharBuffer::new(40, 20);
+//! //Every frame:
+//! renderer.time += 1.0 / 30.0;
+//! buf.fill(' ');
+//! renderer.draw(&mut buf);
+//! ```
+
+use super::rendering::{Mesh, Renderer};
+use super::Vector3;
+use alloc::borrow::Cow;
+use alloc::sync::Arc;
+
+/// A vertex passed to a VertexShader.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vertex {
+    /// The vertex's index in the mesh.
+    pub index: usize,
+    /// Where the vertex is in the mesh's local space.
+    pub position: Vector3,
+}
+
+/// A function from a vertex and the time in seconds to where the vertex should be drawn, in the mesh's local space. Shaders are shared between clones of a mesh.
+#[derive(Clone)]
+pub struct VertexShader(Arc<ShaderFn>);
+
+type ShaderFn = dyn Fn(&Vertex, f32) -> Vector3 + Send + Sync;
+
+impl VertexShader {
+    pub fn new(shader: impl Fn(&Vertex, f32) -> Vector3 + Send + Sync + 'static) -> Self {
+        Self(Arc::new(shader))
+    }
+    pub fn apply(&self, vertex: &Vertex, time: f32) -> Vector3 {
+        (self.0)(vertex, time)
+    }
+}
+
+impl core::fmt::Debug for VertexShader {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("VertexShader")
+    }
+}
+
+impl Mesh {
+    pub fn set_shader(&mut self, shader: impl Fn(&Vertex, f32) -> Vector3 + Send + Sync + 'static) {
+        //! Gives the mesh a shader, replacing any it had. Same as setting Mesh::shader to Some(VertexShader::new(shader)).
+        self.shader = Some(VertexShader::new(shader));
+    }
+    pub fn shaded(&self, time: f32) -> Mesh {
+        //! A copy of the mesh with its vertices moved by its shader at the given time, and no shader. Meshes without a shader are just cloned.
+        let mut shaded = self.clone();
+        if let Some(shader) = shaded.shader.take() {
+            for (&index, position) in shaded.get_verticies_mut().iter_mut() {
+                *position = shader.apply(
+                    &Vertex {
+                        index,
+                        position: *position,
+                    },
+                    time,
+                );
+            }
+        }
+        shaded
+    }
+}
+
+impl Renderer {
+    pub(crate) fn shade<'a>(&self, mesh: &'a Mesh) -> Cow<'a, Mesh> {
+        //! The mesh as it should be drawn this frame, only copying it if it has a shader.
+        match mesh.shader {
+            Some(_) => Cow::Owned(mesh.shaded(self.time)),
+            None => Cow::Borrowed(mesh),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::char_buffer::CharBuffer;
+    use crate::rendering::Camera;
+    use crate::{vec2, vec3, Scalar, Vector2};
+    use alloc::{vec, vec::Vec};
+
+    #[test]
+    fn shading_vertices() {
+        let mut mesh = crate::create_cube();
+        mesh.rotation = vec3!(0.3, 0.5, 0.0);
+        let mut breathing = mesh.clone();
+        breathing.set_shader(|vertex, time| vertex.position * (1.0 + time as Scalar));
+        let mut grown = mesh.clone();
+        for (_, position) in grown.get_verticies_mut().iter_mut() {
+            *position *= 1.5;
+        }
+        let camera = Camera {
+            position: vec3!(0.0, 0.0, -8.0),
+            rotation: vec3!(0.0, 0.0, 0.0),
+            fov: vec2!(1.0, 1.0),
+        };
+        let draw = |meshs: Vec<Mesh>, time: f32, depth: bool| {
+            let mut renderer = Renderer::new(meshs, camera.clone());
+            renderer.time = time;
+            let mut buf = CharBuffer::new(40, 40);
+            if depth {
+                let mut depth = crate::depth::DepthBuffer::new(40, 40);
+                renderer.draw_with_depth(&mut buf, &mut depth);
+            } else {
+                renderer.draw(&mut buf);
+            }
+            buf
+        };
+        //The shader runs before the model transform, every frame
+        for depth in [false, true] {
+            assert_eq!(
+                draw(vec![breathing.clone()], 0.5, depth),
+                draw(vec![grown.clone()], 0.5, depth)
+            );
+            assert_eq!(
+                draw(vec![breathing.clone()], 0.0, depth),
+                draw(vec![mesh.clone()], 0.0, depth)
+            );
+        }
+        assert_eq!(breathing.shaded(0.5).get_verticies(), grown.get_verticies());
+        assert!(breathing.shaded(0.5).shader.is_none());
+        assert_eq!(breathing.get_verticies(), mesh.get_verticies());
+    }
+}
@@ -0,0 +1,163 @@
+//! Planar shadows, a cheap way to make a scene look a lot more 3D. Every mesh is flattened onto a ground plane along the direction light travels, and drawn there with a dim char. Shadows are drawn first, so the meshes (including the ground itself) are drawn over them.
+//!
+//! Shadows fall on the whole plane, so they aren't blocked by other meshes and can reach past the edges of a finite floor.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::shadow::PlanarShadow;
+//! let renderer = Renderer::new(
+//!     vec![ascii_renderer::create_cube()],
+//!     Camera {
+//!         position: vec3!(0.0, -4.0, -8.0),
+//!         rotation: vec3!(-0.5, 0.0, 0.0),
+//!         fov: vec2!(1.0, 1.0),
+//!     },
+//! );
+//! //The ground is 2 below the cube (+y is down), lit from above and to the left
+//! let shadow = PlanarShadow::ground(2.0, vec3!(0.5, 1.0, 0.2));
+//! let mut buf = CharBuffer::new(60, 30);
+//! shadow.draw(&renderer, &mut buf);
+//! renderer.draw(&mut buf);
+//! ```
+
+use super::char_buffer::CharBuffer;
+use super::geometry::Plane;
+use super::rendering::Renderer;
+use super::{vec3, Mat4, Scalar, Vector3};
+
+/// Draws the shadows of a Renderer's meshes on a plane, see the module docs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlanarShadow {
+    /// What the shadows fall on.
+    pub plane: Plane,
+    /// The direction light travels in, from the light towards the plane. It doesn't need to be normalized.
+    pub light: Vector3,
+    /// What the shadows are drawn with.
+    pub char: char,
+}
+
+impl PlanarShadow {
+    pub fn new(plane: Plane, light: Vector3) -> Self {
+        //! Shadows drawn with '.'.
+        Self {
+            plane,
+            light,
+            char: '.',
+        }
+    }
+    pub fn ground(y: Scalar, light: Vector3) -> Self {
+        //! Shadows on flat ground at the given y.
+        Self::new(
+            Plane::from_point_normal(vec3!(0.0, y, 0.0), vec3!(0.0, -1.0, 0.0)),
+            light,
+        )
+    }
+    pub fn with_char(mut self, char: char) -> Self {
+        self.char = char;
+        self
+    }
+    pub fn matrix(&self) -> Option<Mat4> {
+        //! The matrix moving global points along the light onto the plane, or None if the light runs along the plane and never reaches it.
+        let (n, l) = (self.plane.normal, self.light);
+        let facing = n.dot(l);
+        if facing.abs() < 1e-6 {
+            return None;
+        }
+        //p - l * (n.p - distance) / n.l
+        let row = |l: Scalar, axis: usize| {
+            let n = [n.x, n.y, n.z];
+            let mut row = [0.0; 4];
+            for (i, cell) in row.iter_mut().take(3).enumerate() {
+                *cell = if i == axis { 1.0 } else { 0.0 } - l * n[i] / facing;
+            }
+            row[3] = l * self.plane.distance / facing;
+            row
+        };
+        Some(Mat4::new([
+            row(l.x, 0),
+            row(l.y, 1),
+            row(l.z, 2),
+            [0.0, 0.0, 0.0, 1.0],
+        ]))
+    }
+    pub fn draw(&self, renderer: &Renderer, buf: &mut CharBuffer) {
+        //! Draws the shadow of every mesh in renderer, with renderer's camera. Draw the renderer after this, so the meshes are on top.
+        let Some(matrix) = self.matrix() else {
+            return;
+        };
+        for mesh in renderer.meshs.iter() {
+            let mut shadow = mesh.clone();
+            shadow.char = self.char;
+            renderer.draw_mesh_with_matrix(&shadow, matrix * mesh.model_matrix(), buf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::Camera;
+    use crate::{vec2, Vector2};
+
+    fn close(a: Vector3, b: Vector3) -> bool {
+        (a - b).len() < 0.001
+    }
+
+    #[test]
+    fn casting_shadows() {
+        let straight = PlanarShadow::ground(3.0, vec3!(0.0, 2.0, 0.0));
+        let matrix = straight.matrix().unwrap();
+        assert!(close(
+            matrix.transform_point(vec3!(1.0, 0.0, 2.0)),
+            vec3!(1.0, 3.0, 2.0)
+        ));
+        let slanted = PlanarShadow::ground(3.0, vec3!(1.0, 1.0, 0.0));
+        assert!(close(
+            slanted
+                .matrix()
+                .unwrap()
+                .transform_point(vec3!(0.0, 0.0, 0.0)),
+            vec3!(3.0, 3.0, 0.0)
+        ));
+        assert_eq!(
+            PlanarShadow::ground(3.0, vec3!(1.0, 0.0, 0.0)).matrix(),
+            None
+        );
+
+        //Seen from the side, the shadow is a line along the ground under the cube
+        let mut renderer = Renderer::new(
+            vec![crate::create_cube()],
+            Camera {
+                position: vec3!(0.0, 0.0, -10.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(1.0, 1.0),
+            },
+        );
+        renderer.meshs[0].char = '#';
+        let mut buf = CharBuffer::new(40, 40);
+        straight.draw(&renderer, &mut buf);
+        renderer.draw(&mut buf);
+        let under = renderer
+            .camera
+            .project(vec3!(0.0, 3.0, -1.0), buf.dimensions)
+            .unwrap();
+        let (x, y) = (under.x.round() as usize, under.y.round() as usize);
+        assert_eq!(buf.get_char(x, y), Some('.'));
+        let middle = renderer
+            .camera
+            .project(vec3!(0.0, 0.0, -1.0), buf.dimensions)
+            .unwrap();
+        let row = middle.y.round() as usize;
+        assert!(buf.get_char(x, row) != Some('.'));
+        assert!(!buf
+            .to_string()
+            .lines()
+            .take(row + 1)
+            .any(|x| x.contains('.')));
+
+        //Light along the ground never reaches it
+        let mut buf = CharBuffer::new(40, 40);
+        PlanarShadow::ground(3.0, vec3!(1.0, 0.0, 0.0)).draw(&renderer, &mut buf);
+        assert!(!buf.to_string().contains('.'));
+    }
+}
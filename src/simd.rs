@@ -0,0 +1,99 @@
+//! Projects vertices four at a time with SIMD, behind the `simd` feature. When it is enabled, Renderer::draw uses it for every mesh whose projected vertices aren't already cached. Results can differ from the normal path in the last few bits, which very rarely moves a line end by one char.
+
+use super::cache::ProjectionKey;
+use super::HashMap;
+use super::{Scalar, Vector2, Vector3};
+#[cfg(not(feature = "f64"))]
+use wide::f32x4 as ScalarX4;
+#[cfg(feature = "f64")]
+use wide::f64x4 as ScalarX4;
+
+/// Projects every vertex to buffer coordinates, adding them to points.
+pub(crate) fn project_into(
+    points: &mut HashMap<usize, Vector2>,
+    vertices: &HashMap<usize, Vector3>,
+    key: &ProjectionKey,
+) {
+    points.reserve(vertices.len());
+    let mut vertices = vertices.iter();
+    loop {
+        let mut indices = [0; 4];
+        let (mut xs, mut ys, mut zs) = ([0.0; 4], [0.0; 4], [1.0; 4]);
+        let mut count = 0;
+        for (&index, vertex) in vertices.by_ref().take(4) {
+            indices[count] = index;
+            xs[count] = vertex.x;
+            ys[count] = vertex.y;
+            zs[count] = vertex.z;
+            count += 1;
+        }
+        if count == 0 {
+            break;
+        }
+        let (us, vs) = project4(key, xs, ys, zs);
+        for i in 0..count {
+            points.insert(indices[i], Vector2::new(us[i], vs[i]));
+        }
+    }
+}
+
+fn project4(
+    key: &ProjectionKey,
+    xs: [Scalar; 4],
+    ys: [Scalar; 4],
+    zs: [Scalar; 4],
+) -> ([Scalar; 4], [Scalar; 4]) {
+    let m = &key.model_view.data;
+    let (x, y, z) = (ScalarX4::from(xs), ScalarX4::from(ys), ScalarX4::from(zs));
+    let row = |r: [Scalar; 4]| {
+        ScalarX4::splat(r[0]) * x
+            + ScalarX4::splat(r[1]) * y
+            + ScalarX4::splat(r[2]) * z
+            + ScalarX4::splat(r[3])
+    };
+    let w = row(m[3]);
+    let (view_x, view_y, view_z) = (row(m[0]) / w, row(m[1]) / w, row(m[2]) / w);
+    let half = ScalarX4::splat(0.5);
+    let u = (view_x.atan2(view_z) / ScalarX4::splat(key.fov.x) + half)
+        * ScalarX4::splat(key.dimensions.0 as Scalar);
+    let v = (view_y.atan2(view_z) / ScalarX4::splat(key.fov.y) + half)
+        * ScalarX4::splat(key.dimensions.1 as Scalar);
+    (u.to_array(), v.to_array())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::noise::Rng;
+    use crate::prelude::*;
+    use alloc::vec;
+
+    #[test]
+    fn matches_scalar_projection() {
+        let renderer = Renderer::new(
+            vec![],
+            Camera {
+                position: vec3!(1.0, -2.0, -10.0),
+                rotation: vec3!(0.2, -0.4, 0.1),
+                fov: vec2!(1.2, 0.8),
+            },
+        );
+        let model = Transform::new(
+            vec3!(0.5, 0.0, 2.0),
+            vec3!(1.0, 0.3, -0.2),
+            vec3!(1.0, 2.0, 1.0),
+        )
+        .matrix();
+        let key = renderer.projection_key(model, (120, 60));
+        let mut rng = Rng::new(11);
+        let vertices: HashMap<usize, Vector3> = (0..23).map(|i| (i, rng.vector3(5.0))).collect();
+
+        let mut points = HashMap::new();
+        project_into(&mut points, &vertices, &key);
+        assert_eq!(points.len(), vertices.len());
+        for (index, vertex) in vertices.iter() {
+            let expected = renderer.project_to_buffer(&key.model_view, *vertex, key.dimensions);
+            assert!((points[index] - expected).len() < 0.01);
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use super::rendering::Mesh;
+use super::{Scalar, Vector3};
+use std::collections::HashMap;
+
+/// A single joint of a Skeleton. The pivot is where the joint sits in the mesh's bind pose, and the rotation and translation are the bone's current pose relative to its parent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bone {
+    pub name: String,
+    pub parent: Option<usize>,
+    pub pivot: Vector3,
+    pub rotation: Vector3,
+    pub translation: Vector3,
+}
+
+/// A hierarchy of bones. Bones are stored in the order they were added, and a bone's parent always comes before it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Skeleton {
+    bones: Vec<Bone>,
+}
+
+impl Skeleton {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn add_bone(&mut self, name: &str, parent: Option<usize>, pivot: Vector3) -> usize {
+        //! Adds a bone in its rest pose and returns its index. Panics if the parent hasn't been added yet.
+        if let Some(parent) = parent {
+            assert!(
+                parent < self.bones.len(),
+                "parent bone {parent} does not exist"
+            );
+        }
+        self.bones.push(Bone {
+            name: name.to_owned(),
+            parent,
+            pivot,
+            rotation: Vector3::new(0.0, 0.0, 0.0),
+            translation: Vector3::new(0.0, 0.0, 0.0),
+        });
+        self.bones.len() - 1
+    }
+    pub fn get_bone(&self, index: usize) -> Option<&Bone> {
+        self.bones.get(index)
+    }
+    pub fn get_bone_mut(&mut self, index: usize) -> Option<&mut Bone> {
+        self.bones.get_mut(index)
+    }
+    pub fn find_bone(&self, name: &str) -> Option<usize> {
+        self.bones.iter().position(|x| x.name == name)
+    }
+    pub fn get_bones(&self) -> &Vec<Bone> {
+        &self.bones
+    }
+    pub fn reset_pose(&mut self) {
+        //! Puts every bone back into its rest pose.
+        for bone in self.bones.iter_mut() {
+            bone.rotation = Vector3::new(0.0, 0.0, 0.0);
+            bone.translation = Vector3::new(0.0, 0.0, 0.0);
+        }
+    }
+    pub fn transform_point(&self, bone: usize, mut point: Vector3) -> Vector3 {
+        //! Moves a bind pose point as if it were rigidly attached to the given bone, applying the bone's pose and then the pose of each of its parents.
+        let mut current = self.bones.get(bone);
+        while let Some(bone) = current {
+            point = (point - bone.pivot).rotate(bone.rotation) + bone.pivot + bone.translation;
+            current = bone.parent.and_then(|x| self.bones.get(x));
+        }
+        point
+    }
+}
+
+/// A mesh bound to a skeleton. The mesh is kept in its bind pose, and pose() produces a deformed copy of it.
+/// # Example
+/// ```
+/// # use ascii_renderer::prelude::*;
+/// # use ascii_renderer::skeleton::*;
+/// let mut skinned = SkinnedMesh::new(ascii_renderer::create_cube(), Skeleton::new());
+/// let root = skinned.skeleton.add_bone("root", None, vec3!(0.0, 0.0, 0.0));
+/// for i in 0..8 {
+///     skinned.bind_vertex(i, root);
+/// }
+/// skinned.skeleton.get_bone_mut(root).unwrap().rotation.y = 1.0;
+/// let posed: Mesh = skinned.pose();
+/// ```
+#[derive(Debug, Clone)]
+pub struct SkinnedMesh {
+    pub mesh: Mesh,
+    pub skeleton: Skeleton,
+    weights: HashMap<usize, Vec<(usize, Scalar)>>,
+}
+
+impl SkinnedMesh {
+    pub fn new(mesh: Mesh, skeleton: Skeleton) -> Self {
+        Self {
+            mesh,
+            skeleton,
+            weights: HashMap::new(),
+        }
+    }
+    pub fn bind_vertex(&mut self, vertex: usize, bone: usize) {
+        //! Rigidly attaches a vertex to a single bone.
+        self.weights.insert(vertex, vec![(bone, 1.0)]);
+    }
+    pub fn set_weights(&mut self, vertex: usize, weights: Vec<(usize, Scalar)>) {
+        //! Attaches a vertex to several bones as (bone, weight) pairs. The weights are normalized when posing, so they don't need to add up to 1.
+        self.weights.insert(vertex, weights);
+    }
+    pub fn get_weights(&self, vertex: usize) -> Option<&Vec<(usize, Scalar)>> {
+        self.weights.get(&vertex)
+    }
+    pub fn unbind_vertex(&mut self, vertex: usize) -> Option<Vec<(usize, Scalar)>> {
+        self.weights.remove(&vertex)
+    }
+    pub fn pose(&self) -> Mesh {
+        //! Returns a copy of the mesh with every bound vertex moved by the skeleton's current pose. Vertices that aren't bound to any bone are left where they are.
+        let mut mesh = self.mesh.clone();
+        self.pose_into(&mut mesh);
+        mesh
+    }
+    pub fn pose_into(&self, target: &mut Mesh) {
+        //! Like pose(), but writes the deformed vertices into an existing mesh (usually one already in a Renderer) instead of allocating a new one.
+        for (&index, &vertex) in self.mesh.get_verticies().iter() {
+            let posed = match self.weights.get(&index) {
+                Some(weights) => self.skin_vertex(vertex, weights),
+                None => vertex,
+            };
+            target.insert_vertex(index, posed);
+        }
+    }
+    fn skin_vertex(&self, vertex: Vector3, weights: &[(usize, Scalar)]) -> Vector3 {
+        let total: Scalar = weights.iter().map(|x| x.1).sum();
+        if total == 0.0 {
+            return vertex;
+        }
+        weights
+            .iter()
+            .fold(Vector3::new(0.0, 0.0, 0.0), |accum, &(bone, weight)| {
+                accum + self.skeleton.transform_point(bone, vertex) * (weight / total)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec3;
+
+    fn assert_close(a: Vector3, b: Vector3) {
+        assert!((a - b).len() < 0.0001, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn hierarchical_posing() {
+        let mut skeleton = Skeleton::new();
+        let shoulder = skeleton.add_bone("shoulder", None, vec3!(0.0, 0.0, 0.0));
+        let elbow = skeleton.add_bone("elbow", Some(shoulder), vec3!(1.0, 0.0, 0.0));
+        assert_eq!(skeleton.find_bone("elbow"), Some(elbow));
+
+        let hand = vec3!(2.0, 0.0, 0.0);
+        skeleton.get_bone_mut(elbow).unwrap().rotation.z = std::f32::consts::FRAC_PI_2 as Scalar;
+        assert_close(skeleton.transform_point(elbow, hand), vec3!(1.0, 1.0, 0.0));
+
+        skeleton.get_bone_mut(shoulder).unwrap().translation = vec3!(0.0, 0.0, 5.0);
+        assert_close(skeleton.transform_point(elbow, hand), vec3!(1.0, 1.0, 5.0));
+
+        skeleton.reset_pose();
+        assert_close(skeleton.transform_point(elbow, hand), hand);
+    }
+
+    #[test]
+    fn weighted_skinning() {
+        let mut mesh = Mesh::default();
+        mesh.insert_vertex(0, vec3!(0.0, 0.0, 0.0));
+        mesh.insert_vertex(1, vec3!(1.0, 0.0, 0.0));
+        let mut skinned = SkinnedMesh::new(mesh, Skeleton::new());
+        let a = skinned.skeleton.add_bone("a", None, vec3!(0.0, 0.0, 0.0));
+        let b = skinned.skeleton.add_bone("b", None, vec3!(0.0, 0.0, 0.0));
+        skinned.skeleton.get_bone_mut(b).unwrap().translation = vec3!(0.0, 2.0, 0.0);
+        skinned.set_weights(1, vec![(a, 1.0), (b, 1.0)]);
+
+        let posed = skinned.pose();
+        assert_close(posed.get_verticies()[&0], vec3!(0.0, 0.0, 0.0));
+        assert_close(posed.get_verticies()[&1], vec3!(1.0, 1.0, 0.0));
+    }
+}
@@ -0,0 +1,305 @@
+//! Split-screen for local multiplayer. A SplitScreen divides the buffer into a viewport per player, each with its own camera and optionally its own InputMap, and draws the scene once into each of them with borders between.
+//!
+//! Key and mouse events go to every viewport's InputMap, so players are told apart by binding different keys ("w" for one, "ArrowUp" for the other). Gamepad events only go to the viewport the gamepad is assigned to, so both players can bind the same buttons.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::char_buffer::Region;
+//! # use ascii_renderer::input::{Binding, InputMap};
+//! # use ascii_renderer::split::{SplitScreen, Viewport};
+//! let camera = |x| Camera {
+//!     position: vec3!(x, 0.0, -6.0),
+//!     rotation: vec3!(0.0, 0.0, 0.0),
+//!     fov: vec2!(1.0, 1.0),
+//! };
+//! let mut input = InputMap::new();
+//! input.bind("forward", Binding::gamepad_button("DPadUp"));
+//! let mut split = SplitScreen::new(vec![
+//!     Viewport::new(camera(-1.0)).with_input(input.clone()).with_gamepad(0),
+//!     Viewport::new(camera(1.0)).with_input(input).with_gamepad(1),
+//! ]);
+//! split.handle(&InputEvent::GamepadDown(1, "DPadUp".to_string()));
+//! assert!(!split.viewports[0].input.as_ref().unwrap().is_held("forward"));
+//! assert!(split.viewports[1].input.as_ref().unwrap().is_held("forward"));
+//!
+//! let mut renderer = Renderer::new(vec![ascii_renderer::create_cube()], camera(0.0));
+//! let mut buf = CharBuffer::new(40, 20);
+//! split.draw(&mut renderer, &mut buf);
+//! assert_eq!(split.viewports[1].region, Region::new(21, 1, 18, 18));
+//! ```
+
+use super::char_buffer::{CharBuffer, Region};
+use super::charset::BorderStyle;
+use super::input::InputMap;
+use super::rendering::{Camera, Renderer};
+use super::runner::InputEvent;
+
+/// How a SplitScreen arranges its viewports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SplitLayout {
+    /// Side by side, left to right.
+    #[default]
+    Columns,
+    /// Stacked, top to bottom.
+    Rows,
+    /// As square a grid as fits them, filled a row at a time. The last row can have empty cells.
+    Grid,
+}
+
+/// One player's part of a SplitScreen.
+#[derive(Debug, Clone)]
+pub struct Viewport {
+    pub camera: Camera,
+    /// This player's actions, given the events SplitScreen::handle lets through.
+    pub input: Option<InputMap>,
+    /// Which gamepad's events go to input. With None, every gamepad's do.
+    pub gamepad: Option<usize>,
+    /// Where in the buffer the viewport was last drawn, inside its border.
+    pub region: Region,
+}
+
+impl Viewport {
+    pub fn new(camera: Camera) -> Self {
+        Self {
+            camera,
+            input: None,
+            gamepad: None,
+            region: Region::new(0, 0, 0, 0),
+        }
+    }
+    pub fn with_input(mut self, input: InputMap) -> Self {
+        self.input = Some(input);
+        self
+    }
+    pub fn with_gamepad(mut self, gamepad: usize) -> Self {
+        self.gamepad = Some(gamepad);
+        self
+    }
+    fn accepts(&self, event: &InputEvent) -> bool {
+        match event {
+            InputEvent::GamepadDown(id, _)
+            | InputEvent::GamepadUp(id, _)
+            | InputEvent::GamepadAxis(id, _, _) => self.gamepad.is_none_or(|x| x == *id),
+            _ => true,
+        }
+    }
+}
+
+/// Draws a scene once per viewport, each from its own camera, see the module docs.
+#[derive(Debug, Clone)]
+pub struct SplitScreen {
+    pub viewports: Vec<Viewport>,
+    pub layout: SplitLayout,
+    /// What's drawn around each viewport. With None, viewports are right up against each other.
+    pub border: Option<BorderStyle>,
+    scratch: Vec<CharBuffer>,
+}
+
+impl SplitScreen {
+    pub fn new(viewports: Vec<Viewport>) -> Self {
+        //! Viewports side by side with ASCII borders.
+        Self {
+            viewports,
+            layout: SplitLayout::default(),
+            border: Some(BorderStyle::ASCII),
+            scratch: Vec::new(),
+        }
+    }
+    pub fn with_layout(mut self, layout: SplitLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+    pub fn with_border(mut self, border: Option<BorderStyle>) -> Self {
+        self.border = border;
+        self
+    }
+    pub fn cells(&self, dimensions: (usize, usize)) -> Vec<Region> {
+        //! The part of a buffer of the given size each viewport gets, including its border.
+        let count = self.viewports.len();
+        let (columns, rows) = match self.layout {
+            SplitLayout::Columns => (count, 1),
+            SplitLayout::Rows => (1, count),
+            SplitLayout::Grid => {
+                let columns = (1..=count).find(|x| x * x >= count).unwrap_or(0);
+                (columns, count.div_ceil(columns.max(1)))
+            }
+        };
+        //Cells split the leftover chars between them, so they always cover the whole buffer
+        let span = |i: usize, parts: usize, length: usize| {
+            let start = i * length / parts;
+            (start, (i + 1) * length / parts - start)
+        };
+        (0..count)
+            .map(|i| {
+                let (x, width) = span(i % columns, columns, dimensions.0);
+                let (y, height) = span(i / columns, rows, dimensions.1);
+                Region::new(x, y, width, height)
+            })
+            .collect()
+    }
+    pub fn layout(&mut self, dimensions: (usize, usize)) {
+        //! Updates each viewport's region for a buffer of the given size. Drawing does this automatically.
+        let inset = self.border.is_some() as usize;
+        let cells = self.cells(dimensions);
+        for (viewport, cell) in self.viewports.iter_mut().zip(cells) {
+            viewport.region = Region::new(
+                cell.x + inset,
+                cell.y + inset,
+                cell.width.saturating_sub(inset * 2),
+                cell.height.saturating_sub(inset * 2),
+            );
+        }
+    }
+    pub fn viewport_at(&self, x: usize, y: usize) -> Option<usize> {
+        //! The index of the viewport the cell was last drawn in, ex: to find which one was clicked.
+        self.viewports.iter().position(|v| v.region.contains(x, y))
+    }
+    pub fn draw(&mut self, renderer: &mut Renderer, buf: &mut CharBuffer) {
+        //! Draws renderer into every viewport from that viewport's camera, then the borders. renderer's own camera is put back afterwards.
+        let camera = renderer.camera.clone();
+        self.draw_with(buf, |_, viewport, view| {
+            renderer.camera = viewport.camera.clone();
+            renderer.draw(view);
+        });
+        renderer.camera = camera;
+    }
+    pub fn draw_with(
+        &mut self,
+        buf: &mut CharBuffer,
+        mut draw: impl FnMut(usize, &Viewport, &mut CharBuffer),
+    ) {
+        //! Calls draw with each viewport's index, the viewport, and a cleared buffer the size of it, then copies that buffer into the viewport's region and draws the borders. Anything can be drawn this way, ex: a different scene or a HUD per player.
+        self.layout(buf.dimensions);
+        self.scratch
+            .resize_with(self.viewports.len(), || CharBuffer::new(0, 0));
+        for (i, (viewport, view)) in self
+            .viewports
+            .iter()
+            .zip(self.scratch.iter_mut())
+            .enumerate()
+        {
+            let region = viewport.region;
+            if view.dimensions == (region.width, region.height) {
+                view.fill(' ');
+            } else {
+                *view = CharBuffer::new(region.width, region.height);
+            }
+            draw(i, viewport, view);
+            buf.blit(view, region.x as isize, region.y as isize, None);
+        }
+        if let Some(border) = self.border {
+            for cell in self.cells(buf.dimensions) {
+                border.draw(buf, cell);
+            }
+        }
+    }
+    pub fn handle(&mut self, event: &InputEvent) {
+        //! Gives event to every viewport's InputMap, except gamepad events, which only go to the viewports using that gamepad.
+        for viewport in self.viewports.iter_mut() {
+            if viewport.accepts(event) {
+                if let Some(input) = viewport.input.as_mut() {
+                    input.handle(event);
+                }
+            }
+        }
+    }
+    pub fn end_frame(&mut self) {
+        //! Calls InputMap::end_frame on every viewport's InputMap.
+        for input in self.viewports.iter_mut().filter_map(|x| x.input.as_mut()) {
+            input.end_frame();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::Binding;
+    use crate::{vec2, vec3, Vector2, Vector3};
+
+    fn camera(x: crate::Scalar) -> Camera {
+        Camera {
+            position: vec3!(x, 0.0, -5.0),
+            rotation: vec3!(0.0, 0.0, 0.0),
+            fov: vec2!(1.0, 1.0),
+        }
+    }
+
+    #[test]
+    fn layouts() {
+        let viewports = vec![Viewport::new(camera(0.0)); 3];
+        let mut split = SplitScreen::new(viewports).with_layout(SplitLayout::Grid);
+        assert_eq!(
+            split.cells((11, 5)),
+            vec![
+                Region::new(0, 0, 5, 2),
+                Region::new(5, 0, 6, 2),
+                Region::new(0, 2, 5, 3),
+            ]
+        );
+        split.layout((11, 5));
+        assert_eq!(split.viewports[2].region, Region::new(1, 3, 3, 1));
+        assert_eq!(split.viewport_at(2, 3), Some(2));
+        assert_eq!(split.viewport_at(0, 3), None);
+        split = split.with_layout(SplitLayout::Rows).with_border(None);
+        split.layout((4, 7));
+        assert_eq!(split.viewports[1].region, Region::new(0, 2, 4, 2));
+    }
+
+    #[test]
+    fn drawing_and_input() {
+        let mut left = InputMap::new();
+        left.bind("fire", Binding::key("f"));
+        left.bind("fire", Binding::gamepad_button("South"));
+        let mut right = InputMap::new();
+        right.bind("fire", Binding::key("Enter"));
+        right.bind("fire", Binding::gamepad_button("South"));
+        let mut split = SplitScreen::new(vec![
+            Viewport::new(camera(-1.0)).with_input(left).with_gamepad(0),
+            Viewport::new(camera(1.0)).with_input(right),
+        ]);
+        let fire = |split: &SplitScreen| {
+            split
+                .viewports
+                .iter()
+                .map(|x| x.input.as_ref().unwrap().is_pressed("fire"))
+                .collect::<Vec<_>>()
+        };
+        split.handle(&InputEvent::KeyDown("Enter".to_string()));
+        assert_eq!(fire(&split), vec![false, true]);
+        split.end_frame();
+        //The right side takes every gamepad, and the left only the first
+        split.handle(&InputEvent::GamepadDown(1, "South".to_string()));
+        assert_eq!(fire(&split), vec![false, true]);
+        split.end_frame();
+        //South is still down on the right, so it isn't pressed again
+        split.handle(&InputEvent::GamepadDown(0, "South".to_string()));
+        assert_eq!(fire(&split), vec![true, false]);
+
+        //Each side shows the cube from its own camera, the same as drawing it alone at that size
+        let mut renderer = Renderer::new(vec![crate::create_cube()], camera(0.0));
+        let mut buf = CharBuffer::new(30, 15);
+        buf.fill('x');
+        split.draw(&mut renderer, &mut buf);
+        assert_eq!(renderer.camera.position, vec3!(0.0, 0.0, -5.0));
+        for viewport in split.viewports.iter() {
+            let region = viewport.region;
+            let mut alone = CharBuffer::new(region.width, region.height);
+            renderer.camera = viewport.camera.clone();
+            renderer.draw(&mut alone);
+            for y in 0..region.height {
+                for x in 0..region.width {
+                    assert_eq!(
+                        buf.get_char(region.x + x, region.y + y),
+                        alone.get_char(x, y)
+                    );
+                }
+            }
+        }
+        assert_ne!(split.viewports[0].region, split.viewports[1].region);
+        assert_eq!(buf.get_char(0, 0), Some('+'));
+        assert_eq!(buf.get_char(14, 7), Some('|'));
+        assert_eq!(buf.get_char(15, 7), Some('|'));
+        assert!(!buf.to_string().contains('x'));
+    }
+}
@@ -0,0 +1,189 @@
+//! Flipbook animations made of CharBuffers, for HUD effects and 2D games drawn straight into the buffer. Each frame is blitted over whatever is already there, with spaces left transparent by default.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::sprite::AnimatedSprite;
+//! let frames = ["o", "O", "@"].map(CharBuffer::from_text).to_vec();
+//! let mut sprite = AnimatedSprite::uniform(frames, 0.1);
+//! let mut buf = CharBuffer::new(3, 1);
+//! sprite.update(0.15);
+//! sprite.draw(&mut buf, 1, 0);
+//! assert_eq!(buf.get_char(1, 0), Some('O'));
+//! ```
+
+use super::animation::LoopMode;
+use super::char_buffer::CharBuffer;
+
+/// A sprite that steps through frames over time. It starts out playing from the first frame, looping.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimatedSprite {
+    /// Each frame, and how long it's shown for in seconds.
+    frames: Vec<(CharBuffer, f32)>,
+    time: f32,
+    playing: bool,
+    pub loop_mode: LoopMode,
+    /// How fast time passes for the sprite, ex: 2.0 plays it at double speed.
+    pub speed: f32,
+    /// The char in frames that is skipped when drawing, see CharBuffer::blit.
+    pub transparent: Option<char>,
+}
+
+impl AnimatedSprite {
+    pub fn new(frames: Vec<(CharBuffer, f32)>) -> Self {
+        //! A sprite from frames and how long each is shown for, in seconds.
+        Self {
+            frames,
+            time: 0.0,
+            playing: true,
+            loop_mode: LoopMode::Loop,
+            speed: 1.0,
+            transparent: Some(' '),
+        }
+    }
+    pub fn uniform(frames: Vec<CharBuffer>, frame_duration: f32) -> Self {
+        //! A sprite that shows every frame for the same number of seconds.
+        Self::new(frames.into_iter().map(|x| (x, frame_duration)).collect())
+    }
+    pub fn loop_mode(mut self, loop_mode: LoopMode) -> Self {
+        self.loop_mode = loop_mode;
+        self
+    }
+    pub fn get_frames(&self) -> &[(CharBuffer, f32)] {
+        &self.frames
+    }
+    pub fn duration(&self) -> f32 {
+        //! How long it takes to play through every frame once.
+        self.frames.iter().map(|x| x.1).sum()
+    }
+    pub fn update(&mut self, delta: f32) {
+        //! Moves the animation forward by delta seconds, if it's playing.
+        if self.playing {
+            self.time += delta * self.speed;
+        }
+    }
+    pub fn play(&mut self) {
+        self.playing = true;
+    }
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+    pub fn stop(&mut self) {
+        //! Pauses and goes back to the first frame.
+        self.playing = false;
+        self.time = 0.0;
+    }
+    pub fn restart(&mut self) {
+        //! Plays from the first frame.
+        self.playing = true;
+        self.time = 0.0;
+    }
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+    pub fn is_finished(&self) -> bool {
+        //! True once a sprite that plays once has shown its last frame for its full duration. Looping sprites never finish.
+        self.loop_mode == LoopMode::Once && self.time >= self.duration()
+    }
+    pub fn get_time(&self) -> f32 {
+        self.time
+    }
+    pub fn set_time(&mut self, time: f32) {
+        //! Jumps to time seconds after the start, ex: to start several of the same sprite out of sync.
+        self.time = time;
+    }
+    pub fn get_frame_index(&self) -> usize {
+        //! Which frame is shown at the current time.
+        let count = self.frames.len();
+        //PingPong doesn't show the first or last frames twice in a row when turning around
+        let order: Vec<usize> = match self.loop_mode {
+            LoopMode::PingPong => (0..count)
+                .chain((1..count.saturating_sub(1)).rev())
+                .collect(),
+            _ => (0..count).collect(),
+        };
+        let period: f32 = order.iter().map(|&i| self.frames[i].1).sum();
+        if period <= 0.0 {
+            return 0;
+        }
+        let mut time = match self.loop_mode {
+            LoopMode::Once => self.time.max(0.0),
+            _ => self.time.rem_euclid(period),
+        };
+        for &i in order.iter() {
+            if time < self.frames[i].1 {
+                return i;
+            }
+            time -= self.frames[i].1;
+        }
+        //Only sprites that play once get past the end
+        count - 1
+    }
+    pub fn get_frame(&self) -> Option<&CharBuffer> {
+        //! The frame shown at the current time, or None if there are no frames.
+        self.frames.get(self.get_frame_index()).map(|x| &x.0)
+    }
+    pub fn draw(&self, buf: &mut CharBuffer, x: isize, y: isize) {
+        //! Blits the current frame into buf with its top left corner at (x, y).
+        if let Some(frame) = self.get_frame() {
+            buf.blit(frame, x, y, self.transparent);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered(count: usize) -> AnimatedSprite {
+        let frames = (0..count)
+            .map(|i| CharBuffer::from_text(&i.to_string()))
+            .collect();
+        AnimatedSprite::uniform(frames, 1.0)
+    }
+
+    fn indices(sprite: &mut AnimatedSprite, steps: usize) -> Vec<usize> {
+        (0..steps)
+            .map(|_| {
+                let index = sprite.get_frame_index();
+                sprite.update(1.0);
+                index
+            })
+            .collect()
+    }
+
+    #[test]
+    fn loop_modes() {
+        assert_eq!(indices(&mut numbered(3), 7), [0, 1, 2, 0, 1, 2, 0]);
+        let mut once = numbered(3).loop_mode(LoopMode::Once);
+        assert_eq!(indices(&mut once, 5), [0, 1, 2, 2, 2]);
+        assert!(once.is_finished());
+        let mut ping_pong = numbered(3).loop_mode(LoopMode::PingPong);
+        assert_eq!(indices(&mut ping_pong, 7), [0, 1, 2, 1, 0, 1, 2]);
+        let mut single = numbered(1).loop_mode(LoopMode::PingPong);
+        assert_eq!(indices(&mut single, 3), [0, 0, 0]);
+        assert_eq!(AnimatedSprite::new(vec![]).get_frame(), None);
+    }
+
+    #[test]
+    fn playback() {
+        let mut sprite = AnimatedSprite::new(vec![
+            (CharBuffer::from_text("a"), 0.5),
+            (CharBuffer::from_text("b"), 2.0),
+        ]);
+        sprite.speed = 2.0;
+        sprite.update(0.3);
+        assert_eq!(sprite.get_frame_index(), 1);
+        sprite.pause();
+        sprite.update(10.0);
+        assert_eq!(sprite.get_time(), 0.6);
+        sprite.stop();
+        assert!(!sprite.is_playing());
+        assert_eq!(sprite.get_frame_index(), 0);
+
+        let mut buf = CharBuffer::new(2, 1);
+        sprite.set_time(1.0);
+        sprite.draw(&mut buf, -1, 0);
+        sprite.draw(&mut buf, 1, 0);
+        assert_eq!(&buf.to_string(), "  b \n");
+    }
+}
@@ -0,0 +1,150 @@
+//! Masks restricting which cells of a buffer can be written to. While a CharBuffer has a stencil, set_char (and so the renderer, lines, blits, text, and everything else that draws through it) skips the cells the stencil masks out, ex: to draw a 3D scene only inside a portal shaped hole in the HUD.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::char_buffer::Region;
+//! # use ascii_renderer::stencil::Stencil;
+//! let mut buf = CharBuffer::new(6, 3);
+//! buf.fill('=');
+//! //Only the middle of the buffer shows the scene
+//! buf.set_stencil(Some(Stencil::from_region(6, 3, Region::new(1, 1, 4, 1))));
+//! buf.fill(' ');
+//! buf.write_str(0, 1, "portal");
+//! buf.set_stencil(None);
+//! assert_eq!(&buf.to_string(), "= = = = = = \n= o r t a = \n= = = = = = \n");
+//! ```
+
+use super::char_buffer::{CharBuffer, Region};
+use alloc::{vec, vec::Vec};
+
+/// Which cells of a buffer can be written to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Stencil {
+    pub dimensions: (usize, usize),
+    mask: Vec<bool>,
+}
+
+impl Stencil {
+    pub fn new(width: usize, height: usize, allowed: bool) -> Self {
+        //! A stencil allowing every cell, or none of them.
+        Self {
+            dimensions: (width, height),
+            mask: vec![allowed; width * height],
+        }
+    }
+    pub fn from_region(width: usize, height: usize, region: Region) -> Self {
+        //! A stencil only allowing the cells in region.
+        let mut stencil = Self::new(width, height, false);
+        stencil.set_region(region, true);
+        stencil
+    }
+    pub fn from_buffer(buf: &CharBuffer) -> Self {
+        //! A stencil allowing the cells of buf that aren't ' ', ex: a portal shape drawn with a CharBuffer.
+        let mut stencil = Self::new(buf.dimensions.0, buf.dimensions.1, false);
+        for (x, y) in stencil.all_cells() {
+            if buf.get_char(x, y).is_some_and(|x| x != ' ') {
+                stencil.set(x, y, true);
+            }
+        }
+        stencil
+    }
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.dimensions.0 && y < self.dimensions.1).then_some(y * self.dimensions.0 + x)
+    }
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        //! Whether the cell can be written to. Cells outside the stencil can't.
+        self.index(x, y).is_some_and(|i| self.mask[i])
+    }
+    pub fn set(&mut self, x: usize, y: usize, allowed: bool) {
+        if let Some(i) = self.index(x, y) {
+            self.mask[i] = allowed;
+        }
+    }
+    pub fn set_region(&mut self, region: Region, allowed: bool) {
+        let region = region.clipped(self.dimensions);
+        for y in region.y..region.y + region.height {
+            for x in region.x..region.x + region.width {
+                self.set(x, y, allowed);
+            }
+        }
+    }
+    pub fn invert(&mut self) {
+        //! Swaps which cells are allowed, ex: to draw the HUD everywhere except the portal.
+        self.mask.iter_mut().for_each(|x| *x = !*x);
+    }
+    fn all_cells(&self) -> impl Iterator<Item = (usize, usize)> {
+        let (width, height) = self.dimensions;
+        (0..height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        //! Every allowed cell, row by row.
+        self.all_cells().filter(|&(x, y)| self.get(x, y))
+    }
+}
+
+impl CharBuffer {
+    pub fn set_stencil(&mut self, stencil: Option<Stencil>) -> Option<Stencil> {
+        //! Restricts writes to the cells stencil allows, or lifts the restriction with None, returning the old stencil. Cells outside the stencil are masked out, so it should be the same size as the buffer.
+        core::mem::replace(&mut self.stencil, stencil)
+    }
+    pub fn get_stencil(&self) -> Option<&Stencil> {
+        self.stencil.as_ref()
+    }
+    pub fn with_stencil(&mut self, stencil: Stencil, draw: impl FnOnce(&mut CharBuffer)) {
+        //! Runs draw with writes restricted to stencil, then puts the old stencil back.
+        let previous = self.set_stencil(Some(stencil));
+        draw(self);
+        self.set_stencil(previous);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::line::Line;
+    use crate::rendering::{Camera, Renderer};
+    use crate::{vec2, vec3, Vector2, Vector3};
+
+    #[test]
+    fn masking_draws() {
+        let mut portal = CharBuffer::new(9, 9);
+        portal.write_str(3, 3, "###");
+        portal.write_str(3, 4, "###");
+        portal.write_str(3, 5, "###");
+        let stencil = Stencil::from_buffer(&portal);
+        assert_eq!(stencil.cells().count(), 9);
+        assert!(stencil.get(4, 4) && !stencil.get(0, 0) && !stencil.get(20, 4));
+
+        let renderer = Renderer::new(
+            vec![crate::create_cube()],
+            Camera {
+                position: vec3!(0.0, 0.0, -5.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(1.0, 1.0),
+            },
+        );
+        let mut buf = CharBuffer::new(9, 9);
+        buf.with_stencil(stencil.clone(), |buf| {
+            buf.fill('.');
+            renderer.draw(buf);
+            buf.draw_line(Line {
+                char: '-',
+                points: (vec2!(0.0, 8.0), vec2!(8.0, 8.0)),
+            });
+        });
+        assert!(buf.get_stencil().is_none());
+        for y in 0..9 {
+            for x in 0..9 {
+                assert_eq!(buf.get_char(x, y) == Some(' '), !stencil.get(x, y));
+            }
+        }
+        //Inverted, only the outside can be written to
+        let mut outside = stencil;
+        outside.invert();
+        buf.set_stencil(Some(outside));
+        buf.fill('x');
+        assert_eq!(buf.get_char(0, 0), Some('x'));
+        assert_ne!(buf.get_char(4, 4), Some('x'));
+        assert!(buf.set_char(20, 0, 'x').is_err());
+    }
+}
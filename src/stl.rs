@@ -0,0 +1,195 @@
+//! Loads STL files (both the ASCII and binary kinds) into a mesh. Every edge of every triangle becomes an edge of the mesh, with corners and edges that triangles share only added once.
+
+use super::rendering::{Mesh, Scalar, Vector3};
+use std::collections::{HashMap, HashSet};
+
+/// The error returned when an STL file can't be read or doesn't make sense.
+#[derive(Debug)]
+pub enum StlError {
+    Io(std::io::Error),
+    Malformed(String),
+}
+
+impl std::fmt::Display for StlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StlError::Io(err) => write!(f, "could not read stl file: {err}"),
+            StlError::Malformed(reason) => write!(f, "malformed stl file: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for StlError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StlError::Io(err) => Some(err),
+            StlError::Malformed(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for StlError {
+    fn from(err: std::io::Error) -> Self {
+        StlError::Io(err)
+    }
+}
+
+impl Mesh {
+    pub fn load_stl(path: impl AsRef<std::path::Path>) -> Result<Mesh, StlError> {
+        //! Loads an ASCII or binary STL file.
+        Mesh::parse_stl(&std::fs::read(path)?)
+    }
+    pub fn parse_stl(bytes: &[u8]) -> Result<Mesh, StlError> {
+        //! Same as load_stl, but from the contents of the file. It returns an error rather than panicking on anything malformed, and never makes a mesh that can't be drawn (it is fuzzed, see fuzz/).
+        let binary_len = bytes
+            .get(80..84)
+            .map(|count| 84 + 50 * u32::from_le_bytes(count.try_into().unwrap()) as usize);
+        //Binary files are allowed to start with "solid" too, so the length is checked first
+        let triangles = if binary_len == Some(bytes.len()) {
+            parse_binary(bytes)
+        } else if bytes.starts_with(b"solid") {
+            parse_ascii(bytes)?
+        } else {
+            return Err(StlError::Malformed(
+                "not an ascii stl, and the wrong length for a binary one".to_string(),
+            ));
+        };
+
+        let finite = |x: &Vector3| x.x.is_finite() && x.y.is_finite() && x.z.is_finite();
+        if !triangles.iter().flatten().all(finite) {
+            return Err(StlError::Malformed(
+                "a vertex has a NaN or infinite coordinate".to_string(),
+            ));
+        }
+
+        let mut mesh = Mesh::default();
+        let mut indices = HashMap::new();
+        let mut edges: HashSet<(usize, usize)> = HashSet::new();
+        for triangle in triangles {
+            let corners = triangle.map(|corner| {
+                let next = indices.len();
+                *indices
+                    .entry([corner.x, corner.y, corner.z].map(|x| x.to_bits()))
+                    .or_insert_with(|| {
+                        mesh.insert_vertex(next, corner);
+                        next
+                    })
+            });
+            for (a, b) in [(0, 1), (1, 2), (2, 0)] {
+                let edge = (corners[a].min(corners[b]), corners[a].max(corners[b]));
+                if edge.0 != edge.1 && edges.insert(edge) {
+                    mesh.add_edge(edge);
+                }
+            }
+        }
+        Ok(mesh)
+    }
+}
+
+fn parse_binary(bytes: &[u8]) -> Vec<[Vector3; 3]> {
+    bytes[84..]
+        .chunks_exact(50)
+        .map(|triangle| {
+            let float =
+                |at: usize| f32::from_le_bytes(triangle[at..at + 4].try_into().unwrap()) as Scalar;
+            //Skips the normal at the start of each triangle
+            [12, 24, 36].map(|at| Vector3::new(float(at), float(at + 4), float(at + 8)))
+        })
+        .collect()
+}
+
+fn parse_ascii(bytes: &[u8]) -> Result<Vec<[Vector3; 3]>, StlError> {
+    let text = std::str::from_utf8(bytes)
+        .map_err(|_| StlError::Malformed("ascii stl is not valid utf-8".to_string()))?;
+    if !text.contains("endsolid") {
+        return Err(StlError::Malformed(
+            "ascii stl is missing endsolid, so it may be cut off".to_string(),
+        ));
+    }
+    let mut corners = Vec::new();
+    for line in text.lines() {
+        let mut words = line.split_whitespace();
+        if words.next() != Some("vertex") {
+            continue;
+        }
+        let mut coords = [0.0; 3];
+        for coord in coords.iter_mut() {
+            *coord = words
+                .next()
+                .and_then(|x| x.parse::<Scalar>().ok())
+                .ok_or_else(|| StlError::Malformed(format!("bad vertex line: {}", line.trim())))?;
+        }
+        corners.push(Vector3::new(coords[0], coords[1], coords[2]));
+    }
+    if corners.len() % 3 != 0 {
+        return Err(StlError::Malformed(
+            "vertex count is not a multiple of 3".to_string(),
+        ));
+    }
+    Ok(corners
+        .chunks_exact(3)
+        .map(|x| [x[0], x[1], x[2]])
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec3;
+
+    const CORNERS: [[f32; 3]; 4] = [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, 1.0],
+    ];
+    const FACES: [[usize; 3]; 4] = [[0, 1, 2], [0, 2, 3], [0, 3, 1], [1, 3, 2]];
+
+    fn check_tetrahedron(mesh: &Mesh) {
+        assert_eq!(mesh.get_verticies().len(), 4);
+        assert_eq!(mesh.get_edges().len(), 6);
+        assert!(mesh
+            .get_verticies()
+            .values()
+            .any(|x| *x == vec3!(0.0, 0.0, 1.0)));
+    }
+
+    #[test]
+    fn ascii() {
+        let mut text = "solid tetra\n".to_string();
+        for face in FACES {
+            text += "  facet normal 0 0 0\n    outer loop\n";
+            for [x, y, z] in face.map(|i| CORNERS[i]) {
+                text += &format!("      vertex {x} {y} {z}\n");
+            }
+            text += "    endloop\n  endfacet\n";
+        }
+        text += "endsolid tetra\n";
+        check_tetrahedron(&Mesh::parse_stl(text.as_bytes()).unwrap());
+
+        assert!(matches!(
+            Mesh::parse_stl(b"solid x\n vertex 1 2\nendsolid x\n"),
+            Err(StlError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn binary() {
+        //Starts with "solid" to make sure it isn't mistaken for ascii
+        let mut bytes = b"solid".to_vec();
+        bytes.resize(80, 0);
+        bytes.extend_from_slice(&4u32.to_le_bytes());
+        for face in FACES {
+            bytes.extend_from_slice(&[0; 12]);
+            for coord in face.iter().flat_map(|&i| CORNERS[i]) {
+                bytes.extend_from_slice(&coord.to_le_bytes());
+            }
+            bytes.extend_from_slice(&[0; 2]);
+        }
+        check_tetrahedron(&Mesh::parse_stl(&bytes).unwrap());
+        assert!(Mesh::parse_stl(&bytes[..100]).is_err());
+        //The first triangle's first corner
+        bytes[96..100].copy_from_slice(&f32::NAN.to_le_bytes());
+        assert!(Mesh::parse_stl(&bytes).is_err());
+    }
+}
@@ -0,0 +1,162 @@
+//! Supersampling, for smoother wireframes on big terminals at the cost of drawing more cells. Setting Renderer::supersampling makes draw draw every mesh into a buffer several times bigger, then shrink it back down, picking a char for each cell by how much of it the lines covered.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::supersample::Supersampling;
+//! let mut renderer = Renderer::new(
+//!     vec![ascii_renderer::create_cube()],
+//!     Camera {
+//!         position: vec3!(0.0, 0.0, -6.0),
+//!         rotation: vec3!(0.4, 0.6, 0.0),
+//!         fov: vec2!(1.0, 1.0),
+//!     },
+//! );
+//! renderer.supersampling = Some(Supersampling::new(3));
+//! let mut buf = CharBuffer::new(40, 40);
+//! renderer.draw(&mut buf);
+//! ```
+
+use super::cache::lock;
+use super::char_buffer::CharBuffer;
+use super::demos::shade;
+use super::depth::DepthBuffer;
+use super::rendering::Renderer;
+use super::Scalar;
+use alloc::vec::Vec;
+
+/// The default chars for how much of a cell is covered, from least to most.
+pub const COVERAGE_RAMP: &[char] = &['.', ':', '+', '#'];
+
+/// How many times bigger to draw, and the chars to draw with, see the module docs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Supersampling {
+    /// How many times wider and taller the buffer drawn into is. 1 or less draws normally.
+    pub factor: usize,
+    /// The chars for how much of a cell is covered, from least to most. A line running straight through a cell covers all of it.
+    pub ramp: Vec<char>,
+}
+
+impl Supersampling {
+    pub fn new(factor: usize) -> Self {
+        //! Supersampling with COVERAGE_RAMP.
+        Self {
+            factor,
+            ramp: COVERAGE_RAMP.to_vec(),
+        }
+    }
+    pub fn with_ramp(mut self, ramp: Vec<char>) -> Self {
+        self.ramp = ramp;
+        self
+    }
+    pub fn downsample(&self, big: &CharBuffer, buffer: &mut CharBuffer) {
+        //! Shrinks big down into buffer, for every cell of buffer that big has anything other than ' ' drawn in. Each cell of buffer is factor by factor cells of big, and the rest of big is ignored.
+        let factor = self.factor.max(1);
+        for y in 0..buffer.dimensions.1 {
+            for x in 0..buffer.dimensions.0 {
+                let mut covered = 0;
+                for sub_y in y * factor..(y + 1) * factor {
+                    for sub_x in x * factor..(x + 1) * factor {
+                        covered += big.get_char(sub_x, sub_y).is_some_and(|x| x != ' ') as usize;
+                    }
+                }
+                if covered > 0 {
+                    //A line crossing the cell covers about factor of its cells
+                    let coverage = covered as Scalar / factor as Scalar;
+                    let _ = buffer.set_char(x, y, shade(&self.ramp, coverage));
+                }
+            }
+        }
+    }
+}
+
+impl Renderer {
+    pub(crate) fn draw_supersampled(&self, buffer: &mut CharBuffer, supersampling: &Supersampling) {
+        let factor = supersampling.factor.max(1);
+        let dimensions = (buffer.dimensions.0 * factor, buffer.dimensions.1 * factor);
+        let mut scratch = lock(&self.scratch.0);
+        //Reusing the last frame's buffer when it's the right size
+        let mut big = match scratch.supersampled.take() {
+            Some(mut big) if big.dimensions == dimensions => {
+                big.fill(' ');
+                big
+            }
+            _ => CharBuffer::new(dimensions.0, dimensions.1),
+        };
+        if self.record_depth {
+            //Depth tested at the higher resolution, keeping the nearest depth in each cell for depth_at
+            let mut big_depth = DepthBuffer::reuse(scratch.supersampled_depth.take(), dimensions);
+            let mut depth = DepthBuffer::reuse(scratch.depth.take(), buffer.dimensions);
+            drop(scratch);
+            self.draw_with_depth(&mut big, &mut big_depth);
+            big_depth.shrink_into(factor, &mut depth);
+            scratch = lock(&self.scratch.0);
+            scratch.depth = Some(depth);
+            scratch.supersampled_depth = Some(big_depth);
+        } else {
+            for mesh in self.meshs.iter() {
+                self.draw_mesh_scratch(mesh, mesh.model_matrix(), &mut big, &mut scratch);
+            }
+        }
+        supersampling.downsample(&big, buffer);
+        scratch.supersampled = Some(big);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::{Camera, Mesh};
+    use crate::{vec2, vec3, Vector2, Vector3};
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    #[test]
+    fn supersampling() {
+        let mut mesh = Mesh::default();
+        mesh.insert_vertex(0, vec3!(-5.0, 0.0, 0.0));
+        mesh.insert_vertex(1, vec3!(5.0, 2.0, 0.0));
+        mesh.add_edge((0, 1));
+        let mut renderer = Renderer::new(
+            vec![mesh],
+            Camera {
+                position: vec3!(0.0, 0.0, -10.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(1.0, 1.0),
+            },
+        );
+        let mut plain = CharBuffer::new(20, 6);
+        renderer.draw(&mut plain);
+        renderer.supersampling = Some(Supersampling::new(3));
+        let mut buf = CharBuffer::new(20, 6);
+        renderer.draw(&mut buf);
+        //Only the ends and the step between rows are partly covered
+        assert_eq!(
+            &buf.to_string(),
+            concat!(
+                "                                        \n",
+                "                                        \n",
+                "                                        \n",
+                ": # # # # # # # # # # # :               \n",
+                "                        + # # # # # # + \n",
+                "                                        \n",
+            )
+        );
+
+        //Factor 1 is one cell per cell
+        renderer.supersampling = Some(Supersampling::new(1).with_ramp(vec!['+']));
+        let mut buf = CharBuffer::new(20, 6);
+        renderer.draw(&mut buf);
+        assert_eq!(buf, plain);
+
+        //Recording depth still supersamples, with depth kept per cell of the smaller buffer
+        let mut supersampled = CharBuffer::new(20, 6);
+        renderer.supersampling = Some(Supersampling::new(3));
+        renderer.draw(&mut supersampled);
+        renderer.record_depth = true;
+        let mut buf = CharBuffer::new(20, 6);
+        renderer.draw(&mut buf);
+        assert_eq!(buf, supersampled);
+        assert!((renderer.depth_at(0, 3).unwrap() - 10.0).abs() < 0.01);
+        assert_eq!(renderer.depth_at(0, 0), None);
+    }
+}
@@ -0,0 +1,135 @@
+//! Exports frames as SVG images, for crisp figures in documentation and posters. Buffers are exported as text, and renderers can skip the chars entirely and export their lines as true vector paths.
+
+use super::cache::lock;
+use super::char_buffer::CharBuffer;
+use super::rendering::{Renderer, Scalar};
+use std::fmt::Write;
+
+/// How many SVG units wide and tall each cell of a buffer is.
+pub const SVG_CELL_SIZE: usize = 16;
+
+fn escape(char: char, out: &mut String) {
+    match char {
+        '&' => out.push_str("&amp;"),
+        '<' => out.push_str("&lt;"),
+        '>' => out.push_str("&gt;"),
+        '"' => out.push_str("&quot;"),
+        '\'' => out.push_str("&apos;"),
+        _ => out.push(char),
+    }
+}
+
+fn open_svg(dimensions: (usize, usize)) -> String {
+    let (width, height) = (dimensions.0 * SVG_CELL_SIZE, dimensions.1 * SVG_CELL_SIZE);
+    format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n")
+}
+
+impl CharBuffer {
+    pub fn to_svg(&self) -> String {
+        //! The buffer as an SVG image, with each row as a line of monospace text. Every char is placed in the middle of its own square cell, so the image looks the same whatever font ends up being used.
+        let mut svg = open_svg(self.dimensions);
+        svg.push_str(&format!(
+            "<g font-family=\"monospace\" font-size=\"{SVG_CELL_SIZE}\" text-anchor=\"middle\" dominant-baseline=\"central\" fill=\"currentColor\">\n"
+        ));
+        for (y, row) in self.data.iter().enumerate() {
+            let chars: Vec<(usize, char)> = row
+                .iter()
+                .copied()
+                .enumerate()
+                .filter(|(_, x)| !x.is_whitespace())
+                .collect();
+            if chars.is_empty() {
+                continue;
+            }
+            let xs: Vec<String> = chars
+                .iter()
+                .map(|(x, _)| (x * SVG_CELL_SIZE + SVG_CELL_SIZE / 2).to_string())
+                .collect();
+            let _ = write!(
+                svg,
+                "<text x=\"{}\" y=\"{}\">",
+                xs.join(" "),
+                y * SVG_CELL_SIZE + SVG_CELL_SIZE / 2
+            );
+            for &(_, char) in chars.iter() {
+                escape(char, &mut svg);
+            }
+            svg.push_str("</text>\n");
+        }
+        svg.push_str("</g>\n</svg>\n");
+        svg
+    }
+}
+
+impl Renderer {
+    pub fn to_svg(&self, dimensions: (usize, usize)) -> String {
+        //! The renderer's lines as SVG paths (one per mesh) instead of chars, framed the same as drawing into a buffer with the given dimensions.
+        let mut svg = open_svg(dimensions);
+        svg.push_str("<g fill=\"none\" stroke=\"currentColor\" stroke-width=\"1.5\" stroke-linecap=\"round\">\n");
+        let scale = SVG_CELL_SIZE as Scalar;
+        let mut scratch = lock(&self.scratch.0);
+        for mesh in self.meshs.iter() {
+            self.project_mesh_lines(mesh, mesh.model_matrix(), dimensions, &mut scratch);
+            if scratch.lines.is_empty() {
+                continue;
+            }
+            svg.push_str("<path d=\"");
+            for (i, line) in scratch.lines.iter().enumerate() {
+                let (start, end) = (line.points.0 * scale, line.points.1 * scale);
+                let _ = write!(
+                    svg,
+                    "{}M{:.2} {:.2}L{:.2} {:.2}",
+                    if i == 0 { "" } else { " " },
+                    start.x,
+                    start.y,
+                    end.x,
+                    end.y
+                );
+            }
+            svg.push_str("\"/>\n");
+        }
+        svg.push_str("</g>\n</svg>\n");
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn buffer_as_text() {
+        let mut buf = CharBuffer::new(3, 2);
+        buf.set_char(0, 0, '<').unwrap();
+        buf.set_char(2, 0, '#').unwrap();
+        let svg = buf.to_svg();
+        assert!(
+            svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"48\" height=\"32\"")
+        );
+        assert!(svg.contains("<text x=\"8 40\" y=\"8\">&lt;#</text>"));
+        //The empty row is left out
+        assert_eq!(svg.matches("<text").count(), 1);
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn renderer_as_paths() {
+        let renderer = Renderer::new(
+            vec![crate::create_cube(), Mesh::default()],
+            Camera {
+                position: vec3!(0.0, 0.0, -10.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(0.7, 0.7),
+            },
+        );
+        let svg = renderer.to_svg((30, 30));
+        assert_eq!(svg.matches("<path").count(), 1);
+        assert_eq!(svg.matches('M').count(), 12);
+
+        //The paths go through the same points the lines are drawn between
+        let line = renderer.project_lines((30, 30))[0];
+        let start = line.points.0 * SVG_CELL_SIZE as Scalar;
+        assert!(svg.contains(&format!("M{:.2} {:.2}", start.x, start.y)));
+    }
+}
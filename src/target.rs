@@ -0,0 +1,171 @@
+//! Drawing into things other than CharBuffers. Anything that implements Target for some type of cell can be drawn into by Renderer::draw_target, which runs the same projection as draw but asks for the cell to write for each mesh, so scenes can be drawn into brightness grids for LED matrices, RGB framebuffers, or cells of your own.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::target::Grid;
+//! let mut renderer = Renderer::new(
+//!     vec![ascii_renderer::create_cube()],
+//!     Camera {
+//!         position: vec3!(0.0, 0.0, -6.0),
+//!         rotation: vec3!(0.0, 0.0, 0.0),
+//!         fov: vec2!(1.0, 1.0),
+//!     },
+//! );
+//! renderer.meshs[0].char = 'r';
+//! //An RGB framebuffer, with the color picked from each mesh's char
+//! let mut frame = Grid::new(32, 16, [0u8; 3]);
+//! renderer.draw_target(&mut frame, |mesh| match mesh.char {
+//!     'r' => [255, 0, 0],
+//!     _ => [255, 255, 255],
+//! });
+//! assert!(frame.cells().any(|x| *x == [255, 0, 0]));
+//! ```
+
+use super::cache::lock;
+use super::char_buffer::{CharBuffer, Region};
+use super::line::for_each_cell;
+use super::raster::Bitmap;
+use super::rendering::{Mesh, Renderer};
+
+/// Something a grid of cells of type T can be drawn into.
+pub trait Target<T> {
+    /// The width and height in cells.
+    fn dimensions(&self) -> (usize, usize);
+    /// Sets the cell at x, y. Cells outside the target are ignored.
+    fn set_cell(&mut self, x: usize, y: usize, cell: T);
+}
+
+impl Target<char> for CharBuffer {
+    fn dimensions(&self) -> (usize, usize) {
+        self.dimensions
+    }
+    fn set_cell(&mut self, x: usize, y: usize, cell: char) {
+        let _ = self.set_char(x, y, cell);
+    }
+}
+
+impl Target<bool> for Bitmap {
+    fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+    fn set_cell(&mut self, x: usize, y: usize, cell: bool) {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x] = cell;
+        }
+    }
+}
+
+/// A grid of any type of cell, ex: u8 brightnesses or RGB colors.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    pub dimensions: (usize, usize),
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        //! A grid with every cell set to fill.
+        Self {
+            dimensions: (width, height),
+            cells: vec![fill; width * height],
+        }
+    }
+    pub fn fill(&mut self, cell: T) {
+        self.cells.fill(cell);
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        //! The cell at x, y, or None if it's outside the grid.
+        if x >= self.dimensions.0 {
+            return None;
+        }
+        self.cells.get(y * self.dimensions.0 + x)
+    }
+    pub fn cells(&self) -> impl Iterator<Item = &T> {
+        //! Every cell, row by row from the top left.
+        self.cells.iter()
+    }
+}
+
+impl<T> Target<T> for Grid<T> {
+    fn dimensions(&self) -> (usize, usize) {
+        self.dimensions
+    }
+    fn set_cell(&mut self, x: usize, y: usize, cell: T) {
+        if x < self.dimensions.0 && y < self.dimensions.1 {
+            self.cells[y * self.dimensions.0 + x] = cell;
+        }
+    }
+}
+
+impl Renderer {
+    pub fn draw_target<T: Clone>(
+        &self,
+        target: &mut impl Target<T>,
+        mut cell: impl FnMut(&Mesh) -> T,
+    ) {
+        //! Draws every mesh into target like draw, writing the cell returned for the mesh wherever its lines are. Lines aren't antialiased or thickened, since only CharBuffers have chars to do that with.
+        let dimensions = target.dimensions();
+        let region = Region::new(0, 0, dimensions.0, dimensions.1);
+        let mut scratch = lock(&self.scratch.0);
+        for mesh in self.meshs.iter() {
+            let value = cell(mesh);
+            self.project_mesh_lines(mesh, mesh.model_matrix(), dimensions, &mut scratch);
+            for line in scratch.lines.iter() {
+                for_each_cell(*line, region, |x, y| target.set_cell(x, y, value.clone()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::Camera;
+    use crate::{vec2, vec3, Vector2, Vector3};
+
+    #[test]
+    fn drawing_into_targets() {
+        let mut near = crate::create_cube();
+        near.position = vec3!(-1.5, 0.0, -2.0);
+        near.char = 'n';
+        let mut far = crate::create_cube();
+        far.position = vec3!(1.5, 0.0, 2.0);
+        far.char = 'f';
+        let renderer = Renderer::new(
+            vec![far, near],
+            Camera {
+                position: vec3!(0.0, 0.0, -8.0),
+                rotation: vec3!(0.3, 0.4, 0.0),
+                fov: vec2!(1.0, 1.0),
+            },
+        );
+        let mut buf = CharBuffer::new(30, 20);
+        renderer.draw(&mut buf);
+        //The same cells come out whatever the cells are
+        let mut chars = CharBuffer::new(30, 20);
+        renderer.draw_target(&mut chars, |mesh| mesh.char);
+        assert_eq!(chars, buf);
+        let mut brightness = Grid::new(30, 20, 0u8);
+        renderer.draw_target(
+            &mut brightness,
+            |mesh| if mesh.char == 'n' { 255 } else { 100 },
+        );
+        let mut bitmap = Bitmap::new(30, 20);
+        renderer.draw_target(&mut bitmap, |_| true);
+        for y in 0..20 {
+            for x in 0..30 {
+                let expected = match buf.get_char(x, y) {
+                    Some('n') => 255,
+                    Some('f') => 100,
+                    _ => 0,
+                };
+                assert_eq!(brightness.get(x, y), Some(&expected));
+                assert_eq!(bitmap.get(x, y), Some(expected != 0));
+            }
+        }
+        assert_eq!(brightness.get(30, 0), None);
+    }
+}
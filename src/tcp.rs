@@ -0,0 +1,47 @@
+//! The non-blocking client connections TelnetBackend and SyncServer share, so a client that can't keep up never blocks the runner.
+
+use std::io::{ErrorKind, Write};
+use std::net::{TcpListener, TcpStream};
+
+/// A client's socket, with the output that didn't fit in its send buffer yet.
+#[derive(Debug)]
+pub(crate) struct Connection {
+    pub stream: TcpStream,
+    pub unsent: Vec<u8>,
+}
+
+impl Connection {
+    pub fn accept(listener: &TcpListener) -> std::io::Result<Option<Self>> {
+        //! Accepts the next client waiting on a non-blocking listener, or None if there isn't one.
+        match listener.accept() {
+            Ok((stream, _)) => {
+                stream.set_nonblocking(true)?;
+                let _ = stream.set_nodelay(true);
+                Ok(Some(Self {
+                    stream,
+                    unsent: vec![],
+                }))
+            }
+            Err(err) if err.kind() == ErrorKind::WouldBlock => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+    pub fn is_idle(&self) -> bool {
+        //! True once everything queued has been sent, so the next frame can be queued.
+        self.unsent.is_empty()
+    }
+    pub fn send(&mut self) -> std::io::Result<()> {
+        //! Writes as much of the unsent output as the socket will take without blocking.
+        while !self.unsent.is_empty() {
+            match self.stream.write(&self.unsent) {
+                Ok(0) => return Err(ErrorKind::WriteZero.into()),
+                Ok(count) => {
+                    self.unsent.drain(..count);
+                }
+                Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+}
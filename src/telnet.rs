@@ -0,0 +1,267 @@
+//! A backend that serves frames to everyone connected over TCP, so a demo can be watched with `telnet host port` (or `nc`) from anywhere, like the classic Star Wars animation at towel.blinkenlights.nl. Telnet clients are asked for their window size, and each one is sent the middle of the frame cropped to fit it.
+//! # Example
+//! ```no_run
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::telnet::TelnetBackend;
+//! # struct MyLogic;
+//! # impl Logic for MyLogic {
+//! #     fn process(&mut self, _: &mut CharBuffer, _: f32) -> ProcessReturn { ProcessReturn::Continue }
+//! # }
+//! let backend = TelnetBackend::bind("0.0.0.0:2323").unwrap();
+//! let mut runner = Runner::with_backend(60, 30, 20, MyLogic, backend);
+//! runner.run(true);  //Now `telnet localhost 2323`
+//! ```
+
+use super::char_buffer::CharBuffer;
+use super::runner::Backend;
+use super::tcp::Connection;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, ToSocketAddrs};
+
+const IAC: u8 = 255;
+const WILL: u8 = 251;
+const DO: u8 = 253;
+const SB: u8 = 250;
+const SE: u8 = 240;
+const ECHO: u8 = 1;
+const SUPPRESS_GO_AHEAD: u8 = 3;
+const NAWS: u8 = 31;
+
+/// The window size used for clients that don't say what theirs is, ex: netcat.
+const DEFAULT_SIZE: (u16, u16) = (80, 24);
+
+/// Accepts connections and sends every frame to each client, without ever blocking the runner. Clients that fall behind skip frames until they catch up, and clients that disconnect are dropped.
+#[derive(Debug)]
+pub struct TelnetBackend {
+    listener: TcpListener,
+    clients: Vec<Client>,
+    frame: String,
+}
+
+#[derive(Debug)]
+struct Client {
+    connection: Connection,
+    /// The window size in terminal columns and rows, once the client has said.
+    size: Option<(u16, u16)>,
+    received: Vec<u8>,
+}
+
+impl TelnetBackend {
+    pub fn bind(address: impl ToSocketAddrs) -> std::io::Result<Self> {
+        //! Starts listening for clients on the address, ex: "0.0.0.0:23".
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            clients: vec![],
+            frame: String::new(),
+        })
+    }
+    pub fn local_addr(&self) -> std::io::Result<SocketAddr> {
+        //! The address being listened on, useful when bound to port 0.
+        self.listener.local_addr()
+    }
+    pub fn client_count(&self) -> usize {
+        self.clients.len()
+    }
+    pub fn client_sizes(&self) -> Vec<(u16, u16)> {
+        //! The window size (in terminal columns and rows) of each client, using 80 x 24 for the ones that haven't said.
+        self.clients
+            .iter()
+            .map(|x| x.size.unwrap_or(DEFAULT_SIZE))
+            .collect()
+    }
+    fn accept_clients(&mut self) -> std::io::Result<()> {
+        while let Some(mut connection) = Connection::accept(&self.listener)? {
+            //Asks for the window size, and for the client to send keys right away without echoing them
+            connection.unsent.extend_from_slice(&[
+                IAC,
+                DO,
+                NAWS,
+                IAC,
+                WILL,
+                ECHO,
+                IAC,
+                WILL,
+                SUPPRESS_GO_AHEAD,
+            ]);
+            connection.unsent.extend_from_slice(b"\x1b[2J\x1b[?25l");
+            self.clients.push(Client {
+                connection,
+                size: None,
+                received: vec![],
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Client {
+    fn read(&mut self) -> std::io::Result<()> {
+        //! Reads everything the client has sent, keeping track of window size changes. Returns an error once the client has disconnected.
+        let mut bytes = [0; 256];
+        loop {
+            match self.connection.stream.read(&mut bytes) {
+                Ok(0) => return Err(ErrorKind::UnexpectedEof.into()),
+                Ok(count) => self.received.extend_from_slice(&bytes[..count]),
+                Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+                Err(err) => return Err(err),
+            }
+        }
+        let (size, used) = parse_naws(&self.received);
+        self.size = size.or(self.size);
+        self.received.drain(..used);
+        Ok(())
+    }
+}
+
+/// Finds the last window size in the bytes a client sent, returning it and how many bytes can be thrown away. Bytes at the end that could be the start of an unfinished window size are kept.
+fn parse_naws(bytes: &[u8]) -> (Option<(u16, u16)>, usize) {
+    let mut size = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i..].starts_with(&[IAC, SB, NAWS]) {
+            //255 is doubled when it is part of the size
+            let mut values = vec![];
+            let mut j = i + 3;
+            while values.len() < 4 && j < bytes.len() {
+                values.push(bytes[j]);
+                j += if bytes[j] == IAC { 2 } else { 1 };
+            }
+            if values.len() < 4 || j + 2 > bytes.len() {
+                return (size, i);
+            }
+            if bytes[j..j + 2] == [IAC, SE] {
+                size = Some((
+                    u16::from_be_bytes([values[0], values[1]]),
+                    u16::from_be_bytes([values[2], values[3]]),
+                ));
+            }
+            i = j + 2;
+        } else if bytes[i] == IAC && i + 3 > bytes.len() {
+            return (size, i);
+        } else {
+            i += 1;
+        }
+    }
+    (size, bytes.len())
+}
+
+/// Writes the middle of the buffer that fits in a window of the given size, in the same format as printing it but with telnet's line endings.
+fn write_cropped(frame: &mut String, buf: &CharBuffer, size: (u16, u16), clear_screen: bool) {
+    let (width, height) = buf.dimensions;
+    let fit_width = (size.0 as usize / 2).min(width);
+    //One row is left free so the terminal doesn't scroll after the last line
+    let fit_height = (size.1 as usize).saturating_sub(1).min(height);
+    let (left, top) = ((width - fit_width) / 2, (height - fit_height) / 2);
+    frame.clear();
+    if clear_screen {
+        frame.push_str("\x1b[H");
+    }
+    for row in buf.data.iter().skip(top).take(fit_height) {
+        for &char in row.iter().skip(left).take(fit_width) {
+            frame.push(char);
+            frame.push(' ');
+        }
+        frame.push_str("\r\n");
+    }
+}
+
+impl Backend for TelnetBackend {
+    fn present(&mut self, buf: &CharBuffer, clear_screen: bool) -> std::io::Result<()> {
+        self.accept_clients()?;
+        let frame = &mut self.frame;
+        self.clients.retain_mut(|client| {
+            if client.read().is_err() {
+                return false;
+            }
+            if client.connection.is_idle() {
+                write_cropped(
+                    frame,
+                    buf,
+                    client.size.unwrap_or(DEFAULT_SIZE),
+                    clear_screen,
+                );
+                client.connection.unsent.extend_from_slice(frame.as_bytes());
+            }
+            client.connection.send().is_ok()
+        });
+        Ok(())
+    }
+}
+
+impl Drop for TelnetBackend {
+    fn drop(&mut self) {
+        for client in self.clients.iter_mut() {
+            let _ = client.connection.stream.set_nonblocking(false);
+            let _ = client.connection.stream.write_all(b"\x1b[?25h\r\n");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpStream;
+
+    #[test]
+    fn naws() {
+        assert_eq!(parse_naws(b"abc"), (None, 3));
+        assert_eq!(
+            parse_naws(&[b'x', IAC, SB, NAWS, 0, 20, 0, 6, IAC, SE, b'y']),
+            (Some((20, 6)), 11)
+        );
+        //Unfinished, so kept for next time
+        assert_eq!(parse_naws(&[b'x', IAC, SB, NAWS, 0, 20]), (None, 1));
+        //A width of 255 is sent as a doubled 255
+        assert_eq!(
+            parse_naws(&[IAC, SB, NAWS, 0, IAC, IAC, 0, 6, IAC, SE]),
+            (Some((255, 6)), 10)
+        );
+    }
+
+    #[test]
+    fn serves_cropped_frames() {
+        let mut backend = TelnetBackend::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(backend.local_addr().unwrap()).unwrap();
+        client
+            .write_all(&[IAC, SB, NAWS, 0, 6, 0, 3, IAC, SE])
+            .unwrap();
+        client
+            .set_read_timeout(Some(std::time::Duration::from_secs(5)))
+            .unwrap();
+
+        let mut buf = CharBuffer::new(5, 5);
+        for y in 0..5 {
+            for x in 0..5 {
+                buf.set_char(x, y, (b'a' + (y * 5 + x) as u8) as char)
+                    .unwrap();
+            }
+        }
+        let mut received = Vec::new();
+        let expected = b"\x1b[Hg h i \r\nl m n \r\n";
+        for _ in 0..100 {
+            backend.present(&buf, true).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            let mut bytes = [0; 1024];
+            let count = client.read(&mut bytes).unwrap();
+            received.extend_from_slice(&bytes[..count]);
+            if received.windows(expected.len()).any(|x| x == expected) {
+                break;
+            }
+        }
+        assert!(received.starts_with(&[IAC, DO, NAWS]));
+        assert!(received.windows(expected.len()).any(|x| x == expected));
+        assert_eq!(backend.client_sizes(), vec![(6, 3)]);
+
+        drop(client);
+        for _ in 0..100 {
+            backend.present(&buf, true).unwrap();
+            if backend.client_count() == 0 {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert_eq!(backend.client_count(), 0);
+    }
+}
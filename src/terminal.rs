@@ -0,0 +1,216 @@
+//! A [crossterm](https://docs.rs/crossterm) backend for Runner, behind the `crossterm` feature. Instead of clearing the screen and printing, every frame is drawn over the last one in place inside a synchronized update, so terminals that support it (including Windows Terminal) show whole frames without flicker, and the chars can be given colors or other styling.
+//! # Example
+//! ```no_run
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::terminal::CrosstermBackend;
+//! # use crossterm::style::{ContentStyle, Stylize};
+//! # struct MyLogic;
+//! # impl Logic for MyLogic {
+//! #     fn process(&mut self, _: &mut CharBuffer, _: f32) -> ProcessReturn { ProcessReturn::End }
+//! # }
+//! let backend = CrosstermBackend::stdout().style(ContentStyle::new().green());
+//! let mut runner = Runner::with_backend(40, 20, 30, MyLogic, backend);
+//! runner.run(true);
+//! ```
+
+use super::char_buffer::CharBuffer;
+use super::runner::{Backend, InputEvent};
+use crossterm::cursor::{Hide, MoveTo, MoveToNextLine, Show};
+use crossterm::event::{Event, KeyCode, KeyEventKind, MouseEventKind};
+use crossterm::style::{ContentStyle, PrintStyledContent};
+use crossterm::terminal::{BeginSynchronizedUpdate, Clear, ClearType, EndSynchronizedUpdate};
+use crossterm::QueueableCommand;
+use std::io::Write;
+
+/// Queues each frame as crossterm commands and writes them out with a single flush. The cursor is hidden while frames are being drawn over each other, and shown again when the backend is dropped.
+#[derive(Debug)]
+pub struct CrosstermBackend<W: Write> {
+    writer: W,
+    style: ContentStyle,
+    row: String,
+    cursor_hidden: bool,
+}
+
+impl CrosstermBackend<std::io::Stdout> {
+    pub fn stdout() -> Self {
+        Self::new(std::io::stdout())
+    }
+}
+
+impl<W: Write> CrosstermBackend<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            style: ContentStyle::new(),
+            row: String::new(),
+            cursor_hidden: false,
+        }
+    }
+    pub fn style(mut self, style: ContentStyle) -> Self {
+        //! The style (colors, bold, etc.) every char is drawn with.
+        self.style = style;
+        self
+    }
+    pub fn set_style(&mut self, style: ContentStyle) {
+        //! Changes the style between frames, ex: to flash the screen red.
+        self.style = style;
+    }
+    pub fn get_writer(&self) -> &W {
+        &self.writer
+    }
+    fn queue_frame(&mut self, buf: &CharBuffer, clear_screen: bool) -> std::io::Result<()> {
+        self.writer.queue(BeginSynchronizedUpdate)?;
+        if clear_screen {
+            if !self.cursor_hidden {
+                self.writer.queue(Hide)?.queue(Clear(ClearType::All))?;
+                self.cursor_hidden = true;
+            }
+            self.writer.queue(MoveTo(0, 0))?;
+        }
+        for row in buf.data.iter() {
+            self.row.clear();
+            for &char in row.iter() {
+                self.row.push(char);
+                self.row.push(' ');
+            }
+            self.writer
+                .queue(PrintStyledContent(self.style.apply(self.row.as_str())))?
+                .queue(MoveToNextLine(1))?;
+        }
+        self.writer.queue(EndSynchronizedUpdate)?;
+        Ok(())
+    }
+}
+
+impl<W: Write> Backend for CrosstermBackend<W> {
+    fn present(&mut self, buf: &CharBuffer, clear_screen: bool) -> std::io::Result<()> {
+        self.queue_frame(buf, clear_screen)?;
+        self.writer.flush()
+    }
+}
+
+impl<W: Write> Drop for CrosstermBackend<W> {
+    fn drop(&mut self) {
+        if self.cursor_hidden {
+            let _ = self.writer.queue(Show).and_then(|x| x.flush());
+        }
+    }
+}
+
+/// Converts a crossterm event into the runner's input events, naming keys the same way browsers do (ex: "a", "ArrowUp", or "Escape"). Resizes and mouse positions are converted to chars, with each char taking two columns. Mouse events are only reported once mouse capture has been enabled.
+/// Most terminals only report key presses, not releases, unless keyboard enhancement flags have been pushed. If releases_reported is false, every press is treated as a tap, giving a KeyDown followed right away by a KeyUp.
+pub fn input_events(event: &Event, releases_reported: bool) -> Vec<InputEvent> {
+    match event {
+        Event::Key(key) => {
+            let name = match key.code {
+                KeyCode::Char(char) => char.to_string(),
+                KeyCode::F(number) => format!("F{number}"),
+                KeyCode::Left => "ArrowLeft".to_string(),
+                KeyCode::Right => "ArrowRight".to_string(),
+                KeyCode::Up => "ArrowUp".to_string(),
+                KeyCode::Down => "ArrowDown".to_string(),
+                KeyCode::Esc => "Escape".to_string(),
+                KeyCode::BackTab => "Tab".to_string(),
+                KeyCode::Enter
+                | KeyCode::Backspace
+                | KeyCode::Tab
+                | KeyCode::Delete
+                | KeyCode::Insert
+                | KeyCode::Home
+                | KeyCode::End
+                | KeyCode::PageUp
+                | KeyCode::PageDown => format!("{:?}", key.code),
+                _ => return vec![],
+            };
+            match (key.kind, releases_reported) {
+                (KeyEventKind::Release, _) => vec![InputEvent::KeyUp(name)],
+                (_, true) => vec![InputEvent::KeyDown(name)],
+                (_, false) => vec![InputEvent::KeyDown(name.clone()), InputEvent::KeyUp(name)],
+            }
+        }
+        Event::Mouse(mouse) => {
+            let position = InputEvent::MouseMove(mouse.column as usize / 2, mouse.row as usize);
+            match mouse.kind {
+                MouseEventKind::Moved | MouseEventKind::Drag(_) => vec![position],
+                MouseEventKind::Down(button) => {
+                    vec![position, InputEvent::MouseDown(format!("{button:?}"))]
+                }
+                MouseEventKind::Up(button) => {
+                    vec![position, InputEvent::MouseUp(format!("{button:?}"))]
+                }
+                _ => vec![],
+            }
+        }
+        Event::Resize(columns, rows) => {
+            vec![InputEvent::Resize(*columns as usize / 2, *rows as usize)]
+        }
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::style::Stylize;
+
+    #[test]
+    fn frame_is_synchronized_and_styled() {
+        let mut buf = CharBuffer::new(3, 2);
+        buf.set_char(1, 0, '#').unwrap();
+        let mut backend = CrosstermBackend::new(Vec::new()).style(ContentStyle::new().red());
+        backend.present(&buf, true).unwrap();
+        let first = String::from_utf8(backend.get_writer().clone()).unwrap();
+        assert!(first.starts_with("\x1b[?2026h"));
+        assert!(first.ends_with("\x1b[?2026l"));
+        assert!(first.contains("\x1b[?25l"));
+        assert!(first.contains("\x1b[38;5;9m  # "));
+        assert!(first.contains("\x1b[1;1H"));
+
+        //Later frames draw over the first one without clearing the screen again
+        backend.present(&buf, true).unwrap();
+        let both = String::from_utf8(backend.get_writer().clone()).unwrap();
+        let second = &both[first.len()..];
+        assert!(!second.contains("\x1b[2J"));
+        assert!(second.contains("\x1b[1;1H"));
+    }
+
+    #[test]
+    fn converts_events() {
+        use crossterm::event::{KeyEvent, KeyModifiers};
+        let key = |code, kind| Event::Key(KeyEvent::new_with_kind(code, KeyModifiers::NONE, kind));
+        assert_eq!(
+            input_events(&key(KeyCode::Up, KeyEventKind::Press), false),
+            vec![
+                InputEvent::KeyDown("ArrowUp".to_string()),
+                InputEvent::KeyUp("ArrowUp".to_string())
+            ]
+        );
+        assert_eq!(
+            input_events(&key(KeyCode::PageDown, KeyEventKind::Press), true),
+            vec![InputEvent::KeyDown("PageDown".to_string())]
+        );
+        assert_eq!(
+            input_events(&key(KeyCode::Char('x'), KeyEventKind::Release), true),
+            vec![InputEvent::KeyUp("x".to_string())]
+        );
+        assert_eq!(
+            input_events(&Event::Resize(81, 20), true),
+            vec![InputEvent::Resize(40, 20)]
+        );
+
+        use crossterm::event::{MouseButton, MouseEvent};
+        let click = Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 9,
+            row: 2,
+            modifiers: KeyModifiers::NONE,
+        });
+        assert_eq!(
+            input_events(&click, true),
+            vec![
+                InputEvent::MouseMove(4, 2),
+                InputEvent::MouseDown("Left".to_string())
+            ]
+        );
+    }
+}
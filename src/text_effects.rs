@@ -0,0 +1,352 @@
+//! Effects made of text, for intros and loading screens: falling "digital rain" columns, and a Typewriter that reveals text a char at a time, optionally scrambling each char before it settles. Both draw into a region of a buffer.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::char_buffer::Region;
+//! # use ascii_renderer::text_effects::{DigitalRain, Typewriter};
+//! let mut buf = CharBuffer::new(30, 10);
+//! let mut rain = DigitalRain::new(30, 10, 1);
+//! let mut title = Typewriter::new("Wake up...");
+//! title.scramble = 0.2;
+//! //Every frame:
+//! rain.update(0.1);
+//! title.update(0.1);
+//! buf.fill(' ');
+//! rain.draw(&mut buf, Region::new(0, 0, 30, 10));
+//! title.draw(&mut buf, Region::new(10, 4, 10, 1));
+//! ```
+
+use super::char_buffer::{CharBuffer, Region};
+use super::noise::{hash, Rng};
+use super::Scalar;
+
+/// Katakana and digits, like the film.
+pub const RAIN_GLYPHS: &str = "ｱｲｳｴｵｶｷｸｹｺｻｼｽｾｿﾀﾁﾂﾃﾄﾅﾆﾇﾈﾉﾊﾋﾌﾍﾎﾏﾐﾑﾒﾓﾔﾕﾖﾗﾘﾙﾚﾛﾜﾝ0123456789";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Streak {
+    /// The row of the bottom of the streak, which can be above or below the screen.
+    head: Scalar,
+    speed: Scalar,
+    length: usize,
+}
+
+/// Columns of glyphs falling down the screen, each streak's trail flickering between random glyphs.
+#[derive(Debug, Clone)]
+pub struct DigitalRain {
+    streaks: Vec<Streak>,
+    height: usize,
+    time: f32,
+    /// The glyphs streaks are made of.
+    pub glyphs: Vec<char>,
+    /// Drawn at the bottom of each streak, or a random glyph if None.
+    pub head: Option<char>,
+    /// How fast streaks fall, in rows per second, picked between the two.
+    pub speed: (Scalar, Scalar),
+    /// How many rows long streaks are, picked between the two.
+    pub length: (usize, usize),
+    /// How many times a second each glyph in a trail changes.
+    pub flicker: f32,
+    rng: Rng,
+}
+
+impl DigitalRain {
+    pub fn new(width: usize, height: usize, seed: u32) -> Self {
+        //! Rain for a region width columns wide and height rows tall, already falling.
+        let mut rain = Self {
+            streaks: vec![],
+            height,
+            time: 0.0,
+            glyphs: RAIN_GLYPHS.chars().collect(),
+            head: None,
+            speed: (6.0, 16.0),
+            length: (4, height.max(5)),
+            flicker: 4.0,
+            rng: Rng::new(seed),
+        };
+        rain.streaks = (0..width)
+            .map(|_| {
+                let mut streak = rain.spawn();
+                //Spread out over the screen, instead of all starting at the top
+                streak.head = rain.rng.range(0.0, (height + streak.length) as Scalar);
+                streak
+            })
+            .collect();
+        rain
+    }
+    fn spawn(&mut self) -> Streak {
+        let (shortest, longest) = self.length;
+        let length =
+            shortest + (self.rng.next_u32() as usize) % (longest.saturating_sub(shortest) + 1);
+        Streak {
+            //Starts a little above the top, so columns don't all start at once
+            head: -self.rng.range(0.0, self.height as Scalar),
+            speed: self.rng.range(self.speed.0, self.speed.1),
+            length,
+        }
+    }
+    pub fn update(&mut self, delta: f32) {
+        //! Moves the streaks down, starting a new one at the top of every column whose streak has fallen off the bottom.
+        self.time += delta;
+        for i in 0..self.streaks.len() {
+            self.streaks[i].head += self.streaks[i].speed * delta as Scalar;
+            if self.streaks[i].head - self.streaks[i].length as Scalar >= self.height as Scalar {
+                self.streaks[i] = self.spawn();
+            }
+        }
+    }
+    pub fn draw(&self, buf: &mut CharBuffer, region: Region) {
+        //! Draws the streaks over the region, leaving the cells between them as they were.
+        let region = region.clipped(buf.dimensions);
+        if self.glyphs.is_empty() {
+            return;
+        }
+        let tick = (self.time * self.flicker) as u32;
+        for (x, streak) in self.streaks.iter().enumerate().take(region.width) {
+            let head = streak.head.floor() as isize;
+            for offset in 0..streak.length as isize {
+                let Ok(y) = usize::try_from(head - offset) else {
+                    break;
+                };
+                if y >= region.height.min(self.height) {
+                    continue;
+                }
+                //Each cell changes glyph at its own moment, so the trail shimmers instead of changing all at once
+                let cell = hash(x as u32 ^ hash(y as u32));
+                let glyph = self.glyphs[hash(cell.wrapping_add(tick.wrapping_add(cell % 7)))
+                    as usize
+                    % self.glyphs.len()];
+                let char = match (offset, self.head) {
+                    (0, Some(head)) => head,
+                    _ => glyph,
+                };
+                let _ = buf.set_char(region.x + x, region.y + y, char);
+            }
+        }
+    }
+}
+
+/// Reveals text a char at a time, wrapping it at word boundaries to fit the region it's drawn in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Typewriter {
+    text: Vec<char>,
+    time: f32,
+    /// How many chars are revealed each second.
+    pub chars_per_second: f32,
+    /// How many seconds each char shows random glyphs before settling on the real one. 0.0 doesn't scramble.
+    pub scramble: f32,
+    /// The glyphs chars are scrambled with.
+    pub glyphs: Vec<char>,
+    /// Drawn after the last revealed char while typing, if Some.
+    pub cursor: Option<char>,
+}
+
+impl Typewriter {
+    pub fn new(text: &str) -> Self {
+        //! Types text at 20 chars a second, with a '_' cursor and no scrambling.
+        Self {
+            text: text.chars().collect(),
+            time: 0.0,
+            chars_per_second: 20.0,
+            scramble: 0.0,
+            glyphs: "!#$%&*+<>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789"
+                .chars()
+                .collect(),
+            cursor: Some('_'),
+        }
+    }
+    pub fn get_text(&self) -> String {
+        self.text.iter().collect()
+    }
+    pub fn set_text(&mut self, text: &str) {
+        //! Changes the text and starts typing it from the beginning.
+        self.text = text.chars().collect();
+        self.time = 0.0;
+    }
+    pub fn update(&mut self, delta: f32) {
+        self.time += delta;
+    }
+    pub fn restart(&mut self) {
+        self.time = 0.0;
+    }
+    pub fn skip(&mut self) {
+        //! Reveals all the text at once, ex: when a key is pressed during an intro.
+        self.time = self.duration();
+    }
+    pub fn duration(&self) -> f32 {
+        //! How many seconds it takes for the whole text to be revealed and settled.
+        if self.chars_per_second <= 0.0 {
+            return f32::INFINITY;
+        }
+        self.text.len() as f32 / self.chars_per_second + self.scramble
+    }
+    pub fn is_finished(&self) -> bool {
+        self.time >= self.duration()
+    }
+    fn revealed(&self) -> usize {
+        //Float error could otherwise leave the last char hidden at exactly the duration
+        if self.is_finished() {
+            return self.text.len();
+        }
+        ((self.time * self.chars_per_second.max(0.0))
+            .floor()
+            .max(0.0) as usize)
+            .min(self.text.len())
+    }
+    fn char_at(&self, i: usize) -> char {
+        let char = self.text[i];
+        let started = i as f32 / self.chars_per_second;
+        if char.is_whitespace() || self.glyphs.is_empty() || self.time - started >= self.scramble {
+            return char;
+        }
+        //Scrambled chars change about 20 times a second
+        let tick = (self.time * 20.0) as u32;
+        self.glyphs[hash(i as u32 ^ hash(tick)) as usize % self.glyphs.len()]
+    }
+    pub fn get_visible(&self) -> String {
+        //! The text revealed so far, with chars still scrambling shown as their current glyph. The cursor isn't included.
+        (0..self.revealed()).map(|i| self.char_at(i)).collect()
+    }
+    fn layout(&self, width: usize) -> Vec<(usize, usize)> {
+        //! Where each char of the whole text goes when wrapped to width, so words don't jump to the next line while they're being typed.
+        let mut positions = Vec::with_capacity(self.text.len());
+        let (mut x, mut y) = (0, 0);
+        for (i, &char) in self.text.iter().enumerate() {
+            if char == '\n' {
+                positions.push((x, y));
+                (x, y) = (0, y + 1);
+                continue;
+            }
+            let word_start = i == 0 || self.text[i - 1].is_whitespace();
+            if word_start && !char.is_whitespace() {
+                let word = self.text[i..]
+                    .iter()
+                    .take_while(|x| !x.is_whitespace())
+                    .count();
+                //Words longer than a whole line are broken wherever they hit the edge instead
+                if x > 0 && x + word > width {
+                    (x, y) = (0, y + 1);
+                }
+            }
+            if x >= width {
+                (x, y) = (0, y + 1);
+            }
+            if char == ' ' && x == 0 && y > 0 {
+                //Spaces that wrapped onto the start of a line would indent it
+                positions.push((usize::MAX, y));
+                continue;
+            }
+            positions.push((x, y));
+            x += 1;
+        }
+        positions.push((x.min(width.saturating_sub(1)), y));
+        positions
+    }
+    pub fn draw(&self, buf: &mut CharBuffer, region: Region) {
+        //! Draws the revealed text into the region, starting at its top left corner. Only the revealed chars are drawn, so what's under the rest shows through.
+        let region = region.clipped(buf.dimensions);
+        if region.width == 0 {
+            return;
+        }
+        let positions = self.layout(region.width);
+        let revealed = self.revealed();
+        let mut draw = |(x, y): (usize, usize), char: char| {
+            if x < region.width && y < region.height {
+                let _ = buf.set_char(region.x + x, region.y + y, char);
+            }
+        };
+        for (i, &position) in positions.iter().enumerate().take(revealed) {
+            if self.text[i] != '\n' {
+                draw(position, self.char_at(i));
+            }
+        }
+        if let (Some(cursor), false) = (self.cursor, self.is_finished()) {
+            draw(positions[revealed], cursor);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rain() {
+        let mut rain = DigitalRain::new(6, 8, 5);
+        rain.glyphs = vec!['x'];
+        rain.head = Some('@');
+        rain.length = (3, 3);
+        rain.speed = (100.0, 100.0);
+        //Every streak falls off the bottom and starts again above the top
+        rain.update(10.0);
+        let mut buf = CharBuffer::new(6, 8);
+        rain.draw(&mut buf, Region::new(0, 0, 6, 8));
+        assert!(!buf.to_string().contains('x'));
+        rain.update(0.1);
+        rain.draw(&mut buf, Region::new(0, 0, 4, 8));
+        let text = buf.to_string();
+        assert!(text.contains('x') && text.contains('@'));
+        //Nothing past the region is drawn
+        assert!((0..8).all(|y| buf.get_char(4, y) == Some(' ') && buf.get_char(5, y) == Some(' ')));
+        //Heads are at the bottom of their streak
+        for x in 0..4 {
+            let column: String = (0..8).map(|y| buf.get_char(x, y).unwrap()).collect();
+            assert!(!column.contains('@') || column.trim_end().ends_with('@'));
+        }
+    }
+
+    #[test]
+    fn typing() {
+        let mut typewriter = Typewriter::new("hello there");
+        typewriter.chars_per_second = 10.0;
+        typewriter.update(0.35);
+        assert_eq!(typewriter.get_visible(), "hel");
+        let mut buf = CharBuffer::new(6, 3);
+        let region = buf.region();
+        typewriter.draw(&mut buf, region);
+        assert_eq!(
+            &buf.to_string(),
+            "h e l _     \n            \n            \n"
+        );
+        //The word that won't fit is put on the next line before it's typed
+        typewriter.update(0.4);
+        buf.fill(' ');
+        typewriter.draw(&mut buf, region);
+        assert_eq!(
+            &buf.to_string(),
+            "h e l l o   \nt _         \n            \n"
+        );
+        typewriter.skip();
+        assert!(typewriter.is_finished());
+        typewriter.draw(&mut buf, region);
+        assert_eq!(
+            &buf.to_string(),
+            "h e l l o   \nt h e r e   \n            \n"
+        );
+
+        typewriter.set_text("ab\ncdefghij");
+        typewriter.skip();
+        buf.fill(' ');
+        typewriter.draw(&mut buf, region);
+        assert_eq!(
+            &buf.to_string(),
+            "a b         \nc d e f g h \ni j         \n"
+        );
+    }
+
+    #[test]
+    fn scrambling() {
+        let mut typewriter = Typewriter::new("abc d");
+        typewriter.chars_per_second = 10.0;
+        typewriter.scramble = 0.5;
+        typewriter.glyphs = vec!['#'];
+        typewriter.update(0.45);
+        assert_eq!(typewriter.get_visible(), "### ");
+        typewriter.update(0.1);
+        assert_eq!(typewriter.get_visible(), "a## #");
+        assert!(!typewriter.is_finished());
+        typewriter.update(0.5);
+        assert_eq!(typewriter.get_visible(), "abc d");
+        assert!(typewriter.is_finished());
+    }
+}
@@ -0,0 +1,160 @@
+//! Thicker edges for things closer to the camera, which makes it easier to tell which parts of a wireframe are in front without shading it. Setting Renderer::thickness draws every edge with a brush several cells wide, with the width worked out from how far the middle of the edge is from the camera.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::thickness::EdgeThickness;
+//! let mut renderer = Renderer::new(
+//!     vec![ascii_renderer::create_cube()],
+//!     Camera {
+//!         position: vec3!(0.0, 0.0, -6.0),
+//!         rotation: vec3!(0.0, 0.0, 0.0),
+//!         fov: vec2!(1.0, 1.0),
+//!     },
+//! );
+//! renderer.thickness = Some(EdgeThickness::new(6.0, 3));
+//! let mut buf = CharBuffer::new(40, 40);
+//! renderer.draw(&mut buf);
+//! ```
+
+use super::char_buffer::CharBuffer;
+#[cfg(not(feature = "std"))]
+use super::float::Float;
+use super::line::{for_each_cell, Line};
+use super::renderable::NEAR;
+use super::rendering::{Mesh, Renderer};
+use super::{Mat4, Scalar};
+
+/// How thick edges are drawn, by how far they are from the camera.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EdgeThickness {
+    /// Edges this close to the camera or closer are max_width cells thick.
+    pub distance: Scalar,
+    /// The most cells thick an edge is drawn. Farther edges get thinner like they would in perspective, down to 1 cell.
+    pub max_width: usize,
+}
+
+impl EdgeThickness {
+    pub fn new(distance: Scalar, max_width: usize) -> Self {
+        Self {
+            distance,
+            max_width,
+        }
+    }
+    pub fn width(&self, distance: Scalar) -> usize {
+        //! How many cells thick an edge distance from the camera is drawn.
+        let max = self.max_width.max(1);
+        if distance <= self.distance {
+            return max;
+        }
+        ((max as Scalar * self.distance / distance).round() as usize).clamp(1, max)
+    }
+}
+
+impl Renderer {
+    pub(crate) fn draw_thick_mesh(
+        &self,
+        mesh: &Mesh,
+        model: Mat4,
+        buffer: &mut CharBuffer,
+        thickness: EdgeThickness,
+    ) {
+        //! Draws the mesh's edges thickened, ignoring antialiasing. Edges partly behind the camera are cut off at it.
+        let model_view = self.camera.view_matrix() * model;
+        let vertices = mesh.get_verticies();
+        let region = buffer.region();
+        for &(a, b) in self.lod_edges(mesh, &model) {
+            let (Some(a), Some(b)) = (vertices.get(&a), vertices.get(&b)) else {
+                continue;
+            };
+            let (mut start, mut end) = (
+                model_view.transform_point(*a),
+                model_view.transform_point(*b),
+            );
+            if start.z <= NEAR && end.z <= NEAR {
+                continue;
+            }
+            if start.z <= NEAR {
+                start = end.lerp(start, (end.z - NEAR) / (end.z - start.z));
+            } else if end.z <= NEAR {
+                end = start.lerp(end, (start.z - NEAR) / (start.z - end.z));
+            }
+            let width = thickness.width(start.lerp(end, 0.5).len());
+            let points = (
+                self.project_view(start, buffer.dimensions),
+                self.project_view(end, buffer.dimensions),
+            );
+            //The extra cells go across the line, so they thicken it instead of lengthening it
+            let steep = (points.1.y - points.0.y).abs() > (points.1.x - points.0.x).abs();
+            let (before, after) = ((width - 1) / 2, width / 2);
+            let char = mesh.char;
+            for_each_cell(Line { char, points }, region, |x, y| {
+                for offset in 0..=before + after {
+                    let (x, y) = if steep {
+                        ((x + offset).checked_sub(before), Some(y))
+                    } else {
+                        (Some(x), (y + offset).checked_sub(before))
+                    };
+                    if let (Some(x), Some(y)) = (x, y) {
+                        let _ = buffer.set_char(x, y, char);
+                    }
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::Camera;
+    use crate::{vec2, vec3, Vector2, Vector3};
+    use alloc::vec;
+
+    #[test]
+    fn widths() {
+        let thickness = EdgeThickness::new(5.0, 3);
+        assert_eq!(thickness.width(1.0), 3);
+        assert_eq!(thickness.width(5.0), 3);
+        assert_eq!(thickness.width(10.0), 2);
+        assert_eq!(thickness.width(100.0), 1);
+        assert_eq!(EdgeThickness::new(5.0, 0).width(1.0), 1);
+    }
+
+    #[test]
+    fn nearer_edges_are_thicker() {
+        //Two horizontal edges, one near and one far
+        let mut mesh = Mesh::default();
+        mesh.insert_vertices(vec![
+            (0, vec3!(-1.0, -1.0, 0.0)),
+            (1, vec3!(1.0, -1.0, 0.0)),
+            (2, vec3!(-4.0, 4.0, 20.0)),
+            (3, vec3!(4.0, 4.0, 20.0)),
+        ]);
+        mesh.add_edges(vec![(0, 1), (2, 3)]);
+        mesh.char = '#';
+        let mut renderer = Renderer::new(
+            vec![mesh],
+            Camera {
+                position: vec3!(0.0, 0.0, -5.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(1.0, 1.0),
+            },
+        );
+        renderer.thickness = Some(EdgeThickness::new(5.0, 3));
+        let mut buf = CharBuffer::new(40, 40);
+        renderer.draw(&mut buf);
+        let rows = |x: usize| (0..40).filter(|&y| buf.get_char(x, y) == Some('#')).count();
+        //The near edge is 3 rows thick, and the far one (only under the middle of it) is 1
+        assert_eq!(rows(12), 3);
+        assert_eq!(rows(20), 4);
+        renderer.thickness = None;
+        let mut thin = CharBuffer::new(40, 40);
+        renderer.draw(&mut thin);
+        assert_eq!(
+            (0..40)
+                .filter(|&y| thin.get_char(12, y) == Some('#'))
+                .count(),
+            1
+        );
+    }
+}
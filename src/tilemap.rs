@@ -0,0 +1,214 @@
+//! Grids of tiles for roguelike-style 2D worlds, drawn alongside or instead of the 3D renderer. Each cell of the map holds the index of a tile definition, which says what char it's drawn with and whether it's solid. The camera is an offset into the map, so worlds can be bigger than the buffer.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::tilemap::{TileDef, TileMap};
+//! let defs = vec![TileDef::new('.'), TileDef::new('#').solid()];
+//! let mut map = TileMap::from_text("#####\n#...#\n#####", defs);
+//! assert!(map.is_solid(0, 0) && !map.is_solid(1, 1));
+//! map.camera = (1, 0);
+//! let mut buf = CharBuffer::new(3, 3);
+//! map.draw(&mut buf);
+//! assert_eq!(&buf.to_string(), "# # # \n. . . \n# # # \n");
+//! ```
+
+use super::char_buffer::CharBuffer;
+
+/// What a tile looks like and how it behaves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TileDef {
+    pub char: char,
+    /// The tile's color as RGB, if it has one. CharBuffers only hold chars, so this isn't drawn, but it can be looked up with TileMap::get_def for backends that show color.
+    pub color: Option<[u8; 3]>,
+    /// Whether things can move into the tile, see TileMap::is_solid.
+    pub solid: bool,
+}
+
+impl TileDef {
+    pub fn new(char: char) -> Self {
+        Self {
+            char,
+            color: None,
+            solid: false,
+        }
+    }
+    pub fn solid(mut self) -> Self {
+        self.solid = true;
+        self
+    }
+    pub fn color(mut self, color: [u8; 3]) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+/// A grid of tile indices into a list of definitions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileMap {
+    width: usize,
+    height: usize,
+    tiles: Vec<usize>,
+    pub defs: Vec<TileDef>,
+    /// The map cell drawn in the top left corner of the buffer. Can be negative or past the edge, which shows the background.
+    pub camera: (isize, isize),
+    /// Drawn where the buffer shows somewhere outside the map, or a tile without a definition.
+    pub background: char,
+    changed: Vec<(usize, usize)>,
+    /// The camera and buffer size from the last draw, to know when everything needs redrawing.
+    last_draw: Option<((isize, isize), (usize, usize))>,
+}
+
+impl TileMap {
+    pub fn new(width: usize, height: usize, defs: Vec<TileDef>) -> Self {
+        //! A map filled with tile 0.
+        Self {
+            width,
+            height,
+            tiles: vec![0; width * height],
+            defs,
+            camera: (0, 0),
+            background: ' ',
+            changed: vec![],
+            last_draw: None,
+        }
+    }
+    pub fn from_text(text: &str, defs: Vec<TileDef>) -> Self {
+        //! A map drawn as text, one line per row, where each char becomes the first tile defined with that char. Chars without a definition, and the ends of short lines, become tile 0.
+        let width = text.lines().map(|x| x.chars().count()).max().unwrap_or(0);
+        let mut map = Self::new(width, text.lines().count(), defs);
+        for (y, line) in text.lines().enumerate() {
+            for (x, char) in line.chars().enumerate() {
+                if let Some(tile) = map.defs.iter().position(|x| x.char == char) {
+                    map.tiles[y * width + x] = tile;
+                }
+            }
+        }
+        map
+    }
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+    pub fn get(&self, x: usize, y: usize) -> Option<usize> {
+        //! The tile index at (x, y), or None if it's outside the map.
+        (x < self.width && y < self.height).then(|| self.tiles[y * self.width + x])
+    }
+    pub fn set(&mut self, x: usize, y: usize, tile: usize) -> bool {
+        //! Changes the tile at (x, y), returning false if it's outside the map.
+        if x >= self.width || y >= self.height {
+            return false;
+        }
+        let cell = &mut self.tiles[y * self.width + x];
+        if *cell != tile {
+            *cell = tile;
+            self.changed.push((x, y));
+        }
+        true
+    }
+    pub fn fill(&mut self, tile: usize) {
+        self.tiles.fill(tile);
+        self.last_draw = None;
+    }
+    pub fn get_def(&self, x: usize, y: usize) -> Option<&TileDef> {
+        //! The definition of the tile at (x, y).
+        self.defs.get(self.get(x, y)?)
+    }
+    pub fn is_solid(&self, x: isize, y: isize) -> bool {
+        //! Whether the tile at (x, y) is solid. Everywhere outside the map counts as solid, so nothing walks off the edge.
+        let (Ok(x), Ok(y)) = (usize::try_from(x), usize::try_from(y)) else {
+            return true;
+        };
+        self.get(x, y)
+            .is_none_or(|x| self.defs.get(x).is_some_and(|x| x.solid))
+    }
+    pub fn screen_to_map(&self, x: usize, y: usize) -> (isize, isize) {
+        //! The map cell drawn at a cell of the buffer.
+        (x as isize + self.camera.0, y as isize + self.camera.1)
+    }
+    pub fn center_on(&mut self, x: isize, y: isize, dimensions: (usize, usize)) {
+        //! Moves the camera so (x, y) is in the middle of a buffer with the given dimensions, ex: to follow the player.
+        self.camera = (x - dimensions.0 as isize / 2, y - dimensions.1 as isize / 2);
+    }
+    fn char_at(&self, x: isize, y: isize) -> char {
+        let (Ok(x), Ok(y)) = (usize::try_from(x), usize::try_from(y)) else {
+            return self.background;
+        };
+        self.get_def(x, y).map_or(self.background, |x| x.char)
+    }
+    pub fn draw(&mut self, buf: &mut CharBuffer) {
+        //! Draws the whole part of the map the camera sees, covering the buffer.
+        for y in 0..buf.dimensions.1 {
+            for x in 0..buf.dimensions.0 {
+                let (map_x, map_y) = self.screen_to_map(x, y);
+                let _ = buf.set_char(x, y, self.char_at(map_x, map_y));
+            }
+        }
+        self.changed.clear();
+        self.last_draw = Some((self.camera, buf.dimensions));
+    }
+    pub fn draw_changed(&mut self, buf: &mut CharBuffer) {
+        //! Only redraws the tiles set since the last draw, which is much faster for big buffers when little changes. Everything is redrawn if the camera moved or the buffer was resized. This assumes nothing else has drawn over the map since then, so use draw instead when sprites or anything else move around on top of it.
+        if self.last_draw != Some((self.camera, buf.dimensions)) {
+            self.draw(buf);
+            return;
+        }
+        for (x, y) in std::mem::take(&mut self.changed) {
+            let (screen_x, screen_y) = (x as isize - self.camera.0, y as isize - self.camera.1);
+            if let (Ok(screen_x), Ok(screen_y)) =
+                (usize::try_from(screen_x), usize::try_from(screen_y))
+            {
+                let _ = buf.set_char(screen_x, screen_y, self.char_at(x as isize, y as isize));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map() -> TileMap {
+        let defs = vec![
+            TileDef::new('.'),
+            TileDef::new('#').solid().color([128, 128, 128]),
+            TileDef::new('~'),
+        ];
+        TileMap::from_text("###\n#.~\n##", defs)
+    }
+
+    #[test]
+    fn tiles() {
+        let mut map = map();
+        assert_eq!(map.dimensions(), (3, 3));
+        assert_eq!(map.get(2, 1), Some(2));
+        //The short last line is padded with tile 0
+        assert_eq!(map.get(2, 2), Some(0));
+        assert_eq!(map.get(3, 0), None);
+        assert_eq!(map.get_def(0, 0).unwrap().color, Some([128, 128, 128]));
+        assert!(map.is_solid(0, 0) && map.is_solid(-1, 1) && map.is_solid(1, 3));
+        assert!(!map.is_solid(1, 1));
+        assert!(map.set(1, 1, 1) && !map.set(5, 5, 1));
+        assert!(map.is_solid(1, 1));
+    }
+
+    #[test]
+    fn camera_and_redraw() {
+        let mut map = map();
+        map.center_on(1, 1, (3, 3));
+        assert_eq!(map.camera, (0, 0));
+        map.camera = (-1, 1);
+        let mut buf = CharBuffer::new(3, 2);
+        map.draw(&mut buf);
+        assert_eq!(&buf.to_string(), "  # . \n  # # \n");
+        assert_eq!(map.screen_to_map(1, 0), (0, 1));
+
+        //Only the changed tile is redrawn
+        buf.set_char(0, 0, '@').unwrap();
+        map.set(1, 2, 2);
+        map.draw_changed(&mut buf);
+        assert_eq!(&buf.to_string(), "@ # . \n  # ~ \n");
+        //Moving the camera redraws everything
+        map.camera = (0, 1);
+        map.draw_changed(&mut buf);
+        assert_eq!(&buf.to_string(), "# . ~ \n# ~ . \n");
+    }
+}
@@ -0,0 +1,101 @@
+//! Cel (toon) shading for filled faces. Instead of a smooth ramp, brightness is rounded to a few bands of chars, and the silhouette of everything filled is outlined with its own char, which reads much better at terminal resolutions than a ramp whose steps are a char apart. Set voxel::VoxelGrid::toon to draw a filled grid this way.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::toon::Toon;
+//! let toon = Toon::new(2);
+//! assert_eq!(toon.bands, vec!['.', '@']);
+//! assert_eq!(toon.shade(0.3), '.');
+//! assert_eq!(toon.shade(0.8), '@');
+//! ```
+
+use super::char_buffer::CharBuffer;
+use super::demos::{shade, RAMP};
+use super::Scalar;
+
+/// Marks cells of a layer that nothing was drawn in.
+pub(crate) const EMPTY: char = '\0';
+
+/// How toon shaded faces are drawn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Toon {
+    /// The char for each band of brightness, from dark to bright.
+    pub bands: Vec<char>,
+    /// The char the silhouette is outlined with.
+    pub edge: char,
+}
+
+impl Toon {
+    pub fn new(bands: usize) -> Self {
+        //! Bands spread evenly over demos::RAMP, leaving out ' ' so even the darkest faces show, with silhouettes outlined in '#'.
+        let top = RAMP.len() - 1;
+        let bands = match bands {
+            0 => vec![],
+            1 => vec![RAMP[top]],
+            _ => (0..bands)
+                .map(|i| {
+                    RAMP[1 + ((i * (top - 1)) as Scalar / (bands - 1) as Scalar).round() as usize]
+                })
+                .collect(),
+        };
+        Self { bands, edge: '#' }
+    }
+    pub fn shade(&self, brightness: Scalar) -> char {
+        //! The band's char for a brightness from 0.0 to 1.0.
+        shade(&self.bands, brightness)
+    }
+    pub fn composite(&self, layer: &CharBuffer, buffer: &mut CharBuffer) {
+        //! Copies everything drawn in layer (all the cells that aren't EMPTY) into buffer, with the edge char around the outside of it. Cells at the edges of the buffer are only outlined if what's next to them inside it is empty.
+        let (width, height) = layer.dimensions;
+        let drawn = |x: usize, y: usize| layer.get_char(x, y).is_some_and(|x| x != EMPTY);
+        for y in 0..height {
+            for x in 0..width {
+                let Some(char) = layer.get_char(x, y).filter(|&x| x != EMPTY) else {
+                    continue;
+                };
+                let silhouette = (x > 0 && !drawn(x - 1, y))
+                    || (x + 1 < width && !drawn(x + 1, y))
+                    || (y > 0 && !drawn(x, y - 1))
+                    || (y + 1 < height && !drawn(x, y + 1));
+                let _ = buffer.set_char(x, y, if silhouette { self.edge } else { char });
+            }
+        }
+    }
+}
+
+impl Default for Toon {
+    fn default() -> Self {
+        //! Two tones.
+        Self::new(2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bands_and_silhouettes() {
+        assert_eq!(Toon::new(3).bands, vec!['.', '+', '@']);
+        assert_eq!(Toon::new(1).shade(0.0), '@');
+        assert_eq!(Toon::new(0).shade(0.5), ' ');
+        let mut layer = CharBuffer::new(5, 4);
+        layer.fill(EMPTY);
+        for (x, y) in [(1, 1), (2, 1), (3, 1), (1, 2), (2, 2), (3, 2), (0, 3)] {
+            layer.set_char(x, y, '.').unwrap();
+        }
+        layer.set_char(2, 2, '@').unwrap();
+        let mut buf = CharBuffer::new(5, 4);
+        buf.fill('x');
+        Toon::default().composite(&layer, &mut buf);
+        //The middle cell of the bottom row is on the silhouette too, since the cell below it is empty
+        assert_eq!(
+            &buf.to_string(),
+            "x x x x x \nx # # # x \nx # # # x \n# x x x x \n"
+        );
+        let mut buf = CharBuffer::new(5, 4);
+        layer.set_char(2, 3, '.').unwrap();
+        Toon::default().composite(&layer, &mut buf);
+        assert_eq!(buf.get_char(2, 2), Some('@'));
+    }
+}
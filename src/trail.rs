@@ -0,0 +1,193 @@
+//! Trails behind moving meshes: a Trail remembers where a mesh has been, and draws either a line through those positions or faded ghost copies of the mesh at them. Older parts of the trail use fainter chars from its ramp.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::renderable::RenderContext;
+//! # use ascii_renderer::trail::{Trail, TrailStyle};
+//! let mut cube = ascii_renderer::create_cube();
+//! let mut trail = Trail::new(8);
+//! trail.style = TrailStyle::Ghosts;
+//! let renderer = Renderer::new(
+//!     vec![],
+//!     Camera {
+//!         position: vec3!(0.0, 0.0, -15.0),
+//!         rotation: vec3!(0.0, 0.0, 0.0),
+//!         fov: vec2!(1.0, 1.0),
+//!     },
+//! );
+//! let mut buf = CharBuffer::new(40, 20);
+//! //Every frame:
+//! cube.position.x += 0.5;
+//! trail.record(&cube);
+//! let mut ctx = RenderContext::new(&renderer, &mut buf);
+//! trail.draw(&mut ctx, &cube);
+//! ctx.mesh(&cube);
+//! ```
+
+use super::renderable::RenderContext;
+use super::rendering::Mesh;
+use super::transform::Transform;
+use super::Scalar;
+use std::collections::VecDeque;
+
+/// How a trail is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrailStyle {
+    /// A line through the positions the mesh has been at.
+    Line,
+    /// Copies of the mesh where it's been, without the newest one (which is where the mesh is drawn itself).
+    Ghosts,
+}
+
+/// The recent transforms of a mesh, drawn behind it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trail {
+    transforms: VecDeque<Transform>,
+    /// How many transforms are kept, dropping the oldest ones.
+    pub length: usize,
+    /// The chars the trail is drawn with, from the oldest (faintest) part to the newest.
+    pub ramp: Vec<char>,
+    pub style: TrailStyle,
+    /// How far the mesh has to move from the last recorded position before recording another, so a mesh standing still doesn't pile up ghosts on top of itself. 0.0 records every time.
+    pub spacing: Scalar,
+}
+
+impl Trail {
+    pub fn new(length: usize) -> Self {
+        //! A line trail of the given length, fading through '.', ':', '+', and '*'.
+        Self {
+            transforms: VecDeque::with_capacity(length),
+            length,
+            ramp: vec!['.', ':', '+', '*'],
+            style: TrailStyle::Line,
+            spacing: 0.0,
+        }
+    }
+    pub fn get_transforms(&self) -> &VecDeque<Transform> {
+        //! The recorded transforms, oldest first.
+        &self.transforms
+    }
+    pub fn record(&mut self, mesh: &Mesh) {
+        //! Adds the mesh's current transform to the trail. Call it every frame, or whenever the trail should grow.
+        if let Some(last) = self.transforms.back() {
+            if last.position.distance(mesh.position) < self.spacing {
+                return;
+            }
+        }
+        self.transforms.push_back(mesh.transform);
+        while self.transforms.len() > self.length {
+            self.transforms.pop_front();
+        }
+    }
+    pub fn clear(&mut self) {
+        //! Forgets every position, ex: when the mesh teleports.
+        self.transforms.clear();
+    }
+    fn char_at(&self, age: usize, count: usize) -> char {
+        //The index counts up from the oldest, so the newest gets the end of the ramp
+        if self.ramp.is_empty() || count == 0 {
+            return ' ';
+        }
+        self.ramp[(age * self.ramp.len() / count).min(self.ramp.len() - 1)]
+    }
+    pub fn draw(&self, ctx: &mut RenderContext, mesh: &Mesh) {
+        //! Draws the trail of mesh. Draw the mesh itself afterwards so it's on top of its trail.
+        let count = self.transforms.len();
+        match self.style {
+            TrailStyle::Line => {
+                for (i, (start, end)) in self
+                    .transforms
+                    .iter()
+                    .zip(self.transforms.iter().skip(1))
+                    .enumerate()
+                {
+                    ctx.line(start.position, end.position, self.char_at(i, count - 1));
+                }
+            }
+            TrailStyle::Ghosts => {
+                let vertices = mesh.get_verticies();
+                for (i, transform) in self
+                    .transforms
+                    .iter()
+                    .take(count.saturating_sub(1))
+                    .enumerate()
+                {
+                    let char = self.char_at(i, count - 1);
+                    ctx.with_model(transform.matrix(), |ctx| {
+                        for (a, b) in mesh.get_edges() {
+                            if let (Some(a), Some(b)) = (vertices.get(a), vertices.get(b)) {
+                                ctx.line(*a, *b, char);
+                            }
+                        }
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::char_buffer::CharBuffer;
+    use crate::rendering::{Camera, Renderer};
+    use crate::{vec2, vec3, Vector2, Vector3};
+
+    fn renderer() -> Renderer {
+        Renderer::new(
+            vec![],
+            Camera {
+                position: vec3!(0.0, 0.0, -20.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(1.0, 1.0),
+            },
+        )
+    }
+
+    #[test]
+    fn recording() {
+        let mut cube = crate::create_cube();
+        let mut trail = Trail::new(3);
+        trail.spacing = 0.5;
+        for x in 0..5 {
+            cube.position.x = x as Scalar;
+            trail.record(&cube);
+            //Standing still doesn't add anything
+            trail.record(&cube);
+        }
+        let xs: Vec<Scalar> = trail
+            .get_transforms()
+            .iter()
+            .map(|x| x.position.x)
+            .collect();
+        assert_eq!(xs, vec![2.0, 3.0, 4.0]);
+        trail.clear();
+        assert!(trail.get_transforms().is_empty());
+    }
+
+    #[test]
+    fn fading() {
+        let renderer = renderer();
+        let mut cube = crate::create_cube();
+        let mut trail = Trail::new(5);
+        for x in -4..=4 {
+            cube.position.x = x as Scalar * 2.0;
+            trail.record(&cube);
+        }
+        let mut buf = CharBuffer::new(40, 20);
+        let mut ctx = RenderContext::new(&renderer, &mut buf);
+        trail.draw(&mut ctx, &cube);
+        //A line from x = 0 to x = 8, the oldest part faintest
+        let text = buf.to_string();
+        assert!(text.contains('.') && text.contains('*'));
+        assert_eq!(buf.get_char(20, 10), Some('.'));
+
+        trail.style = TrailStyle::Ghosts;
+        let mut ghosts = CharBuffer::new(40, 20);
+        let mut ctx = RenderContext::new(&renderer, &mut ghosts);
+        trail.draw(&mut ctx, &cube);
+        //Four ghosts, leaving out the cube's current position
+        let text = ghosts.to_string();
+        assert!(['.', ':', '+', '*'].iter().all(|x| text.contains(*x)));
+    }
+}
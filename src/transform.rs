@@ -0,0 +1,257 @@
+#[cfg(not(feature = "std"))]
+use super::float::Float;
+use super::matrix::Mat4;
+use super::{Scalar, Vector3};
+
+/// A position, rotation (in radians, applied the same way as Vector3::rotate), and scale. Points are scaled, then rotated, then moved.
+/// # Example
+/// ```
+/// # use ascii_renderer::prelude::*;
+/// let transform = Transform::new(vec3!(0.0, 0.0, 5.0), vec3!(0.0, 0.0, 0.0), vec3!(2.0, 2.0, 2.0));
+/// let world = transform.transform_point(vec3!(1.0, 0.0, 0.0));
+/// assert_eq!(world, vec3!(2.0, 0.0, 5.0));
+/// assert_eq!(transform.inverse_transform_point(world), vec3!(1.0, 0.0, 0.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub position: Vector3,
+    pub rotation: Vector3,
+    pub scale: Vector3,
+}
+
+impl Transform {
+    pub fn new(position: Vector3, rotation: Vector3, scale: Vector3) -> Self {
+        Self {
+            position,
+            rotation,
+            scale,
+        }
+    }
+    pub fn identity() -> Self {
+        Self::new(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(1.0, 1.0, 1.0),
+        )
+    }
+    pub fn from_position(position: Vector3) -> Self {
+        Self {
+            position,
+            ..Self::identity()
+        }
+    }
+    pub fn matrix(&self) -> Mat4 {
+        //! The matrix that scales, rotates, and then moves points.
+        Mat4::translation(self.position) * Mat4::rotation(self.rotation) * Mat4::scale(self.scale)
+    }
+    pub fn inverse_matrix(&self) -> Option<Mat4> {
+        //! The matrix that undoes matrix(). Returns None if any axis has a scale of 0.
+        if self.scale.x == 0.0 || self.scale.y == 0.0 || self.scale.z == 0.0 {
+            return None;
+        }
+        let inverse_scale =
+            Vector3::new(1.0 / self.scale.x, 1.0 / self.scale.y, 1.0 / self.scale.z);
+        Some(
+            Mat4::scale(inverse_scale)
+                * Mat4::rotation(self.rotation).transpose()
+                * Mat4::translation(-self.position),
+        )
+    }
+    pub fn transform_point(&self, point: Vector3) -> Vector3 {
+        //! Converts a point from local space to world space.
+        (point * self.scale).rotate(self.rotation) + self.position
+    }
+    pub fn transform_vector(&self, vector: Vector3) -> Vector3 {
+        //! Like transform_point, but ignores the position. Used for directions and offsets.
+        (vector * self.scale).rotate(self.rotation)
+    }
+    pub fn inverse_transform_point(&self, point: Vector3) -> Vector3 {
+        //! Converts a point from world space to local space.
+        Mat4::rotation(self.rotation)
+            .transpose()
+            .transform_vector(point - self.position)
+            / self.scale
+    }
+    pub fn inverse_transform_vector(&self, vector: Vector3) -> Vector3 {
+        Mat4::rotation(self.rotation)
+            .transpose()
+            .transform_vector(vector)
+            / self.scale
+    }
+    pub fn compose(&self, child: &Transform) -> Transform {
+        //! Returns the world transform of a child whose transform is relative to self. Exact as long as self's scale is the same on every axis; non-uniform scales on rotated parents would need a shear, which a Transform can't store, so use `self.matrix() * child.matrix()` for those.
+        Transform {
+            position: self.transform_point(child.position),
+            rotation: euler_from_matrix(
+                &(Mat4::rotation(self.rotation) * Mat4::rotation(child.rotation)),
+            ),
+            scale: self.scale * child.scale,
+        }
+    }
+    pub fn inverse(&self) -> Transform {
+        //! Returns the transform that undoes this one. As with compose(), this is only exact when the scale is the same on every axis.
+        let scale = Vector3::new(1.0, 1.0, 1.0) / self.scale;
+        let rotation = Mat4::rotation(self.rotation).transpose();
+        Transform {
+            position: -(rotation.transform_vector(self.position) * scale),
+            rotation: euler_from_matrix(&rotation),
+            scale,
+        }
+    }
+    pub fn forward(&self) -> Vector3 {
+        //! The direction +z points after rotation
+        Vector3::new(0.0, 0.0, 1.0).rotate(self.rotation)
+    }
+    pub fn right(&self) -> Vector3 {
+        //! The direction +x points after rotation
+        Vector3::new(1.0, 0.0, 0.0).rotate(self.rotation)
+    }
+    pub fn up(&self) -> Vector3 {
+        //! The direction +y points after rotation
+        Vector3::new(0.0, 1.0, 0.0).rotate(self.rotation)
+    }
+    pub fn lerp(&self, other: &Transform, t: Scalar) -> Transform {
+        //! Blends from self (at t = 0.0) to other (at t = 1.0). Each rotation angle turns the short way around, so going from just under a full turn to just over 0 doesn't spin all the way back.
+        let turn = |from: Scalar, to: Scalar| {
+            let tau = 2.0 * core::f64::consts::PI as Scalar;
+            let difference = (to - from).rem_euclid(tau);
+            let difference = if difference > tau / 2.0 {
+                difference - tau
+            } else {
+                difference
+            };
+            from + difference * t
+        };
+        Transform {
+            position: self.position.lerp(other.position, t),
+            rotation: Vector3::new(
+                turn(self.rotation.x, other.rotation.x),
+                turn(self.rotation.y, other.rotation.y),
+                turn(self.rotation.z, other.rotation.z),
+            ),
+            scale: self.scale.lerp(other.scale, t),
+        }
+    }
+}
+
+impl core::default::Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl core::ops::Mul for Transform {
+    type Output = Transform;
+    /// Same as compose()
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.compose(&rhs)
+    }
+}
+
+impl core::convert::From<Transform> for Mat4 {
+    fn from(transform: Transform) -> Self {
+        transform.matrix()
+    }
+}
+
+/// Gets the rotation vector (as used by Vector3::rotate and Mat4::rotation) of a pure rotation matrix.
+pub(crate) fn euler_from_matrix(matrix: &Mat4) -> Vector3 {
+    let m = &matrix.data;
+    let y = m[2][0].clamp(-1.0, 1.0).asin();
+    if y.cos().abs() > 0.0001 {
+        Vector3::new((-m[2][1]).atan2(m[2][2]), y, m[1][0].atan2(m[0][0]))
+    } else {
+        //Gimbal lock, x and z rotate around the same axis so all of it is put into x
+        Vector3::new(m[1][2].atan2(m[1][1]), y, 0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec3;
+
+    fn assert_close(a: Vector3, b: Vector3) {
+        assert!((a - b).len() < 0.0001, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn euler_round_trip() {
+        for rotation in [
+            vec3!(0.3, -1.2, 2.5),
+            vec3!(0.0, 1.5707964, 0.4),
+            vec3!(-2.0, 0.1, 0.0),
+        ] {
+            let point = vec3!(1.0, 2.0, 3.0);
+            let extracted = euler_from_matrix(&Mat4::rotation(rotation));
+            assert_close(point.rotate(extracted), point.rotate(rotation));
+        }
+    }
+
+    #[test]
+    fn composition_and_inversion() {
+        let parent = Transform::new(
+            vec3!(1.0, 2.0, 3.0),
+            vec3!(0.5, -0.3, 1.1),
+            vec3!(2.0, 2.0, 2.0),
+        );
+        let child = Transform::new(
+            vec3!(-1.0, 0.5, 0.0),
+            vec3!(0.2, 0.9, -0.4),
+            vec3!(1.0, 3.0, 0.5),
+        );
+        let point = vec3!(0.25, -0.5, 1.0);
+
+        let composed = parent * child;
+        assert_close(
+            composed.transform_point(point),
+            parent.transform_point(child.transform_point(point)),
+        );
+        assert_close(
+            (parent.matrix() * child.matrix()).transform_point(point),
+            composed.transform_point(point),
+        );
+
+        assert_close(
+            parent
+                .inverse()
+                .transform_point(parent.transform_point(point)),
+            point,
+        );
+        assert_close(
+            parent.inverse_transform_point(parent.transform_point(point)),
+            point,
+        );
+        assert_close(
+            child
+                .inverse_matrix()
+                .unwrap()
+                .transform_point(child.transform_point(point)),
+            point,
+        );
+    }
+
+    #[test]
+    fn interpolation() {
+        let from = Transform::new(
+            vec3!(0.0, 0.0, 0.0),
+            vec3!(0.0, 6.0, 0.0),
+            vec3!(1.0, 1.0, 1.0),
+        );
+        let to = Transform::new(
+            vec3!(2.0, 4.0, 0.0),
+            vec3!(0.0, 0.2, 1.0),
+            vec3!(3.0, 1.0, 1.0),
+        );
+        let half = from.lerp(&to, 0.5);
+        assert_close(half.position, vec3!(1.0, 2.0, 0.0));
+        assert_close(half.scale, vec3!(2.0, 1.0, 1.0));
+        //6.0 to 0.2 is shorter going forwards through a full turn
+        let tau = 2.0 * core::f64::consts::PI as Scalar;
+        assert_close(
+            half.rotation,
+            vec3!(0.0, 6.0 + (0.2 + tau - 6.0) / 2.0, 0.5),
+        );
+        assert_eq!(from.lerp(&to, 0.0), from);
+    }
+}
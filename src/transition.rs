@@ -0,0 +1,241 @@
+//! Transitions between two frames, for swapping scenes without a hard cut. They only look at the chars in CharBuffers, so they work with anything that draws into one: keep drawing both the old and new content each frame, and a Transition combines them.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::transition::{Transition, TransitionKind};
+//! let mut old = CharBuffer::new(10, 4);
+//! old.fill('#');
+//! let new = CharBuffer::new(10, 4);
+//! let mut screen = CharBuffer::new(10, 4);
+//! let mut transition = Transition::new(TransitionKind::Iris, 1.0);
+//! //Every frame, until transition.is_finished():
+//! transition.update(0.5);
+//! transition.draw(&old, &new, &mut screen);
+//! ```
+
+use super::char_buffer::CharBuffer;
+use super::demos::{shade, RAMP};
+#[cfg(not(feature = "std"))]
+use super::float::Float;
+use super::noise::hash;
+use super::Scalar;
+
+/// Which edge the new frame comes in from in a wipe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WipeDirection {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransitionKind {
+    /// The new frame slides over the old one from an edge.
+    Wipe(WipeDirection),
+    /// Cells of the old frame break up into random noise, which then settles into the new frame. The seed picks which cells go first.
+    Dissolve(u32),
+    /// The new frame shows through a circle growing from the middle.
+    Iris,
+    /// The old frame fades out, getting lighter chars until it's gone, then the new one fades in the same way.
+    Crossfade,
+}
+
+pub fn density(char: char) -> Scalar {
+    //! Roughly how much of its cell a char covers, from 0.0 for a space to 1.0 for '@'. Chars in demos::RAMP use their place in it, shade blocks and braille patterns how much of them is filled in, and other chars are guessed from what kind of char they are.
+    if let Some(index) = RAMP.iter().position(|&x| x == char) {
+        return index as Scalar / (RAMP.len() - 1) as Scalar;
+    }
+    match char {
+        '░' => return 0.25,
+        '▒' => return 0.5,
+        '▓' => return 0.75,
+        '█' => return 1.0,
+        //The other block elements are halves and quarters
+        '\u{2580}'..='\u{259f}' => return 0.5,
+        '\u{2800}'..='\u{28ff}' => return (char as u32 - 0x2800).count_ones() as Scalar / 8.0,
+        _ => (),
+    }
+    if char.is_whitespace() {
+        0.0
+    } else if char.is_alphanumeric() {
+        0.7
+    } else {
+        0.4
+    }
+}
+
+pub(crate) fn faded(char: char, amount: Scalar) -> char {
+    //The char itself when fully shown, so fading doesn't change anything at either end
+    if amount >= 1.0 {
+        char
+    } else {
+        shade(RAMP, density(char) * amount)
+    }
+}
+
+pub fn blend(
+    kind: TransitionKind,
+    from: &CharBuffer,
+    to: &CharBuffer,
+    progress: f32,
+    out: &mut CharBuffer,
+) {
+    //! Draws the transition from one frame to another into out, which should be the same size. A progress of 0.0 is all from, and 1.0 is all to.
+    let progress = progress.clamp(0.0, 1.0) as Scalar;
+    let (width, height) = out.dimensions;
+    let center = (width as Scalar / 2.0, height as Scalar / 2.0);
+    let max_radius = (center.0 * center.0 + center.1 * center.1).sqrt();
+    for y in 0..height {
+        for x in 0..width {
+            let old = from.get_char(x, y).unwrap_or(' ');
+            let new = to.get_char(x, y).unwrap_or(' ');
+            let char = match kind {
+                TransitionKind::Wipe(direction) => {
+                    let (position, length) = match direction {
+                        WipeDirection::Left => (x as Scalar, width),
+                        WipeDirection::Right => ((width - 1 - x) as Scalar, width),
+                        WipeDirection::Top => (y as Scalar, height),
+                        WipeDirection::Bottom => ((height - 1 - y) as Scalar, height),
+                    };
+                    if position < progress * length as Scalar {
+                        new
+                    } else {
+                        old
+                    }
+                }
+                TransitionKind::Dissolve(seed) => {
+                    //Each cell turns to noise at its own moment in the first half, and settles at the same moment in the second
+                    let cell = hash(seed ^ hash(x as u32 ^ hash(y as u32)));
+                    let moment = (cell >> 8) as Scalar / (1 << 24) as Scalar;
+                    if progress < 0.5 && progress * 2.0 <= moment {
+                        old
+                    } else if progress >= 0.5 && (progress - 0.5) * 2.0 > moment {
+                        new
+                    } else {
+                        RAMP[1 + (cell as usize % (RAMP.len() - 1))]
+                    }
+                }
+                TransitionKind::Iris => {
+                    let (dx, dy) = (x as Scalar + 0.5 - center.0, y as Scalar + 0.5 - center.1);
+                    if (dx * dx + dy * dy).sqrt() < progress * max_radius {
+                        new
+                    } else {
+                        old
+                    }
+                }
+                TransitionKind::Crossfade => {
+                    if progress < 0.5 {
+                        faded(old, 1.0 - progress * 2.0)
+                    } else {
+                        faded(new, progress * 2.0 - 1.0)
+                    }
+                }
+            };
+            let _ = out.set_char(x, y, char);
+        }
+    }
+}
+
+/// A transition playing over time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transition {
+    pub kind: TransitionKind,
+    /// How many seconds it takes.
+    pub duration: f32,
+    time: f32,
+}
+
+impl Transition {
+    pub fn new(kind: TransitionKind, duration: f32) -> Self {
+        Self {
+            kind,
+            duration,
+            time: 0.0,
+        }
+    }
+    pub fn update(&mut self, delta: f32) {
+        self.time += delta;
+    }
+    pub fn restart(&mut self) {
+        self.time = 0.0;
+    }
+    pub fn get_progress(&self) -> f32 {
+        //! How far through the transition is, from 0.0 to 1.0.
+        if self.duration <= 0.0 {
+            return 1.0;
+        }
+        (self.time / self.duration).clamp(0.0, 1.0)
+    }
+    pub fn is_finished(&self) -> bool {
+        self.get_progress() >= 1.0
+    }
+    pub fn draw(&self, from: &CharBuffer, to: &CharBuffer, out: &mut CharBuffer) {
+        //! Draws the transition at its current progress, see blend.
+        blend(self.kind, from, to, self.get_progress(), out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    fn frames() -> (CharBuffer, CharBuffer, CharBuffer) {
+        let mut from = CharBuffer::new(4, 4);
+        from.fill('o');
+        let mut to = CharBuffer::new(4, 4);
+        to.fill('x');
+        (from, to, CharBuffer::new(4, 4))
+    }
+
+    #[test]
+    fn ends_match_frames() {
+        let (from, to, mut out) = frames();
+        for kind in [
+            TransitionKind::Wipe(WipeDirection::Bottom),
+            TransitionKind::Dissolve(3),
+            TransitionKind::Iris,
+            TransitionKind::Crossfade,
+        ] {
+            blend(kind, &from, &to, 0.0, &mut out);
+            assert_eq!(out, from);
+            blend(kind, &from, &to, 1.0, &mut out);
+            assert_eq!(out, to);
+        }
+    }
+
+    #[test]
+    fn kinds() {
+        let (from, to, mut out) = frames();
+        blend(
+            TransitionKind::Wipe(WipeDirection::Right),
+            &from,
+            &to,
+            0.5,
+            &mut out,
+        );
+        assert_eq!(out.to_string(), "o o x x \n".repeat(4));
+        blend(TransitionKind::Iris, &from, &to, 0.4, &mut out);
+        assert_eq!(&out.to_string(), "o o o o \no x x o \no x x o \no o o o \n");
+        //Halfway through a dissolve, every cell is noise
+        blend(TransitionKind::Dissolve(3), &from, &to, 0.5, &mut out);
+        assert!(!out.to_string().contains(['o', 'x']));
+        blend(TransitionKind::Crossfade, &from, &to, 0.25, &mut out);
+        assert_eq!(out.get_char(0, 0), Some(shade(RAMP, 0.35)));
+        blend(TransitionKind::Crossfade, &from, &to, 0.5, &mut out);
+        assert_eq!(out, CharBuffer::new(4, 4));
+    }
+
+    #[test]
+    fn playback() {
+        let mut transition = Transition::new(TransitionKind::Iris, 2.0);
+        transition.update(0.5);
+        assert_eq!(transition.get_progress(), 0.25);
+        transition.update(5.0);
+        assert!(transition.is_finished());
+        transition.restart();
+        assert_eq!(transition.get_progress(), 0.0);
+        assert!(Transition::new(TransitionKind::Iris, 0.0).is_finished());
+    }
+}
@@ -0,0 +1,99 @@
+//! Widgets for drawing into a [ratatui](https://ratatui.rs) layout, behind the `ratatui` feature, so a 3D view can sit next to panels, lists, and the rest of an existing TUI. Like when printing, every char is followed by a space, so a buffer takes up twice its width in terminal columns.
+//! # Example
+//! ```ignore
+//! terminal.draw(|frame| {
+//!     let [view, side] = Layout::horizontal([Constraint::Fill(1), Constraint::Length(20)]).areas(frame.area());
+//!     frame.render_widget(RendererWidget::new(&renderer), view);
+//!     frame.render_widget(Paragraph::new("stats"), side);
+//! })?;
+//! ```
+
+use super::char_buffer::CharBuffer;
+use super::rendering::Renderer;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::widgets::Widget;
+
+/// Draws a renderer's scene sized to fill the area it is given.
+#[derive(Debug, Clone, Copy)]
+pub struct RendererWidget<'a> {
+    renderer: &'a Renderer,
+    style: Style,
+}
+
+impl<'a> RendererWidget<'a> {
+    pub fn new(renderer: &'a Renderer) -> Self {
+        Self {
+            renderer,
+            style: Style::default(),
+        }
+    }
+    pub fn style(mut self, style: Style) -> Self {
+        //! The style (colors, bold, etc.) the chars are drawn with.
+        self.style = style;
+        self
+    }
+}
+
+impl Widget for RendererWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut chars = CharBuffer::new(area.width as usize / 2, area.height as usize);
+        self.renderer.draw(&mut chars);
+        write_char_buffer(&chars, area, buf, self.style);
+    }
+}
+
+/// Copies the buffer into the top left of the area, cutting off anything that doesn't fit.
+impl Widget for &CharBuffer {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        write_char_buffer(self, area, buf, Style::default());
+    }
+}
+
+fn write_char_buffer(chars: &CharBuffer, area: Rect, buf: &mut Buffer, style: Style) {
+    let area = area.intersection(buf.area);
+    for (y, row) in chars.data.iter().enumerate().take(area.height as usize) {
+        for (x, &char) in row.iter().enumerate().take(area.width as usize / 2) {
+            let column = area.x + x as u16 * 2;
+            let line = area.y + y as u16;
+            if let Some(cell) = buf.cell_mut((column, line)) {
+                cell.set_char(char).set_style(style);
+            }
+            if let Some(cell) = buf.cell_mut((column + 1, line)) {
+                cell.set_char(' ').set_style(style);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn fills_area() {
+        let mut chars = CharBuffer::new(3, 2);
+        chars.set_char(1, 1, '#').unwrap();
+        let mut buf = Buffer::empty(Rect::new(0, 0, 10, 3));
+        chars.render(Rect::new(2, 1, 8, 2), &mut buf);
+        assert_eq!(buf.cell((4, 2)).unwrap().symbol(), "#");
+        assert_eq!(buf.cell((2, 1)).unwrap().symbol(), " ");
+
+        let renderer = Renderer::new(
+            vec![crate::create_cube()],
+            Camera {
+                position: vec3!(0.0, 0.0, -5.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(0.7, 0.7),
+            },
+        );
+        let mut buf = Buffer::empty(Rect::new(0, 0, 40, 20));
+        RendererWidget::new(&renderer).render(buf.area, &mut buf);
+        let mut expected = CharBuffer::new(20, 20);
+        renderer.draw(&mut expected);
+        let text: String = buf.content().iter().map(|x| x.symbol()).collect();
+        assert_eq!(text, expected.to_string().replace('\n', ""));
+    }
+}
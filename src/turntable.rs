@@ -0,0 +1,201 @@
+//! Automatic rotation for model viewers. A Turntable attached to a mesh spins it in place, and one attached to the camera orbits it around a point while it keeps facing the same way relative to it, so a model can be shown from every side. Either can swing back and forth instead of turning all the way around. Turntables are added to a Renderer and moved on by Renderer::update_turntables every frame.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::turntable::{Turntable, TurntableTarget};
+//! let mut renderer = Renderer::new(
+//!     vec![ascii_renderer::create_cube()],
+//!     Camera {
+//!         position: vec3!(0.0, 0.0, -6.0),
+//!         rotation: vec3!(0.0, 0.0, 0.0),
+//!         fov: vec2!(1.0, 1.0),
+//!     },
+//! );
+//! renderer.add_turntable(TurntableTarget::Mesh(0), Turntable::new(1.0));
+//! //In Logic::process, with the frame's delta
+//! renderer.update_turntables(0.5);
+//! assert!((renderer.meshs[0].rotation.y - 0.5).abs() < 0.001);
+//! ```
+
+#[cfg(not(feature = "std"))]
+use super::float::Float;
+use super::matrix::Mat4;
+use super::rendering::Renderer;
+use super::transform::euler_from_matrix;
+use super::{vec3, Scalar, Vector3};
+
+/// What a Turntable turns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TurntableTarget {
+    /// The mesh at this index in Renderer::meshs, spun around its position.
+    Mesh(usize),
+    /// The renderer's camera, orbited around the turntable's center.
+    Camera,
+}
+
+/// Turns something around an axis at a steady speed, or swings it back and forth. Every setting is a public field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Turntable {
+    /// The global axis to turn around. It doesn't need to be normalized. For +y (the default), positive speeds turn the same way as increasing rotation.y does.
+    pub axis: Vector3,
+    /// In radians per second. Negative speeds turn the other way.
+    pub speed: Scalar,
+    /// When set, the turntable swings this many radians either way instead of turning all the way around, moving fastest (at speed) through the middle.
+    pub oscillation: Option<Scalar>,
+    /// The point the camera orbits. Meshes ignore this and spin around their own position.
+    pub center: Vector3,
+    /// Seconds the turntable has been turning for.
+    pub time: Scalar,
+}
+
+impl Turntable {
+    pub fn new(speed: Scalar) -> Self {
+        //! A turntable around +y, centered on the origin.
+        Self {
+            axis: vec3!(0.0, 1.0, 0.0),
+            speed,
+            oscillation: None,
+            center: vec3!(0.0, 0.0, 0.0),
+            time: 0.0,
+        }
+    }
+    pub fn with_axis(mut self, axis: Vector3) -> Self {
+        self.axis = axis;
+        self
+    }
+    pub fn with_oscillation(mut self, amplitude: Scalar) -> Self {
+        self.oscillation = Some(amplitude);
+        self
+    }
+    pub fn with_center(mut self, center: Vector3) -> Self {
+        self.center = center;
+        self
+    }
+    pub fn angle(&self) -> Scalar {
+        //! How far the turntable has turned since it started, in radians.
+        match self.oscillation {
+            Some(amplitude) if amplitude > 0.0 => {
+                amplitude * (self.time * self.speed / amplitude).sin()
+            }
+            Some(_) => 0.0,
+            None => self.time * self.speed,
+        }
+    }
+    pub fn advance(&mut self, delta: f32) -> Mat4 {
+        //! Moves the turntable forward by delta seconds, returning the rotation it turned by.
+        let before = self.angle();
+        self.time += delta as Scalar;
+        axis_rotation(self.axis, self.angle() - before)
+    }
+}
+
+fn axis_rotation(axis: Vector3, angle: Scalar) -> Mat4 {
+    //Rodrigues' rotation formula, turning the same way as Mat4::rotation_x and rotation_y do
+    let length = axis.len();
+    if length == 0.0 || angle == 0.0 {
+        return Mat4::identity();
+    }
+    let k = axis / length;
+    let (sin, cos) = (-angle).sin_cos();
+    let t = 1.0 - cos;
+    Mat4::new([
+        [
+            cos + k.x * k.x * t,
+            k.x * k.y * t - k.z * sin,
+            k.x * k.z * t + k.y * sin,
+            0.0,
+        ],
+        [
+            k.y * k.x * t + k.z * sin,
+            cos + k.y * k.y * t,
+            k.y * k.z * t - k.x * sin,
+            0.0,
+        ],
+        [
+            k.z * k.x * t - k.y * sin,
+            k.z * k.y * t + k.x * sin,
+            cos + k.z * k.z * t,
+            0.0,
+        ],
+        [0.0, 0.0, 0.0, 1.0],
+    ])
+}
+
+impl Renderer {
+    pub fn add_turntable(&mut self, target: TurntableTarget, turntable: Turntable) {
+        //! Starts turning target every update_turntables. A target can have more than one turntable.
+        self.turntables.push((target, turntable));
+    }
+    pub fn update_turntables(&mut self, delta: f32) {
+        //! Moves every turntable forward by delta seconds, turning what they're attached to. Turntables for meshes that don't exist are skipped. Other changes to the meshes and camera are kept, since each update only adds on how far the turntable turned.
+        for (target, turntable) in self.turntables.iter_mut() {
+            let spin = turntable.advance(delta);
+            match *target {
+                TurntableTarget::Mesh(index) => {
+                    if let Some(mesh) = self.meshs.get_mut(index) {
+                        mesh.rotation = euler_from_matrix(&(spin * Mat4::rotation(mesh.rotation)));
+                    }
+                }
+                TurntableTarget::Camera => {
+                    let camera = &mut self.camera;
+                    camera.position = turntable.center
+                        + spin.transform_vector(camera.position - turntable.center);
+                    //The view turns the other way, so the camera keeps facing the center the same way
+                    camera.rotation =
+                        euler_from_matrix(&(Mat4::rotation(camera.rotation) * spin.transpose()));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::Camera;
+    use crate::{vec2, Vector2};
+    use alloc::vec;
+
+    fn close(a: Vector3, b: Vector3) -> bool {
+        (a - b).len() < 0.001
+    }
+
+    #[test]
+    fn turning() {
+        //Around x and y, the same way as the rotation angles
+        assert!(close(
+            axis_rotation(vec3!(2.0, 0.0, 0.0), 0.7).transform_vector(vec3!(0.0, 1.0, 1.0)),
+            Mat4::rotation_x(0.7).transform_vector(vec3!(0.0, 1.0, 1.0))
+        ));
+        let mut swing = Turntable::new(2.0).with_oscillation(0.5);
+        for _ in 0..100 {
+            swing.advance(0.1);
+            assert!(swing.angle().abs() <= 0.5);
+        }
+
+        let mut renderer = Renderer::new(
+            vec![crate::create_cube()],
+            Camera {
+                position: vec3!(0.0, 0.0, -5.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(1.0, 1.0),
+            },
+        );
+        renderer.add_turntable(TurntableTarget::Mesh(0), Turntable::new(1.0));
+        renderer.add_turntable(TurntableTarget::Mesh(3), Turntable::new(1.0));
+        renderer.add_turntable(
+            TurntableTarget::Camera,
+            Turntable::new(core::f64::consts::FRAC_PI_2 as Scalar),
+        );
+        renderer.update_turntables(0.5);
+        assert!(close(renderer.meshs[0].rotation, vec3!(0.0, 0.5, 0.0)));
+        renderer.update_turntables(0.5);
+        assert!(close(renderer.meshs[0].rotation, vec3!(0.0, 1.0, 0.0)));
+
+        //A quarter turn around the cube, still looking at it
+        let camera = &renderer.camera;
+        assert!(close(camera.position, vec3!(5.0, 0.0, 0.0)));
+        let middle = camera.map_point_uv(vec3!(0.0, 0.0, 0.0));
+        assert!((middle - vec2!(0.5, 0.5)).len() < 0.001);
+    }
+}
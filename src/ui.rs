@@ -0,0 +1,802 @@
+//! Immediate-mode widgets (buttons, toggles, sliders, menus, text boxes, and dialogs) drawn straight into the CharBuffer, for exposing a few settings without pulling in a TUI framework. Widgets are declared again every frame, and each call both draws the widget and returns whether it was used.
+//!
+//! Widgets are driven by an InputMap, through the actions named by Ui's constants. Focus moves between widgets with NEXT and PREVIOUS, the focused widget is used with ACTIVATE, and sliders are moved with DECREASE and INCREASE. With the mouse, hovering over a widget focuses it and CLICK uses it. Ui::bind_defaults binds all of these to the usual keys and gamepad buttons.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::input::InputMap;
+//! # use ascii_renderer::ui::Ui;
+//! struct Viewer {
+//!     ui: Ui,
+//!     input: InputMap,
+//!     wireframe: bool,
+//!     zoom: f32,
+//! }
+//! impl Logic for Viewer {
+//!     fn process(&mut self, screen_buf: &mut CharBuffer, _delta: f32) -> ProcessReturn {
+//!         screen_buf.fill(' ');
+//!         let mut ui = self.ui.begin(screen_buf, &self.input);
+//!         ui.toggle("Wireframe", &mut self.wireframe);
+//!         ui.slider("Zoom", &mut self.zoom, 0.5..=4.0);
+//!         let quit = ui.button("Quit");
+//!         drop(ui);
+//!         self.input.end_frame();
+//!         if quit { ProcessReturn::End } else { ProcessReturn::Continue }
+//!     }
+//!     fn input(&mut self, event: &InputEvent) {
+//!         self.input.handle(event);
+//!     }
+//! }
+//! let mut input = InputMap::new();
+//! Ui::bind_defaults(&mut input);
+//! let viewer = Viewer { ui: Ui::new(), input, wireframe: true, zoom: 1.0 };
+//! ```
+
+use super::char_buffer::{CharBuffer, Region};
+use super::charset::BorderStyle;
+use super::input::{Binding, InputMap};
+use std::ops::RangeInclusive;
+
+/// How many cells wide a slider's track is.
+const SLIDER_WIDTH: usize = 10;
+
+/// The state widgets keep between frames, like which one is focused. The state of menus and text boxes is kept in their own structs instead, so they can be stored next to what they're for.
+#[derive(Debug, Clone, Default)]
+pub struct Ui {
+    focus: usize,
+    widget_count: usize,
+    last_pointer: Option<(usize, usize)>,
+    capturing: bool,
+    typing: bool,
+    modal: bool,
+    focus_before_modal: usize,
+    /// What dialogs are drawn with, ex: Charset::Unicode.border() for box drawing chars.
+    pub border: BorderStyle,
+}
+
+impl Ui {
+    pub const NEXT: &'static str = "ui_next";
+    pub const PREVIOUS: &'static str = "ui_previous";
+    pub const ACTIVATE: &'static str = "ui_activate";
+    pub const DECREASE: &'static str = "ui_decrease";
+    pub const INCREASE: &'static str = "ui_increase";
+    pub const CLICK: &'static str = "ui_click";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn bind_defaults(input: &mut InputMap) {
+        //! Binds the UI's actions to the arrow keys, Tab, Enter, and space, the gamepad's d-pad and South button, and the left mouse button.
+        let bindings = [
+            (
+                Self::NEXT,
+                vec![
+                    Binding::key("ArrowDown"),
+                    Binding::key("Tab"),
+                    Binding::gamepad_button("DPadDown"),
+                ],
+            ),
+            (
+                Self::PREVIOUS,
+                vec![Binding::key("ArrowUp"), Binding::gamepad_button("DPadUp")],
+            ),
+            (
+                Self::ACTIVATE,
+                vec![
+                    Binding::key("Enter"),
+                    Binding::key(" "),
+                    Binding::gamepad_button("South"),
+                ],
+            ),
+            (
+                Self::DECREASE,
+                vec![
+                    Binding::key("ArrowLeft"),
+                    Binding::gamepad_button("DPadLeft"),
+                ],
+            ),
+            (
+                Self::INCREASE,
+                vec![
+                    Binding::key("ArrowRight"),
+                    Binding::gamepad_button("DPadRight"),
+                ],
+            ),
+            (Self::CLICK, vec![Binding::mouse_button("Left")]),
+        ];
+        for (action, bindings) in bindings {
+            for binding in bindings {
+                input.bind(action, binding);
+            }
+        }
+    }
+    pub fn get_focus(&self) -> usize {
+        //! The index of the focused widget, counting every focusable widget in the order they were declared last frame.
+        self.focus
+    }
+    pub fn set_focus(&mut self, index: usize) {
+        self.focus = index;
+    }
+    pub fn is_typing(&self) -> bool {
+        //! True if a text box was focused last frame, ex: to stop letter keys from triggering other actions while typing.
+        self.typing
+    }
+    pub fn begin<'a>(&'a mut self, buf: &'a mut CharBuffer, input: &'a InputMap) -> UiFrame<'a> {
+        //! Starts declaring this frame's widgets, moving focus first if NEXT or PREVIOUS was pressed. Widgets are laid out one per row, starting from the top left corner.
+        if self.widget_count > 0 && !self.capturing {
+            if input.is_pressed(Self::NEXT) {
+                self.focus = (self.focus + 1) % self.widget_count;
+            }
+            if input.is_pressed(Self::PREVIOUS) {
+                self.focus = (self.focus + self.widget_count - 1) % self.widget_count;
+            }
+        }
+        let pointer = input.get_pointer();
+        let pointer_moved = pointer != self.last_pointer;
+        self.last_pointer = pointer;
+        UiFrame {
+            buf,
+            input,
+            index: 0,
+            cursor: (0, 0),
+            pointer_moved,
+            enabled: !self.modal,
+            was_capturing: self.capturing,
+            capturing: false,
+            typing: false,
+            dialog_buttons: None,
+            ui: self,
+        }
+    }
+}
+
+/// Where a widget was placed, and how it is being interacted with this frame.
+#[derive(Debug, Clone, Copy)]
+struct WidgetState {
+    index: usize,
+    x: usize,
+    y: usize,
+    focused: bool,
+    clicked: bool,
+    /// Where the mouse is over the widget, relative to the start of its text.
+    pointer: Option<(usize, usize)>,
+}
+
+/// The widgets being declared for one frame, see Ui::begin. Focus is kept in range of however many widgets were declared when this is dropped.
+#[derive(Debug)]
+pub struct UiFrame<'a> {
+    ui: &'a mut Ui,
+    buf: &'a mut CharBuffer,
+    input: &'a InputMap,
+    index: usize,
+    cursor: (usize, usize),
+    pointer_moved: bool,
+    enabled: bool,
+    was_capturing: bool,
+    capturing: bool,
+    typing: bool,
+    /// Some once a dialog has been declared, with how many buttons it had if it was just opened.
+    dialog_buttons: Option<usize>,
+}
+
+impl UiFrame<'_> {
+    pub fn at(&mut self, x: usize, y: usize) -> &mut Self {
+        //! Moves where the next widget goes. The ones after it go on the rows below it.
+        self.cursor = (x, y);
+        self
+    }
+    pub fn get_cursor(&self) -> (usize, usize) {
+        self.cursor
+    }
+    pub fn set_enabled(&mut self, enabled: bool) {
+        //! Disabled widgets are still drawn, but can't be focused or used. While a dialog is open, every widget other than the dialog's buttons is disabled.
+        self.enabled = enabled;
+    }
+    pub fn label(&mut self, text: &str) {
+        //! Text that can't be focused, lined up with the widgets' text.
+        let (x, y) = self.cursor;
+        self.buf.write_str(x + 2, y, text);
+        self.cursor.1 += 1;
+    }
+    pub fn button(&mut self, label: &str) -> bool {
+        //! A button, returning true on the frame it's used.
+        let state = self.widget(label.chars().count() + 2, 1);
+        self.buf.write_str(state.x, state.y, &format!("[{label}]"));
+        state.clicked || state.focused && self.input.is_pressed(Ui::ACTIVATE)
+    }
+    pub fn toggle(&mut self, label: &str, value: &mut bool) -> bool {
+        //! A checkbox that flips value when used, returning true if it changed.
+        let state = self.widget(label.chars().count() + 4, 1);
+        let changed = state.clicked || state.focused && self.input.is_pressed(Ui::ACTIVATE);
+        if changed {
+            *value = !*value;
+        }
+        let check = if *value { 'x' } else { ' ' };
+        self.buf
+            .write_str(state.x, state.y, &format!("[{check}] {label}"));
+        changed
+    }
+    pub fn slider(&mut self, label: &str, value: &mut f32, range: RangeInclusive<f32>) -> bool {
+        //! A bar for picking a value in range, returning true if it changed. DECREASE and INCREASE move it a tenth of the range at a time, and clicking on the bar moves it to where it was clicked.
+        let label_width = label.chars().count();
+        let state = self.widget(label_width + SLIDER_WIDTH + 3, 1);
+        let (start, end) = (*range.start(), *range.end());
+        let old = *value;
+        let step = (end - start) / SLIDER_WIDTH as f32;
+        if state.focused && self.input.is_pressed(Ui::DECREASE) {
+            *value -= step;
+        }
+        if state.focused && self.input.is_pressed(Ui::INCREASE) {
+            *value += step;
+        }
+        let track_x = label_width + 2;
+        if let (true, Some((x, _))) = (state.clicked, state.pointer) {
+            if x >= track_x {
+                let cell = (x - track_x).min(SLIDER_WIDTH - 1);
+                *value = start + (end - start) * (cell + 1) as f32 / SLIDER_WIDTH as f32;
+            }
+        }
+        *value = value.clamp(start.min(end), start.max(end));
+
+        let filled = if end == start {
+            SLIDER_WIDTH
+        } else {
+            ((*value - start) / (end - start) * SLIDER_WIDTH as f32).round() as usize
+        };
+        let track: String = (0..SLIDER_WIDTH)
+            .map(|i| if i < filled { '=' } else { '-' })
+            .collect();
+        self.buf
+            .write_str(state.x, state.y, &format!("{label} [{track}]"));
+        *value != old
+    }
+    pub fn menu(&mut self, menu: &mut Menu) -> Option<usize> {
+        //! A scrolling list of items, returning the index of an item when it's chosen with ACTIVATE or clicked. While the menu is focused, NEXT and PREVIOUS move through its items instead of between widgets, until they go past its first or last item.
+        let item_width = menu.items.iter().map(|x| x.chars().count()).max();
+        let width = item_width.unwrap_or(0) + 2;
+        let height = menu.height.max(1);
+        let state = self.widget(width, height);
+        let count = menu.items.len();
+        let mut chosen = None;
+        if state.focused {
+            if self.was_capturing && self.input.is_pressed(Ui::NEXT) {
+                if menu.selected + 1 < count {
+                    menu.selected += 1;
+                } else {
+                    self.move_focus(state.index, true);
+                }
+            }
+            if self.was_capturing && self.input.is_pressed(Ui::PREVIOUS) {
+                if menu.selected > 0 {
+                    menu.selected -= 1;
+                } else {
+                    self.move_focus(state.index, false);
+                }
+            }
+            if self.input.is_pressed(Ui::ACTIVATE) && count > 0 {
+                chosen = Some(menu.selected);
+            }
+            self.capturing |= self.ui.focus == state.index;
+        }
+        if let Some((_, row)) = state.pointer {
+            let item = menu.scroll + row;
+            if item < count && (self.pointer_moved || state.clicked) {
+                menu.selected = item;
+                chosen = chosen.or(state.clicked.then_some(item));
+            }
+        }
+        menu.scroll_to_selected();
+
+        for row in 0..height {
+            let item = menu.scroll + row;
+            let Some(text) = menu.items.get(item) else {
+                break;
+            };
+            self.buf.write_str(state.x, state.y + row, text);
+            if item == menu.selected && !state.focused {
+                let _ = self.buf.set_char(state.x - 2, state.y + row, '-');
+            }
+        }
+        if state.focused {
+            let _ = self
+                .buf
+                .set_char(state.x - 2, state.y + menu.selected - menu.scroll, '>');
+        }
+        //Arrows for when there are more items above or below
+        if menu.scroll > 0 {
+            let _ = self.buf.set_char(state.x + width - 1, state.y, '^');
+        }
+        if menu.scroll + height < count {
+            let _ = self
+                .buf
+                .set_char(state.x + width - 1, state.y + height - 1, 'v');
+        }
+        chosen
+    }
+    pub fn text_box(&mut self, text_box: &mut TextBox, width: usize) -> bool {
+        //! A box for typing a line of text into, width chars wide, returning true when Enter is pressed in it. While it's focused, typed keys go into the box, see Ui::is_typing. The cursor is moved with the arrow keys, Home, End, or by clicking.
+        let width = width.max(1);
+        let state = self.widget(width + 2, 1);
+        let mut submitted = false;
+        if state.focused {
+            self.typing = true;
+            for key in self.input.get_typed() {
+                submitted |= text_box.type_key(key);
+            }
+        }
+        if let (true, Some((x, _))) = (state.clicked, state.pointer) {
+            text_box.cursor = (text_box.scroll + x.saturating_sub(1)).min(text_box.len());
+        }
+
+        let mut shown: Vec<char> = text_box.text.chars().collect();
+        if state.focused {
+            shown.insert(text_box.cursor, '|');
+        }
+        //Scroll just far enough to keep the cursor in the box
+        text_box.scroll = text_box
+            .scroll
+            .min(text_box.cursor)
+            .max((text_box.cursor + 1).saturating_sub(width));
+        let visible: String = shown
+            .iter()
+            .skip(text_box.scroll)
+            .chain(std::iter::repeat(&' '))
+            .take(width)
+            .collect();
+        self.buf
+            .write_str(state.x, state.y, &format!("[{visible}]"));
+        submitted
+    }
+    pub fn dialog(&mut self, dialog: &Dialog) -> Option<usize> {
+        //! Draws the dialog in the middle of the buffer, over everything declared before it, returning the index of a button when it's used. Declare it last, and stop declaring it once it's answered. While it's open, only its buttons can be focused, and focus goes back to where it was once it's closed.
+        let lines: Vec<&str> = dialog.message.lines().collect();
+        let content_width = lines
+            .iter()
+            .map(|x| x.chars().count())
+            .chain(dialog.buttons.iter().map(|x| x.chars().count() + 4))
+            .chain([dialog.title.chars().count() + 2])
+            .max()
+            .unwrap_or(0);
+        let (width, height) = (content_width + 4, lines.len() + dialog.buttons.len() + 3);
+        let x = self.buf.dimensions.0.saturating_sub(width) / 2;
+        let y = self.buf.dimensions.1.saturating_sub(height) / 2;
+
+        let inside = " ".repeat(width - 2);
+        for row in 1..height - 1 {
+            self.buf.write_str(x + 1, y + row, &inside);
+        }
+        self.ui
+            .border
+            .draw(self.buf, Region::new(x, y, width, height));
+        self.buf.write_str(x + 2, y, &format!(" {} ", dialog.title));
+        for (row, line) in lines.iter().enumerate() {
+            self.buf.write_str(x + 2, y + 1 + row, line);
+        }
+
+        //The buttons can't be used on the frame the dialog opens, so the key that opened it doesn't answer it too
+        let opening = !self.ui.modal;
+        if opening && self.dialog_buttons.is_none() {
+            self.ui.focus_before_modal = self.ui.focus;
+            let _ = self.buf.set_char(x + 2, y + lines.len() + 2, '>');
+        }
+        *self.dialog_buttons.get_or_insert(0) += dialog.buttons.len();
+        let (cursor, enabled) = (self.cursor, self.enabled);
+        self.enabled = !opening;
+        self.at(x + 2, y + lines.len() + 2);
+        let mut chosen = None;
+        for (i, button) in dialog.buttons.iter().enumerate() {
+            if self.button(button) {
+                chosen = Some(i);
+            }
+        }
+        (self.cursor, self.enabled) = (cursor, enabled);
+        chosen
+    }
+    fn move_focus(&mut self, from: usize, forward: bool) {
+        let count = self.ui.widget_count.max(from + 1);
+        self.ui.focus = if forward {
+            (from + 1) % count
+        } else {
+            (from + count - 1) % count
+        };
+    }
+    fn widget(&mut self, width: usize, height: usize) -> WidgetState {
+        let (x, y) = self.cursor;
+        self.cursor.1 += height;
+        let pointer = self
+            .input
+            .get_pointer()
+            .filter(|&(px, py)| py >= y && py < y + height && px >= x && px < x + 2 + width)
+            .map(|(px, py)| (px.saturating_sub(x + 2), py - y));
+        if !self.enabled {
+            return WidgetState {
+                index: usize::MAX,
+                x: x + 2,
+                y,
+                focused: false,
+                clicked: false,
+                pointer: None,
+            };
+        }
+        let index = self.index;
+        self.index += 1;
+        let clicked = pointer.is_some() && self.input.is_pressed(Ui::CLICK);
+        if pointer.is_some() && (self.pointer_moved || clicked) {
+            self.ui.focus = index;
+        }
+        let focused = self.ui.focus == index;
+        if focused && height == 1 {
+            let _ = self.buf.set_char(x, y, '>');
+        }
+        WidgetState {
+            index,
+            x: x + 2,
+            y,
+            focused,
+            clicked,
+            pointer,
+        }
+    }
+}
+
+impl Drop for UiFrame<'_> {
+    fn drop(&mut self) {
+        let ui = &mut *self.ui;
+        let mut count = self.index;
+        match (self.dialog_buttons, ui.modal) {
+            //The dialog just opened, so only its buttons are focusable next frame
+            (Some(buttons), false) => {
+                ui.focus = 0;
+                count = buttons;
+            }
+            (None, true) => ui.focus = ui.focus_before_modal,
+            _ => {}
+        }
+        ui.modal = self.dialog_buttons.is_some();
+        ui.capturing = self.capturing;
+        ui.typing = self.typing;
+        ui.widget_count = count;
+        if ui.focus >= count && count > 0 {
+            ui.focus = count - 1;
+        }
+    }
+}
+
+/// The items of a list menu, and which one is selected.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Menu {
+    pub items: Vec<String>,
+    /// How many items are shown at once. The menu scrolls to keep the selected item in view.
+    pub height: usize,
+    selected: usize,
+    scroll: usize,
+}
+
+impl Menu {
+    pub fn new(items: Vec<String>, height: usize) -> Self {
+        Self {
+            items,
+            height,
+            selected: 0,
+            scroll: 0,
+        }
+    }
+    pub fn get_selected(&self) -> usize {
+        self.selected
+    }
+    pub fn set_selected(&mut self, index: usize) {
+        self.selected = index;
+        self.scroll_to_selected();
+    }
+    pub fn get_scroll(&self) -> usize {
+        //! The index of the first item shown.
+        self.scroll
+    }
+    fn scroll_to_selected(&mut self) {
+        let height = self.height.max(1);
+        self.selected = self.selected.min(self.items.len().saturating_sub(1));
+        self.scroll = self
+            .scroll
+            .min(self.selected)
+            .max((self.selected + 1).saturating_sub(height))
+            .min(self.items.len().saturating_sub(height));
+    }
+}
+
+/// The text in a text box, and where its cursor is.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextBox {
+    pub text: String,
+    /// The cursor's position in chars, not bytes.
+    cursor: usize,
+    scroll: usize,
+}
+
+impl TextBox {
+    pub fn new(text: impl Into<String>) -> Self {
+        //! A text box with the cursor at the end of text.
+        let text = text.into();
+        Self {
+            cursor: text.chars().count(),
+            text,
+            scroll: 0,
+        }
+    }
+    pub fn get_cursor(&self) -> usize {
+        self.cursor.min(self.len())
+    }
+    pub fn set_cursor(&mut self, cursor: usize) {
+        self.cursor = cursor.min(self.len());
+    }
+    fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+    fn byte_index(&self, cursor: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(cursor)
+            .map_or(self.text.len(), |x| x.0)
+    }
+    /// Applies a key named the same way as InputEvent, returning true if it was Enter.
+    pub(crate) fn type_key(&mut self, key: &str) -> bool {
+        self.cursor = self.cursor.min(self.len());
+        match key {
+            "Enter" => return true,
+            "Backspace" if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.text.remove(self.byte_index(self.cursor));
+            }
+            "Delete" if self.cursor < self.len() => {
+                self.text.remove(self.byte_index(self.cursor));
+            }
+            "ArrowLeft" => self.cursor = self.cursor.saturating_sub(1),
+            "ArrowRight" => self.cursor = (self.cursor + 1).min(self.len()),
+            "Home" => self.cursor = 0,
+            "End" => self.cursor = self.len(),
+            _ => {
+                let mut chars = key.chars();
+                if let (Some(char), None) = (chars.next(), chars.next()) {
+                    self.text.insert(self.byte_index(self.cursor), char);
+                    self.cursor += 1;
+                }
+            }
+        }
+        false
+    }
+}
+
+/// A message box with buttons, see UiFrame::dialog.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dialog {
+    pub title: String,
+    /// Can be several lines long.
+    pub message: String,
+    pub buttons: Vec<String>,
+}
+
+impl Dialog {
+    pub fn new(title: impl Into<String>, message: impl Into<String>, buttons: &[&str]) -> Self {
+        Self {
+            title: title.into(),
+            message: message.into(),
+            buttons: buttons.iter().map(|x| x.to_string()).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::InputEvent;
+
+    fn key(input: &mut InputMap, key: &str) {
+        input.handle(&InputEvent::KeyDown(key.to_string()));
+        input.handle(&InputEvent::KeyUp(key.to_string()));
+    }
+
+    #[test]
+    fn keyboard_navigation() {
+        let mut ui = Ui::new();
+        let mut input = InputMap::new();
+        Ui::bind_defaults(&mut input);
+        let mut buf = CharBuffer::new(20, 3);
+        let mut on = false;
+        let mut frame = |ui: &mut Ui, input: &mut InputMap, on: &mut bool| {
+            buf.fill(' ');
+            let mut frame = ui.begin(&mut buf, input);
+            let pressed = frame.button("Go");
+            frame.toggle("On", on);
+            drop(frame);
+            input.end_frame();
+            (pressed, buf.to_string())
+        };
+
+        let (pressed, text) = frame(&mut ui, &mut input, &mut on);
+        assert!(!pressed);
+        assert!(text.starts_with(">   [ G o ]"));
+        assert!(text.contains("\n    [   ]   O n"));
+
+        key(&mut input, "Enter");
+        assert!(frame(&mut ui, &mut input, &mut on).0);
+        key(&mut input, "ArrowDown");
+        key(&mut input, " ");
+        let (_, text) = frame(&mut ui, &mut input, &mut on);
+        assert!(on);
+        assert!(text.contains("\n>   [ x ]   O n"));
+        //Focus wraps around
+        key(&mut input, "ArrowDown");
+        frame(&mut ui, &mut input, &mut on);
+        assert_eq!(ui.get_focus(), 0);
+    }
+
+    #[test]
+    fn slider() {
+        let mut ui = Ui::new();
+        let mut input = InputMap::new();
+        Ui::bind_defaults(&mut input);
+        let mut buf = CharBuffer::new(20, 1);
+        let mut value = 0.5;
+
+        key(&mut input, "ArrowRight");
+        assert!(ui
+            .begin(&mut buf, &input)
+            .slider("V", &mut value, 0.0..=1.0));
+        assert!((value - 0.6).abs() < 1e-5);
+        assert_eq!(
+            &buf.data[0][2..16].iter().collect::<String>(),
+            "V [======----]"
+        );
+        input.end_frame();
+
+        //Can't go past the end
+        let mut value = 1.0;
+        key(&mut input, "ArrowRight");
+        assert!(!ui
+            .begin(&mut buf, &input)
+            .slider("V", &mut value, 0.0..=1.0));
+        assert_eq!(value, 1.0);
+        input.end_frame();
+
+        //Clicking the 3rd cell of the track
+        input.handle(&InputEvent::MouseMove(7, 0));
+        input.handle(&InputEvent::MouseDown("Left".to_string()));
+        ui.begin(&mut buf, &input)
+            .slider("V", &mut value, 0.0..=1.0);
+        assert!((value - 0.3).abs() < 1e-5);
+    }
+
+    #[test]
+    fn hover_focuses() {
+        let mut ui = Ui::new();
+        let mut input = InputMap::new();
+        Ui::bind_defaults(&mut input);
+        let mut buf = CharBuffer::new(20, 4);
+        let mut frame = |ui: &mut Ui, input: &InputMap| {
+            let mut frame = ui.begin(&mut buf, input);
+            frame.at(0, 1);
+            [frame.button("A"), frame.button("B")]
+        };
+        frame(&mut ui, &input);
+        input.handle(&InputEvent::MouseMove(3, 2));
+        assert_eq!(frame(&mut ui, &input), [false, false]);
+        assert_eq!(ui.get_focus(), 1);
+        //Keys still move focus while the mouse stays still
+        key(&mut input, "ArrowUp");
+        frame(&mut ui, &input);
+        assert_eq!(ui.get_focus(), 0);
+        input.end_frame();
+
+        input.handle(&InputEvent::MouseDown("Left".to_string()));
+        assert_eq!(frame(&mut ui, &input), [false, true]);
+    }
+
+    #[test]
+    fn menu_scrolls() {
+        let mut ui = Ui::new();
+        let mut input = InputMap::new();
+        Ui::bind_defaults(&mut input);
+        let mut buf = CharBuffer::new(12, 4);
+        let items = ["a", "b", "c", "d"].map(String::from).to_vec();
+        let mut menu = Menu::new(items, 2);
+        let mut frame = |ui: &mut Ui, input: &mut InputMap, menu: &mut Menu| {
+            buf.fill(' ');
+            let mut frame = ui.begin(&mut buf, input);
+            let chosen = frame.menu(menu);
+            let pressed = frame.button("Ok");
+            drop(frame);
+            input.end_frame();
+            (chosen, pressed, buf.to_string())
+        };
+
+        let (_, _, text) = frame(&mut ui, &mut input, &mut menu);
+        assert!(text.starts_with(">   a"));
+        assert!(text.contains("\n    b   v "));
+        for _ in 0..3 {
+            key(&mut input, "ArrowDown");
+            frame(&mut ui, &mut input, &mut menu);
+        }
+        assert_eq!((menu.get_selected(), menu.get_scroll()), (3, 2));
+        key(&mut input, "Enter");
+        let (chosen, pressed, text) = frame(&mut ui, &mut input, &mut menu);
+        assert_eq!((chosen, pressed), (Some(3), false));
+        assert!(text.starts_with("    c   ^ ") && text.contains("\n>   d"));
+
+        //Going past the last item moves on to the next widget
+        key(&mut input, "ArrowDown");
+        frame(&mut ui, &mut input, &mut menu);
+        assert_eq!(ui.get_focus(), 1);
+        key(&mut input, "Enter");
+        assert!(frame(&mut ui, &mut input, &mut menu).1);
+
+        //Clicking an item chooses it
+        input.handle(&InputEvent::MouseMove(2, 0));
+        input.handle(&InputEvent::MouseDown("Left".to_string()));
+        assert_eq!(frame(&mut ui, &mut input, &mut menu).0, Some(2));
+    }
+
+    #[test]
+    fn text_editing() {
+        let mut ui = Ui::new();
+        let mut input = InputMap::new();
+        Ui::bind_defaults(&mut input);
+        let mut buf = CharBuffer::new(8, 1);
+        let mut text_box = TextBox::new("héllo");
+        for name in ["ArrowLeft", "Backspace", "Home", "Delete", "J", "End", "!"] {
+            key(&mut input, name);
+        }
+        assert!(!ui.begin(&mut buf, &input).text_box(&mut text_box, 4));
+        assert_eq!(text_box.text, "Jélo!");
+        assert_eq!(text_box.get_cursor(), 5);
+        assert!(ui.is_typing());
+        //Scrolled so the cursor at the end is shown
+        assert_eq!(&buf.to_string(), ">   [ l o ! | ] \n");
+        input.end_frame();
+
+        input.handle(&InputEvent::MouseMove(4, 0));
+        input.handle(&InputEvent::MouseDown("Left".to_string()));
+        ui.begin(&mut buf, &input).text_box(&mut text_box, 4);
+        assert_eq!(text_box.get_cursor(), 3);
+        input.end_frame();
+        key(&mut input, "Enter");
+        assert!(ui.begin(&mut buf, &input).text_box(&mut text_box, 4));
+    }
+
+    #[test]
+    fn modal_dialog() {
+        let mut ui = Ui::new();
+        let mut input = InputMap::new();
+        Ui::bind_defaults(&mut input);
+        let mut buf = CharBuffer::new(20, 9);
+        let dialog = Dialog::new("Quit", "Are you sure?", &["Yes", "No"]);
+        let mut frame = |ui: &mut Ui, input: &mut InputMap, open: bool| {
+            buf.fill(' ');
+            let mut frame = ui.begin(&mut buf, input);
+            let behind = [frame.button("A"), frame.button("B")];
+            let answer = open.then(|| frame.dialog(&dialog)).flatten();
+            drop(frame);
+            input.end_frame();
+            (behind, answer, buf.to_string())
+        };
+
+        frame(&mut ui, &mut input, false);
+        key(&mut input, "ArrowDown");
+        frame(&mut ui, &mut input, false);
+        assert_eq!(ui.get_focus(), 1);
+        //The Enter that opens the dialog doesn't answer it
+        key(&mut input, "Enter");
+        let (behind, answer, text) = frame(&mut ui, &mut input, true);
+        assert_eq!((behind, answer), ([false, true], None));
+        assert!(text.contains("+ -   Q u i t   - - - - - - - - +"));
+        assert!(text.contains("|   A r e   y o u   s u r e ?   |"));
+
+        //Only the dialog's buttons can be used while it's open
+        key(&mut input, "ArrowDown");
+        key(&mut input, "Enter");
+        let (behind, answer, _) = frame(&mut ui, &mut input, true);
+        assert_eq!((behind, answer), ([false, false], Some(1)));
+
+        //Focus goes back once it's closed
+        frame(&mut ui, &mut input, false);
+        assert_eq!(ui.get_focus(), 1);
+    }
+}
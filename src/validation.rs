@@ -0,0 +1,155 @@
+//! Finding and fixing broken meshes. Meshes loaded from files can have edges pointing at vertices that don't exist, the same edge twice, or coordinates that aren't numbers, which otherwise trip debug assertions when drawn, and in release builds show up as lines missing (or everywhere). validate lists what's wrong with a mesh, and repair removes it.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::validation::MeshIssue;
+//! let mut mesh = ascii_renderer::create_cube();
+//! mesh.add_edge((0, 99));
+//! assert_eq!(
+//!     mesh.validate(),
+//!     vec![MeshIssue::MissingVertex { edge: (0, 99), vertex: 99 }]
+//! );
+//! mesh.repair();
+//! assert!(mesh.validate().is_empty());
+//! ```
+
+use super::rendering::Mesh;
+use std::collections::HashSet;
+
+/// Something wrong with a mesh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MeshIssue {
+    /// An edge uses a vertex the mesh doesn't have.
+    MissingVertex { edge: (usize, usize), vertex: usize },
+    /// An edge is in the mesh more than once, in either direction. Only the copies after the first are issues.
+    DuplicateEdge((usize, usize)),
+    /// A vertex has a NaN or infinite coordinate.
+    NonFiniteVertex(usize),
+    /// An edge has no length, since both ends are the same vertex or at the same position.
+    DegenerateEdge((usize, usize)),
+}
+
+impl std::fmt::Display for MeshIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MeshIssue::MissingVertex { edge, vertex } => {
+                write!(f, "edge {edge:?} uses missing vertex {vertex}")
+            }
+            MeshIssue::DuplicateEdge(edge) => write!(f, "edge {edge:?} is duplicated"),
+            MeshIssue::NonFiniteVertex(index) => {
+                write!(f, "vertex {index} has a non-finite coordinate")
+            }
+            MeshIssue::DegenerateEdge(edge) => write!(f, "edge {edge:?} has no length"),
+        }
+    }
+}
+
+impl Mesh {
+    pub fn validate(&self) -> Vec<MeshIssue> {
+        //! Every issue with the mesh: bad vertices first (by index), then bad edges in the order they were added. Each edge has at most one issue, and edges using a non-finite vertex are left out, since that vertex's issue covers them.
+        let vertices = self.get_verticies();
+        let mut bad: Vec<usize> = vertices
+            .iter()
+            .filter(|(_, x)| !(x.x.is_finite() && x.y.is_finite() && x.z.is_finite()))
+            .map(|(&i, _)| i)
+            .collect();
+        bad.sort_unstable();
+        let mut issues: Vec<MeshIssue> =
+            bad.iter().map(|&x| MeshIssue::NonFiniteVertex(x)).collect();
+        let mut seen = HashSet::new();
+        for &edge in self.get_edges() {
+            let (Some(a), Some(b)) = (vertices.get(&edge.0), vertices.get(&edge.1)) else {
+                let vertex = if vertices.contains_key(&edge.0) {
+                    edge.1
+                } else {
+                    edge.0
+                };
+                issues.push(MeshIssue::MissingVertex { edge, vertex });
+                continue;
+            };
+            if bad.contains(&edge.0) || bad.contains(&edge.1) {
+                //Already reported with the vertex
+                continue;
+            }
+            if a == b {
+                issues.push(MeshIssue::DegenerateEdge(edge));
+            } else if !seen.insert((edge.0.min(edge.1), edge.0.max(edge.1))) {
+                issues.push(MeshIssue::DuplicateEdge(edge));
+            }
+        }
+        issues
+    }
+    pub fn repair(&mut self) -> Vec<MeshIssue> {
+        //! Fixes every issue validate finds, returning them: non-finite vertices are removed along with their edges, and missing, degenerate, and duplicate edges are removed. Everything else stays in the same order.
+        let issues = self.validate();
+        if issues.is_empty() {
+            return issues;
+        }
+        for issue in issues.iter() {
+            if let MeshIssue::NonFiniteVertex(index) = issue {
+                self.remove_vertex(*index);
+            }
+        }
+        //The same checks as validate, keeping the first copy of duplicates
+        let vertices = self.get_verticies();
+        let mut seen = HashSet::new();
+        let edges = self
+            .get_edges()
+            .iter()
+            .copied()
+            .filter(|&(a, b)| match (vertices.get(&a), vertices.get(&b)) {
+                (Some(x), Some(y)) => x != y && seen.insert((a.min(b), a.max(b))),
+                _ => false,
+            })
+            .collect();
+        *self.get_edges_mut() = edges;
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{vec3, Scalar, Vector3};
+
+    #[test]
+    fn validating_and_repairing() {
+        let mut mesh = Mesh::default();
+        for i in 0..4 {
+            mesh.insert_vertex(i, vec3!(i as Scalar, 0.0, 0.0));
+        }
+        mesh.insert_vertex(4, vec3!(0.0, Scalar::NAN, 0.0));
+        mesh.insert_vertex(5, vec3!(1.0, 0.0, 0.0));
+        mesh.add_edges(vec![
+            (0, 1),
+            (1, 0),
+            (1, 2),
+            (2, 2),
+            (2, 7),
+            (3, 4),
+            (1, 5),
+            (2, 3),
+            (1, 2),
+        ]);
+        let issues = mesh.validate();
+        assert_eq!(
+            issues,
+            vec![
+                MeshIssue::NonFiniteVertex(4),
+                MeshIssue::DuplicateEdge((1, 0)),
+                MeshIssue::DegenerateEdge((2, 2)),
+                MeshIssue::MissingVertex {
+                    edge: (2, 7),
+                    vertex: 7
+                },
+                MeshIssue::DegenerateEdge((1, 5)),
+                MeshIssue::DuplicateEdge((1, 2)),
+            ]
+        );
+        assert_eq!(issues[3].to_string(), "edge (2, 7) uses missing vertex 7");
+        assert_eq!(mesh.repair(), issues);
+        assert_eq!(mesh.get_edges(), &vec![(0, 1), (1, 2), (2, 3)]);
+        assert_eq!(mesh.get_verticies().len(), 5);
+        assert!(mesh.validate().is_empty() && mesh.repair().is_empty());
+    }
+}
@@ -0,0 +1,327 @@
+//! Grids of blocks for Minecraft-like scenes. Faces between two blocks can't be seen, so only faces against empty space are drawn, and faces lying flat against each other are merged, so a wall of blocks is outlined as one shape instead of a grid of cubes. Blocks are drawn as outlines, or with fill on, as faces shaded by a light (or toon shaded, see toon::Toon) with their outlines on top, hiding whatever is behind them.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::voxel::VoxelGrid;
+//! let mut grid = VoxelGrid::new(4, 2, 4);
+//! for x in 0..4 {
+//!     for z in 0..4 {
+//!         grid.set(x, 1, z, 1);
+//!     }
+//! }
+//! grid.set(1, 0, 1, 2);
+//! grid.chars = vec!['#', '@'];
+//! grid.fill = true;
+//! grid.transform.position = vec3!(-2.0, 0.0, -2.0);
+//! let renderer = Renderer::new(
+//!     vec![],
+//!     Camera {
+//!         position: vec3!(0.0, -4.0, -8.0),
+//!         rotation: vec3!(0.5, 0.0, 0.0),
+//!         fov: vec2!(1.0, 1.0),
+//!     },
+//! );
+//! let mut buf = CharBuffer::new(40, 30);
+//! renderer.draw_renderable(&grid, &mut buf);
+//! ```
+
+use super::char_buffer::CharBuffer;
+use super::demos::{shade, RAMP};
+use super::renderable::{RenderContext, Renderable};
+use super::toon::{Toon, EMPTY};
+use super::transform::Transform;
+use super::{Scalar, Vector3};
+use std::collections::HashSet;
+
+type Cell = [i32; 3];
+
+/// The directions faces can point, as (axis, sign).
+const DIRECTIONS: [(usize, i32); 6] = [(0, -1), (0, 1), (1, -1), (1, 1), (2, -1), (2, 1)];
+
+/// A side of a block that isn't against another block.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Face {
+    cell: Cell,
+    axis: usize,
+    sign: i32,
+    voxel: u8,
+}
+
+impl Face {
+    fn corners(&self) -> [Cell; 4] {
+        //Going around the face, along the other two axes u and v
+        let (u, v) = ((self.axis + 1) % 3, (self.axis + 2) % 3);
+        let mut base = self.cell;
+        if self.sign > 0 {
+            base[self.axis] += 1;
+        }
+        [(0, 0), (1, 0), (1, 1), (0, 1)].map(|(i, j)| {
+            let mut corner = base;
+            corner[u] += i;
+            corner[v] += j;
+            corner
+        })
+    }
+    fn normal(&self) -> Vector3 {
+        let mut normal = [0.0; 3];
+        normal[self.axis] = self.sign as Scalar;
+        Vector3::new(normal[0], normal[1], normal[2])
+    }
+}
+
+fn point(cell: Cell) -> Vector3 {
+    Vector3::new(cell[0] as Scalar, cell[1] as Scalar, cell[2] as Scalar)
+}
+
+/// A 3D grid of blocks, each 1 unit across in the grid's local space, with the block at (x, y, z) going from (x, y, z) to (x + 1, y + 1, z + 1). Each block is a type from 1 to 255, and 0 is empty.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoxelGrid {
+    width: usize,
+    height: usize,
+    depth: usize,
+    voxels: Vec<u8>,
+    /// Moves the grid into the world, ex: scale to change the size of the blocks.
+    pub transform: Transform,
+    /// The char blocks of each type are outlined with, where type n uses chars[n - 1]. Types without a char use '#'.
+    pub chars: Vec<char>,
+    /// Whether to fill in faces, hiding what's behind them, instead of only drawing outlines.
+    pub fill: bool,
+    /// The direction light shines from when filling faces, in world space. Note that +y is down.
+    pub light: Vector3,
+    /// The chars filled faces are shaded with, from dark to bright.
+    pub ramp: Vec<char>,
+    /// When set, filled faces are toon shaded with its bands instead of ramp, and outlined with its edge char where they meet anything else.
+    pub toon: Option<Toon>,
+}
+
+impl VoxelGrid {
+    pub fn new(width: usize, height: usize, depth: usize) -> Self {
+        //! An empty grid, drawn as outlines of '#'.
+        Self {
+            width,
+            height,
+            depth,
+            voxels: vec![0; width * height * depth],
+            transform: Transform::identity(),
+            chars: vec!['#'],
+            fill: false,
+            light: Vector3::new(0.4, -1.0, -0.6).normalize(),
+            ramp: RAMP.to_vec(),
+            toon: None,
+        }
+    }
+    pub fn dimensions(&self) -> (usize, usize, usize) {
+        (self.width, self.height, self.depth)
+    }
+    fn index(&self, x: usize, y: usize, z: usize) -> Option<usize> {
+        (x < self.width && y < self.height && z < self.depth)
+            .then(|| (z * self.height + y) * self.width + x)
+    }
+    pub fn get(&self, x: usize, y: usize, z: usize) -> Option<u8> {
+        //! The type of the block at (x, y, z), or None if it's outside the grid.
+        self.index(x, y, z).map(|x| self.voxels[x])
+    }
+    pub fn set(&mut self, x: usize, y: usize, z: usize, voxel: u8) -> bool {
+        //! Changes the block at (x, y, z), returning false if it's outside the grid. Set it to 0 to remove it.
+        match self.index(x, y, z) {
+            Some(index) => {
+                self.voxels[index] = voxel;
+                true
+            }
+            None => false,
+        }
+    }
+    pub fn is_solid(&self, x: usize, y: usize, z: usize) -> bool {
+        self.get(x, y, z).is_some_and(|x| x != 0)
+    }
+    pub fn clear(&mut self) {
+        self.voxels.fill(0);
+    }
+    fn at(&self, cell: Cell) -> u8 {
+        //Everything outside the grid is empty
+        if cell.iter().any(|&x| x < 0) {
+            return 0;
+        }
+        self.get(cell[0] as usize, cell[1] as usize, cell[2] as usize)
+            .unwrap_or(0)
+    }
+    fn char(&self, voxel: u8) -> char {
+        self.chars.get(voxel as usize - 1).copied().unwrap_or('#')
+    }
+    fn faces(&self) -> Vec<Face> {
+        let mut faces = vec![];
+        for z in 0..self.depth as i32 {
+            for y in 0..self.height as i32 {
+                for x in 0..self.width as i32 {
+                    let voxel = self.at([x, y, z]);
+                    if voxel == 0 {
+                        continue;
+                    }
+                    for (axis, sign) in DIRECTIONS {
+                        let mut neighbor = [x, y, z];
+                        neighbor[axis] += sign;
+                        if self.at(neighbor) == 0 {
+                            faces.push(Face {
+                                cell: [x, y, z],
+                                axis,
+                                sign,
+                                voxel,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        faces
+    }
+    fn outline(&self, face: &Face) -> Vec<(Cell, Cell)> {
+        //! The edges of a face that are on the outline of its merged shape. An edge is left out when the next block over in the same plane is the same type and has the same face showing, so the two faces continue into each other.
+        let corners = face.corners();
+        let (u, v) = ((face.axis + 1) % 3, (face.axis + 2) % 3);
+        //Which way is across each edge, from the face to the one next to it
+        let across = [(v, -1), (u, 1), (v, 1), (u, -1)];
+        (0..4)
+            .filter(|&i| {
+                let (axis, sign) = across[i];
+                let mut next = face.cell;
+                next[axis] += sign;
+                let mut above = next;
+                above[face.axis] += face.sign;
+                !(self.at(next) == face.voxel && self.at(above) == 0)
+            })
+            .map(|i| (corners[i], corners[(i + 1) % 4]))
+            .collect()
+    }
+    fn outlines(&self) -> Vec<(Cell, Cell, char)> {
+        //Edges shared by two faces of the same outline are only drawn once
+        let mut seen = HashSet::new();
+        let mut edges = vec![];
+        for face in self.faces() {
+            for (start, end) in self.outline(&face) {
+                let key = (start.min(end), start.max(end), face.voxel);
+                if seen.insert(key) {
+                    edges.push((start, end, self.char(face.voxel)));
+                }
+            }
+        }
+        edges
+    }
+    fn draw_toon(&self, ctx: &mut RenderContext, toon: &Toon) {
+        //Faces are drawn into a layer first, so the silhouette of all of them can be found
+        let (width, height) = ctx.buffer().dimensions;
+        let mut layer = CharBuffer::new(width, height);
+        layer.fill(EMPTY);
+        ctx.with_buffer(&mut layer, |ctx| self.draw_filled(ctx, |x| toon.shade(x)));
+        toon.composite(&layer, ctx.buffer());
+    }
+    fn draw_filled(&self, ctx: &mut RenderContext, shade: impl Fn(Scalar) -> char) {
+        let light = self.light.normalize();
+        let mut faces: Vec<(Scalar, Face)> = self
+            .faces()
+            .into_iter()
+            .filter_map(|face| {
+                let center = point(face.cell) + Vector3::new(0.5, 0.5, 0.5) + face.normal() * 0.5;
+                let view = ctx.to_view(center);
+                //Faces pointing away from the camera are hidden by the block's other faces
+                let normal = ctx.to_view(center + face.normal()) - view;
+                (normal.dot(view) < 0.0).then(|| (view.length(), face))
+            })
+            .collect();
+        faces.sort_by(|a, b| b.0.total_cmp(&a.0));
+        for (_, face) in faces {
+            let normal = self.transform.transform_vector(face.normal()).normalize();
+            let brightness = 0.25 + 0.75 * normal.dot(light).max(0.0);
+            let corners = face.corners().map(point);
+            ctx.polygon(&corners, shade(brightness));
+            for (start, end) in self.outline(&face) {
+                ctx.line(point(start), point(end), self.char(face.voxel));
+            }
+        }
+    }
+}
+
+impl Renderable for VoxelGrid {
+    fn draw(&self, ctx: &mut RenderContext) {
+        ctx.with_model(self.transform.matrix(), |ctx| {
+            if let (true, Some(toon)) = (self.fill, &self.toon) {
+                self.draw_toon(ctx, toon);
+            } else if self.fill {
+                self.draw_filled(ctx, |x| shade(&self.ramp, x));
+            } else {
+                for (start, end, char) in self.outlines() {
+                    ctx.line(point(start), point(end), char);
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::char_buffer::CharBuffer;
+    use crate::rendering::{Camera, Renderer};
+    use crate::{vec2, vec3, Vector2};
+
+    fn grid(blocks: &[(usize, usize, usize, u8)]) -> VoxelGrid {
+        let mut grid = VoxelGrid::new(3, 3, 3);
+        for &(x, y, z, voxel) in blocks {
+            assert!(grid.set(x, y, z, voxel));
+        }
+        grid
+    }
+
+    #[test]
+    fn merged_outlines() {
+        let single = grid(&[(0, 0, 0, 1)]);
+        assert_eq!((single.faces().len(), single.outlines().len()), (6, 12));
+        //Two blocks in a row only show their outside faces, which merge into one long box without the ring of edges where they meet
+        let pair = grid(&[(0, 0, 0, 1), (1, 0, 0, 1)]);
+        assert_eq!((pair.faces().len(), pair.outlines().len()), (10, 16));
+        assert!(pair
+            .outlines()
+            .iter()
+            .all(|(start, end, _)| start[0] != 1 || end[0] != 1));
+        //An L shape is outlined 8 units around each end, with 6 edges between them including the inside corner
+        let l = grid(&[(0, 0, 0, 1), (1, 0, 0, 1), (0, 1, 0, 1)]);
+        assert_eq!((l.faces().len(), l.outlines().len()), (14, 22));
+        //Blocks of different types are outlined separately
+        let mixed = grid(&[(0, 0, 0, 1), (1, 0, 0, 2)]);
+        assert_eq!(mixed.outlines().len(), 24);
+        assert!(!grid(&[]).is_solid(0, 0, 0) && !single.is_solid(5, 0, 0));
+        assert_eq!(single.get(3, 0, 0), None);
+    }
+
+    #[test]
+    fn drawing() {
+        let renderer = Renderer::new(
+            vec![],
+            Camera {
+                position: vec3!(0.5, 0.5, -5.0),
+                rotation: vec3!(0.0, 0.0, 0.0),
+                fov: vec2!(1.0, 1.0),
+            },
+        );
+        let mut cube = grid(&[(0, 0, 0, 1)]);
+        let mut buf = CharBuffer::new(21, 21);
+        renderer.draw_renderable(&cube, &mut buf);
+        assert_eq!(buf.get_char(10, 10), Some(' '));
+        assert!(buf.to_string().contains('#'));
+
+        //Filled, the front face covers the middle, lit from the front
+        cube.fill = true;
+        cube.light = vec3!(0.0, 0.0, -1.0);
+        let mut filled = CharBuffer::new(21, 21);
+        renderer.draw_renderable(&cube, &mut filled);
+        assert_eq!(filled.get_char(10, 10), Some('@'));
+
+        //Toon shaded, the face is one band with the silhouette around it
+        cube.toon = Some(Toon::new(2));
+        cube.light = vec3!(0.0, 0.0, 1.0);
+        let mut toon = CharBuffer::new(21, 21);
+        renderer.draw_renderable(&cube, &mut toon);
+        assert_eq!(toon.get_char(10, 10), Some('.'));
+        let row: String = (0..21).filter_map(|x| toon.get_char(x, 10)).collect();
+        assert_eq!(row.trim(), "#....#");
+    }
+}
@@ -0,0 +1,259 @@
+//! Animated water: a grid mesh whose heights change every frame, from a sum of travelling sine waves plus ripples that spread out from disturbances and bounce off the edges. Waves go up, which is -y since +y is down on screen.
+//! # Example
+//! ```
+//! # use ascii_renderer::prelude::*;
+//! # use ascii_renderer::wave::{Wave, WaveSurface};
+//! let mut water = WaveSurface::new(20, 20, 0.5);
+//! water.waves.push(Wave::new(vec2!(1.0, 0.3), 0.2, 4.0, 1.5));
+//! water.disturb(10, 10, 1.0);
+//! let renderer = Renderer::new(
+//!     vec![],
+//!     Camera {
+//!         position: vec3!(0.0, -4.0, -8.0),
+//!         rotation: vec3!(-0.4, 0.0, 0.0),
+//!         fov: vec2!(1.2, 0.8),
+//!     },
+//! );
+//! let mut buf = CharBuffer::new(60, 30);
+//! //Every frame:
+//! water.update(1.0 / 30.0);
+//! buf.fill(' ');
+//! renderer.draw_renderable(&water, &mut buf);
+//! ```
+
+use super::renderable::{RenderContext, Renderable};
+use super::rendering::Mesh;
+use super::{Scalar, Vector2, Vector3};
+
+/// A sine wave travelling across a surface.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Wave {
+    /// Which way the wave travels across the surface, as (x, z). Only the direction matters, not the length.
+    pub direction: Vector2,
+    /// How far above and below the surface the wave reaches.
+    pub amplitude: Scalar,
+    /// The distance from one crest to the next.
+    pub wavelength: Scalar,
+    /// How far a crest moves each second.
+    pub speed: Scalar,
+}
+
+impl Wave {
+    pub fn new(direction: Vector2, amplitude: Scalar, wavelength: Scalar, speed: Scalar) -> Self {
+        Self {
+            direction,
+            amplitude,
+            wavelength,
+            speed,
+        }
+    }
+    pub fn height(&self, position: Vector2, time: Scalar) -> Scalar {
+        //! The height of the wave at a point (x, z) on the surface, at some time in seconds.
+        let length = self.direction.length();
+        if length == 0.0 || self.wavelength == 0.0 {
+            return 0.0;
+        }
+        let distance = (position.x * self.direction.x + position.y * self.direction.y) / length;
+        let phase = (distance - self.speed * time) / self.wavelength;
+        self.amplitude * (phase * 2.0 * std::f64::consts::PI as Scalar).sin()
+    }
+}
+
+/// A grid of vertices columns wide (along x) and rows deep (along z), spacing apart and centered on the origin, moved up and down by waves and ripples.
+#[derive(Debug, Clone)]
+pub struct WaveSurface {
+    columns: usize,
+    rows: usize,
+    spacing: Scalar,
+    /// Waves that keep travelling across the surface, added together.
+    pub waves: Vec<Wave>,
+    /// How far ripples spread each second.
+    pub ripple_speed: Scalar,
+    /// How quickly ripples die down, as the fraction of their motion lost each second.
+    pub damping: Scalar,
+    ripples: Vec<Scalar>,
+    velocities: Vec<Scalar>,
+    time: Scalar,
+    mesh: Mesh,
+}
+
+impl WaveSurface {
+    pub fn new(columns: usize, rows: usize, spacing: Scalar) -> Self {
+        //! A flat surface with no waves, drawn with '~'.
+        let mut mesh = Mesh::default();
+        mesh.char = '~';
+        let center = Vector2::new(
+            (columns.max(1) - 1) as Scalar / 2.0,
+            (rows.max(1) - 1) as Scalar / 2.0,
+        );
+        for row in 0..rows {
+            for column in 0..columns {
+                let index = row * columns + column;
+                mesh.insert_vertex(
+                    index,
+                    Vector3::new(
+                        (column as Scalar - center.x) * spacing,
+                        0.0,
+                        (row as Scalar - center.y) * spacing,
+                    ),
+                );
+                if column > 0 {
+                    mesh.add_edge((index - 1, index));
+                }
+                if row > 0 {
+                    mesh.add_edge((index - columns, index));
+                }
+            }
+        }
+        Self {
+            columns,
+            rows,
+            spacing,
+            waves: vec![],
+            ripple_speed: 3.0,
+            damping: 0.5,
+            ripples: vec![0.0; columns * rows],
+            velocities: vec![0.0; columns * rows],
+            time: 0.0,
+            mesh,
+        }
+    }
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.columns, self.rows)
+    }
+    pub fn get_time(&self) -> Scalar {
+        self.time
+    }
+    pub fn get_mesh(&self) -> &Mesh {
+        &self.mesh
+    }
+    pub fn get_mesh_mut(&mut self) -> &mut Mesh {
+        //! The mesh, ex: to move the surface or change its char. Its vertices are overwritten by every update.
+        &mut self.mesh
+    }
+    pub fn get_height(&self, column: usize, row: usize) -> Option<Scalar> {
+        //! How far the vertex at (column, row) is above the flat surface, or None if it's outside the grid.
+        if column >= self.columns || row >= self.rows {
+            return None;
+        }
+        let position = self.local_position(column, row);
+        let waves: Scalar = self
+            .waves
+            .iter()
+            .map(|x| x.height(position, self.time))
+            .sum();
+        Some(waves + self.ripples[row * self.columns + column])
+    }
+    fn local_position(&self, column: usize, row: usize) -> Vector2 {
+        Vector2::new(
+            (column as Scalar - (self.columns - 1) as Scalar / 2.0) * self.spacing,
+            (row as Scalar - (self.rows - 1) as Scalar / 2.0) * self.spacing,
+        )
+    }
+    pub fn disturb(&mut self, column: usize, row: usize, strength: Scalar) {
+        //! Pushes the surface up at (column, row), ex: where something hit the water, starting ripples that spread out from it. Negative strengths push it down.
+        if column < self.columns && row < self.rows {
+            self.velocities[row * self.columns + column] += strength * self.ripple_speed;
+        }
+    }
+    pub fn calm(&mut self) {
+        //! Stops all the ripples. The waves keep going.
+        self.ripples.fill(0.0);
+        self.velocities.fill(0.0);
+    }
+    fn step_ripples(&mut self, delta: Scalar) {
+        let (columns, rows) = (self.columns, self.rows);
+        let stiffness = self.ripple_speed * self.ripple_speed / (self.spacing * self.spacing);
+        let damping = (1.0 - self.damping * delta).max(0.0);
+        for row in 0..rows {
+            for column in 0..columns {
+                let index = row * columns + column;
+                let height = self.ripples[index];
+                //Missing neighbors past the edges count as level with the vertex, so ripples bounce back
+                let neighbor = |x: Option<usize>| x.map(|x| self.ripples[x]).unwrap_or(height);
+                let sum = neighbor(column.checked_sub(1).map(|_| index - 1))
+                    + neighbor((column + 1 < columns).then(|| index + 1))
+                    + neighbor(row.checked_sub(1).map(|_| index - columns))
+                    + neighbor((row + 1 < rows).then(|| index + columns));
+                self.velocities[index] += (sum - 4.0 * height) * stiffness * delta;
+                self.velocities[index] *= damping;
+            }
+        }
+        for (height, velocity) in self.ripples.iter_mut().zip(self.velocities.iter()) {
+            *height += velocity * delta;
+        }
+    }
+    pub fn update(&mut self, delta: f32) {
+        //! Moves the waves and ripples forward by delta seconds and updates the mesh.
+        let delta = delta as Scalar;
+        self.time += delta;
+        if self.spacing > 0.0 && self.ripple_speed > 0.0 {
+            //Ripples blow up if they move more than about half a vertex per step, so long frames take several steps
+            let steps = (delta * self.ripple_speed / self.spacing / 0.5)
+                .ceil()
+                .clamp(1.0, 64.0);
+            for _ in 0..steps as usize {
+                self.step_ripples(delta / steps);
+            }
+        }
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                let position = self.local_position(column, row);
+                let height = self.get_height(column, row).unwrap_or(0.0);
+                self.mesh.get_verticies_mut().insert(
+                    row * self.columns + column,
+                    Vector3::new(position.x, -height, position.y),
+                );
+            }
+        }
+    }
+}
+
+impl Renderable for WaveSurface {
+    fn draw(&self, ctx: &mut RenderContext) {
+        ctx.mesh(&self.mesh);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec2;
+
+    #[test]
+    fn sine_waves() {
+        let wave = Wave::new(vec2!(2.0, 0.0), 0.5, 4.0, 1.0);
+        assert!((wave.height(vec2!(1.0, 0.0), 0.0) - 0.5).abs() < 1e-5);
+        //A second later, the crest has moved 1 unit along
+        assert!((wave.height(vec2!(2.0, 7.0), 1.0) - 0.5).abs() < 1e-5);
+        let mut surface = WaveSurface::new(5, 3, 1.0);
+        assert_eq!(surface.get_mesh().get_edges().len(), 4 * 3 + 5 * 2);
+        surface.waves.push(wave);
+        surface.update(0.0);
+        //The middle vertex is at the origin, and a crest is 1 unit to its right, which is up on screen
+        assert!(surface.get_height(2, 1).unwrap().abs() < 1e-5);
+        assert!((surface.get_mesh().get_verticies()[&8].y + 0.5).abs() < 1e-5);
+        assert_eq!(surface.get_height(5, 0), None);
+    }
+
+    #[test]
+    fn ripples_spread_and_settle() {
+        let mut surface = WaveSurface::new(9, 9, 1.0);
+        surface.disturb(4, 4, 1.0);
+        surface.update(0.1);
+        assert!(surface.get_height(4, 4).unwrap() > 0.0);
+        assert_eq!(surface.get_height(0, 0), Some(0.0));
+        for _ in 0..20 {
+            surface.update(0.1);
+        }
+        assert!(surface.get_height(0, 0).unwrap() != 0.0);
+        //Even huge steps stay stable, and damping calms everything down
+        for _ in 0..50 {
+            surface.update(1.0);
+        }
+        let mut heights = (0..81).map(|i| surface.get_height(i % 9, i / 9).unwrap());
+        assert!(heights.all(|x| x.is_finite() && x.abs() < 0.1));
+        surface.calm();
+        assert_eq!(surface.get_height(4, 4), Some(0.0));
+    }
+}
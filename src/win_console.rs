@@ -0,0 +1,71 @@
+//! Windows console support. Windows 10 and later consoles only handle escape sequences (for moving the cursor and clearing) once they've been asked to, and older ones print them as text. StdoutBackend turns them on when it's made, and on consoles that can't handle them it moves the cursor with console API calls instead, so the runner works the same everywhere. On other platforms, escape sequences always work and nothing here does anything.
+//! # Example
+//! ```no_run
+//! # use ascii_renderer::win_console::enable_virtual_terminal;
+//! //Before printing escape sequences yourself
+//! if enable_virtual_terminal() {
+//!     print!("\x1b[2J");
+//! }
+//! ```
+
+use std::sync::OnceLock;
+
+pub fn enable_virtual_terminal() -> bool {
+    //! Turns on escape sequence handling for stdout if it's a Windows console, returning whether escape sequences can be printed. Only the first call does anything, later ones return the same result. Always true on other platforms, and when stdout isn't a console (ex: piped, or a terminal like mintty that handles escape sequences itself).
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(sys::enable_virtual_terminal)
+}
+
+pub(crate) fn move_cursor_home() -> bool {
+    //Returns whether the cursor was moved, which only happens on Windows consoles
+    sys::move_cursor_home()
+}
+
+#[cfg(windows)]
+mod sys {
+    use windows_sys::Win32::Foundation::{HANDLE, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::System::Console::{
+        GetConsoleMode, GetStdHandle, SetConsoleCursorPosition, SetConsoleMode, COORD,
+        ENABLE_VIRTUAL_TERMINAL_PROCESSING, STD_OUTPUT_HANDLE,
+    };
+
+    fn stdout() -> Option<HANDLE> {
+        //SAFETY: GetStdHandle has no preconditions
+        let handle = unsafe { GetStdHandle(STD_OUTPUT_HANDLE) };
+        (!handle.is_null() && handle != INVALID_HANDLE_VALUE).then_some(handle)
+    }
+
+    pub fn enable_virtual_terminal() -> bool {
+        let Some(handle) = stdout() else {
+            return true;
+        };
+        let mut mode = 0;
+        //SAFETY: handle is this process's stdout, and mode lives until the calls return
+        unsafe {
+            if GetConsoleMode(handle, &mut mode) == 0 {
+                //Not a console, so whatever is reading the output deals with escape sequences
+                return true;
+            }
+            mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0
+                || SetConsoleMode(handle, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0
+        }
+    }
+
+    pub fn move_cursor_home() -> bool {
+        //SAFETY: handle is this process's stdout
+        stdout().is_some_and(|handle| unsafe {
+            SetConsoleCursorPosition(handle, COORD { X: 0, Y: 0 }) != 0
+        })
+    }
+}
+
+#[cfg(not(windows))]
+mod sys {
+    pub fn enable_virtual_terminal() -> bool {
+        true
+    }
+
+    pub fn move_cursor_home() -> bool {
+        false
+    }
+}